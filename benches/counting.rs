@@ -0,0 +1,97 @@
+//! Wall-clock cost of the off-chain counting algorithms at growing ballot
+//! counts, to tell which methods are cheap enough to count in one
+//! `finalize` call and which need chunked `finalize_step` calls.
+//!
+//! Runs under criterion, e.g. `cargo bench --bench counting`. The contract
+//! counts no Schulze elections, so only plurality and instant runoff, a
+//! single-seat transferable vote, are measured.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use voting::counting::{self, Settlement};
+
+const BALLOTS: [u32; 3] = [1_000, 10_000, 100_000];
+const CANDIDATES: u32 = 10;
+
+/// A deterministic stream of pseudo-random numbers below `CANDIDATES`.
+fn picks(seed: u64) -> impl Iterator<Item = u32> {
+    let mut state = seed;
+    std::iter::repeat_with(move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        ((state >> 33) % u64::from(CANDIDATES)) as u32
+    })
+}
+
+/// Ranked ballots, each ranking three distinct candidates.
+fn rankings(ballots: u32) -> Vec<Vec<u32>> {
+    let mut picks = picks(u64::from(ballots));
+    (0..ballots)
+        .map(|_| {
+            let mut ranking = Vec::with_capacity(3);
+            while ranking.len() < 3 {
+                let pick = picks.next().unwrap();
+                if !ranking.contains(&pick) {
+                    ranking.push(pick);
+                }
+            }
+            ranking
+        })
+        .collect()
+}
+
+/// Counts ranked ballots by instant runoff: every pass recounts each
+/// ballot for its highest hopeful preference, then elects or excludes
+/// with `counting::settle_pass`.
+fn instant_runoff(rankings: &[Vec<u32>]) -> Option<u32> {
+    let mut hopeful: Vec<usize> = (0..CANDIDATES as usize).collect();
+    loop {
+        let mut tallies = vec![0u128; CANDIDATES as usize];
+        for ranking in rankings {
+            if let Some(first) = ranking.iter().find(|c| hopeful.contains(&(**c as usize))) {
+                tallies[*first as usize] += 1;
+            }
+        }
+        let total = tallies.iter().sum();
+        match counting::settle_pass(&tallies, &hopeful, counting::droop_quota(total, 1))? {
+            Settlement::Elect(winner) => return Some(winner as u32),
+            Settlement::Exclude(last) => hopeful.retain(|index| *index != last),
+        }
+    }
+}
+
+fn plurality(c: &mut Criterion) {
+    let mut group = c.benchmark_group("plurality");
+    for ballots in BALLOTS {
+        let choices: Vec<u32> = picks(u64::from(ballots)).take(ballots as usize).collect();
+        group.throughput(Throughput::Elements(ballots.into()));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(ballots),
+            &choices,
+            |b, choices| {
+                b.iter(|| {
+                    let totals = counting::totals(choices.iter().map(|choice| (*choice, 1)));
+                    counting::winners(black_box(&totals), false)
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn irv(c: &mut Criterion) {
+    let mut group = c.benchmark_group("instant_runoff");
+    for ballots in BALLOTS {
+        let rankings = rankings(ballots);
+        group.throughput(Throughput::Elements(ballots.into()));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(ballots),
+            &rankings,
+            |b, rankings| b.iter(|| instant_runoff(black_box(rankings))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, plurality, irv);
+criterion_main!(benches);
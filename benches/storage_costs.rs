@@ -0,0 +1,139 @@
+//! Storage accesses of the hot messages at growing candidate counts.
+//!
+//! Storage reads and writes dominate the weight of `vote`, `finalize` and the
+//! winner queries, and unlike wall-clock time they are deterministic, so they
+//! are compared against `benches/baseline.csv`. The run fails when any count
+//! grows. Pass `--bless` to rewrite the baseline, e.g.
+//! `cargo bench --bench storage_costs -- --bless`.
+use ink::{
+    env::{
+        test::{self, DefaultAccounts},
+        DefaultEnvironment,
+    },
+    primitives::AccountId,
+};
+use std::{fmt::Write as _, fs, path::Path, time::Instant};
+use voting::voting::Voting;
+
+const SIZES: [u32; 3] = [10, 100, 1_000];
+
+/// Storage reads and writes of one call.
+struct Cost {
+    operation: &'static str,
+    candidates: u32,
+    reads: usize,
+    writes: usize,
+    micros: u128,
+}
+
+fn measure<R>(
+    operation: &'static str,
+    candidates: u32,
+    costs: &mut Vec<Cost>,
+    f: impl FnOnce() -> R,
+) -> R {
+    let contract = test::callee::<DefaultEnvironment>();
+    let (reads_before, writes_before) =
+        test::get_contract_storage_rw::<DefaultEnvironment>(&contract);
+    let started = Instant::now();
+    let output = f();
+    let micros = started.elapsed().as_micros();
+    let (reads, writes) = test::get_contract_storage_rw::<DefaultEnvironment>(&contract);
+    costs.push(Cost {
+        operation,
+        candidates,
+        reads: reads - reads_before,
+        writes: writes - writes_before,
+        micros,
+    });
+    output
+}
+
+fn candidate(n: u32) -> AccountId {
+    let mut id = [0; 32];
+    id[..4].copy_from_slice(&n.to_le_bytes());
+    AccountId::from(id)
+}
+
+fn election_costs(candidates: u32, accounts: &DefaultAccounts<DefaultEnvironment>) -> Vec<Cost> {
+    let mut costs = Vec::new();
+    let mut voting = Voting::new_with_candidates((0..candidates).map(candidate).collect());
+    for n in 0..candidates {
+        let mut voter = [0xff; 32];
+        voter[..4].copy_from_slice(&n.to_le_bytes());
+        test::set_caller::<DefaultEnvironment>(AccountId::from(voter));
+        voting.vote(0, candidate(n)).unwrap();
+    }
+
+    test::set_caller::<DefaultEnvironment>(accounts.bob);
+    measure("vote", candidates, &mut costs, || {
+        voting.vote(0, candidate(0)).unwrap()
+    });
+    measure("get_current_winner", candidates, &mut costs, || {
+        voting.get_current_winner(0)
+    });
+    test::set_caller::<DefaultEnvironment>(accounts.alice);
+    measure("finalize", candidates, &mut costs, || {
+        voting.finalize(0).unwrap()
+    });
+    measure(
+        "get_current_winner_finalized",
+        candidates,
+        &mut costs,
+        || voting.get_current_winner(0),
+    );
+    costs
+}
+
+fn main() {
+    let bless = std::env::args().any(|arg| arg == "--bless");
+    let mut costs = Vec::new();
+    for candidates in SIZES {
+        test::run_test::<DefaultEnvironment, _>(|accounts| {
+            costs.extend(election_costs(candidates, &accounts));
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    let mut table = String::from("operation,candidates,reads,writes\n");
+    for cost in &costs {
+        writeln!(
+            table,
+            "{},{},{},{}",
+            cost.operation, cost.candidates, cost.reads, cost.writes
+        )
+        .unwrap();
+        println!(
+            "{:<30} {:>6} candidates: {:>6} reads {:>6} writes {:>8} us",
+            cost.operation, cost.candidates, cost.reads, cost.writes, cost.micros
+        );
+    }
+
+    let baseline_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/baseline.csv");
+    if bless {
+        fs::write(&baseline_path, table).unwrap();
+        println!("baseline written to {}", baseline_path.display());
+        return;
+    }
+    let baseline = fs::read_to_string(&baseline_path).unwrap_or_default();
+    let mut regressions = Vec::new();
+    for (line, expected) in table.lines().zip(baseline.lines()).skip(1) {
+        let now: Vec<&str> = line.split(',').collect();
+        let then: Vec<&str> = expected.split(',').collect();
+        let grew = |i: usize| now[i].parse::<usize>().unwrap() > then[i].parse::<usize>().unwrap();
+        if now[..2] != then[..2] || grew(2) || grew(3) {
+            regressions.push(format!("{expected} -> {line}"));
+        }
+    }
+    if table.lines().count() != baseline.lines().count() {
+        regressions.push("the set of measurements changed".into());
+    }
+    if !regressions.is_empty() {
+        eprintln!("storage costs regressed against the baseline:");
+        for regression in regressions {
+            eprintln!("  {regression}");
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,141 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Storage contract running the voting contract's code by `delegate_call`.
+///
+/// A voting contract becomes one through `Voting::delegate_logic`, keeping
+/// its storage and its address. Every call is then run, input and value
+/// included, by the logic code against that storage, and the admin may swap
+/// the logic for a fixed one, e.g. to correct a counting bug while elections
+/// are voting, with the state untouched. Only the `@` selector is handled
+/// by the delegator itself.
+///
+/// The root cell belongs to the voting contract, so the delegator keeps
+/// nothing in it and writes only its own cells, at the keys of
+/// `voting::delegator`.
+#[ink::contract]
+pub mod voting_delegator {
+    use voting::delegator::{ADMIN_KEY, LOGIC_KEY};
+
+    #[ink(storage)]
+    pub struct VotingDelegator {}
+
+    /// Emitted when calls start being run by other code.
+    #[ink(event)]
+    pub struct LogicChanged {
+        #[ink(topic)]
+        old: Hash,
+        #[ink(topic)]
+        new: Hash,
+    }
+
+    /// Emitted when the admin hands the delegator over.
+    #[ink(event)]
+    pub struct AdminChanged {
+        #[ink(topic)]
+        old: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    /// What the admin asks the delegator to do.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum AdminCommand {
+        /// Runs calls with the code of another voting contract from now on.
+        SetLogic(Hash),
+        /// Hands the admin rights over to another account.
+        SetAdmin(AccountId),
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum DelegatorError {
+        Unauthorized,
+    }
+
+    impl VotingDelegator {
+        /// Constructor running calls with `logic`, administered by the caller.
+        /// It starts without any voting storage; a delegator holding
+        /// elections comes from `Voting::delegate_logic` instead.
+        #[ink(constructor)]
+        pub fn new(logic: Hash) -> Self {
+            ink::env::set_contract_storage(&LOGIC_KEY, &logic);
+            ink::env::set_contract_storage(&ADMIN_KEY, &Self::env().caller());
+            Self {}
+        }
+
+        /// Runs an admin command. Only the admin may call it. Takes `&self`
+        /// so that no root cell is written back over the voting storage.
+        #[ink(message, selector = @)]
+        pub fn admin(&self, command: AdminCommand) -> Result<(), DelegatorError> {
+            let caller = self.env().caller();
+            if Some(caller) != ink::env::get_contract_storage(&ADMIN_KEY).ok().flatten() {
+                return Err(DelegatorError::Unauthorized);
+            }
+            match command {
+                AdminCommand::SetLogic(new) => {
+                    let old = self.logic();
+                    ink::env::set_contract_storage(&LOGIC_KEY, &new);
+                    self.env().emit_event(LogicChanged { old, new });
+                }
+                AdminCommand::SetAdmin(new) => {
+                    ink::env::set_contract_storage(&ADMIN_KEY, &new);
+                    self.env().emit_event(AdminChanged { old: caller, new });
+                }
+            }
+            Ok(())
+        }
+
+        /// Runs any other call with the logic code as a tail call, so its
+        /// output becomes the delegator's output.
+        #[ink(message, payable, selector = _)]
+        pub fn forward(&self) {
+            ink::env::call::build_call::<ink::env::DefaultEnvironment>()
+                .delegate(self.logic())
+                .call_flags(ink::env::CallFlags::FORWARD_INPUT | ink::env::CallFlags::TAIL_CALL)
+                .invoke();
+            unreachable!("a tail call never returns");
+        }
+
+        /// Returns the code hash calls are run with.
+        fn logic(&self) -> Hash {
+            ink::env::get_contract_storage(&LOGIC_KEY)
+                .ok()
+                .flatten()
+                .unwrap_or_default()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// We test that only the admin swaps the logic and the admin, with
+        /// events, in the delegator's own cells.
+        #[ink::test]
+        fn admin_swaps_logic_and_admin() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let delegator = VotingDelegator::new(Hash::from([1; 32]));
+            let fixed = Hash::from([2; 32]);
+
+            assert_eq!(delegator.admin(AdminCommand::SetLogic(fixed)), Ok(()));
+            assert_eq!(delegator.logic(), fixed);
+            assert_eq!(
+                delegator.admin(AdminCommand::SetAdmin(accounts.bob)),
+                Ok(())
+            );
+            assert_eq!(
+                delegator.admin(AdminCommand::SetLogic(Hash::from([3; 32]))),
+                Err(DelegatorError::Unauthorized)
+            );
+            assert_eq!(ink::env::test::recorded_events().count(), 2);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                delegator.admin(AdminCommand::SetLogic(Hash::from([3; 32]))),
+                Ok(())
+            );
+            assert_eq!(delegator.logic(), Hash::from([3; 32]));
+        }
+    }
+}
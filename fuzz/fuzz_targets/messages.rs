@@ -0,0 +1,554 @@
+//! Decodes random SCALE bytes into a sequence of contract calls and drives
+//! them against a fresh off-chain contract.
+//!
+//! Every call must return, never panic, and the tallies must stay consistent
+//! whatever the input. Run it with `cargo fuzz run messages`.
+#![no_main]
+
+use ink::{env::DefaultEnvironment, primitives::AccountId, scale::Decode};
+use libfuzzer_sys::fuzz_target;
+use voting::{
+    queries::ElectionId,
+    voting::{AdminAction, BallotChoice, ElectionConfig, Ruling, SurveyId, Voting, VotingMode},
+};
+
+/// One contract call, with accounts chosen out of a small pool so that
+/// calls interact with each other.
+#[derive(Debug)]
+#[ink::scale_derive(Decode)]
+enum Action {
+    Caller(u8),
+    Value(u16),
+    AdvanceBlock,
+    CreateElection(ElectionConfig),
+    CloneElection(ElectionId),
+    OpenRegistration(ElectionId),
+    CloseRegistration(ElectionId),
+    AdvancePhase(ElectionId),
+    OpenVoting(ElectionId),
+    Finalize(ElectionId),
+    FinalizeStep(ElectionId, u8),
+    Cancel(ElectionId),
+    Expire(ElectionId),
+    ProcessRefunds(ElectionId, u8),
+    Gc(ElectionId, u8),
+    ProposeTally(ElectionId, Vec<(u8, u32)>),
+    ChallengeTally(ElectionId, u32),
+    PostKeyShare(ElectionId, Vec<u8>),
+    RevealBallots(ElectionId, u8),
+    Endorse(ElectionId, u8),
+    Refer(ElectionId, u8),
+    RegisterCandidate(ElectionId),
+    RegisterSlate(ElectionId, Vec<u8>),
+    UpdateProfile(ElectionId, Vec<u8>, [u8; 32]),
+    SetCategories(ElectionId, u8, Vec<u16>),
+    AddRace(ElectionId, ElectionId),
+    BanCandidate(ElectionId, u8),
+    AddVoters(ElectionId, Vec<u8>),
+    ImportCandidates(ElectionId, Vec<u8>),
+    RegisterVoter(ElectionId),
+    DeregisterVoter(ElectionId),
+    AddInvitationCodes(ElectionId, Vec<[u8; 32]>),
+    RegisterWithCode(ElectionId, Vec<u8>),
+    RemoveVoter(ElectionId, u8),
+    Vouch(ElectionId, u8),
+    RevokeVouch(ElectionId, u8),
+    BanVoter(ElectionId, u8),
+    SetIdentity(u8, Option<u64>),
+    SetIdentityMode(bool),
+    SetAggregator(u8, bool),
+    AdjustReputation(u8, i32),
+    Delegate(u8),
+    Undelegate,
+    Vote(ElectionId, u8),
+    VoteWithMemo(ElectionId, u8, Vec<u8>),
+    VoteWithReason(ElectionId, u8, [u8; 32]),
+    VoteSplit(ElectionId, Vec<(u8, u16)>),
+    VoteRanked(ElectionId, Vec<u8>),
+    VoteRaces(ElectionId, Vec<u8>),
+    VoteQuadratic(ElectionId, u8, u16),
+    VoteScored(ElectionId, Vec<(u8, u16)>),
+    VoteCumulative(ElectionId, Vec<(u8, u16)>),
+    VoteEncrypted(ElectionId, Vec<u8>),
+    VoteAnonymous(ElectionId, u8, Vec<u8>, [u8; 32]),
+    StartRecall(ElectionId),
+    SignRecall(ElectionId),
+    VoteRecall(ElectionId, bool),
+    CloseRecall(ElectionId),
+    CreateSurvey([u8; 32], Vec<Vec<u8>>),
+    AnswerSurvey(SurveyId, u32),
+    CloseSurvey(SurveyId),
+    RaiseDispute(ElectionId),
+    ResolveDispute(ElectionId, bool),
+    Disqualify(ElectionId, u8),
+    EnforceRuling(ElectionId),
+    PauseElection(ElectionId),
+    ResumeElection(ElectionId),
+    SignSlashPetition(ElectionId),
+    ReclaimAdminBond(ElectionId),
+    SetResolver(Option<u8>),
+    SetModerator(Option<u8>),
+    SetDisplayName(Vec<u8>),
+    ClearDisplayName,
+    RenounceOwnership,
+    SetTimelockDelay(u32),
+    /// Upgrades are left out: `set_code_hash` is not available off-chain.
+    ScheduleBan(ElectionId, u8),
+    ScheduleCreationFee(u128),
+    ExecuteAction(u32),
+    CancelAction(u32),
+    AssignRole(u8, Option<u32>),
+    SetPermission(u32, [u8; 4], bool),
+    AssignConstituency(ElectionId, u32, Vec<u8>),
+    DelegateTopic(u32, u8),
+    UndelegateTopic(u32),
+    DelegateSplit(Vec<(u8, u16)>),
+    UndelegateSplit,
+    RerunElection(ElectionId),
+    SetVerified(ElectionId, u8, bool),
+    FundPrize(ElectionId),
+    TipBounty(ElectionId),
+    FundLottery(ElectionId),
+    FundSponsorship(ElectionId),
+    ReclaimSponsorship(ElectionId),
+    FundCampaign(ElectionId, u8),
+    ReleaseCampaignFund(ElectionId, u8),
+    RefundCampaignPledge(ElectionId, u8),
+    CommitLotterySeed(ElectionId, [u8; 32]),
+    DrawLottery(ElectionId, [u8; 32]),
+    ClaimPrize(ElectionId),
+    MirrorOutcome(ElectionId),
+    Claim,
+    WithdrawSurplus(u8, u16),
+    PruneElection(ElectionId, Vec<u8>),
+}
+
+/// Inputs: the candidates passed to `new_with_candidates`, then the calls.
+#[derive(Debug)]
+#[ink::scale_derive(Decode)]
+struct Input {
+    candidates: Vec<u8>,
+    actions: Vec<Action>,
+}
+
+fn account(n: u8) -> AccountId {
+    AccountId::from([n % 8; 32])
+}
+
+fn run(input: Input) {
+    let candidates = input.candidates.into_iter().map(account).collect();
+    let mut voting = Voting::new_with_candidates(candidates);
+    ink::env::test::set_account_balance::<DefaultEnvironment>(
+        ink::env::test::callee::<DefaultEnvironment>(),
+        u128::MAX / 2,
+    );
+
+    // whether each election takes split ballots, counted in thousandths
+    let mut split = vec![false];
+    // whether the tally of each election may stray from its turnout, as when
+    // ballots carry more than one vote, a posted tally is adopted or the
+    // counts are hidden
+    let mut weighted = vec![false];
+    let mut delegated = false;
+    for action in input.actions {
+        match action {
+            Action::Caller(n) => ink::env::test::set_caller::<DefaultEnvironment>(account(n)),
+            Action::Value(value) => {
+                ink::env::test::set_value_transferred::<DefaultEnvironment>(value.into())
+            }
+            Action::AdvanceBlock => ink::env::test::advance_block::<DefaultEnvironment>(),
+            Action::CreateElection(mut config) => {
+                // no chain extension nor other contract is reachable off-chain
+                config.asset_weight = None;
+                config.fee_token = None;
+                config.mirror_outcome = false;
+                config.encrypted_ballots = None;
+                config.anonymous = false;
+                config.tally_module = None;
+                config.eligibility_source = None;
+                config.weight_source = None;
+                config.token_weight = None;
+                config.hook = None;
+                config.arbitrator = None;
+                let mode = config.mode;
+                let boosted = config.reputation_weighted
+                    || config.tenure_boost.is_some()
+                    || !config.attendance_boost.is_empty()
+                    || matches!(
+                        mode,
+                        VotingMode::Quadratic(_) | VotingMode::Score(_) | VotingMode::Cumulative(_)
+                    )
+                    || config.optimistic_tally.is_some()
+                    || config.blind_results;
+                if voting.create_election(config).is_ok() {
+                    split.push(mode == VotingMode::Split);
+                    weighted.push(boosted);
+                }
+            }
+            Action::CloneElection(id) => {
+                if voting.clone_election(id).is_ok() {
+                    split.push(split[id as usize]);
+                    weighted.push(weighted[id as usize]);
+                }
+            }
+            Action::OpenRegistration(id) => {
+                let _ = voting.open_registration(id);
+            }
+            Action::CloseRegistration(id) => {
+                let _ = voting.close_registration(id);
+            }
+            Action::AdvancePhase(id) => {
+                let _ = voting.advance_phase(id);
+            }
+            Action::OpenVoting(id) => {
+                let _ = voting.open_voting(id);
+            }
+            Action::Finalize(id) => {
+                let _ = voting.finalize(id);
+            }
+            Action::FinalizeStep(id, batch) => {
+                let _ = voting.finalize_step(id, batch.into());
+            }
+            Action::Cancel(id) => {
+                let _ = voting.cancel(id);
+            }
+            Action::Expire(id) => {
+                let _ = voting.expire(id);
+            }
+            Action::ProcessRefunds(id, max_entries) => {
+                let _ = voting.process_refunds(id, max_entries.into());
+            }
+            Action::Gc(id, max_entries) => {
+                let _ = voting.gc(id, max_entries.into());
+            }
+            Action::ProposeTally(id, totals) => {
+                let totals = totals
+                    .into_iter()
+                    .map(|(n, votes)| (account(n), votes))
+                    .collect();
+                let _ = voting.propose_tally(id, totals);
+            }
+            Action::ChallengeTally(id, index) => {
+                let _ = voting.challenge_tally(id, index);
+            }
+            Action::PostKeyShare(id, share) => {
+                let _ = voting.post_key_share(id, share);
+            }
+            Action::RevealBallots(id, batch) => {
+                let _ = voting.reveal_ballots(id, batch.into());
+            }
+            Action::Endorse(id, n) => {
+                let _ = voting.endorse(id, account(n));
+            }
+            Action::Refer(id, n) => {
+                let _ = voting.refer(id, account(n));
+            }
+            Action::RegisterCandidate(id) => {
+                let _ = voting.register_candidate(id);
+            }
+            Action::RegisterSlate(id, members) => {
+                let _ = voting.register_slate(id, members.into_iter().map(account).collect());
+            }
+            Action::UpdateProfile(id, name, manifesto) => {
+                let _ = voting.update_profile(id, name, manifesto.into());
+            }
+            Action::SetCategories(id, n, categories) => {
+                let _ = voting.set_categories(id, account(n), categories);
+            }
+            Action::AddRace(id, race_id) => {
+                let _ = voting.add_race(id, race_id);
+            }
+            Action::BanCandidate(id, n) => {
+                let _ = voting.ban_candidate(id, account(n));
+            }
+            Action::AddVoters(id, voters) => {
+                let _ = voting.add_voters(id, voters.into_iter().map(account).collect());
+            }
+            Action::ImportCandidates(id, candidates) => {
+                let _ = voting.import_candidates(id, candidates.into_iter().map(account).collect());
+            }
+            Action::RegisterVoter(id) => {
+                let _ = voting.register_voter(id);
+            }
+            Action::DeregisterVoter(id) => {
+                let _ = voting.deregister_voter(id);
+            }
+            Action::AddInvitationCodes(id, hashes) => {
+                let _ =
+                    voting.add_invitation_codes(id, hashes.into_iter().map(Into::into).collect());
+            }
+            Action::RegisterWithCode(id, code) => {
+                let _ = voting.register_with_code(id, code);
+            }
+            Action::RemoveVoter(id, n) => {
+                let _ = voting.remove_voter(id, account(n));
+            }
+            Action::Vouch(id, n) => {
+                let _ = voting.vouch(id, account(n));
+            }
+            Action::RevokeVouch(id, n) => {
+                let _ = voting.revoke_vouch(id, account(n));
+            }
+            Action::BanVoter(id, n) => {
+                let _ = voting.ban_voter(id, account(n));
+            }
+            Action::SetIdentity(n, identity) => {
+                let _ = voting.set_identity(account(n), identity);
+            }
+            Action::SetIdentityMode(enabled) => {
+                let _ = voting.set_identity_mode(enabled);
+            }
+            Action::SetAggregator(n, approved) => {
+                let _ = voting.set_aggregator(account(n), approved);
+            }
+            Action::AdjustReputation(n, delta) => {
+                let _ = voting.adjust_reputation(account(n), delta);
+            }
+            Action::Delegate(n) => {
+                delegated |= voting.delegate(account(n)).is_ok();
+            }
+            Action::Undelegate => {
+                let _ = voting.undelegate();
+            }
+            Action::Vote(id, n) => {
+                let _ = voting.vote(id, account(n));
+            }
+            Action::VoteWithMemo(id, n, memo) => {
+                let _ = voting.vote_with_memo(id, account(n), memo);
+            }
+            Action::VoteWithReason(id, n, reason) => {
+                let _ = voting.vote_with_reason(id, account(n), reason.into());
+            }
+            Action::VoteSplit(id, allocations) => {
+                let allocations = allocations
+                    .into_iter()
+                    .map(|(n, share)| (account(n), share))
+                    .collect();
+                let _ = voting.vote_split(id, allocations);
+            }
+            Action::VoteRanked(id, ranking) => {
+                let _ = voting.vote_ranked(id, ranking.into_iter().map(account).collect());
+            }
+            Action::VoteRaces(id, choices) => {
+                let _ = voting.vote_races(id, choices.into_iter().map(account).collect());
+            }
+            Action::VoteQuadratic(id, n, votes) => {
+                let _ = voting.vote_quadratic(id, account(n), votes);
+            }
+            Action::VoteScored(id, scores) => {
+                let scores = scores.into_iter().map(|(n, score)| (account(n), score));
+                let _ = voting.vote_ballot(id, BallotChoice::Scored(scores.collect()));
+            }
+            Action::VoteCumulative(id, votes) => {
+                let votes = votes.into_iter().map(|(n, votes)| (account(n), votes));
+                let _ = voting.vote_ballot(id, BallotChoice::Cumulative(votes.collect()));
+            }
+            Action::VoteEncrypted(id, ciphertext) => {
+                let _ = voting.vote_encrypted(id, ciphertext);
+            }
+            Action::VoteAnonymous(id, n, ring, key_image) => {
+                let ring = ring.into_iter().map(account).collect();
+                let _ = voting.vote_anonymous(id, account(n), ring, key_image.into(), Vec::new());
+            }
+            Action::StartRecall(id) => {
+                let _ = voting.start_recall(id);
+            }
+            Action::SignRecall(id) => {
+                let _ = voting.sign_recall(id);
+            }
+            Action::VoteRecall(id, remove) => {
+                let _ = voting.vote_recall(id, remove);
+            }
+            Action::CloseRecall(id) => {
+                let _ = voting.close_recall(id);
+            }
+            Action::CreateSurvey(question, options) => {
+                let _ = voting.create_survey(question.into(), options);
+            }
+            Action::AnswerSurvey(id, option) => {
+                let _ = voting.answer_survey(id, option);
+            }
+            Action::CloseSurvey(id) => {
+                let _ = voting.close_survey(id);
+            }
+            Action::RaiseDispute(id) => {
+                let _ = voting.raise_dispute(id);
+            }
+            Action::ResolveDispute(id, upheld) => {
+                let ruling = if upheld {
+                    Ruling::Upheld
+                } else {
+                    Ruling::Dismissed
+                };
+                let _ = voting.resolve_dispute(id, ruling);
+            }
+            Action::Disqualify(id, candidate) => {
+                let _ = voting.resolve_dispute(id, Ruling::Disqualified(account(candidate)));
+            }
+            Action::EnforceRuling(id) => {
+                let _ = voting.enforce_ruling(id);
+            }
+            Action::PauseElection(id) => {
+                let _ = voting.pause_election(id);
+            }
+            Action::ResumeElection(id) => {
+                let _ = voting.resume_election(id);
+            }
+            Action::SignSlashPetition(id) => {
+                let _ = voting.sign_slash_petition(id);
+            }
+            Action::ReclaimAdminBond(id) => {
+                let _ = voting.reclaim_admin_bond(id);
+            }
+            Action::SetResolver(n) => {
+                let _ = voting.set_resolver(n.map(account));
+            }
+            Action::SetModerator(n) => {
+                let _ = voting.set_moderator(n.map(account));
+            }
+            Action::SetDisplayName(name) => {
+                let _ = voting.set_display_name(name);
+            }
+            Action::ClearDisplayName => voting.clear_display_name(),
+            Action::RenounceOwnership => {
+                let _ = voting.renounce_ownership();
+            }
+            Action::SetTimelockDelay(delay) => {
+                let _ = voting.set_timelock_delay(delay);
+            }
+            Action::ScheduleBan(id, n) => {
+                let _ = voting.schedule_action(AdminAction::BanCandidate(id, account(n)));
+            }
+            Action::ScheduleCreationFee(fee) => {
+                let _ = voting.schedule_action(AdminAction::CreationFee(fee));
+            }
+            Action::ExecuteAction(action_id) => {
+                let _ = voting.execute_action(action_id);
+            }
+            Action::CancelAction(action_id) => {
+                let _ = voting.cancel_action(action_id);
+            }
+            Action::AssignRole(n, role) => {
+                let _ = voting.assign_role(account(n), role);
+            }
+            Action::SetPermission(role, selector, granted) => {
+                let _ = voting.set_permission(role, selector, granted);
+            }
+            Action::DelegateTopic(topic, n) => {
+                delegated |= voting.delegate_topic(topic, account(n)).is_ok();
+            }
+            Action::UndelegateTopic(topic) => {
+                let _ = voting.undelegate_topic(topic);
+            }
+            Action::DelegateSplit(shares) => {
+                let shares = shares
+                    .into_iter()
+                    .map(|(n, share)| (account(n), share))
+                    .collect();
+                delegated |= voting.delegate_split(shares).is_ok();
+            }
+            Action::UndelegateSplit => {
+                let _ = voting.undelegate_split();
+            }
+            Action::RerunElection(id) => {
+                if voting.rerun_election(id).is_ok() {
+                    split.push(split[id as usize]);
+                    weighted.push(weighted[id as usize]);
+                }
+            }
+            Action::AssignConstituency(id, constituency, voters) => {
+                let _ = voting.assign_constituency(
+                    id,
+                    constituency,
+                    voters.into_iter().map(account).collect(),
+                );
+            }
+            Action::SetVerified(id, n, verified) => {
+                let _ = voting.set_verified(id, account(n), verified);
+            }
+            Action::FundPrize(id) => {
+                let _ = voting.fund_prize(id);
+            }
+            Action::TipBounty(id) => {
+                let _ = voting.tip_bounty(id);
+            }
+            Action::FundCampaign(id, n) => {
+                let _ = voting.fund_campaign(id, account(n));
+            }
+            Action::ReleaseCampaignFund(id, n) => {
+                let _ = voting.release_campaign_fund(id, account(n));
+            }
+            Action::RefundCampaignPledge(id, n) => {
+                let _ = voting.refund_campaign_pledge(id, account(n));
+            }
+            Action::FundLottery(id) => {
+                let _ = voting.fund_lottery(id);
+            }
+            Action::FundSponsorship(id) => {
+                let _ = voting.fund_sponsorship(id);
+            }
+            Action::ReclaimSponsorship(id) => {
+                let _ = voting.reclaim_sponsorship(id);
+            }
+            Action::CommitLotterySeed(id, commitment) => {
+                let _ = voting.commit_lottery_seed(id, commitment.into());
+            }
+            Action::DrawLottery(id, seed) => {
+                let _ = voting.draw_lottery(id, seed.into());
+            }
+            Action::ClaimPrize(id) => {
+                let _ = voting.claim_prize(id);
+            }
+            Action::MirrorOutcome(id) => {
+                let _ = voting.mirror_outcome(id);
+            }
+            Action::Claim => {
+                let _ = voting.claim();
+            }
+            Action::WithdrawSurplus(n, amount) => {
+                let _ = voting.withdraw_surplus(account(n), amount.into());
+            }
+            Action::PruneElection(id, voters) => {
+                let _ = voting.prune_election(id, voters.into_iter().map(account).collect());
+            }
+        }
+
+        for (id, &split) in split.iter().enumerate() {
+            let Some(results) = voting.get_results(id as ElectionId) else {
+                continue;
+            };
+            let counted: u32 = results.totals.iter().map(|(_, votes)| votes).sum();
+            if !delegated && !weighted[id] {
+                if split {
+                    assert!(
+                        counted <= results.turnout.saturating_mul(1_000),
+                        "tally of election {id} drifted"
+                    );
+                } else {
+                    assert_eq!(counted, results.turnout, "tally of election {id} drifted");
+                }
+            }
+            for winner in &results.winners {
+                assert!(results.totals.iter().any(|(runner, _)| runner == winner));
+            }
+            let electorate_of = voting
+                .get_rerun_of(id as ElectionId)
+                .unwrap_or(id as ElectionId);
+            let electorate = voting.get_electorate_size(electorate_of);
+            // one page holds at most 100 voters
+            let exported = voting.export_voters(id as ElectionId, 0, u32::MAX);
+            assert_eq!(exported.len() as u32, electorate.min(100));
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = Input::decode(&mut &data[..]) else {
+        return;
+    };
+    ink::env::test::run_test::<DefaultEnvironment, _>(|_| {
+        run(input);
+        Ok(())
+    })
+    .unwrap();
+});
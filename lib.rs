@@ -1,139 +1,19248 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
-#[ink::contract]
-mod voting {
-    use core::cmp::Ordering;
-    use ink::{prelude::vec::Vec, storage::Mapping};
+extern crate alloc;
 
-    /// Defines the storage of your contract.
-    /// Add new fields to the below struct in order
-    /// to add new static storage fields to your contract.
-    #[ink(storage)]
-    pub struct Voting {
-        runners: ink::prelude::vec::Vec<AccountId>,
-        votes: ink::storage::Mapping<AccountId, u32>,
-        already_voted: ink::storage::Mapping<AccountId, bool>,
+/// Read-only view of elections for other contracts, using only primitive
+/// types. Its selectors are fixed, so they survive renames and upgrades.
+pub mod queries {
+    use ink::{prelude::vec::Vec, primitives::AccountId};
+
+    /// Identifier of an election hosted by the contract.
+    pub type ElectionId = u32;
+
+    /// Lifecycle of an election.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum ElectionStatus {
+        /// Created, but neither registration nor voting has started.
+        Pending,
+        /// Candidates may register.
+        Registration,
+        /// Ballots are being accepted.
+        Voting,
+        /// Ballots are closed and being revealed before the count.
+        RevealPhase,
+        /// Ballots are closed and being counted over several `finalize_step` calls.
+        Tallying,
+        /// The outcome is final and no more ballots are accepted.
+        Finalized,
+        /// The election was called off and has no outcome.
+        Cancelled,
+        /// Registration has closed and the candidate list is final, published
+        /// for the grace period before voting opens.
+        SlateFrozen,
+        /// Candidates campaign with statements and supporters endorse them;
+        /// no ballots are accepted yet.
+        Campaign,
+        /// The election was not finalized in time after `voting_closes_at`
+        /// and has no outcome; its funds are refunded as for a cancelled one.
+        Expired,
     }
 
-    #[derive(Debug, PartialEq, Eq)]
+    #[ink::trait_definition]
+    pub trait VotingQueries {
+        /// Returns the current winner(s) of an election.
+        #[ink(message, selector = 0x5654_0001)]
+        fn winner(&self, election_id: ElectionId) -> Vec<AccountId>;
+
+        /// Returns the current phase of an election.
+        #[ink(message, selector = 0x5654_0002)]
+        fn status(&self, election_id: ElectionId) -> Option<ElectionStatus>;
+
+        /// Returns the votes a candidate received in an election.
+        #[ink(message, selector = 0x5654_0003)]
+        fn votes_of(&self, election_id: ElectionId, candidate: AccountId) -> u32;
+    }
+}
+
+/// A voting interface shared by every deployment of this crate and its
+/// forks, so that aggregators can drive any of them alike. It uses only
+/// primitive types, reports errors as their stable `code`, and its
+/// selectors are fixed.
+///
+/// The set is versioned by `STANDARD_VERSION`: a selector is never
+/// reassigned or given another signature, and a later version only adds
+/// messages under new selectors.
+pub mod standard {
+    use crate::queries::{ElectionId, ElectionStatus};
+    use ink::{prelude::vec::Vec, primitives::AccountId};
+
+    /// Version of the selector set implemented.
+    pub const STANDARD_VERSION: u16 = 1;
+
+    #[ink::trait_definition]
+    pub trait VotingStandard {
+        /// Returns the version of the standard implemented.
+        #[ink(message, selector = 0x5653_0000)]
+        fn standard_version(&self) -> u16;
+
+        /// Casts the caller's ballot for `candidate`, carrying any fee the
+        /// election charges. Returns the id of the ballot.
+        #[ink(message, payable, selector = 0x5653_0001)]
+        fn cast_vote(&mut self, election_id: ElectionId, candidate: AccountId) -> Result<u64, u32>;
+
+        /// Returns the votes of every candidate of an election and its
+        /// winners, final once the election is.
+        #[ink(message, selector = 0x5653_0002)]
+        fn results(
+            &self,
+            election_id: ElectionId,
+        ) -> Option<(Vec<(AccountId, u32)>, Vec<AccountId>)>;
+
+        /// Returns the current phase of an election.
+        #[ink(message, selector = 0x5653_0003)]
+        fn election_status(&self, election_id: ElectionId) -> Option<ElectionStatus>;
+
+        /// Delegates the caller's vote to `delegate`.
+        #[ink(message, selector = 0x5653_0004)]
+        fn delegate_vote(&mut self, delegate: AccountId) -> Result<(), u32>;
+    }
+}
+
+/// Bits of the bitmask returned by `Voting::capabilities`, one per optional
+/// feature. Bits are never reassigned; a feature dropped later clears its bit.
+pub mod capabilities {
+    /// Several elections hosted side by side, addressed by `ElectionId`.
+    pub const MULTI_ELECTION: u64 = 1 << 0;
+    /// `VotingMode::Approval` ballots.
+    pub const APPROVAL: u64 = 1 << 1;
+    /// `VotingMode::Split` ballots, through `vote_split`.
+    pub const SPLIT: u64 = 1 << 2;
+    /// `VotingMode::SingleTransferable` ballots, through `vote_ranked`.
+    pub const SINGLE_TRANSFERABLE: u64 = 1 << 3;
+    /// Committees elected from party slates.
+    pub const COMMITTEE: u64 = 1 << 4;
+    /// `Outcome::Sortition` elections.
+    pub const SORTITION: u64 = 1 << 5;
+    /// `delegate` and `undelegate`.
+    pub const DELEGATION: u64 = 1 << 6;
+    /// Recalls of a seated winner.
+    pub const RECALL: u64 = 1 << 7;
+    /// Challenge periods and disputes of finalized results.
+    pub const DISPUTES: u64 = 1 << 8;
+    /// Prize pools and the `claim` ledger.
+    pub const PRIZES: u64 = 1 << 9;
+    /// Voter lotteries drawn from a committed seed.
+    pub const LOTTERY: u64 = 1 << 10;
+    /// Reputation-weighted ballots.
+    pub const REPUTATION: u64 = 1 << 11;
+    /// Participation streaks.
+    pub const PARTICIPATION: u64 = 1 << 12;
+    /// Vote fees and candidacy deposits paid in a PSP22 token.
+    pub const FEE_TOKENS: u64 = 1 << 13;
+    /// Ballots relayed by a trusted bridge, through `relay_vote`.
+    pub const RELAYED_BALLOTS: u64 = 1 << 14;
+    /// Deduplication of ballots per identity.
+    pub const IDENTITY: u64 = 1 << 15;
+    /// Ballots weighted by a `pallet-assets` holding.
+    pub const ASSET_WEIGHT: u64 = 1 << 16;
+    /// Code upgrades gated by a referendum.
+    pub const GOVERNED_UPGRADES: u64 = 1 << 17;
+    /// Archived results with Merkle proofs of inclusion for ballots.
+    pub const BALLOT_PROOFS: u64 = 1 << 18;
+    /// Elections rejecting ballots from contracts other than approved
+    /// aggregators.
+    pub const CONTRACT_FILTER: u64 = 1 << 19;
+    /// Ballots weighted by the voter's registration tenure.
+    pub const TENURE_BOOST: u64 = 1 << 20;
+    /// `VotingMode::Quadratic` ballots, through `vote_quadratic`.
+    pub const QUADRATIC: u64 = 1 << 21;
+    /// Outcomes noted with the runtime's governance.
+    pub const MIRRORED_OUTCOMES: u64 = 1 << 22;
+    /// Ballots signed off-chain and settled in batches.
+    pub const SIGNED_BALLOTS: u64 = 1 << 23;
+    /// Tallies posted by a bonded proposer, open to fraud proofs.
+    pub const OPTIMISTIC_TALLY: u64 = 1 << 24;
+    /// Ballots encrypted to an election key and revealed by a decryption
+    /// committee.
+    pub const ENCRYPTED_BALLOTS: u64 = 1 << 25;
+    /// Ballots carrying a linkable ring signature over the electorate.
+    pub const ANONYMOUS_BALLOTS: u64 = 1 << 26;
+    /// Campaign funds escrowed for candidates by their supporters.
+    pub const CAMPAIGN_FUNDS: u64 = 1 << 27;
+    /// Elections holding several races, voted on with one ballot.
+    pub const MULTI_RACE: u64 = 1 << 28;
+    /// Signaling surveys counting options rather than candidates.
+    pub const SURVEYS: u64 = 1 << 29;
+    /// `VotingMode::Score` ballots, through `vote_ballot`.
+    pub const SCORE: u64 = 1 << 30;
+    /// `VotingMode::Cumulative` ballots, through `vote_ballot`.
+    pub const CUMULATIVE: u64 = 1 << 31;
+    /// Ballots counted by an external tally contract.
+    pub const TALLY_MODULES: u64 = 1 << 32;
+    /// Voters vetted by an external eligibility source.
+    pub const ELIGIBILITY_SOURCES: u64 = 1 << 33;
+    /// Ballot weights supplied by an external weight calculator.
+    pub const WEIGHT_CALCULATORS: u64 = 1 << 34;
+    /// Votes and outcomes pushed to a subscribed hook contract.
+    pub const HOOKS: u64 = 1 << 35;
+    /// Administrative actions delayed by a timelock.
+    pub const TIMELOCK: u64 = 1 << 36;
+    /// Election-admin messages delegated to roles, message by message.
+    pub const PERMISSIONS: u64 = 1 << 37;
+    /// `ElectionConfig::ballots_per_block`.
+    pub const RATE_LIMITS: u64 = 1 << 38;
+    /// `ElectionConfig::registrations_per_block`, `registration_cooldown`
+    /// and `profile_cooldown`.
+    pub const REGISTRATION_LIMITS: u64 = 1 << 39;
+    /// `ElectionConfig::forbid_self_votes`.
+    pub const NO_SELF_VOTES: u64 = 1 << 40;
+    /// `ElectionConfig::candidates_barred`.
+    pub const BARRED_CANDIDATES: u64 = 1 << 41;
+    /// Per-constituency tallies, through `assign_constituency`.
+    pub const CONSTITUENCIES: u64 = 1 << 42;
+    /// Elections won on constituency electors, `ElectionConfig::electors`.
+    pub const ELECTORAL_COLLEGE: u64 = 1 << 43;
+    /// Delegations scoped to an election topic, `delegate_topic`.
+    pub const TOPIC_DELEGATION: u64 = 1 << 44;
+    /// Runners kept sorted by votes, `ElectionConfig::track_standings`.
+    pub const STANDINGS: u64 = 1 << 45;
+    /// Counts hidden until voting closes, `ElectionConfig::blind_results`.
+    pub const BLIND_RESULTS: u64 = 1 << 46;
+    /// Re-runs bound to the original electorate, through `rerun_election`.
+    pub const RERUNS: u64 = 1 << 47;
+    /// Paginated electorate and candidate exports, loaded into a successor
+    /// through `add_voters` and `import_candidates`.
+    pub const ELECTORATE_EXPORT: u64 = 1 << 48;
+    /// Deployments taking over an election, `Voting::new_from_snapshot`.
+    pub const SNAPSHOT_IMPORT: u64 = 1 << 49;
+    /// Value sent without a purpose handled by policy, `Voting::receive`.
+    pub const FUNDS_POLICY: u64 = 1 << 50;
+    /// Tips on the finalization bounty, `Voting::tip_bounty`.
+    pub const BOUNTY_TIPS: u64 = 1 << 51;
+    /// Cross-election participation scores, `Voting::participation_of`.
+    pub const PARTICIPATION_SCORE: u64 = 1 << 52;
+    /// Frozen candidate lists ahead of voting, `Voting::close_registration`.
+    pub const SLATE_FREEZE: u64 = 1 << 53;
+    /// Campaign phases with candidate statements, `Voting::post_statement`.
+    pub const CAMPAIGN_PHASE: u64 = 1 << 54;
+    /// Ballot weight boosted by past attendance.
+    pub const ATTENDANCE_BOOST: u64 = 1 << 55;
+    /// Voters joining a closed electorate with a single-use invitation
+    /// code, `Voting::register_with_code`.
+    pub const INVITATION_CODES: u64 = 1 << 56;
+    /// Candidacies gated behind a referral from the admin or a verified
+    /// candidate, `Voting::refer`.
+    pub const REFERRALS: u64 = 1 << 57;
+    /// Unique display names for participants, `Voting::set_display_name`.
+    pub const DISPLAY_NAMES: u64 = 1 << 58;
+    /// Names resolved through an external name service, `Voting::resolve_name`.
+    pub const NAME_SERVICE: u64 = 1 << 59;
+    /// Votes delegated in shares to several delegates,
+    /// `Voting::delegate_split`.
+    pub const SPLIT_DELEGATION: u64 = 1 << 60;
+    /// Ballot events naming no voter, `ElectionConfig::quiet_events`.
+    pub const QUIET_EVENTS: u64 = 1 << 61;
+    /// The treasury held by a treasury contract, `Voting::sweep_treasury`.
+    pub const TREASURY_SWEEP: u64 = 1 << 62;
+}
+
+/// Interfaces `Voting::supports` answers for, each given as the selectors of
+/// its messages. An interface is identified by the XOR of its selectors.
+pub mod interfaces {
+    use ink::selector_id;
+
+    /// The `VotingQueries` trait.
+    pub const VOTING_QUERIES: &[u32] = &[0x5654_0001, 0x5654_0002, 0x5654_0003];
+
+    /// The `VotingStandard` trait, as of `STANDARD_VERSION`.
+    pub const VOTING_STANDARD: &[u32] = &[
+        0x5653_0000,
+        0x5653_0001,
+        0x5653_0002,
+        0x5653_0003,
+        0x5653_0004,
+    ];
+
+    /// Version and feature introspection.
+    pub const INTROSPECTION: &[u32] = &[
+        selector_id!("version"),
+        selector_id!("capabilities"),
+        selector_id!("supports"),
+    ];
+
+    /// Vote delegation.
+    pub const DELEGATION: &[u32] = &[
+        selector_id!("delegate"),
+        selector_id!("undelegate"),
+        selector_id!("get_delegate"),
+        selector_id!("get_delegators"),
+        selector_id!("effective_weight"),
+    ];
+
+    /// Vote delegation scoped to an election topic.
+    pub const TOPIC_DELEGATION: &[u32] = &[
+        selector_id!("delegate_topic"),
+        selector_id!("undelegate_topic"),
+        selector_id!("get_topic_delegate"),
+        selector_id!("get_topic_delegators"),
+    ];
+
+    /// Vote delegation in shares to several delegates.
+    pub const SPLIT_DELEGATION: &[u32] = &[
+        selector_id!("delegate_split"),
+        selector_id!("undelegate_split"),
+        selector_id!("get_split_delegation"),
+        selector_id!("get_split_delegators"),
+    ];
+
+    /// Referendums gating code upgrades.
+    pub const REFERENDUM: &[u32] = &[
+        selector_id!("govern_upgrades"),
+        selector_id!("are_upgrades_governed"),
+        selector_id!("propose_upgrade"),
+        selector_id!("vote_referendum"),
+        selector_id!("close_referendum"),
+        selector_id!("get_referendum"),
+        selector_id!("upgrade"),
+    ];
+
+    /// Removal of a seated winner by recall.
+    pub const RECALL: &[u32] = &[
+        selector_id!("start_recall"),
+        selector_id!("sign_recall"),
+        selector_id!("vote_recall"),
+        selector_id!("close_recall"),
+        selector_id!("get_recall"),
+        selector_id!("is_seat_vacated"),
+    ];
+
+    /// Disputes of finalized results.
+    pub const DISPUTES: &[u32] = &[
+        selector_id!("raise_dispute"),
+        selector_id!("resolve_dispute"),
+        selector_id!("get_dispute"),
+        selector_id!("get_open_disputes"),
+        selector_id!("is_result_effective"),
+    ];
+
+    /// Signaling surveys.
+    pub const SURVEYS: &[u32] = &[
+        selector_id!("create_survey"),
+        selector_id!("answer_survey"),
+        selector_id!("close_survey"),
+        selector_id!("get_survey"),
+        selector_id!("get_survey_distribution"),
+    ];
+
+    /// The pull-based payout ledger.
+    pub const CLAIMS: &[u32] = &[
+        selector_id!("get_claimable"),
+        selector_id!("claim"),
+        selector_id!("get_token_claimable"),
+        selector_id!("claim_tokens"),
+    ];
+
+    /// Every interface above.
+    pub const ALL: &[&[u32]] = &[
+        VOTING_QUERIES,
+        VOTING_STANDARD,
+        INTROSPECTION,
+        DELEGATION,
+        TOPIC_DELEGATION,
+        SPLIT_DELEGATION,
+        REFERENDUM,
+        RECALL,
+        DISPUTES,
+        SURVEYS,
+        CLAIMS,
+    ];
+
+    /// Returns the identifier of an interface.
+    pub const fn id(selectors: &[u32]) -> u32 {
+        let mut id = 0;
+        let mut i = 0;
+        while i < selectors.len() {
+            id ^= selectors[i];
+            i += 1;
+        }
+        id
+    }
+}
+
+/// The transfers of the PSP22 fungible token standard, under the standard's
+/// selectors, for elections whose fees are paid in a token.
+pub mod psp22 {
+    use ink::{
+        env::{DefaultEnvironment, Environment},
+        prelude::{string::String, vec::Vec},
+        primitives::AccountId,
+    };
+
+    type Balance = <DefaultEnvironment as Environment>::Balance;
+    type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+    /// Reasons a PSP22 transfer is refused.
+    #[derive(Debug, Clone, PartialEq, Eq)]
     #[ink::scale_derive(Encode, Decode, TypeInfo)]
-    pub enum VoteError {
-        AlreadyVoted,
-        VoteOverflow,
+    pub enum Psp22Error {
+        Custom(String),
+        InsufficientBalance,
+        InsufficientAllowance,
+        ZeroRecipientAddress,
+        ZeroSenderAddress,
+        SafeTransferCheckFailed(String),
     }
 
-    impl Default for Voting {
-        fn default() -> Self {
-            Voting::new()
+    #[ink::trait_definition]
+    pub trait Psp22 {
+        /// Moves `value` of the caller's tokens to `to`.
+        #[ink(message, selector = 0xdb20_f9f5)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Psp22Error>;
+
+        /// Moves `value` of `from`'s tokens to `to`, out of the allowance
+        /// `from` granted the caller.
+        #[ink(message, selector = 0x54b3_c76e)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Psp22Error>;
+    }
+
+    /// The balance history of a checkpointed token, as kept by Compound- or
+    /// OpenBrush-style governance tokens. The selector is the one ink!
+    /// derives for a `balance_of_at` message, so that such tokens answer it
+    /// as they are.
+    #[ink::trait_definition]
+    pub trait Psp22Checkpoints {
+        /// Returns the balance `owner` held as of `block`.
+        #[ink(message, selector = 0x5a2f_8344)]
+        fn balance_of_at(&self, owner: AccountId, block: BlockNumber) -> Balance;
+    }
+}
+
+/// The interface of an external tally contract, which counts the ballots of
+/// the elections configured with it. The voting contract still checks who
+/// may vote, that nobody votes twice and that fees are paid, and forwards
+/// every ballot it accepts. Its selectors are fixed, so that tally contracts
+/// written against it keep working across upgrades of either side.
+pub mod tally {
+    use crate::{queries::ElectionId, voting::BallotChoice};
+    use ink::{prelude::vec::Vec, primitives::AccountId};
+
+    #[ink::trait_definition]
+    pub trait TallyModule {
+        /// Counts an accepted ballot of `voter`, worth `weight` votes.
+        /// Returns whether the module took it; a refused ballot is not cast.
+        #[ink(message, selector = 0x544c_0001)]
+        fn ingest_ballot(
+            &mut self,
+            election_id: ElectionId,
+            voter: AccountId,
+            ballot: BallotChoice,
+            weight: u32,
+        ) -> bool;
+
+        /// Returns the final votes of each of `runners`, in the same order.
+        #[ink(message, selector = 0x544c_0002)]
+        fn compute_result(&self, election_id: ElectionId, runners: Vec<AccountId>) -> Vec<u32>;
+    }
+}
+
+/// The counting algorithms of the contract as plain functions over runner
+/// totals, free of ink! so that off-chain verifiers can run the very code the
+/// contract runs against exported ballots and totals. Runners are any
+/// copyable identifier; the contract counts `AccountId`s.
+pub mod counting {
+    use alloc::vec::Vec;
+    use core::cmp::Ordering;
+
+    /// Votes a runner holds or a ballot carries. Tally code goes through
+    /// `VoteArithmetic` rather than assuming a width, so that counting in
+    /// another unsigned type only takes changing this alias.
+    pub type VoteWeight = u32;
+
+    /// The arithmetic tally code needs of a vote count.
+    pub trait VoteArithmetic: Copy + Ord {
+        /// No votes.
+        const ZERO: Self;
+
+        /// Adds `other`, or returns `None` on overflow.
+        fn checked_add(self, other: Self) -> Option<Self>;
+
+        /// Adds `other`, stopping at the largest count.
+        fn saturating_add(self, other: Self) -> Self;
+    }
+
+    macro_rules! vote_arithmetic {
+        ($($weight:ty),*) => {$(
+            impl VoteArithmetic for $weight {
+                const ZERO: Self = 0;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$weight>::checked_add(self, other)
+                }
+
+                fn saturating_add(self, other: Self) -> Self {
+                    <$weight>::saturating_add(self, other)
+                }
+            }
+        )*};
+    }
+
+    vote_arithmetic!(u32, u64, u128);
+
+    /// Adds up weighted single-candidate ballots into totals, one entry per
+    /// runner in order of first ballot, as the contract books plurality and
+    /// approval ballots.
+    pub fn totals<R: Copy + PartialEq>(
+        ballots: impl IntoIterator<Item = (R, VoteWeight)>,
+    ) -> Vec<(R, VoteWeight)> {
+        let mut totals: Vec<(R, VoteWeight)> = Vec::new();
+        for (candidate, weight) in ballots {
+            match totals.iter_mut().find(|(runner, _)| *runner == candidate) {
+                Some((_, votes)) => *votes = VoteArithmetic::saturating_add(*votes, weight),
+                None => totals.push((candidate, weight)),
+            }
         }
+        totals
     }
 
-    impl Voting {
-        /// Constructor that initializes the `bool` value to the given `init_value`.
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            let votes = Mapping::default();
-            let already_voted = Mapping::default();
-            let runners = Vec::new();
-            Self {
-                votes,
-                already_voted,
-                runners,
+    /// Returns the runners with the highest vote count, ignoring `excluded`,
+    /// together with that vote count.
+    pub fn leaders<R: Copy + PartialEq>(
+        totals: &[(R, VoteWeight)],
+        excluded: &[R],
+    ) -> (Vec<R>, VoteWeight) {
+        let mut current_winners = Vec::new();
+        let mut highest_votes = VoteWeight::ZERO;
+        for (runner, votes) in totals.iter().filter(|(r, _)| !excluded.contains(r)) {
+            match votes.cmp(&highest_votes) {
+                Ordering::Greater => {
+                    highest_votes = *votes;
+                    current_winners.clear();
+                    current_winners.push(*runner)
+                }
+                Ordering::Equal => current_winners.push(*runner),
+                Ordering::Less => {}
             }
         }
+        (current_winners, highest_votes)
+    }
 
-        /// Constructor that initializes the `bool` value to `false`.
-        ///
-        /// Constructors can delegate to other constructors.
-        #[ink(constructor)]
-        pub fn default() -> Self {
-            Self::new()
+    /// Returns the winners and their vote count. A tie for first place is
+    /// shared, or won by the earliest runner if `earliest_runner` is set.
+    pub fn winners<R: Copy + PartialEq>(
+        totals: &[(R, VoteWeight)],
+        earliest_runner: bool,
+    ) -> (Vec<R>, VoteWeight) {
+        let (mut winners, votes) = leaders(totals, &[]);
+        if earliest_runner {
+            winners.truncate(1);
         }
+        (winners, votes)
+    }
 
-        #[ink(message)]
-        pub fn get_votes(&self, address: AccountId) -> u32 {
-            self.votes.get(address).unwrap_or_default()
+    /// Puts `winners` in canonical order: most votes in `totals` first, and
+    /// runners with as many votes in ascending order, so that a winner set
+    /// reads the same on every node and client whatever order it was found
+    /// in.
+    pub fn canonical_order<R: Copy + Ord>(winners: &mut [R], totals: &[(R, VoteWeight)]) {
+        let votes_of = |winner: &R| {
+            totals
+                .iter()
+                .find(|(runner, _)| runner == winner)
+                .map_or(VoteWeight::ZERO, |(_, votes)| *votes)
+        };
+        winners.sort_by(|a, b| votes_of(b).cmp(&votes_of(a)).then(a.cmp(b)));
+    }
+
+    /// Draws a runner with a chance proportional to its votes: the first
+    /// 16 bytes of `seed`, as a little-endian number modulo the total
+    /// votes, pick a vote, and the runner holding it in runner order wins.
+    /// Returns the drawn vote and the runner holding it.
+    ///
+    /// # Panics
+    ///
+    /// If `seed` is shorter than 16 bytes.
+    pub fn draw<R: Copy>(totals: &[(R, VoteWeight)], seed: &[u8]) -> (VoteWeight, Option<R>) {
+        let total = totals.iter().fold(VoteWeight::ZERO, |sum, (_, votes)| {
+            VoteArithmetic::saturating_add(sum, *votes)
+        });
+        if total == VoteWeight::ZERO {
+            return (VoteWeight::ZERO, None);
         }
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&seed[..16]);
+        let draw = (u128::from_le_bytes(bytes) % u128::from(total)) as VoteWeight;
+        let mut counted = VoteWeight::ZERO;
+        let winner = totals.iter().find_map(|(runner, votes)| {
+            counted = VoteArithmetic::saturating_add(counted, *votes);
+            (draw < counted).then_some(*runner)
+        });
+        (draw, winner)
+    }
 
-        #[ink(message)]
-        pub fn vote(&mut self, address: AccountId) -> Result<(), VoteError> {
-            // check if caller already voted
-            let caller = self.env().caller();
-            if self.already_voted.get(caller).unwrap_or(false) {
-                return Err(VoteError::AlreadyVoted);
+    /// Shares `seats` among slates by highest averages: each seat goes to
+    /// the slate with the largest votes-to-divisor quotient that still has
+    /// members to seat, ties going to the earlier runner. `divisor` gives a
+    /// slate's divisor once it holds that many seats, and `sizes` the
+    /// members of each slate. Returns the seats won by each slate.
+    pub fn apportion<R>(
+        totals: &[(R, VoteWeight)],
+        sizes: &[u32],
+        seats: u32,
+        divisor: impl Fn(u32) -> u64,
+    ) -> Vec<u32> {
+        let mut won = alloc::vec![0u32; totals.len()];
+        for _ in 0..seats {
+            let mut best: Option<usize> = None;
+            for (index, (_, votes)) in totals.iter().enumerate() {
+                if won[index] >= sizes[index] || *votes == VoteWeight::ZERO {
+                    continue;
+                }
+                // votes / divisor > best votes / best divisor, without division
+                let beats = best.is_none_or(|best| {
+                    u128::from(*votes) * u128::from(divisor(won[best]))
+                        > u128::from(totals[best].1) * u128::from(divisor(won[index]))
+                });
+                if beats {
+                    best = Some(index);
+                }
             }
+            let Some(best) = best else {
+                break;
+            };
+            won[best] += 1;
+        }
+        won
+    }
 
-            // tag caller as already voted
-            self.already_voted.insert(caller, &true);
+    /// The Droop quota for `seats` out of `total` votes.
+    pub fn droop_quota(total: u128, seats: u32) -> u128 {
+        total / (u128::from(seats) + 1) + 1
+    }
 
-            // store vote
-            let current_votes = self.votes.get(address).unwrap_or_default();
+    /// How a pass of a single-transferable-vote count is settled.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Settlement {
+        /// The candidate at this index reached the quota and is elected.
+        Elect(usize),
+        /// No candidate reached the quota; the one at this index is excluded.
+        Exclude(usize),
+    }
 
-            // if no votes yet, add address to runners
-            if current_votes == 0 {
-                self.runners.push(address);
+    /// Settles a pass of a single-transferable-vote count over the
+    /// `tallies` of each candidate: the leading `hopeful` candidate is
+    /// elected if it reaches `quota`, otherwise the last one is excluded,
+    /// ties going against the later candidate. Returns `None` when no
+    /// candidate is hopeful.
+    pub fn settle_pass(tallies: &[u128], hopeful: &[usize], quota: u128) -> Option<Settlement> {
+        let leader = hopeful.iter().copied().reduce(|best, index| {
+            if tallies[index] > tallies[best] {
+                index
+            } else {
+                best
             }
+        })?;
+        if tallies[leader] >= quota {
+            return Some(Settlement::Elect(leader));
+        }
+        hopeful
+            .iter()
+            .copied()
+            .reduce(|worst, index| {
+                if tallies[index] <= tallies[worst] {
+                    index
+                } else {
+                    worst
+                }
+            })
+            .map(Settlement::Exclude)
+    }
+}
 
-            // Issue: Potential overflow
-            // Could use saturating_add so it wont return an error.
-            match current_votes.checked_add(1) {
-                Some(new_votes) => self.votes.insert(address, &new_votes),
-                None => return Err(VoteError::VoteOverflow),
-            };
+/// The interface of an external eligibility source, which decides who may
+/// vote in the elections configured with it, e.g. a staking registry or an
+/// attendance system. Its selectors are fixed like those of `TallyModule`.
+pub mod eligibility {
+    use crate::queries::ElectionId;
+    use ink::primitives::AccountId;
 
-            Ok(())
+    #[ink::trait_definition]
+    pub trait EligibilitySource {
+        /// Returns whether `account` may vote in the election.
+        #[ink(message, selector = 0x454c_0001)]
+        fn is_eligible(&self, account: AccountId, election_id: ElectionId) -> bool;
+    }
+}
+
+/// The interface of an external weight source, which supplies the weight
+/// of ballots in the elections configured with it, e.g. from liquidity
+/// positions or a reputation system. Its selectors are fixed like those of
+/// `TallyModule`.
+pub mod weighting {
+    use ink::{
+        env::{DefaultEnvironment, Environment},
+        primitives::AccountId,
+    };
+
+    type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+    #[ink::trait_definition]
+    pub trait WeightCalculator {
+        /// Returns the votes a ballot of `account` is worth as of
+        /// `snapshot_block`.
+        #[ink(message, selector = 0x5747_0001)]
+        fn weight_of(&self, account: AccountId, snapshot_block: BlockNumber) -> u128;
+    }
+}
+
+/// The interface of a contract subscribed to an election's activity, e.g. a
+/// reward system or a mirror. Callbacks run under a gas cap, and a failing
+/// one never holds up the ballot or finalization that triggered it. Its
+/// selectors are fixed like those of `TallyModule`.
+pub mod hooks {
+    use crate::{queries::ElectionId, voting::ElectionResult};
+    use ink::primitives::AccountId;
+
+    #[ink::trait_definition]
+    pub trait ElectionHook {
+        /// Called when a ballot of `voter` gives `weight` votes to `candidate`.
+        #[ink(message, selector = 0x484b_0001)]
+        fn on_vote(
+            &mut self,
+            election_id: ElectionId,
+            voter: AccountId,
+            candidate: AccountId,
+            weight: u32,
+        );
+
+        /// Called once the election is finalized, with its outcome.
+        #[ink(message, selector = 0x484b_0002)]
+        fn on_finalize(&mut self, election_id: ElectionId, result: ElectionResult);
+    }
+}
+
+/// The interfaces of external naming contracts: a name validator, which vets
+/// the display names participants register, e.g. against a list of banned
+/// words, and a name service, which the contract asks for the names of
+/// accounts, e.g. an AZERO.ID-style domain registry. Their selectors are
+/// fixed like those of `TallyModule`.
+pub mod naming {
+    use ink::{prelude::vec::Vec, primitives::AccountId};
+
+    #[ink::trait_definition]
+    pub trait NameValidator {
+        /// Returns whether `account` may go by `name`.
+        #[ink(message, selector = 0x4e4d_0001)]
+        fn is_acceptable(&self, account: AccountId, name: Vec<u8>) -> bool;
+    }
+
+    /// The selector is the one ink! derives for a `get_primary_name`
+    /// message, so that registries exposing one answer it as they are.
+    #[ink::trait_definition]
+    pub trait NameService {
+        /// Returns the name `account` set as its primary one, if any.
+        #[ink(message, selector = 0x404f_1d73)]
+        fn get_primary_name(&self, account: AccountId) -> Option<Vec<u8>>;
+    }
+}
+
+/// The interface of an external arbitration contract, e.g. a Kleros-style
+/// court, to which the disputes of the elections configured with it are
+/// escalated, and whose rulings the contract enforces. Its selectors are
+/// fixed like those of `TallyModule`.
+pub mod arbitration {
+    use crate::{queries::ElectionId, voting::Ruling};
+    use ink::primitives::AccountId;
+
+    #[ink::trait_definition]
+    pub trait Arbitrator {
+        /// Opens a case over the result of the election, disputed by
+        /// `challenger`. Returns whether the case was accepted.
+        #[ink(message, selector = 0x4152_0001)]
+        fn create_dispute(&mut self, election_id: ElectionId, challenger: AccountId) -> bool;
+
+        /// Returns the ruling on the election's case, once one is given.
+        #[ink(message, selector = 0x4152_0002)]
+        fn ruling_of(&self, election_id: ElectionId) -> Option<Ruling>;
+    }
+}
+
+/// The interface of an external treasury contract, to which the owner
+/// sweeps the funds the contract owns, so that they are held and spent
+/// apart from the ballots. Its selectors are fixed like those of
+/// `TallyModule`.
+pub mod treasury {
+    use ink::env::{DefaultEnvironment, Environment};
+
+    type Balance = <DefaultEnvironment as Environment>::Balance;
+
+    #[ink::trait_definition]
+    pub trait Treasury {
+        /// Takes the value sent along into the treasury.
+        #[ink(message, payable, selector = 0x5452_0001)]
+        fn deposit(&mut self);
+
+        /// Returns the funds the treasury holds.
+        #[ink(message, selector = 0x5452_0002)]
+        fn funds(&self) -> Balance;
+    }
+}
+
+/// Storage cells of the `voting_delegator` contract, which keeps the
+/// contract's storage and runs its code through `delegate_call`. They sit
+/// apart from every key of the contract's own layout, so the two share one
+/// storage; `Voting::delegate_logic` fills them in.
+pub mod delegator {
+    /// Key of the code hash calls are delegated to.
+    pub const LOGIC_KEY: u32 = 0x444c_0001;
+    /// Key of the account allowed to swap the code hash.
+    pub const ADMIN_KEY: u32 = 0x444c_0002;
+}
+
+/// Runtime functionality the contract reaches through its chain extension.
+pub mod runtime {
+    use crate::queries::ElectionId;
+    use ink::env::{DefaultEnvironment, Environment};
+    use ink::prelude::vec::Vec;
+    use ink::primitives::{AccountId, Hash};
+
+    /// Identifier of a `pallet-assets` asset.
+    pub type AssetId = u32;
+
+    type Balance = <DefaultEnvironment as Environment>::Balance;
+
+    /// Read access to `pallet-assets`.
+    #[ink::chain_extension(extension = 1)]
+    pub trait AssetsExtension {
+        type ErrorCode = AssetsError;
+
+        /// Returns the balance `owner` holds of `asset_id`.
+        #[ink(function = 1, handle_status = false)]
+        fn balance_of(asset_id: AssetId, owner: AccountId) -> Balance;
+
+        /// Returns the total issuance of `asset_id`.
+        #[ink(function = 2, handle_status = false)]
+        fn total_supply(asset_id: AssetId) -> Balance;
+    }
+
+    /// Status code of a failed `AssetsExtension` call.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum AssetsError {
+        Failed,
+    }
+
+    impl ink::env::chain_extension::FromStatusCode for AssetsError {
+        fn from_status_code(status_code: u32) -> Result<(), Self> {
+            match status_code {
+                0 => Ok(()),
+                _ => Err(Self::Failed),
+            }
         }
+    }
 
-        #[ink(message)]
-        pub fn get_current_winner(&self) -> Vec<AccountId> {
-            let mut current_winners = Vec::new();
-            let mut highest_votes = 0;
-            for runner in &self.runners {
-                let votes = self.votes.get(*runner).unwrap_or(0);
+    /// Write access to the runtime's governance pallets.
+    #[ink::chain_extension(extension = 2)]
+    pub trait GovernanceExtension {
+        type ErrorCode = GovernanceError;
 
-                match votes.cmp(&highest_votes) {
-                    Ordering::Greater => {
-                        highest_votes = votes;
-                        current_winners.clear();
-                        current_winners.push(*runner)
-                    }
-                    Ordering::Equal => current_winners.push(*runner),
-                    Ordering::Less => {}
+        /// Notes the winners and tally hash of a finalized election, e.g. as a
+        /// remark or a preimage, for governance to act on.
+        #[ink(function = 1)]
+        fn note_outcome(election_id: ElectionId, winners: Vec<AccountId>, tally_hash: Hash);
+    }
+
+    /// Status code of a failed `GovernanceExtension` call.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum GovernanceError {
+        Failed,
+    }
+
+    impl ink::env::chain_extension::FromStatusCode for GovernanceError {
+        fn from_status_code(status_code: u32) -> Result<(), Self> {
+            match status_code {
+                0 => Ok(()),
+                _ => Err(Self::Failed),
+            }
+        }
+    }
+
+    /// Threshold decryption offered by the runtime.
+    #[ink::chain_extension(extension = 3)]
+    pub trait DecryptionExtension {
+        type ErrorCode = DecryptionError;
+
+        /// Combines `shares` of the secret behind `public_key` and decrypts
+        /// `ciphertext` with it, returning the plaintext.
+        #[ink(function = 1)]
+        fn threshold_decrypt(
+            public_key: [u8; 32],
+            shares: Vec<Vec<u8>>,
+            ciphertext: Vec<u8>,
+        ) -> Vec<u8>;
+    }
+
+    /// Status code of a failed `DecryptionExtension` call.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum DecryptionError {
+        Failed,
+    }
+
+    impl ink::env::chain_extension::FromStatusCode for DecryptionError {
+        fn from_status_code(status_code: u32) -> Result<(), Self> {
+            match status_code {
+                0 => Ok(()),
+                _ => Err(Self::Failed),
+            }
+        }
+    }
+
+    /// Linkable ring signatures verified by the runtime.
+    #[ink::chain_extension(extension = 4)]
+    pub trait RingSignatureExtension {
+        type ErrorCode = RingSignatureError;
+
+        /// Returns whether `signature` signs `message` by one of the `ring`
+        /// accounts' keys, linked to the signer by `key_image`, which is the
+        /// same for every signature of one key.
+        #[ink(function = 1, handle_status = false)]
+        fn verify(
+            ring: Vec<AccountId>,
+            message: [u8; 32],
+            key_image: Hash,
+            signature: Vec<u8>,
+        ) -> bool;
+    }
+
+    /// Status code of a failed `RingSignatureExtension` call.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum RingSignatureError {
+        Failed,
+    }
+
+    impl ink::env::chain_extension::FromStatusCode for RingSignatureError {
+        fn from_status_code(status_code: u32) -> Result<(), Self> {
+            match status_code {
+                0 => Ok(()),
+                _ => Err(Self::Failed),
+            }
+        }
+    }
+
+    ink::combine_extensions! {
+        /// Every chain extension the contract calls.
+        pub struct Extensions {
+            /// Reached as `self.env().extension().assets`.
+            pub assets: AssetsExtension,
+            /// Reached as `self.env().extension().governance`.
+            pub governance: GovernanceExtension,
+            /// Reached as `self.env().extension().decryption`.
+            pub decryption: DecryptionExtension,
+            /// Reached as `self.env().extension().ring`.
+            pub ring: RingSignatureExtension,
+        }
+    }
+
+    /// Every call the contract makes into the runtime, so that unit tests can
+    /// stand in for the chain extensions with `TestRuntime`. The contract
+    /// reaches it through `active`.
+    pub trait RuntimeApi {
+        /// See `AssetsExtension::balance_of`.
+        fn balance_of(&mut self, asset_id: AssetId, owner: AccountId) -> Balance;
+
+        /// See `AssetsExtension::total_supply`.
+        fn total_supply(&mut self, asset_id: AssetId) -> Balance;
+
+        /// See `GovernanceExtension::note_outcome`.
+        fn note_outcome(
+            &mut self,
+            election_id: ElectionId,
+            winners: Vec<AccountId>,
+            tally_hash: Hash,
+        ) -> Result<(), GovernanceError>;
+
+        /// See `DecryptionExtension::threshold_decrypt`.
+        fn threshold_decrypt(
+            &mut self,
+            public_key: [u8; 32],
+            shares: Vec<Vec<u8>>,
+            ciphertext: Vec<u8>,
+        ) -> Result<Vec<u8>, DecryptionError>;
+
+        /// See `RingSignatureExtension::verify`.
+        fn verify_ring_signature(
+            &mut self,
+            ring: Vec<AccountId>,
+            message: [u8; 32],
+            key_image: Hash,
+            signature: Vec<u8>,
+        ) -> bool;
+    }
+
+    /// The runtime reached through the chain extensions of `Extensions`.
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct OnChain;
+
+    impl OnChain {
+        fn extensions() -> <Extensions as ink::ChainExtensionInstance>::Instance {
+            <Extensions as ink::ChainExtensionInstance>::instantiate()
+        }
+    }
+
+    impl RuntimeApi for OnChain {
+        fn balance_of(&mut self, asset_id: AssetId, owner: AccountId) -> Balance {
+            Self::extensions().assets.balance_of(asset_id, owner)
+        }
+
+        fn total_supply(&mut self, asset_id: AssetId) -> Balance {
+            Self::extensions().assets.total_supply(asset_id)
+        }
+
+        fn note_outcome(
+            &mut self,
+            election_id: ElectionId,
+            winners: Vec<AccountId>,
+            tally_hash: Hash,
+        ) -> Result<(), GovernanceError> {
+            Self::extensions()
+                .governance
+                .note_outcome(election_id, winners, tally_hash)
+        }
+
+        fn threshold_decrypt(
+            &mut self,
+            public_key: [u8; 32],
+            shares: Vec<Vec<u8>>,
+            ciphertext: Vec<u8>,
+        ) -> Result<Vec<u8>, DecryptionError> {
+            Self::extensions()
+                .decryption
+                .threshold_decrypt(public_key, shares, ciphertext)
+        }
+
+        fn verify_ring_signature(
+            &mut self,
+            ring: Vec<AccountId>,
+            message: [u8; 32],
+            key_image: Hash,
+            signature: Vec<u8>,
+        ) -> bool {
+            Self::extensions()
+                .ring
+                .verify(ring, message, key_image, signature)
+        }
+    }
+
+    /// The runtime the contract calls: the chain extensions, or the
+    /// `TestRuntime` of the current unit test.
+    #[cfg(not(test))]
+    pub fn active() -> impl RuntimeApi {
+        OnChain
+    }
+
+    /// The runtime the contract calls: the chain extensions, or the
+    /// `TestRuntime` of the current unit test.
+    #[cfg(test)]
+    pub fn active() -> impl RuntimeApi {
+        Mocked
+    }
+
+    /// Election id, winners and tally hash of an outcome noted with the
+    /// `TestRuntime`.
+    #[cfg(test)]
+    pub type Note = (ElectionId, Vec<AccountId>, Hash);
+
+    /// Decrypts a ciphertext from the public key and the shares, or fails
+    /// with `None`.
+    #[cfg(test)]
+    pub type Decrypt = fn([u8; 32], &[Vec<u8>], &[u8]) -> Option<Vec<u8>>;
+
+    /// The runtime of a unit test, one per test thread. By default nobody
+    /// holds any asset, outcomes are noted, nothing decrypts and no ring
+    /// signature verifies; tests adjust it with `TestRuntime::set`.
+    #[cfg(test)]
+    pub struct TestRuntime {
+        /// The balance an account holds of an asset.
+        pub holding: fn(AssetId, AccountId) -> Balance,
+        /// The total issuance of every asset.
+        pub supply: Balance,
+        /// Whether `note_outcome` is refused.
+        pub refuse_notes: bool,
+        /// The outcomes noted so far, oldest first.
+        pub notes: Vec<Note>,
+        /// Decrypts a ciphertext, see `Decrypt`.
+        pub decrypt: Decrypt,
+        /// Verifies a ring signature from the ring, key image and signature.
+        pub verify: fn(&[AccountId], Hash, &[u8]) -> bool,
+    }
+
+    #[cfg(test)]
+    impl Default for TestRuntime {
+        fn default() -> Self {
+            Self {
+                holding: |_, _| 0,
+                supply: 0,
+                refuse_notes: false,
+                notes: Vec::new(),
+                decrypt: |_, _, _| None,
+                verify: |_, _, _| false,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    std::thread_local! {
+        static TEST_RUNTIME: core::cell::RefCell<TestRuntime> = Default::default();
+    }
+
+    #[cfg(test)]
+    impl TestRuntime {
+        /// Adjusts the runtime of the current test.
+        pub fn set(adjust: impl FnOnce(&mut TestRuntime)) {
+            TEST_RUNTIME.with(|runtime| adjust(&mut runtime.borrow_mut()));
+        }
+
+        /// Returns the outcomes noted so far in the current test.
+        pub fn notes() -> Vec<Note> {
+            TEST_RUNTIME.with(|runtime| runtime.borrow().notes.clone())
+        }
+    }
+
+    /// The `TestRuntime` of the current unit test.
+    #[cfg(test)]
+    struct Mocked;
+
+    #[cfg(test)]
+    impl RuntimeApi for Mocked {
+        fn balance_of(&mut self, asset_id: AssetId, owner: AccountId) -> Balance {
+            TEST_RUNTIME.with(|runtime| (runtime.borrow().holding)(asset_id, owner))
+        }
+
+        fn total_supply(&mut self, _asset_id: AssetId) -> Balance {
+            TEST_RUNTIME.with(|runtime| runtime.borrow().supply)
+        }
+
+        fn note_outcome(
+            &mut self,
+            election_id: ElectionId,
+            winners: Vec<AccountId>,
+            tally_hash: Hash,
+        ) -> Result<(), GovernanceError> {
+            TEST_RUNTIME.with(|runtime| {
+                let mut runtime = runtime.borrow_mut();
+                if runtime.refuse_notes {
+                    return Err(GovernanceError::Failed);
+                }
+                runtime.notes.push((election_id, winners, tally_hash));
+                Ok(())
+            })
+        }
+
+        fn threshold_decrypt(
+            &mut self,
+            public_key: [u8; 32],
+            shares: Vec<Vec<u8>>,
+            ciphertext: Vec<u8>,
+        ) -> Result<Vec<u8>, DecryptionError> {
+            TEST_RUNTIME
+                .with(|runtime| (runtime.borrow().decrypt)(public_key, &shares, &ciphertext))
+                .ok_or(DecryptionError::Failed)
+        }
+
+        fn verify_ring_signature(
+            &mut self,
+            ring: Vec<AccountId>,
+            _message: [u8; 32],
+            key_image: Hash,
+            signature: Vec<u8>,
+        ) -> bool {
+            TEST_RUNTIME.with(|runtime| (runtime.borrow().verify)(&ring, key_image, &signature))
+        }
+    }
+
+    /// The default environment, extended with every extension of
+    /// `Extensions`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(TypeInfo)]
+    pub enum VotingEnvironment {}
+
+    impl Environment for VotingEnvironment {
+        const MAX_EVENT_TOPICS: usize = <DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+        type AccountId = <DefaultEnvironment as Environment>::AccountId;
+        type Balance = Balance;
+        type Hash = <DefaultEnvironment as Environment>::Hash;
+        type Timestamp = <DefaultEnvironment as Environment>::Timestamp;
+        type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+        type ChainExtension = Extensions;
+    }
+}
+
+// the generated constructor dispatch embeds a whole `ElectionConfig`
+#[allow(clippy::large_enum_variant)]
+#[ink::contract(env = crate::runtime::VotingEnvironment)]
+pub mod voting {
+    use crate::arbitration::Arbitrator;
+    use crate::counting::{self, Settlement, VoteWeight};
+    use crate::eligibility::EligibilitySource;
+    use crate::hooks::ElectionHook;
+    use crate::naming::{NameService, NameValidator};
+    use crate::psp22::{Psp22, Psp22Checkpoints};
+    pub use crate::queries::{ElectionId, ElectionStatus, VotingQueries};
+    use crate::runtime::{self, AssetId, RuntimeApi};
+    use crate::standard::{VotingStandard, STANDARD_VERSION};
+    use crate::tally::TallyModule;
+    use crate::treasury::Treasury;
+    use crate::weighting::WeightCalculator;
+    use core::cmp::Ordering;
+    use ink::codegen::TraitCallBuilder;
+    use ink::{
+        prelude::vec::Vec,
+        storage::{traits::ManualKey, Mapping},
+    };
+
+    /// Largest page returned by paginated queries.
+    const MAX_PAGE_SIZE: u32 = 100;
+
+    /// Highest protocol fee, in basis points of the collected fees.
+    const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+
+    /// Version of the code, as `(major, minor, patch)`, from the crate version.
+    const VERSION: (u16, u16, u16) = (
+        parse_version_part(env!("CARGO_PKG_VERSION_MAJOR")),
+        parse_version_part(env!("CARGO_PKG_VERSION_MINOR")),
+        parse_version_part(env!("CARGO_PKG_VERSION_PATCH")),
+    );
+
+    /// Features this code supports, as `capabilities` bits.
+    const CAPABILITIES: u64 = {
+        use crate::capabilities::*;
+        MULTI_ELECTION
+            | APPROVAL
+            | SPLIT
+            | SINGLE_TRANSFERABLE
+            | COMMITTEE
+            | SORTITION
+            | DELEGATION
+            | RECALL
+            | DISPUTES
+            | PRIZES
+            | LOTTERY
+            | REPUTATION
+            | PARTICIPATION
+            | FEE_TOKENS
+            | RELAYED_BALLOTS
+            | IDENTITY
+            | ASSET_WEIGHT
+            | GOVERNED_UPGRADES
+            | BALLOT_PROOFS
+            | CONTRACT_FILTER
+            | TENURE_BOOST
+            | QUADRATIC
+            | MIRRORED_OUTCOMES
+            | SIGNED_BALLOTS
+            | OPTIMISTIC_TALLY
+            | ENCRYPTED_BALLOTS
+            | ANONYMOUS_BALLOTS
+            | CAMPAIGN_FUNDS
+            | MULTI_RACE
+            | SURVEYS
+            | SCORE
+            | CUMULATIVE
+            | TALLY_MODULES
+            | ELIGIBILITY_SOURCES
+            | WEIGHT_CALCULATORS
+            | HOOKS
+            | TIMELOCK
+            | PERMISSIONS
+            | RATE_LIMITS
+            | REGISTRATION_LIMITS
+            | NO_SELF_VOTES
+            | BARRED_CANDIDATES
+            | CONSTITUENCIES
+            | ELECTORAL_COLLEGE
+            | TOPIC_DELEGATION
+            | STANDINGS
+            | BLIND_RESULTS
+            | RERUNS
+            | ELECTORATE_EXPORT
+            | SNAPSHOT_IMPORT
+            | FUNDS_POLICY
+            | BOUNTY_TIPS
+            | PARTICIPATION_SCORE
+            | SLATE_FREEZE
+            | CAMPAIGN_PHASE
+            | ATTENDANCE_BOOST
+            | INVITATION_CODES
+            | REFERRALS
+            | DISPLAY_NAMES
+            | NAME_SERVICE
+            | SPLIT_DELEGATION
+            | QUIET_EVENTS
+            | TREASURY_SWEEP
+    };
+
+    /// Reads one decimal component of the crate version.
+    const fn parse_version_part(digits: &str) -> u16 {
+        let digits = digits.as_bytes();
+        let mut value = 0u16;
+        let mut i = 0;
+        while i < digits.len() {
+            value = value * 10 + (digits[i] - b'0') as u16;
+            i += 1;
+        }
+        value
+    }
+
+    /// Version of the storage layout this code reads and writes. Bump it on
+    /// every layout change and teach `migrate_storage` the step from the
+    /// previous version.
+    const STORAGE_VERSION: u8 = 1;
+
+    /// Longest memo, in bytes, a ballot may carry.
+    const MAX_MEMO_LEN: usize = 256;
+
+    /// Longest candidate name, in bytes.
+    const MAX_NAME_LEN: usize = 64;
+
+    /// Longest display name, in bytes.
+    const MAX_DISPLAY_NAME_LEN: usize = 32;
+
+    /// Longest invitation code, in bytes.
+    const MAX_CODE_LEN: usize = 64;
+
+    /// Most categories a candidate may be tagged with.
+    const MAX_CATEGORIES: usize = 8;
+
+    /// Most constituencies an electoral college is made of.
+    const MAX_COLLEGE_CONSTITUENCIES: usize = 64;
+
+    /// Most races an election may hold besides its own.
+    const MAX_RACES: usize = 8;
+
+    /// Most options a survey may offer.
+    const MAX_SURVEY_OPTIONS: usize = 16;
+
+    /// Most accounts `add_voters` takes at once.
+    const MAX_VOTER_BATCH: usize = 100;
+
+    /// Most signed ballots `settle_ballots` takes at once.
+    const MAX_SIGNED_BATCH: usize = 100;
+
+    /// Longest encrypted ballot, in bytes.
+    const MAX_CIPHERTEXT_LEN: usize = 512;
+
+    /// Most trustees a decryption committee may have.
+    const MAX_TRUSTEES: usize = 16;
+
+    /// Largest ring an anonymous ballot may be signed over, and so the
+    /// largest electorate that can vote anonymously.
+    const MAX_RING: usize = 256;
+
+    /// Owner of the contract once ownership is renounced: an account nobody
+    /// holds the key of.
+    const NO_OWNER: [u8; 32] = [0; 32];
+
+    /// Computation time a hook callback may use, in picoseconds of weight.
+    const HOOK_REF_TIME: u64 = 2_000_000_000;
+
+    /// Proof size a hook callback may use, in bytes.
+    const HOOK_PROOF_SIZE: u64 = 64 * 1024;
+
+    /// Highest reputation an account can hold.
+    const MAX_REPUTATION: u32 = 100;
+
+    /// Largest reputation adjustment, up or down, the owner may hold an
+    /// account at.
+    const MAX_REPUTATION_ADJUSTMENT: i32 = 50;
+
+    /// Fewest signatures that slash an admin bond, however small the
+    /// turnout.
+    const MIN_SLASH_SIGNERS: u32 = 3;
+
+    /// Shortest voting window, from `voting_opens_at` or the creation of
+    /// the election to `voting_closes_at`, in blocks or in milliseconds.
+    const MIN_VOTING_BLOCKS: BlockNumber = 1;
+    const MIN_VOTING_MILLIS: Timestamp = 6_000;
+
+    /// Longest voting window, about a year of 6-second blocks.
+    const MAX_VOTING_BLOCKS: BlockNumber = 5_256_000;
+    const MAX_VOTING_MILLIS: Timestamp = 31_536_000_000;
+
+    /// Blocks after finalization, about a week of 6-second blocks, before
+    /// anyone may garbage-collect an election's ballots with `gc`.
+    const RETENTION_BLOCKS: BlockNumber = 100_800;
+
+    /// Time after `voting_closes_at`, about a week in blocks or in
+    /// milliseconds, within which an election must be finalized before it
+    /// expires.
+    const EXPIRY_BLOCKS: BlockNumber = 100_800;
+    const EXPIRY_MILLIS: Timestamp = 604_800_000;
+
+    /// Share of the admin bond, in percent, paid to whoever expires an
+    /// election, on top of its bounty tips.
+    const EXPIRY_BOUNTY_PERCENT: Balance = 10;
+
+    /// Blocks each turnout histogram bucket starts out spanning.
+    const HISTOGRAM_BUCKET_BLOCKS: BlockNumber = 100;
+
+    /// Most buckets a turnout histogram keeps before coarsening.
+    const MAX_HISTOGRAM_BUCKETS: usize = 64;
+
+    /// Most tiers an attendance boost may have.
+    const MAX_ATTENDANCE_TIERS: usize = 8;
+
+    /// Highest weight, in percent of the votes, an attendance tier may
+    /// grant.
+    const MAX_ATTENDANCE_PERCENT: u32 = 200;
+
+    /// Most voters a lottery may draw.
+    const MAX_LOTTERY_WINNERS: u32 = 50;
+
+    /// Participation streaks announced with a `StreakMilestone` event.
+    const STREAK_MILESTONES: [u32; 3] = [5, 10, 25];
+
+    /// Links of a delegation chain a ballot carries weight through.
+    const MAX_DELEGATION_DEPTH: u32 = 4;
+
+    /// Most accounts that may delegate directly to one account.
+    const MAX_DELEGATORS: usize = 32;
+
+    /// Most delegates a vote may be split among.
+    const MAX_SPLIT_DELEGATES: usize = 8;
+
+    /// Per-mille units a split ballot may allocate, worth one whole vote.
+    const SPLIT_BUDGET: u16 = 1_000;
+
+    /// Most candidates a ranked ballot may list.
+    const MAX_RANKING: usize = 16;
+
+    /// Fixed-point units of one vote in a single-transferable-vote count.
+    const STV_UNIT: u128 = 1_000_000_000;
+
+    /// Depth of the ballot Merkle tree, enough for every `u32` ballot index.
+    const BALLOT_TREE_DEPTH: usize = 32;
+
+    /// `(election, endorser, candidate)`.
+    type EndorsementKey = (ElectionId, AccountId, AccountId);
+
+    /// `(election, candidate, supporter)`.
+    type PledgeKey = (ElectionId, AccountId, AccountId);
+
+    /// `(election, candidate, revision)`.
+    type RevisionKey = (ElectionId, AccountId, u32);
+
+    /// Sequence number of an accepted ballot, unique across elections.
+    pub type BallotId = u64;
+
+    /// Identifier of a person, who may control several accounts.
+    pub type IdentityId = u64;
+
+    /// Identifier of the chain a relayed ballot comes from, e.g. a parachain id.
+    pub type ChainId = u32;
+
+    /// Identifier of a signaling survey.
+    pub type SurveyId = u32;
+
+    /// Identifier of a role in the permission matrix.
+    pub type RoleId = u32;
+
+    /// Selector of a contract message.
+    pub type Selector = [u8; 4];
+
+    /// Identifier of a category candidates are tagged with, e.g. a region or
+    /// a kind of seat, given meaning by the election admin.
+    pub type CategoryId = u16;
+
+    /// Identifier of a topic elections are about, e.g. treasury or
+    /// technical matters, scoping delegations.
+    pub type TopicId = u32;
+
+    /// Identifier of a constituency voters are assigned to, below the
+    /// election's `constituencies`.
+    pub type ConstituencyId = u32;
+
+    /// `(election, candidate, position of the ballot among the candidate's)`.
+    type CandidateBallotKey = (ElectionId, AccountId, u32);
+
+    /// `(election, ballot index)`.
+    type BallotKey = (ElectionId, u32);
+
+    /// `(election, party)`.
+    type SlateKey = (ElectionId, AccountId);
+
+    /// `(election, account vouched for)`.
+    type VouchKey = (ElectionId, AccountId);
+
+    /// `(election, candidate)`.
+    type CandidateKey = (ElectionId, AccountId);
+
+    /// `(election, category)`.
+    type CategoryKey = (ElectionId, CategoryId);
+
+    /// `(topic, account)`.
+    type TopicKey = (TopicId, AccountId);
+
+    /// `(election, constituency, candidate)`.
+    type ConstituencyKey = (ElectionId, ConstituencyId, AccountId);
+
+    /// `(runner, votes)` pairs, in runner order.
+    type RunnerTotals = Vec<(AccountId, VoteWeight)>;
+
+    /// `(trustee, share)` pairs, in the order the shares were posted.
+    type KeyShares = Vec<(AccountId, Vec<u8>)>;
+
+    /// `(delegate, per-mille share)` pairs of a split delegation.
+    type SplitShares = Vec<(AccountId, u16)>;
+
+    /// Defines the storage of your contract.
+    /// Add new fields to the below struct in order
+    /// to add new static storage fields to your contract.
+    ///
+    /// Every `Mapping` and `Lazy` field has its own `ManualKey`, pinned to the
+    /// key ink! derived from the field name when it was added, so renaming,
+    /// reordering or removing fields never moves another field's entries.
+    /// A new field takes the key of `Voting::<field name>` and the key of a
+    /// removed field is never reused. Plain fields share the root cell, whose
+    /// layout still changes with them and goes through `migrate_storage`.
+    #[ink(storage)]
+    pub struct Voting {
+        elections: ink::storage::Mapping<ElectionId, Election, ManualKey<0x28e6_77bc>>,
+        next_election_id: ElectionId,
+        runners: ink::storage::Mapping<ElectionId, Vec<AccountId>, ManualKey<0x68f9_8b1d>>,
+        votes: ink::storage::Mapping<(ElectionId, AccountId), VoteWeight, ManualKey<0xcdd1_90b8>>,
+        /// Registered voters of closed-electorate elections, with the
+        /// deposit each of them holds.
+        electorate: ink::storage::Mapping<(ElectionId, AccountId), Balance, ManualKey<0x3ae9_afc6>>,
+        /// Registered voters of each election by position, below its
+        /// electorate size, so that the electorate can be exported.
+        electorate_at: ink::storage::Mapping<(ElectionId, u32), AccountId, ManualKey<0xd6fc_6ce2>>,
+        /// Position of each registered voter in `electorate_at`.
+        electorate_position:
+            ink::storage::Mapping<(ElectionId, AccountId), u32, ManualKey<0x5a8a_1b2c>>,
+        /// Ordered candidate list of each party slate of a committee election.
+        slates: ink::storage::Mapping<SlateKey, Vec<AccountId>, ManualKey<0xe806_3a39>>,
+        /// Current profile of each candidate that set one.
+        profiles: ink::storage::Mapping<
+            (ElectionId, AccountId),
+            CandidateProfile,
+            ManualKey<0x3320_2e26>,
+        >,
+        /// Categories each candidate is tagged with.
+        candidate_categories:
+            ink::storage::Mapping<CandidateKey, Vec<CategoryId>, ManualKey<0x1c70_4082>>,
+        /// Candidates tagged with each category, in the order they were tagged.
+        category_members:
+            ink::storage::Mapping<CategoryKey, Vec<AccountId>, ManualKey<0x0302_f962>>,
+        /// Constituency each voter is assigned to.
+        constituency_of:
+            ink::storage::Mapping<(ElectionId, AccountId), ConstituencyId, ManualKey<0xc92d_1a1d>>,
+        /// Runners with votes of elections tracking standings, most votes
+        /// first.
+        standings: ink::storage::Mapping<ElectionId, RunnerTotals, ManualKey<0x646b_37c5>>,
+        /// Votes each candidate received from each constituency.
+        constituency_votes: ink::storage::Mapping<ConstituencyKey, u32, ManualKey<0x79de_fe8d>>,
+        /// Elections added as races of each election, in the order they were added.
+        races: ink::storage::Mapping<ElectionId, Vec<ElectionId>, ManualKey<0xac6b_53a4>>,
+        /// Election each race belongs to.
+        race_of: ink::storage::Mapping<ElectionId, ElectionId, ManualKey<0x3333_16f9>>,
+        /// Election whose electorate each re-run votes with.
+        rerun_of: ink::storage::Mapping<ElectionId, ElectionId, ManualKey<0xe24b_a3e3>>,
+        /// Latest re-run of each election whose electorate was reused.
+        latest_rerun: ink::storage::Mapping<ElectionId, ElectionId, ManualKey<0xc347_df89>>,
+        /// Profiles each candidate replaced.
+        profile_history:
+            ink::storage::Mapping<RevisionKey, ProfileRevision, ManualKey<0x7caa_421f>>,
+        /// Hash of the statement each candidate posted for its campaign.
+        statements: ink::storage::Mapping<(ElectionId, AccountId), Hash, ManualKey<0x4ffd_f8e5>>,
+        /// Voters who vouched for an account not in the electorate yet, or
+        /// who vouched it in.
+        vouchers_of: ink::storage::Mapping<VouchKey, Vec<AccountId>, ManualKey<0xa48b_9ad5>>,
+        /// Voters who vouched for a banned account and may not vouch again.
+        vouching_barred: ink::storage::Mapping<(ElectionId, AccountId), (), ManualKey<0x0078_985a>>,
+        /// Accounts barred from the electorate.
+        banned_voters: ink::storage::Mapping<(ElectionId, AccountId), (), ManualKey<0xdad6_e1c9>>,
+        /// Voters who cast every ballot the voting mode allows them.
+        already_voted: ink::storage::Mapping<(ElectionId, AccountId), bool, ManualKey<0xf125_8f8d>>,
+        /// Ballots cast so far by a voter of an approval election.
+        approvals_cast: ink::storage::Mapping<(ElectionId, AccountId), u32, ManualKey<0xedd4_d5bd>>,
+        /// `(election, voter, candidate)` approvals, for approval elections.
+        approved: ink::storage::Mapping<EndorsementKey, (), ManualKey<0xf11e_a565>>,
+        endorsements: ink::storage::Mapping<(ElectionId, AccountId), u32, ManualKey<0x7f30_5910>>,
+        endorsed: ink::storage::Mapping<EndorsementKey, (), ManualKey<0x845a_37cd>>,
+        recalls: ink::storage::Mapping<ElectionId, Recall, ManualKey<0x8c9a_e2b9>>,
+        /// Open dispute against each finalized election's result.
+        disputes: ink::storage::Mapping<ElectionId, Dispute, ManualKey<0x18eb_da1a>>,
+        /// Tally posted by the proposer of an optimistic election, until it
+        /// is adopted or refuted.
+        tally_claims: ink::storage::Mapping<ElectionId, TallyClaim, ManualKey<0x4346_e1bc>>,
+        /// Optimistic elections whose posted tally was refuted, and which are
+        /// counted on-chain.
+        refuted_tallies: ink::storage::Mapping<ElectionId, (), ManualKey<0xd531_feb3>>,
+        /// Encrypted ballots of each election, by ballot index, until they
+        /// are revealed.
+        sealed_ballots:
+            ink::storage::Mapping<(ElectionId, u32), SealedBallot, ManualKey<0x5ba5_2155>>,
+        /// Key shares posted by the trustees of each encrypted election.
+        key_shares: ink::storage::Mapping<ElectionId, KeyShares, ManualKey<0x2978_2560>>,
+        /// Encrypted ballots of each election revealed and counted so far.
+        revealed_ballots: ink::storage::Mapping<ElectionId, u32, ManualKey<0x5a18_04c3>>,
+        /// Nullifiers spent by privacy-preserving ballots, which dedupe them
+        /// in place of `already_voted` without naming the voter. Each is
+        /// derived from the election it was spent in.
+        nullifiers: ink::storage::Mapping<Hash, bool, ManualKey<0x1b67_9dbc>>,
+        /// Voter lottery of each election that has one funded or committed.
+        lotteries: ink::storage::Mapping<ElectionId, Lottery, ManualKey<0x5569_0633>>,
+        /// Pools reimbursing the storage deposit of ballots, per election.
+        sponsorships: ink::storage::Mapping<ElectionId, Sponsorship, ManualKey<0xfeb0_77d8>>,
+        /// Elections with an open dispute, in the order they were disputed.
+        open_disputes: Vec<ElectionId>,
+        /// Set while `claim` hands control to the recipient. Messages that
+        /// take or pay out funds refuse to run meanwhile. Kept out of the root
+        /// so that a re-entered call sees it before the outer call returns.
+        paying_out: ink::storage::Lazy<bool, ManualKey<0x130c_a773>>,
+        /// Arbitrator ruling on disputes. Election admins rule on their own
+        /// elections' disputes while it is `None`.
+        resolver: Option<AccountId>,
+        /// Account vetting the candidates of every election. Election admins
+        /// vet their own elections' candidates while it is `None`.
+        moderator: Option<AccountId>,
+        /// Contract vetting display names. Only the built-in checks apply
+        /// while it is `None`.
+        name_validator: Option<AccountId>,
+        /// Contract resolving accounts to names ahead of display names.
+        name_service: Option<AccountId>,
+        /// Storage deposit one entry is reckoned to hold, of which `gc`
+        /// callers owe the treasury half for every entry they go through.
+        entry_deposit: Balance,
+        /// Bond every election admin posts at creation, answerable for the
+        /// running of the election.
+        admin_bond: Balance,
+        /// Candidates the moderator verified, per election.
+        verified: ink::storage::Mapping<(ElectionId, AccountId), (), ManualKey<0xe190_0795>>,
+        recall_signed: ink::storage::Mapping<(ElectionId, AccountId), (), ManualKey<0x26b3_bb93>>,
+        recall_voted: ink::storage::Mapping<(ElectionId, AccountId), (), ManualKey<0xaa8b_1a36>>,
+        /// Sole winner of the latest finalized term-limited election.
+        incumbent: Option<AccountId>,
+        consecutive_terms: ink::storage::Mapping<AccountId, u32, ManualKey<0x8143_ef75>>,
+        win_history: ink::storage::Mapping<AccountId, Vec<ElectionId>, ManualKey<0x26ba_c7ce>>,
+        archive: ink::storage::Mapping<ElectionId, ArchivedResult, ManualKey<0xc306_c611>>,
+        archived_count: u32,
+        /// Archived election ids, in order of finalization.
+        archive_index: ink::storage::Mapping<u32, ElectionId, ManualKey<0xf1e4_69b9>>,
+        /// Hash of the final tally of each finalized election.
+        tally_hashes: ink::storage::Mapping<ElectionId, Hash, ManualKey<0x8921_d7b5>>,
+        /// Elections whose outcome was noted with the runtime's governance.
+        mirrored: ink::storage::Mapping<ElectionId, (), ManualKey<0x4ebe_62d5>>,
+        /// Left-hand nodes of the ballot Merkle tree still waiting for a
+        /// right-hand sibling, one per level.
+        ballot_branches: ink::storage::Mapping<ElectionId, Vec<Hash>, ManualKey<0x2cbc_40f5>>,
+        /// Root of the ballot Merkle tree of each finalized election.
+        ballot_roots: ink::storage::Mapping<ElectionId, Hash, ManualKey<0x0f77_f733>>,
+        /// Ranking of each ballot of a single-transferable-vote election, by
+        /// ballot index.
+        rankings: ink::storage::Mapping<BallotKey, Vec<AccountId>, ManualKey<0x0847_d441>>,
+        /// Preference each ranked ballot currently counts for and its value
+        /// in `STV_UNIT`s, by ballot index.
+        transfers: ink::storage::Mapping<BallotKey, (u32, u128), ManualKey<0xc85f_f3cd>>,
+        /// Single-transferable-vote counts in progress.
+        stv_counts: ink::storage::Mapping<ElectionId, StvCount, ManualKey<0x8f37_fab6>>,
+        /// Candidates a single-transferable-vote election seated, in order
+        /// of election.
+        stv_elected: ink::storage::Mapping<ElectionId, Vec<AccountId>, ManualKey<0x4782_30d0>>,
+        /// Seed and draw that picked the winner of each sortition election.
+        sortition_draws: ink::storage::Mapping<ElectionId, (Hash, u32), ManualKey<0x95f9_b35c>>,
+        /// Candidacy deposits held per candidate.
+        deposits: ink::storage::Mapping<(ElectionId, AccountId), Balance, ManualKey<0xa523_3865>>,
+        /// Prize shares awarded to winners and not claimed yet.
+        prizes: ink::storage::Mapping<(ElectionId, AccountId), Balance, ManualKey<0x937a_9551>>,
+        /// Campaign fund escrowed for each candidate and not paid out yet.
+        campaign_funds:
+            ink::storage::Mapping<(ElectionId, AccountId), Balance, ManualKey<0x1812_019d>>,
+        /// Pledges to campaign funds.
+        campaign_pledges: ink::storage::Mapping<PledgeKey, Balance, ManualKey<0xb42b_3a8c>>,
+        /// Funds owned by the contract itself, such as prize rounding remainders.
+        treasury: Balance,
+        /// Funds held by the contract, by what they are held for.
+        funds: TreasuryReport,
+        /// Deployer of the contract, allowed to manage contract-wide settings,
+        /// or `NO_OWNER` once renounced.
+        owner: AccountId,
+        protocol_fee: ProtocolFee,
+        /// Balances owed to each account and not withdrawn yet: released
+        /// prizes, routed protocol fees and refunds. Only `claim` pays out.
+        claimable: ink::storage::Mapping<AccountId, Balance, ManualKey<0xfcc7_f2f0>>,
+        /// Fee tokens owed to each account, keyed by token, paid out by
+        /// `claim_tokens`.
+        token_claimable:
+            ink::storage::Mapping<(AccountId, AccountId), Balance, ManualKey<0x58ef_55ae>>,
+        /// Upper bounds for every fee, fixed at instantiation.
+        fee_limits: FeeLimits,
+        /// Amount that must be paid to create an election.
+        creation_fee: Balance,
+        /// Number of elections currently accepting ballots.
+        elections_voting: u32,
+        /// Accounts barred from running in an election.
+        banned: ink::storage::Mapping<(ElectionId, AccountId), (), ManualKey<0xe1d5_9382>>,
+        /// Voters of an election in the order they cast their ballot.
+        voters: ink::storage::Mapping<(ElectionId, u32), AccountId, ManualKey<0xae8b_480c>>,
+        /// Voters of a candidate, in order, for public-ballot elections.
+        voters_for: ink::storage::Mapping<CandidateBallotKey, AccountId, ManualKey<0x242f_383b>>,
+        /// Number of ballots cast for a candidate, for public-ballot elections.
+        ballots_for: ink::storage::Mapping<(ElectionId, AccountId), u32, ManualKey<0x6026_ee4d>>,
+        /// Ballots of each account, in the order it cast them.
+        vote_history: ink::storage::Mapping<(AccountId, u32), VoteRecord, ManualKey<0x85b4_5ffe>>,
+        vote_history_len: ink::storage::Mapping<AccountId, u32, ManualKey<0xeaff_d71d>>,
+        /// Hash of the rationale published for a ballot, if the voter gave one.
+        reasons: ink::storage::Mapping<BallotId, Hash, ManualKey<0x6578_e2ad>>,
+        /// Reputation the owner added to or took from each account, on top of
+        /// what its ballots earned.
+        reputation_adjustments: ink::storage::Mapping<AccountId, i32, ManualKey<0xa786_9288>>,
+        /// Participation of each account in tracked elections.
+        participation: ink::storage::Mapping<AccountId, Participation, ManualKey<0xc240_c1c1>>,
+        /// Tracked round and next ballot of participation bookings in progress.
+        participation_progress:
+            ink::storage::Mapping<ElectionId, (u32, u32), ManualKey<0x53b0_5238>>,
+        /// Races each account entered as a candidate, across elections.
+        candidacies: ink::storage::Mapping<AccountId, u32, ManualKey<0x9920_6beb>>,
+        /// Participation-tracked elections booked so far, each one a round.
+        tracked_rounds: u32,
+        /// Version of the layout the storage was last written in.
+        storage_version: u8,
+        /// Whether code upgrades need an approving referendum.
+        upgrades_governed: bool,
+        /// Upgrade referendums by id.
+        referendums: ink::storage::Mapping<u32, Referendum, ManualKey<0x8eb2_7317>>,
+        next_referendum_id: u32,
+        /// Signaling surveys by id.
+        surveys: ink::storage::Mapping<SurveyId, Survey, ManualKey<0x426d_f6df>>,
+        next_survey_id: SurveyId,
+        /// Accounts that answered a survey.
+        survey_responded: ink::storage::Mapping<(SurveyId, AccountId), (), ManualKey<0x77d6_da2e>>,
+        /// Blocks a scheduled administrative action waits before it may run;
+        /// zero lets them run directly.
+        timelock_delay: BlockNumber,
+        /// Administrative actions waiting out the timelock, by id.
+        scheduled_actions: ink::storage::Mapping<u32, ScheduledAction, ManualKey<0x690b_0ac3>>,
+        next_action_id: u32,
+        /// Whether election `0` was instantiated from a snapshot, taking
+        /// imported state until voting opens.
+        importing: bool,
+        /// What `receive` does with the value sent along.
+        incoming_funds: IncomingFunds,
+        /// The role each account holds, if any.
+        roles: ink::storage::Mapping<AccountId, RoleId, ManualKey<0x14be_c451>>,
+        /// Block of the latest ballot of a rate-limited election and the
+        /// ballots accepted in it.
+        block_ballots:
+            ink::storage::Mapping<ElectionId, (BlockNumber, u32), ManualKey<0x4996_c8e5>>,
+        /// Block of the latest candidacy of a rate-limited election and the
+        /// candidates registered in it.
+        block_registrations:
+            ink::storage::Mapping<ElectionId, (BlockNumber, u32), ManualKey<0x187c_1392>>,
+        /// Block each account last registered as a candidate in.
+        last_registered: ink::storage::Mapping<AccountId, BlockNumber, ManualKey<0x415a_8a0b>>,
+        /// Election-admin messages each role may call in place of the admin.
+        role_permissions: ink::storage::Mapping<(RoleId, Selector), (), ManualKey<0xda02_7bfb>>,
+        /// Id the next accepted ballot gets.
+        next_ballot_id: BallotId,
+        /// Accounts that voted in a referendum.
+        referendum_voted: ink::storage::Mapping<(u32, AccountId), (), ManualKey<0x5fb9_8d32>>,
+        /// Code hashes approved by a referendum and not deployed yet.
+        approved_upgrades: ink::storage::Mapping<Hash, u32, ManualKey<0xa475_4910>>,
+        /// Runner totals counted so far by `finalize_step`.
+        partial_totals: ink::storage::Mapping<ElectionId, RunnerTotals, ManualKey<0x8d56_9c23>>,
+        /// Accounts trusted to relay ballots, with the chain they attest for.
+        bridges: ink::storage::Mapping<AccountId, ChainId, ManualKey<0xa990_e539>>,
+        /// Chain each relayed ballot came from.
+        relayed_from:
+            ink::storage::Mapping<(ElectionId, AccountId), ChainId, ManualKey<0xcbc6_3842>>,
+        /// Number of ballots relayed from each chain.
+        relayed_turnout: ink::storage::Mapping<(ElectionId, ChainId), u32, ManualKey<0x6559_fd91>>,
+        /// Whether ballots are deduplicated per identity rather than per account.
+        identity_mode: bool,
+        /// Identity each account was registered under by the owner.
+        identities: ink::storage::Mapping<AccountId, IdentityId, ManualKey<0xe833_0b91>>,
+        /// Block since which each account has been registered under an
+        /// identity without interruption.
+        registered_since: ink::storage::Mapping<AccountId, BlockNumber, ManualKey<0x9252_50d5>>,
+        /// Account each identity voted from, per election.
+        identity_voted:
+            ink::storage::Mapping<(ElectionId, IdentityId), AccountId, ManualKey<0xa110_a906>>,
+        /// Nonce the next signed ballot of each account must carry.
+        ballot_nonces: ink::storage::Mapping<AccountId, u64, ManualKey<0x0582_d6b2>>,
+        /// Contracts the owner approved to vote where contract ballots are
+        /// otherwise rejected.
+        aggregators: ink::storage::Mapping<AccountId, (), ManualKey<0x2080_ddb0>>,
+        /// Account each account delegates its vote to.
+        delegates: ink::storage::Mapping<AccountId, AccountId, ManualKey<0xc71e_9f30>>,
+        /// Accounts delegating directly to each account.
+        delegators: ink::storage::Mapping<AccountId, Vec<AccountId>, ManualKey<0x9ce2_d992>>,
+        /// Account each account delegates its vote to on a topic, ahead of
+        /// its general delegate.
+        topic_delegates: ink::storage::Mapping<TopicKey, AccountId, ManualKey<0x10e8_737f>>,
+        /// Accounts delegating directly to each account on a topic.
+        topic_delegators: ink::storage::Mapping<TopicKey, Vec<AccountId>, ManualKey<0x80a3_7175>>,
+        /// Per-mille shares of its vote each account delegates to several
+        /// delegates, in place of a general delegate.
+        split_delegates: ink::storage::Mapping<AccountId, SplitShares, ManualKey<0x3aba_8fef>>,
+        /// Accounts delegating a share of their vote to each account.
+        split_delegators: ink::storage::Mapping<AccountId, Vec<AccountId>, ManualKey<0xd9db_536a>>,
+        /// Per-mille shares of each account's vote its delegates spent in an
+        /// election.
+        split_spent: ink::storage::Mapping<(ElectionId, AccountId), u16, ManualKey<0xf737_9145>>,
+        /// Number of delegations currently in place, general, split or on a
+        /// topic, so that ballots skip the delegation lookup while there are
+        /// none.
+        delegations: u32,
+        /// Hashes of the invitation codes of each election not redeemed yet.
+        invitation_codes: ink::storage::Mapping<(ElectionId, Hash), (), ManualKey<0x6852_f396>>,
+        /// Account that referred each prospective candidate of an election.
+        referrals:
+            ink::storage::Mapping<(ElectionId, AccountId), AccountId, ManualKey<0xa388_5cff>>,
+        /// Display name of each account that registered one.
+        display_names: ink::storage::Mapping<AccountId, Vec<u8>, ManualKey<0x2297_a77a>>,
+        /// Account going by each display name, keyed by `display_name_key`.
+        name_owners: ink::storage::Mapping<Hash, AccountId, ManualKey<0x8767_5f3f>>,
+        /// Entries of each cancelled election `process_refunds` went through.
+        refund_cursors: ink::storage::Mapping<ElectionId, u32, ManualKey<0xecab_4e3e>>,
+        /// Ballots of each archived election `gc` went through.
+        gc_cursors: ink::storage::Mapping<ElectionId, u32, ManualKey<0x3031_5a4d>>,
+        /// Signatures collected by the petition to slash each admin bond.
+        slash_petitions: ink::storage::Mapping<ElectionId, u32, ManualKey<0xcf81_8e17>>,
+        slash_signed: ink::storage::Mapping<(ElectionId, AccountId), (), ManualKey<0x0c5d_39be>>,
+        /// Ballots of each election, by the blocks they were cast in.
+        turnout_histograms:
+            ink::storage::Mapping<ElectionId, TurnoutHistogram, ManualKey<0xc733_290a>>,
+    }
+
+    /// Emitted when the owner changes a fee.
+    #[ink(event)]
+    pub struct FeeUpdated {
+        /// `None` for contract-wide fees.
+        #[ink(topic)]
+        election_id: Option<ElectionId>,
+        kind: FeeKind,
+        amount: Balance,
+    }
+
+    /// Emitted for every change of a runtime parameter, next to the event
+    /// describing it, so that one subscription sees them all.
+    #[ink(event)]
+    pub struct ConfigChanged {
+        /// `None` for contract-wide parameters.
+        #[ink(topic)]
+        election_id: Option<ElectionId>,
+        /// Selector of the message setting the parameter.
+        setting: [u8; 4],
+    }
+
+    /// Emitted when part of an election's collected fees is routed to the
+    /// protocol fee beneficiary.
+    #[ink(event)]
+    pub struct FeeRouted {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        beneficiary: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when an election is created.
+    #[ink(event)]
+    pub struct ElectionCreated {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        admin: AccountId,
+        config: ElectionConfig,
+    }
+
+    /// Emitted when an election moves from one phase into another.
+    #[ink(event)]
+    pub struct PhaseChanged {
+        #[ink(topic)]
+        election_id: ElectionId,
+        from: ElectionStatus,
+        to: ElectionStatus,
+    }
+
+    /// Emitted when a candidate joins the ballot, either by registering or by
+    /// receiving a write-in vote.
+    #[ink(event)]
+    pub struct CandidateRegistered {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+        deposit: Balance,
+    }
+
+    /// Emitted when a candidate posts or replaces its campaign statement.
+    #[ink(event)]
+    pub struct StatementPosted {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+        statement: Hash,
+    }
+
+    /// Emitted when a candidate sets or changes its profile.
+    #[ink(event)]
+    pub struct CandidateProfileUpdated {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+        name: Vec<u8>,
+        manifesto: Hash,
+        /// Earlier profiles the candidate replaced.
+        revisions: u32,
+    }
+
+    /// Emitted when the admin tags a candidate with categories.
+    #[ink(event)]
+    pub struct CandidateCategorized {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+        categories: Vec<CategoryId>,
+    }
+
+    /// Emitted when the admin assigns voters to a constituency.
+    #[ink(event)]
+    pub struct VotersAssigned {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        constituency: ConstituencyId,
+        assigned: u32,
+    }
+
+    /// Emitted when a survey is created, answered or closed, carrying the
+    /// answer counts afterwards.
+    #[ink(event)]
+    pub struct SurveyUpdated {
+        #[ink(topic)]
+        survey_id: SurveyId,
+        /// The account that answered, if any.
+        #[ink(topic)]
+        account: Option<AccountId>,
+        counts: Vec<u32>,
+        open: bool,
+    }
+
+    /// Emitted when the admin subscribes a hook contract to an election, or
+    /// unsubscribes it.
+    #[ink(event)]
+    pub struct HookUpdated {
+        #[ink(topic)]
+        election_id: ElectionId,
+        hook: Option<AccountId>,
+    }
+
+    /// Emitted when the owner gives up ownership for good.
+    #[ink(event)]
+    pub struct OwnershipRenounced {
+        #[ink(topic)]
+        previous_owner: AccountId,
+    }
+
+    /// Emitted when the owner changes the timelock delay.
+    #[ink(event)]
+    pub struct TimelockDelayUpdated {
+        delay: BlockNumber,
+    }
+
+    /// Emitted when an administrative action is scheduled behind the timelock.
+    #[ink(event)]
+    pub struct ActionScheduled {
+        #[ink(topic)]
+        action_id: u32,
+        #[ink(topic)]
+        proposer: AccountId,
+        action: AdminAction,
+        executable_at: BlockNumber,
+    }
+
+    /// Emitted when a scheduled action runs.
+    #[ink(event)]
+    pub struct ActionExecuted {
+        #[ink(topic)]
+        action_id: u32,
+    }
+
+    /// Emitted when a scheduled action is dropped without running.
+    #[ink(event)]
+    pub struct ActionCancelled {
+        #[ink(topic)]
+        action_id: u32,
+    }
+
+    /// Emitted when the owner gives an account a role or takes it away.
+    #[ink(event)]
+    pub struct RoleAssigned {
+        #[ink(topic)]
+        account: AccountId,
+        role: Option<RoleId>,
+    }
+
+    /// Emitted when the owner grants a role a message or revokes it.
+    #[ink(event)]
+    pub struct PermissionUpdated {
+        #[ink(topic)]
+        role: RoleId,
+        selector: Selector,
+        granted: bool,
+    }
+
+    /// Emitted when the admin adds a race to an election.
+    #[ink(event)]
+    pub struct RaceAdded {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        race_id: ElectionId,
+    }
+
+    /// Emitted when the admin re-runs a closed election with its electorate.
+    #[ink(event)]
+    pub struct ElectionRerun {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        rerun_id: ElectionId,
+        /// Election whose electorate the re-run votes with.
+        electorate_of: ElectionId,
+    }
+
+    /// Emitted when the admin registers voters of a closed electorate.
+    #[ink(event)]
+    pub struct VotersAdded {
+        #[ink(topic)]
+        election_id: ElectionId,
+        /// Accounts newly registered, leaving out the ones already in.
+        added: u32,
+        electorate: u32,
+    }
+
+    /// Emitted when the owner imports voters into a snapshot deployment.
+    #[ink(event)]
+    pub struct VotersImported {
+        #[ink(topic)]
+        election_id: ElectionId,
+        /// Entries newly imported, leaving out the ones already in.
+        imported: u32,
+        electorate: u32,
+        turnout: u32,
+    }
+
+    /// Emitted when a voter joins a closed electorate on its own.
+    #[ink(event)]
+    pub struct VoterRegistered {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        voter: AccountId,
+        deposit: Balance,
+    }
+
+    /// Emitted when a voter leaves, or is removed from, a closed electorate.
+    #[ink(event)]
+    pub struct VoterRemoved {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        voter: AccountId,
+        refund: Balance,
+    }
+
+    /// Emitted when a voter vouches for an account, or takes the vouch back.
+    #[ink(event)]
+    pub struct VouchUpdated {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        voucher: AccountId,
+        #[ink(topic)]
+        account: AccountId,
+        /// Vouches the account holds afterwards.
+        vouches: u32,
+    }
+
+    /// Emitted when an account is barred from the electorate.
+    #[ink(event)]
+    pub struct VoterBanned {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        voter: AccountId,
+        /// Deposit moved to the treasury.
+        forfeited: Balance,
+        /// Vouchers of the account barred from vouching again.
+        vouchers_barred: u32,
+    }
+
+    /// Emitted when a committee election is finalized.
+    #[ink(event)]
+    pub struct CommitteeElected {
+        #[ink(topic)]
+        election_id: ElectionId,
+        /// Seats won by each slate, in runner order.
+        seats: Vec<(AccountId, u32)>,
+        elected: Vec<AccountId>,
+    }
+
+    /// Emitted when an account is barred from running in an election.
+    #[ink(event)]
+    pub struct CandidateBanned {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+    }
+
+    /// Emitted when a prospective candidate is endorsed.
+    #[ink(event)]
+    pub struct Endorsed {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        endorser: AccountId,
+        #[ink(topic)]
+        candidate: AccountId,
+    }
+
+    /// Emitted when a prospective candidate is referred.
+    #[ink(event)]
+    pub struct Referred {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        referrer: AccountId,
+        #[ink(topic)]
+        candidate: AccountId,
+    }
+
+    /// Emitted when a ballot of a split, score or cumulative election is
+    /// accepted, in place of `VoteCast`.
+    #[ink(event)]
+    pub struct SplitVoteCast {
+        #[ink(topic)]
+        election_id: ElectionId,
+        ballot_id: BallotId,
+        #[ink(topic)]
+        voter: AccountId,
+        /// Share of the ballot given to each candidate: per-mille in a split
+        /// election, points or votes otherwise.
+        allocations: Vec<(AccountId, u16)>,
+        fee: Balance,
+        /// Public rationale attached by the voter; never stored.
+        memo: Vec<u8>,
+    }
+
+    /// Emitted when a ballot of a single-transferable-vote election is
+    /// accepted, in place of `VoteCast`.
+    #[ink(event)]
+    pub struct RankedVoteCast {
+        #[ink(topic)]
+        election_id: ElectionId,
+        ballot_id: BallotId,
+        #[ink(topic)]
+        voter: AccountId,
+        /// Candidates in order of preference.
+        ranking: Vec<AccountId>,
+        fee: Balance,
+        /// Public rationale attached by the voter; never stored.
+        memo: Vec<u8>,
+    }
+
+    /// Emitted when the single-transferable-vote count of an election has
+    /// filled every seat.
+    #[ink(event)]
+    pub struct SeatsFilled {
+        #[ink(topic)]
+        election_id: ElectionId,
+        /// In order of election.
+        elected: Vec<AccountId>,
+        /// Droop quota, in billionths of a vote.
+        quota: u128,
+        /// Passes over the ballots the count took.
+        rounds: u32,
+    }
+
+    /// Emitted when the outcome of an election is noted with the runtime's
+    /// governance.
+    #[ink(event)]
+    pub struct OutcomeMirrored {
+        #[ink(topic)]
+        election_id: ElectionId,
+        winners: Vec<AccountId>,
+        tally_hash: Hash,
+    }
+
+    /// Emitted when the winner of a sortition election is drawn.
+    #[ink(event)]
+    pub struct SortitionDrawn {
+        #[ink(topic)]
+        election_id: ElectionId,
+        seed: Hash,
+        /// Position of the draw among the votes, counted in runner order.
+        draw: u32,
+        /// `None` when no votes were cast.
+        winner: Option<AccountId>,
+    }
+
+    /// Emitted when a ballot is accepted.
+    #[ink(event)]
+    pub struct VoteCast {
+        #[ink(topic)]
+        election_id: ElectionId,
+        ballot_id: BallotId,
+        #[ink(topic)]
+        voter: AccountId,
+        #[ink(topic)]
+        candidate: AccountId,
+        fee: Balance,
+        /// Public rationale attached by the voter; never stored.
+        memo: Vec<u8>,
+    }
+
+    /// Emitted alongside `VoteCast` when the ballot was relayed from another chain.
+    #[ink(event)]
+    pub struct VoteRelayed {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        chain: ChainId,
+        #[ink(topic)]
+        voter: AccountId,
+        bridge: AccountId,
+    }
+
+    /// Emitted alongside `VoteCast` when the voter commits to a published
+    /// rationale.
+    #[ink(event)]
+    pub struct ReasonRecorded {
+        #[ink(topic)]
+        election_id: ElectionId,
+        ballot_id: BallotId,
+        #[ink(topic)]
+        voter: AccountId,
+        reason: Hash,
+    }
+
+    /// Emitted when the owner registers an account under an identity, or
+    /// clears it.
+    #[ink(event)]
+    pub struct IdentityUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        identity: Option<IdentityId>,
+    }
+
+    /// Emitted when an account delegates its vote or takes it back.
+    #[ink(event)]
+    pub struct DelegationChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        /// `None` when the delegation is withdrawn.
+        delegate: Option<AccountId>,
+    }
+
+    /// Emitted when an account splits its vote among delegates or takes it
+    /// back.
+    #[ink(event)]
+    pub struct SplitDelegationChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        /// Per-mille share of each delegate, empty when the delegation is
+        /// withdrawn.
+        shares: Vec<(AccountId, u16)>,
+    }
+
+    /// Emitted when an account delegates its vote on a topic or takes it
+    /// back.
+    #[ink(event)]
+    pub struct TopicDelegationChanged {
+        #[ink(topic)]
+        topic: TopicId,
+        #[ink(topic)]
+        delegator: AccountId,
+        /// `None` when the delegation is withdrawn.
+        delegate: Option<AccountId>,
+    }
+
+    /// Emitted when the owner switches identity mode on or off.
+    #[ink(event)]
+    pub struct IdentityModeChanged {
+        enabled: bool,
+    }
+
+    /// Emitted when the owner approves an aggregator contract, or withdraws
+    /// the approval.
+    #[ink(event)]
+    pub struct AggregatorUpdated {
+        #[ink(topic)]
+        aggregator: AccountId,
+        approved: bool,
+    }
+
+    /// Emitted when the owner trusts a bridge, or stops trusting it.
+    #[ink(event)]
+    pub struct BridgeUpdated {
+        #[ink(topic)]
+        bridge: AccountId,
+        /// `None` once the bridge is no longer trusted.
+        chain: Option<ChainId>,
+    }
+
+    /// Emitted alongside `VoteCast` with the candidate's running total, so
+    /// live standings can be kept from the event stream alone.
+    #[ink(event)]
+    pub struct TallyUpdated {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+        new_total: u32,
+    }
+
+    /// Emitted whenever a recall is started, signed, voted on or closed,
+    /// carrying its state afterwards.
+    #[ink(event)]
+    pub struct RecallUpdated {
+        #[ink(topic)]
+        election_id: ElectionId,
+        /// The account that signed or voted, if any.
+        #[ink(topic)]
+        account: Option<AccountId>,
+        recall: Recall,
+    }
+
+    /// Emitted when the proposer of an optimistic election posts its tally.
+    #[ink(event)]
+    pub struct TallyProposed {
+        #[ink(topic)]
+        election_id: ElectionId,
+        proposer: AccountId,
+        bond: Balance,
+    }
+
+    /// Emitted in place of the ballot events of an election run with
+    /// `quiet_events`. Anonymous, so it does not even name its own kind.
+    #[ink(event, anonymous)]
+    pub struct BallotCounted {
+        #[ink(topic)]
+        election_id: ElectionId,
+        turnout: u32,
+    }
+
+    /// Emitted when an encrypted ballot is cast. Its choice stays secret
+    /// until the ballots are revealed.
+    #[ink(event)]
+    pub struct EncryptedVoteCast {
+        #[ink(topic)]
+        election_id: ElectionId,
+        ballot_id: BallotId,
+        #[ink(topic)]
+        voter: AccountId,
+        fee: Balance,
+    }
+
+    /// Emitted when an anonymous ballot is cast. `nullifier` links it to
+    /// any other ballot signed with the same key, but not to the voter.
+    #[ink(event)]
+    pub struct AnonymousVoteCast {
+        #[ink(topic)]
+        election_id: ElectionId,
+        ballot_id: BallotId,
+        #[ink(topic)]
+        candidate: AccountId,
+        nullifier: Hash,
+        fee: Balance,
+    }
+
+    /// Emitted when a trustee posts its key share for an encrypted election.
+    #[ink(event)]
+    pub struct KeySharePosted {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        trustee: AccountId,
+    }
+
+    /// Emitted when a batch of encrypted ballots is revealed. Spoiled
+    /// ballots did not decrypt to an eligible candidate and are not counted.
+    #[ink(event)]
+    pub struct BallotsRevealed {
+        #[ink(topic)]
+        election_id: ElectionId,
+        counted: u32,
+        spoiled: u32,
+    }
+
+    /// Emitted when a posted tally is proven wrong about the runner at
+    /// `index`, slashing the proposer's bond to the challenger.
+    #[ink(event)]
+    pub struct TallyRefuted {
+        #[ink(topic)]
+        election_id: ElectionId,
+        challenger: AccountId,
+        index: u32,
+    }
+
+    /// Emitted when the result of an election is disputed.
+    #[ink(event)]
+    pub struct DisputeRaised {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        challenger: AccountId,
+        bond: Balance,
+    }
+
+    /// Emitted when a dispute is ruled on.
+    #[ink(event)]
+    pub struct DisputeResolved {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        challenger: AccountId,
+        ruling: Ruling,
+    }
+
+    /// Emitted when an account's participation streak reaches one of the
+    /// `STREAK_MILESTONES`.
+    #[ink(event)]
+    pub struct StreakMilestone {
+        #[ink(topic)]
+        account: AccountId,
+        streak: u32,
+    }
+
+    /// Emitted when the owner adjusts an account's reputation. Reputation
+    /// earned by voting shows in the ballot events instead.
+    #[ink(event)]
+    pub struct ReputationChanged {
+        #[ink(topic)]
+        account: AccountId,
+        adjustment: i32,
+        reputation: u32,
+    }
+
+    /// Emitted when the owner appoints or removes the dispute resolver.
+    #[ink(event)]
+    pub struct ResolverUpdated {
+        resolver: Option<AccountId>,
+    }
+
+    /// Emitted when the owner appoints or removes the candidate moderator.
+    #[ink(event)]
+    pub struct ModeratorUpdated {
+        moderator: Option<AccountId>,
+    }
+
+    /// Emitted when the owner sets or removes the display-name validator.
+    #[ink(event)]
+    pub struct NameValidatorUpdated {
+        validator: Option<AccountId>,
+    }
+
+    /// Emitted when the owner sets or removes the name service.
+    #[ink(event)]
+    pub struct NameServiceUpdated {
+        name_service: Option<AccountId>,
+    }
+
+    /// Emitted when an account takes, changes or gives up its display name.
+    #[ink(event)]
+    pub struct DisplayNameSet {
+        #[ink(topic)]
+        account: AccountId,
+        name: Option<Vec<u8>>,
+    }
+
+    /// Emitted when a candidate's verified badge is granted or withdrawn.
+    #[ink(event)]
+    pub struct CandidateVerified {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+        verified: bool,
+    }
+
+    /// Emitted when an election is paused or resumed.
+    #[ink(event)]
+    pub struct ElectionPauseChanged {
+        #[ink(topic)]
+        election_id: ElectionId,
+        paused: bool,
+    }
+
+    /// Emitted when value sent through `receive` is booked as treasury
+    /// income.
+    #[ink(event)]
+    pub struct FundsReceived {
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when value is added to an election's prize pool.
+    #[ink(event)]
+    pub struct PrizeFunded {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        funder: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when value is added to an election's finalization bounty.
+    #[ink(event)]
+    pub struct BountyTipped {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        tipper: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when a supporter adds to a candidate's campaign fund.
+    #[ink(event)]
+    pub struct CampaignFunded {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+        #[ink(topic)]
+        supporter: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when a candidate who reached the campaign threshold is paid
+    /// its campaign fund.
+    #[ink(event)]
+    pub struct CampaignFundReleased {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when a supporter takes back a pledge the candidate did not
+    /// earn.
+    #[ink(event)]
+    pub struct CampaignPledgeRefunded {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        candidate: AccountId,
+        #[ink(topic)]
+        supporter: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when `process_refunds` unwinds part of a cancelled election.
+    #[ink(event)]
+    pub struct RefundsProcessed {
+        #[ink(topic)]
+        election_id: ElectionId,
+        /// Entries gone through by this call.
+        entries: u32,
+        /// Credited by this call.
+        refunded: Balance,
+        /// Whether every entry has been gone through.
+        done: bool,
+    }
+
+    /// Emitted when value is added to an election's voter lottery pool.
+    #[ink(event)]
+    pub struct LotteryFunded {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        funder: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when value is added to an election's storage sponsorship pool.
+    #[ink(event)]
+    pub struct SponsorshipFunded {
+        #[ink(topic)]
+        election_id: ElectionId,
+        amount: Balance,
+        pool: Balance,
+    }
+
+    /// Emitted when the admin commits to the seed of an election's voter
+    /// lottery.
+    #[ink(event)]
+    pub struct LotterySeedCommitted {
+        #[ink(topic)]
+        election_id: ElectionId,
+        commitment: Hash,
+    }
+
+    /// Emitted when the voter lottery of an election is drawn.
+    #[ink(event)]
+    pub struct LotteryDrawn {
+        #[ink(topic)]
+        election_id: ElectionId,
+        seed: Hash,
+        winners: Vec<AccountId>,
+        /// Prize each drawn voter may claim.
+        share: Balance,
+    }
+
+    /// Emitted when the account finalizing an election after its deadline is
+    /// paid the finalization bounty.
+    #[ink(event)]
+    pub struct BountyPaid {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        keeper: AccountId,
+        amount: Balance,
+        /// Tips paid on top, in the native currency.
+        tips: Balance,
+    }
+
+    /// Emitted when a winner is awarded a share of the prize pool.
+    #[ink(event)]
+    pub struct PrizeAwarded {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        winner: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when funds are owed to an account, to be withdrawn with `claim`.
+    #[ink(event)]
+    pub struct Credited {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+        /// Balance the account may now claim.
+        claimable: Balance,
+    }
+
+    /// Emitted when fee tokens are owed to an account, to be withdrawn with
+    /// `claim_tokens`.
+    #[ink(event)]
+    pub struct TokensCredited {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when an account withdraws the fee tokens it is owed.
+    #[ink(event)]
+    pub struct TokensClaimed {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when an account withdraws what it is owed.
+    #[ink(event)]
+    pub struct Claimed {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when the owner moves surplus funds out of the contract.
+    #[ink(event)]
+    pub struct SurplusWithdrawn {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when the owner changes the protocol fee split.
+    #[ink(event)]
+    pub struct ProtocolFeeUpdated {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        basis_points: u16,
+    }
+
+    /// Emitted when the storage is migrated to a newer layout.
+    #[ink(event)]
+    pub struct StorageMigrated {
+        from: u8,
+        to: u8,
+        /// The code that migrated it.
+        #[ink(topic)]
+        code_hash: Hash,
+    }
+
+    /// Emitted whenever an upgrade referendum is proposed, voted on or
+    /// closed, carrying its state afterwards.
+    #[ink(event)]
+    pub struct ReferendumUpdated {
+        #[ink(topic)]
+        referendum_id: u32,
+        /// The account that voted, if any.
+        #[ink(topic)]
+        account: Option<AccountId>,
+        referendum: Referendum,
+    }
+
+    /// Emitted when the contract switches to new code.
+    #[ink(event)]
+    pub struct CodeHashUpdated {
+        #[ink(topic)]
+        old_code_hash: Hash,
+        #[ink(topic)]
+        new_code_hash: Hash,
+        /// Layout the new code finds the storage in, which `migrate_storage`
+        /// brings up to date.
+        storage_version: u8,
+    }
+
+    /// Emitted when an election is finalized, summing up its outcome and
+    /// committing to its final tally.
+    #[ink(event)]
+    pub struct RoundFinalized {
+        #[ink(topic)]
+        election_id: ElectionId,
+        /// See `get_round_result`.
+        round: u32,
+        winners: Vec<AccountId>,
+        /// Number of ballots cast.
+        turnout: u32,
+        /// Votes counted, which differ from `turnout` when ballots are weighted
+        /// or split.
+        total_votes: u32,
+        tally_hash: Hash,
+        ballot_root: Hash,
+    }
+
+    /// Emitted when the detailed state of an election is pruned.
+    #[ink(event)]
+    pub struct ElectionPruned {
+        #[ink(topic)]
+        election_id: ElectionId,
+        voters_removed: u32,
+    }
+
+    /// Emitted when an election expires, with the bounty paid to whoever
+    /// expired it.
+    #[ink(event)]
+    pub struct ElectionExpired {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        keeper: AccountId,
+        bounty: Balance,
+    }
+
+    /// Emitted when an election's admin bond is released to the admin or
+    /// slashed.
+    #[ink(event)]
+    pub struct AdminBondSettled {
+        #[ink(topic)]
+        election_id: ElectionId,
+        #[ink(topic)]
+        admin: AccountId,
+        amount: Balance,
+        slashed: bool,
+    }
+
+    /// Emitted when `settle_ballots` skips a ballot of its batch.
+    #[ink(event)]
+    pub struct BallotSkipped {
+        #[ink(topic)]
+        election_id: ElectionId,
+        /// Position of the ballot in the batch.
+        index: u32,
+        /// `VoteError::code` of why it was skipped.
+        code: u32,
+    }
+
+    /// Emitted when `gc` deletes part of an archived election's ballots.
+    #[ink(event)]
+    pub struct ElectionCollected {
+        #[ink(topic)]
+        election_id: ElectionId,
+        /// Ballots gone through by this call.
+        entries: u32,
+        /// Paid to the treasury out of the released storage deposit.
+        treasury_share: Balance,
+        /// Whether every ballot has been gone through.
+        done: bool,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum VoteError {
+        AlreadyVoted,
+        VoteOverflow,
+        Unauthorized,
+        VotingClosed,
+        ElectionNotFound,
+        InvalidTransition,
+        NotEnoughCandidates,
+        NotACandidate,
+        RecallUnavailable,
+        RecallAlreadyStarted,
+        RecallNotFound,
+        RecallClosed,
+        AlreadySigned,
+        VotingNotOpenYet,
+        VotingStillOpen,
+        QuorumNotReached,
+        IncorrectFee,
+        NothingToClaim,
+        TransferFailed,
+        FeeTooHigh,
+        FeesLocked,
+        MemoTooLong,
+        UnsupportedStorageVersion,
+        ReferendumNotFound,
+        ReferendumClosed,
+        UpgradeNotApproved,
+        UpgradeFailed,
+        InsufficientHolding,
+        InvalidSplit,
+        InvalidRanking,
+        InvalidVoteCount,
+        DelegationCycle,
+        TooManyDelegators,
+        NotDelegating,
+        NotInElectorate,
+        BatchTooLarge,
+        NoIdentity,
+        ChallengeClosed,
+        DisputeOpen,
+        NoDispute,
+        ResultNotEffective,
+        ReputationOutOfBounds,
+        LotteryUnavailable,
+        SeedMismatch,
+        Reentrancy,
+        ExceedsSurplus,
+        ContractVoter,
+        MirrorDisabled,
+        AlreadyMirrored,
+        MirrorFailed,
+        SignedBallotsDisabled,
+        InvalidSignature,
+        StaleNonce,
+        TallyAlreadyProposed,
+        TallyUnderChallenge,
+        TallyRefuted,
+        NoTallyClaim,
+        NoFraud,
+        EncryptionDisabled,
+        BallotsEncrypted,
+        CiphertextTooLong,
+        ShareAlreadyPosted,
+        BallotsSealed,
+        DecryptionFailed,
+        AnonymityDisabled,
+        BallotsAnonymous,
+        InvalidRing,
+        NullifierSpent,
+        CampaignFundsDisabled,
+        CampaignThresholdMissed,
+        CampaignThresholdReached,
+        TooManyCategories,
+        TooManyRaces,
+        AlreadyARace,
+        WrongRaceCount,
+        UnsupportedBallotType,
+        InvalidAllotment,
+        TallyModuleFailed,
+        NotEligible,
+        WeightSourceFailed,
+        ElectionsInFlight,
+        RateLimited,
+        SelfVoteForbidden,
+        CandidateBarred,
+        InvalidConstituency,
+        Timelocked,
+        TimelockPending,
+        ActionNotFound,
+        SurveyNotFound,
+        SurveyClosed,
+        InvalidSurvey,
+        InvalidOption,
+        ElectorateNotPinned,
+        TransferRejected,
+        BountyUnavailable,
+        ConfigFrozen,
+        ElectorateOpen,
+        SponsorshipDisabled,
+        SponsorshipExhausted,
+        StorageBudgetExceeded,
+        RetentionPending,
+        ArbitrationFailed,
+        RulingPending,
+        NoAdminBond,
+        ElectionPaused,
+        NotStale,
+    }
+
+    impl VoteError {
+        /// Returns the stable code of this error. Codes are never reused nor
+        /// renumbered, whatever the order of the variants, so that frontends
+        /// and indexers may map them without decoding the enum.
+        pub fn code(&self) -> u32 {
+            match self {
+                VoteError::AlreadyVoted => 1001,
+                VoteError::VoteOverflow => 1002,
+                VoteError::Unauthorized => 1003,
+                VoteError::VotingClosed => 1004,
+                VoteError::ElectionNotFound => 1005,
+                VoteError::InvalidTransition => 1006,
+                VoteError::NotEnoughCandidates => 1007,
+                VoteError::NotACandidate => 1008,
+                VoteError::RecallUnavailable => 1009,
+                VoteError::RecallAlreadyStarted => 1010,
+                VoteError::RecallNotFound => 1011,
+                VoteError::RecallClosed => 1012,
+                VoteError::AlreadySigned => 1013,
+                VoteError::VotingNotOpenYet => 1014,
+                VoteError::VotingStillOpen => 1015,
+                VoteError::QuorumNotReached => 1016,
+                VoteError::IncorrectFee => 1017,
+                VoteError::NothingToClaim => 1018,
+                VoteError::TransferFailed => 1019,
+                VoteError::FeeTooHigh => 1020,
+                VoteError::FeesLocked => 1021,
+                VoteError::MemoTooLong => 1022,
+                VoteError::UnsupportedStorageVersion => 1023,
+                VoteError::ReferendumNotFound => 1024,
+                VoteError::ReferendumClosed => 1025,
+                VoteError::UpgradeNotApproved => 1026,
+                VoteError::UpgradeFailed => 1027,
+                VoteError::InsufficientHolding => 1028,
+                VoteError::InvalidSplit => 1029,
+                VoteError::InvalidRanking => 1030,
+                VoteError::InvalidVoteCount => 1031,
+                VoteError::DelegationCycle => 1032,
+                VoteError::TooManyDelegators => 1033,
+                VoteError::NotDelegating => 1034,
+                VoteError::NotInElectorate => 1035,
+                VoteError::BatchTooLarge => 1036,
+                VoteError::NoIdentity => 1037,
+                VoteError::ChallengeClosed => 1038,
+                VoteError::DisputeOpen => 1039,
+                VoteError::NoDispute => 1040,
+                VoteError::ResultNotEffective => 1041,
+                VoteError::ReputationOutOfBounds => 1042,
+                VoteError::LotteryUnavailable => 1043,
+                VoteError::SeedMismatch => 1044,
+                VoteError::Reentrancy => 1045,
+                VoteError::ExceedsSurplus => 1046,
+                VoteError::ContractVoter => 1047,
+                VoteError::MirrorDisabled => 1048,
+                VoteError::AlreadyMirrored => 1049,
+                VoteError::MirrorFailed => 1050,
+                VoteError::SignedBallotsDisabled => 1051,
+                VoteError::InvalidSignature => 1052,
+                VoteError::StaleNonce => 1053,
+                VoteError::TallyAlreadyProposed => 1054,
+                VoteError::TallyUnderChallenge => 1055,
+                VoteError::TallyRefuted => 1056,
+                VoteError::NoTallyClaim => 1057,
+                VoteError::NoFraud => 1058,
+                VoteError::EncryptionDisabled => 1059,
+                VoteError::BallotsEncrypted => 1060,
+                VoteError::CiphertextTooLong => 1061,
+                VoteError::ShareAlreadyPosted => 1062,
+                VoteError::BallotsSealed => 1063,
+                VoteError::DecryptionFailed => 1064,
+                VoteError::AnonymityDisabled => 1065,
+                VoteError::BallotsAnonymous => 1066,
+                VoteError::InvalidRing => 1067,
+                VoteError::NullifierSpent => 1068,
+                VoteError::CampaignFundsDisabled => 1069,
+                VoteError::CampaignThresholdMissed => 1070,
+                VoteError::CampaignThresholdReached => 1071,
+                VoteError::TooManyCategories => 1072,
+                VoteError::TooManyRaces => 1073,
+                VoteError::AlreadyARace => 1074,
+                VoteError::WrongRaceCount => 1075,
+                VoteError::UnsupportedBallotType => 1076,
+                VoteError::InvalidAllotment => 1077,
+                VoteError::TallyModuleFailed => 1078,
+                VoteError::NotEligible => 1079,
+                VoteError::WeightSourceFailed => 1080,
+                VoteError::ElectionsInFlight => 1081,
+                VoteError::RateLimited => 1082,
+                VoteError::SelfVoteForbidden => 1083,
+                VoteError::CandidateBarred => 1084,
+                VoteError::InvalidConstituency => 1085,
+                VoteError::Timelocked => 1086,
+                VoteError::TimelockPending => 1087,
+                VoteError::ActionNotFound => 1088,
+                VoteError::SurveyNotFound => 1089,
+                VoteError::SurveyClosed => 1090,
+                VoteError::InvalidSurvey => 1091,
+                VoteError::InvalidOption => 1092,
+                VoteError::ElectorateNotPinned => 1093,
+                VoteError::TransferRejected => 1094,
+                VoteError::BountyUnavailable => 1095,
+                VoteError::ConfigFrozen => 1096,
+                VoteError::ElectorateOpen => 1097,
+                VoteError::SponsorshipDisabled => 1098,
+                VoteError::SponsorshipExhausted => 1099,
+                VoteError::StorageBudgetExceeded => 1100,
+                VoteError::RetentionPending => 1101,
+                VoteError::ArbitrationFailed => 1102,
+                VoteError::RulingPending => 1103,
+                VoteError::NoAdminBond => 1104,
+                VoteError::ElectionPaused => 1105,
+                VoteError::NotStale => 1106,
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum RegistrationError {
+        ElectionNotFound,
+        RegistrationClosed,
+        AlreadyRegistered,
+        NotEnoughEndorsements,
+        AlreadyEndorsed,
+        SelfEndorsement,
+        TermLimitReached,
+        IncorrectDeposit,
+        Banned,
+        CandidateCapReached,
+        NotRegistered,
+        NotAVoucher,
+        AlreadyVouched,
+        InvalidSlate,
+        Reentrancy,
+        TransferFailed,
+        NameTooLong,
+        RateLimited,
+        CooldownActive,
+        InvalidCode,
+        NotReferred,
+        AlreadyReferred,
+        NotAReferrer,
+        InvalidSignature,
+        InvalidName,
+        NameTaken,
+        NameRejected,
+        StorageBudgetExceeded,
+        ElectionPaused,
+    }
+
+    impl RegistrationError {
+        /// Returns the stable code of this error, as `VoteError::code` does.
+        pub fn code(&self) -> u32 {
+            match self {
+                RegistrationError::ElectionNotFound => 2001,
+                RegistrationError::RegistrationClosed => 2002,
+                RegistrationError::AlreadyRegistered => 2003,
+                RegistrationError::NotEnoughEndorsements => 2004,
+                RegistrationError::AlreadyEndorsed => 2005,
+                RegistrationError::SelfEndorsement => 2006,
+                RegistrationError::TermLimitReached => 2007,
+                RegistrationError::IncorrectDeposit => 2008,
+                RegistrationError::Banned => 2009,
+                RegistrationError::CandidateCapReached => 2010,
+                RegistrationError::NotRegistered => 2011,
+                RegistrationError::NotAVoucher => 2012,
+                RegistrationError::AlreadyVouched => 2013,
+                RegistrationError::InvalidSlate => 2014,
+                RegistrationError::Reentrancy => 2015,
+                RegistrationError::TransferFailed => 2016,
+                RegistrationError::NameTooLong => 2017,
+                RegistrationError::RateLimited => 2018,
+                RegistrationError::CooldownActive => 2019,
+                RegistrationError::InvalidCode => 2020,
+                RegistrationError::NotReferred => 2021,
+                RegistrationError::AlreadyReferred => 2022,
+                RegistrationError::NotAReferrer => 2023,
+                RegistrationError::InvalidSignature => 2024,
+                RegistrationError::InvalidName => 2025,
+                RegistrationError::NameTaken => 2026,
+                RegistrationError::NameRejected => 2027,
+                RegistrationError::StorageBudgetExceeded => 2028,
+                RegistrationError::ElectionPaused => 2029,
+            }
+        }
+    }
+
+    /// Reasons an `ElectionConfig` is refused.
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum ConfigError {
+        /// `voting_closes_at` is not after `voting_opens_at`.
+        VotingClosesBeforeOpening,
+        /// `voting_closes_at` has already passed.
+        VotingClosesInPast,
+        /// The voting window is shorter than `MIN_VOTING_BLOCKS` or
+        /// `MIN_VOTING_MILLIS`.
+        VotingWindowTooShort,
+        /// The voting window is longer than `MAX_VOTING_BLOCKS` or
+        /// `MAX_VOTING_MILLIS`.
+        VotingWindowTooLong,
+        /// A fee exceeds the contract's `FeeLimits`.
+        FeeAboveMaximum,
+        /// The transferred value is not the creation fee plus the admin bond.
+        IncorrectCreationFee,
+        /// The weight cap allows no votes, or more than the whole supply.
+        InvalidWeightCap,
+        /// Ballots are weighted by more than one of an asset, a weight source
+        /// and a token.
+        ConflictingWeights,
+        /// The voting mode lets voters cast no ballot.
+        NoBallotsPerVoter,
+        /// The committee or single-transferable-vote election has no seats.
+        NoSeats,
+        /// `max_candidates` is below `min_candidates`, or below the seats to
+        /// fill.
+        CandidateCapTooLow,
+        /// The quorum exceeds the largest electorate the election can have,
+        /// `MAX_RING` voters for anonymous ballots.
+        QuorumUnreachable,
+        /// The campaign ends before it starts, or the election also has a
+        /// grace period.
+        InvalidCampaign,
+        /// The optimistic tally has no challenge period, so that a posted
+        /// tally would be adopted before anyone could prove it wrong.
+        NoChallengePeriod,
+        /// The voter lottery draws more than `MAX_LOTTERY_WINNERS`.
+        TooManyLotteryWinners,
+        /// Sortition draws a single winner, which a committee or
+        /// single-transferable-vote election does not have.
+        SortitionWithSeats,
+        /// The optimistic tally has no voting deadline to follow, or the
+        /// election must visit its ballots to be finalized.
+        UnsupportedOptimisticTally,
+        /// The tenure boost has no step length or no multiplier.
+        InvalidTenureBoost,
+        /// The storage sponsorship reimburses nothing.
+        InvalidSponsorship,
+        /// The deposit scaling raises no deposit, or there is no deposit to
+        /// scale.
+        InvalidDepositScaling,
+        /// The attendance tiers are out of order, outside 100 to
+        /// `MAX_ATTENDANCE_PERCENT` percent, or more than
+        /// `MAX_ATTENDANCE_TIERS`.
+        InvalidAttendanceBoost,
+        /// The electoral college does not give every constituency its
+        /// electors, or has more than `MAX_COLLEGE_CONSTITUENCIES`.
+        InvalidElectors,
+        /// The decryption committee cannot meet its threshold, or the
+        /// election has no voting deadline, takes other than plurality
+        /// ballots or refuses self-votes it could not see.
+        UnsupportedEncryptedBallots,
+        /// Anonymous ballots need a closed electorate and plurality ballots of
+        /// one vote each, and leave no voter to encrypt, draw, track or vet.
+        UnsupportedAnonymity,
+        /// A tally module takes neither ranked, posted, encrypted nor
+        /// anonymous ballots, and leaves no count to track participation by.
+        UnsupportedTallyModule,
+        /// Constituencies are tallied from ballots counted in the clear by
+        /// the contract, and not by transfer.
+        UnsupportedConstituencies,
+        /// The call was made from within a payout.
+        Reentrancy,
+    }
+
+    /// Fees the owner can change at runtime.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum FeeKind {
+        Vote,
+        CandidacyDeposit,
+        Creation,
+    }
+
+    /// Maximum fees, fixed at instantiation. Fees set at runtime are clamped to them.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct FeeLimits {
+        pub max_vote_fee: Balance,
+        pub max_candidacy_deposit: Balance,
+        pub max_creation_fee: Balance,
+    }
+
+    /// What `receive` does with value sent to the contract without naming
+    /// what it is for, fixed at instantiation.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum IncomingFunds {
+        /// The transfer is refused and the value stays with the sender.
+        #[default]
+        Reject,
+        /// The value is booked as treasury income.
+        Treasury,
+        /// The value is added to the prize pool of the election, until it
+        /// closes.
+        PrizePool(ElectionId),
+    }
+
+    /// Funds held by the contract, by what they are held for. Together they
+    /// are what the contract's balance must cover.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct TreasuryReport {
+        /// Vote and creation fees, including those of elections not finalized
+        /// yet, and everything forfeited to the treasury.
+        pub fee_income: Balance,
+        /// Prize and lottery pools, and prizes awarded but not released yet.
+        pub prize_escrow: Balance,
+        /// Candidacy and voter deposits, dispute, tally and admin bonds,
+        /// campaign funds and storage sponsorship pools.
+        pub deposits: Balance,
+        /// Funds credited to accounts and not claimed yet.
+        pub payouts: Balance,
+    }
+
+    /// Share of every election's collected fees and deposits that is routed to
+    /// `beneficiary` on finalization.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ProtocolFee {
+        pub beneficiary: AccountId,
+        /// At most `MAX_PROTOCOL_FEE_BPS`.
+        pub basis_points: u16,
+    }
+
+    /// A phase boundary, given either as a block number or as a block
+    /// timestamp in milliseconds.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum Moment {
+        Block(BlockNumber),
+        Time(Timestamp),
+    }
+
+    impl Moment {
+        /// Orders the current block (`block`, `time`) against this moment.
+        fn compare_now(&self, block: BlockNumber, time: Timestamp) -> Ordering {
+            match self {
+                Moment::Block(at) => block.cmp(at),
+                Moment::Time(at) => time.cmp(at),
+            }
+        }
+
+        /// Orders two moments of the same kind. A block number and a
+        /// timestamp cannot be ordered.
+        fn compare(&self, other: &Moment) -> Option<Ordering> {
+            match (self, other) {
+                (Moment::Block(a), Moment::Block(b)) => Some(a.cmp(b)),
+                (Moment::Time(a), Moment::Time(b)) => Some(a.cmp(b)),
+                _ => None,
+            }
+        }
+
+        /// Returns this moment pushed back by `blocks` blocks or `millis`
+        /// milliseconds, whichever it is given in.
+        fn delayed(&self, blocks: BlockNumber, millis: Timestamp) -> Moment {
+            match self {
+                Moment::Block(at) => Moment::Block(at.saturating_add(blocks)),
+                Moment::Time(at) => Moment::Time(at.saturating_add(millis)),
+            }
+        }
+    }
+
+    /// How ballots are cast and counted.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum VotingMode {
+        /// One ballot per voter for a single candidate; most votes wins.
+        #[default]
+        Plurality,
+        /// Every voter may cast one ballot for each of up to this many
+        /// distinct candidates; most votes wins.
+        Approval(u32),
+        /// One ballot per voter, split across candidates in per-mille shares
+        /// out of `SPLIT_BUDGET`. Tallies count thousandths of a vote.
+        Split,
+        /// One ranked ballot per voter, filling this many seats by single
+        /// transferable vote. Tallies count first preferences.
+        SingleTransferable(u32),
+        /// One ballot per voter for a single candidate, carrying up to this
+        /// many votes bought through `vote_quadratic` at `vote_fee` times
+        /// their number squared.
+        Quadratic(u16),
+        /// One ballot per voter giving distinct candidates between one and
+        /// this many points each; most points wins.
+        Score(u16),
+        /// One ballot per voter spreading up to this many whole votes over
+        /// distinct candidates; most votes wins.
+        Cumulative(u16),
+    }
+
+    impl VotingMode {
+        /// Ballots a single voter may cast.
+        fn ballots_per_voter(self) -> u32 {
+            match self {
+                Self::Plurality | Self::Split | Self::SingleTransferable(_) => 1,
+                Self::Approval(approvals) => approvals,
+                Self::Quadratic(most) | Self::Score(most) | Self::Cumulative(most) => {
+                    u32::from(most).min(1)
+                }
+            }
+        }
+
+        /// Whether a ballot may hand out shares of its weight to several
+        /// candidates.
+        fn allots(self) -> bool {
+            matches!(self, Self::Split | Self::Score(_) | Self::Cumulative(_))
+        }
+    }
+
+    /// Whether ballots may name accounts that are not on the ballot yet.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum CandidateList {
+        /// A ballot for an unknown account registers it as a candidate.
+        #[default]
+        OpenWriteIn,
+        /// Only ballots for registered candidates are accepted.
+        ClosedList,
+    }
+
+    /// How a tie for first place is settled.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum TieBreakPolicy {
+        /// All tied runners share the win.
+        #[default]
+        AllowTie,
+        /// The tied runner who entered the race first wins.
+        EarliestRunner,
+    }
+
+    /// How the winner is picked from the final tally.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum Outcome {
+        /// The runner with the most votes wins, ties settled by `tie_break`.
+        #[default]
+        MostVotes,
+        /// The winner is drawn at finalization, each runner with a chance
+        /// proportional to its votes. The seed hashes the election id, ballot
+        /// root, tally hash and finalization block, so anyone can recompute
+        /// the draw, but the last voter or the admin timing the finalization
+        /// can sway it: fit for rotating roles, not for contested seats.
+        Sortition,
+    }
+
+    /// Rules an election is run under, chosen at creation.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ElectionConfig {
+        /// Voting cannot open until at least this many candidates are running.
+        pub min_candidates: u32,
+        /// Most candidates that may register. Zero means unlimited.
+        pub max_candidates: u32,
+        /// Endorsements a candidate must collect before registering. While
+        /// nonzero, ballots for unregistered accounts are rejected as well.
+        pub endorsements_required: u32,
+        /// Whether a candidate must be referred by the admin or a verified
+        /// candidate, with `refer` or `redeem_referral`, before registering.
+        /// While set, ballots for unregistered accounts are rejected as well.
+        pub referral_required: bool,
+        pub candidate_list: CandidateList,
+        /// Whether only registered voters may vote.
+        pub closed_electorate: bool,
+        /// Rejects ballots cast by contract accounts, save for the aggregators
+        /// the owner approved with `set_aggregator`.
+        pub reject_contracts: bool,
+        /// Accepts ballots signed off-chain, counted in batches by
+        /// `settle_ballots`.
+        pub signed_ballots: bool,
+        /// Takes only anonymous ballots, cast with `vote_anonymous` under a
+        /// ring signature over the whole electorate.
+        pub anonymous: bool,
+        /// Deposit with which anyone may join a closed electorate. `None`
+        /// leaves registering voters to the admin.
+        pub voter_deposit: Option<Balance>,
+        /// Vouches from registered voters with which an account joins a closed
+        /// electorate. Vouching is disabled while it is zero.
+        pub vouches_required: u32,
+        /// Petition signatures needed to put the winner up for recall.
+        /// Recalls are disabled while it is zero.
+        pub recall_threshold: u32,
+        /// Blocks after finalization during which anyone may dispute the
+        /// result. Prize payouts wait for the window to lapse and for any
+        /// dispute to be resolved. Disputes are disabled while it is zero.
+        pub challenge_period: BlockNumber,
+        /// Bond a dispute must carry.
+        pub dispute_bond: Balance,
+        /// Contract implementing `Arbitrator` that disputes are escalated to
+        /// when raised. It alone rules on them, its ruling being applied by
+        /// `enforce_ruling`.
+        pub arbitrator: Option<AccountId>,
+        /// Consecutive terms after which the incumbent may not run again.
+        /// A nonzero limit also makes the election count as a term.
+        pub term_limit: u32,
+        /// Earliest moment at which voting may open.
+        pub voting_opens_at: Option<Moment>,
+        /// Last moment at which ballots are accepted. Finalization waits for it.
+        pub voting_closes_at: Option<Moment>,
+        pub mode: VotingMode,
+        /// Ballots needed for the election to be finalized.
+        pub quorum: u32,
+        /// Amount every ballot must carry.
+        pub vote_fee: Balance,
+        /// Amount every candidate must deposit when registering.
+        pub candidacy_deposit: Balance,
+        /// Raises the candidacy deposit with every candidate already running,
+        /// so that late registrations cost more than early ones.
+        pub deposit_scaling: Option<DepositScaling>,
+        /// Paid out of the collected vote fees, up to all of them, to whoever
+        /// finalizes the election once `voting_closes_at` has passed.
+        pub finalization_bounty: Balance,
+        /// Votes a candidate needs to be paid the campaign fund its supporters
+        /// escrowed with `fund_campaign`; short of it, they are refunded.
+        /// Campaign funds are disabled while it is zero.
+        pub campaign_threshold: u32,
+        /// PSP22 token the vote fee and candidacy deposit are paid in, pulled
+        /// with `transfer_from` out of the allowance the payer granted the
+        /// contract. They are paid in native currency while it is `None`.
+        pub fee_token: Option<AccountId>,
+        pub tie_break: TieBreakPolicy,
+        pub outcome: Outcome,
+        /// Whether who voted for whom may be queried.
+        pub public_ballot: bool,
+        /// Books the election into its voters' participation streaks when it
+        /// is finalized, which then visits every ballot.
+        pub track_participation: bool,
+        /// Notes the outcome with the runtime's governance through
+        /// `GovernanceExtension` once the result has taken effect.
+        pub mirror_outcome: bool,
+        /// Voters drawn after finalization to share the lottery pool. The
+        /// lottery is disabled while it is zero.
+        pub lottery_winners: u32,
+        /// Reimburses the storage deposit of ballots out of a pool the admin
+        /// funds with `fund_sponsorship`.
+        pub storage_sponsorship: Option<StorageSponsorship>,
+        /// Multiplies ballot weight by one plus the voter's reputation.
+        pub reputation_weighted: bool,
+        /// Multiplies ballot weight by how long the voter has been registered
+        /// under an identity.
+        pub tenure_boost: Option<TenureBoost>,
+        /// Multiplies ballot weight by the highest tier the voter's past
+        /// ballots, as counted by `participation_of`, reach. Tiers are
+        /// ordered by `min_ballots`, between 100 and `MAX_ATTENDANCE_PERCENT`
+        /// percent, and at most `MAX_ATTENDANCE_TIERS`.
+        pub attendance_boost: Vec<AttendanceTier>,
+        /// Weights ballots by the voter's holding of a `pallet-assets` asset.
+        pub asset_weight: Option<AssetWeight>,
+        /// Weights ballots by what an external calculator says, in place of
+        /// the asset holding.
+        pub weight_source: Option<WeightSource>,
+        /// Weights ballots by the voter's balance of a checkpointed PSP22
+        /// token at a snapshot block, in place of the asset holding.
+        pub token_weight: Option<TokenWeight>,
+        /// Elects a committee from party slates instead of a single winner.
+        pub committee: Option<Committee>,
+        /// Lets a designated proposer post the final tally instead of having
+        /// it counted on-chain.
+        pub optimistic_tally: Option<OptimisticTally>,
+        /// Takes ballots encrypted to an election key instead of plain ones,
+        /// revealed once a decryption committee has posted its key shares.
+        pub encrypted_ballots: Option<EncryptedBallots>,
+        /// Contract implementing `TallyModule` that counts the ballots in
+        /// place of the built-in tallies, which stay empty.
+        pub tally_module: Option<AccountId>,
+        /// Contract implementing `EligibilitySource` that every voter must
+        /// pass, on top of the closed electorate if there is one.
+        pub eligibility_source: Option<AccountId>,
+        /// Contract implementing `ElectionHook` told about every counted
+        /// vote and the final outcome.
+        pub hook: Option<AccountId>,
+        /// Most ballots accepted in one block; the rest are refused with
+        /// `RateLimited` and must be cast again later. Zero means unlimited.
+        pub ballots_per_block: u32,
+        /// Most candidates that may register in one block. Zero means
+        /// unlimited.
+        pub registrations_per_block: u32,
+        /// Blocks an account must wait after registering as a candidate, in
+        /// any election, before registering in this one.
+        pub registration_cooldown: BlockNumber,
+        /// Blocks a candidate must wait after setting its profile before
+        /// replacing it, so that no one can take on another identity just
+        /// before the vote. Zero means no wait.
+        pub profile_cooldown: BlockNumber,
+        /// Storage entries the election's registrations and ballots may
+        /// create before self-service registrations and ballots for new
+        /// candidates are refused. Unlimited while it is zero.
+        pub storage_budget: u32,
+        /// Whether ballots for the voter's own account are refused.
+        pub forbid_self_votes: bool,
+        /// Whether registered candidates are refused a ballot.
+        pub candidates_barred: bool,
+        /// Constituencies voters may be assigned to, each tallied on its own
+        /// besides the election as a whole. Zero tallies the whole only.
+        pub constituencies: u32,
+        /// Elector weight of each constituency, in constituency order. When
+        /// set, a constituency's sole winner takes all its electors and the
+        /// election is won on electors rather than votes; electors of a
+        /// constituency tied or without votes go to nobody.
+        pub electors: Vec<u32>,
+        /// Topic the election is about. Delegations on that topic take
+        /// precedence over general ones.
+        pub topic: Option<TopicId>,
+        /// Whether runners are kept sorted by votes as ballots come in, so
+        /// that `get_rank` reads one entry instead of every runner. Ignored
+        /// by an electoral college, which ranks on electors.
+        pub track_standings: bool,
+        /// Whether counts stay hidden until voting closes, against
+        /// bandwagon and strategic voting: vote queries report no votes and
+        /// no winner meanwhile, and no `TallyUpdated` is emitted. Ballot
+        /// events still name the candidate unless ballots are encrypted.
+        pub blind_results: bool,
+        /// Whether ballots go unannounced, for polls where taking part is
+        /// itself sensitive: each ballot emits only an anonymous
+        /// `BallotCounted` with the turnout so far, in place of the events
+        /// naming the voter. Votes are still found through the queries.
+        pub quiet_events: bool,
+        /// Blocks between `close_registration` and the earliest
+        /// `open_voting`, during which the candidate list stays frozen for
+        /// anyone to audit. While nonzero, voting only opens from
+        /// `SlateFrozen`.
+        pub grace_period: BlockNumber,
+        /// Campaign between registration and voting, during which candidates
+        /// post statements and no ballots are accepted.
+        pub campaign: Option<CampaignPhase>,
+    }
+
+    /// Decryption committee of an election with encrypted ballots. Ballots
+    /// are encrypted to `public_key`, whose secret is shared among
+    /// `trustees`. Once voting closes, any `threshold` of them posting their
+    /// shares lets `reveal_ballots` decrypt and count the ballots through
+    /// `DecryptionExtension`, so that no interim result is ever public.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct EncryptedBallots {
+        pub public_key: [u8; 32],
+        pub trustees: Vec<AccountId>,
+        pub threshold: u32,
+    }
+
+    /// An encrypted ballot waiting to be revealed.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct SealedBallot {
+        pub voter: AccountId,
+        /// Votes the ballot is worth, fixed when it was cast.
+        pub weight: u32,
+        /// SCALE-encoded candidate, encrypted to the election key.
+        pub ciphertext: Vec<u8>,
+    }
+
+    /// Terms under which the tally of an election is posted rather than
+    /// counted. Once voting closes, `proposer` posts the tally with `bond`.
+    /// Until `challenge_period` blocks have passed, anyone may prove a
+    /// runner's total wrong against the on-chain count, taking the bond and
+    /// sending the election back to on-chain counting. After that, the tally
+    /// finalizes the election and the bond is returned.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct OptimisticTally {
+        pub proposer: AccountId,
+        pub bond: Balance,
+        pub challenge_period: BlockNumber,
+    }
+
+    /// A tally posted for an optimistic election.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct TallyClaim {
+        pub proposer: AccountId,
+        pub bond: Balance,
+        /// Claimed total of every runner, in the order they entered the race.
+        pub totals: Vec<(AccountId, u32)>,
+        pub proposed_at: BlockNumber,
+    }
+
+    /// Seats of a committee and how they are shared among party slates.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Committee {
+        pub seats: u32,
+        pub method: Apportionment,
+    }
+
+    /// Highest-averages method allocating committee seats to slates.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum Apportionment {
+        /// Divisors 1, 2, 3, ...
+        DHondt,
+        /// Divisors 1, 3, 5, ...
+        SainteLague,
+    }
+
+    impl Apportionment {
+        /// Divisor of a slate's votes once it holds `seats` seats.
+        fn divisor(self, seats: u32) -> u64 {
+            match self {
+                Self::DHondt => u64::from(seats) + 1,
+                Self::SainteLague => 2 * u64::from(seats) + 1,
+            }
+        }
+    }
+
+    /// Weighting of ballots by a `pallet-assets` holding.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct AssetWeight {
+        pub asset_id: AssetId,
+        /// Voters holding less cannot vote.
+        pub min_holding: Balance,
+        /// Holding worth one vote; zero counts every unit.
+        pub unit: Balance,
+        /// Most votes a single ballot may carry, whatever the holding.
+        pub max_weight: Option<WeightCap>,
+    }
+
+    /// External contract implementing `WeightCalculator` that supplies the
+    /// weight of each ballot.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct WeightSource {
+        pub calculator: AccountId,
+        /// Block whose weights are asked for; `None` asks for the block each
+        /// ballot is cast in.
+        pub snapshot_block: Option<BlockNumber>,
+    }
+
+    /// Weighting of ballots by the balance of a checkpointed PSP22 token, as
+    /// the token recorded it at `snapshot_block`, so that tokens moved after
+    /// the snapshot carry no weight.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct TokenWeight {
+        /// Contract implementing `Psp22Checkpoints`.
+        pub token: AccountId,
+        pub snapshot_block: BlockNumber,
+        /// Voters holding less cannot vote.
+        pub min_holding: Balance,
+        /// Holding worth one vote; zero counts every unit.
+        pub unit: Balance,
+    }
+
+    /// Boost of ballot weight growing with the voter's registration tenure.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct TenureBoost {
+        /// Blocks of continuous registration that raise the multiplier by one.
+        pub blocks_per_step: BlockNumber,
+        /// Highest multiplier, reached however long the tenure.
+        pub max_multiplier: u32,
+    }
+
+    /// Growth of the candidacy deposit with the size of the field: every
+    /// runner beyond the first `free_candidates` adds `percent_per_candidate`
+    /// percent of `candidacy_deposit` to what the next candidate deposits.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct DepositScaling {
+        pub free_candidates: u32,
+        pub percent_per_candidate: u32,
+    }
+
+    /// Terms on which the storage deposit of an election's ballots is paid
+    /// back to whoever cast them, so that voting costs participants nothing.
+    /// Encrypted and anonymous ballots are not reimbursed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct StorageSponsorship {
+        /// Credited to the caller of every ballot, at least the storage
+        /// deposit the entries of one ballot take.
+        pub per_ballot: Balance,
+        /// Whether ballots are refused once the pool cannot cover them;
+        /// otherwise they are cast at the caller's expense.
+        pub required: bool,
+    }
+
+    /// Pool paying back the storage deposit of an election's ballots.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Sponsorship {
+        /// Left to reimburse ballots with.
+        pub pool: Balance,
+        /// Reimbursed so far.
+        pub spent: Balance,
+        /// Ballots reimbursed so far.
+        pub ballots: u32,
+    }
+
+    /// Tier of an attendance boost: ballots of voters who cast at least
+    /// `min_ballots` ballots before weigh `percent` percent of their votes.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct AttendanceTier {
+        pub min_ballots: u32,
+        pub percent: u32,
+    }
+
+    /// Blocks bounding the campaign of an election. Once `starts_at` is
+    /// reached registration closes and the campaign begins; once `ends_at`
+    /// is, voting opens. Both happen on the first call that finds the
+    /// threshold passed, or through `advance_phase`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct CampaignPhase {
+        pub starts_at: BlockNumber,
+        pub ends_at: BlockNumber,
+    }
+
+    /// Upper bound on the weight of one ballot.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum WeightCap {
+        /// At most this many votes.
+        Votes(u32),
+        /// At most the votes this percentage of the asset's total supply is
+        /// worth, read when the ballot is cast.
+        SupplyPercent(u8),
+    }
+
+    impl ElectionConfig {
+        /// Returns the deposit a candidate registering next to `running`
+        /// others must make.
+        fn candidacy_deposit_for(&self, running: usize) -> Balance {
+            let Some(scaling) = self.deposit_scaling else {
+                return self.candidacy_deposit;
+            };
+            let beyond = u32::try_from(running)
+                .unwrap_or(u32::MAX)
+                .saturating_sub(scaling.free_candidates);
+            let percent =
+                Balance::from(beyond).saturating_mul(scaling.percent_per_candidate.into());
+            self.candidacy_deposit
+                .saturating_add(self.candidacy_deposit.saturating_mul(percent) / 100)
+        }
+
+        /// Whether candidacy is restricted, so that ballots may only name
+        /// registered candidates.
+        fn candidacy_gated(&self) -> bool {
+            self.candidate_list == CandidateList::ClosedList
+                || self.endorsements_required > 0
+                || self.referral_required
+        }
+
+        /// Checks that the configuration describes a runnable election.
+        fn validate(
+            &self,
+            limits: &FeeLimits,
+            block: BlockNumber,
+            time: Timestamp,
+        ) -> Result<(), ConfigError> {
+            if self.vote_fee > limits.max_vote_fee
+                || self.candidacy_deposit > limits.max_candidacy_deposit
+            {
+                return Err(ConfigError::FeeAboveMaximum);
+            }
+            if let Some(closes_at) = self.voting_closes_at {
+                if self
+                    .voting_opens_at
+                    .and_then(|opens_at| opens_at.compare(&closes_at))
+                    .is_some_and(|order| order != Ordering::Less)
+                {
+                    return Err(ConfigError::VotingClosesBeforeOpening);
+                }
+                if closes_at.compare_now(block, time) == Ordering::Greater {
+                    return Err(ConfigError::VotingClosesInPast);
+                }
+                let (window, min, max) = match (closes_at, self.voting_opens_at) {
+                    (Moment::Block(closes), Some(Moment::Block(opens))) => (
+                        u64::from(closes.saturating_sub(opens.max(block))),
+                        u64::from(MIN_VOTING_BLOCKS),
+                        u64::from(MAX_VOTING_BLOCKS),
+                    ),
+                    (Moment::Block(closes), _) => (
+                        u64::from(closes.saturating_sub(block)),
+                        u64::from(MIN_VOTING_BLOCKS),
+                        u64::from(MAX_VOTING_BLOCKS),
+                    ),
+                    (Moment::Time(closes), Some(Moment::Time(opens))) => (
+                        closes.saturating_sub(opens.max(time)),
+                        MIN_VOTING_MILLIS,
+                        MAX_VOTING_MILLIS,
+                    ),
+                    (Moment::Time(closes), _) => (
+                        closes.saturating_sub(time),
+                        MIN_VOTING_MILLIS,
+                        MAX_VOTING_MILLIS,
+                    ),
+                };
+                if window < min {
+                    return Err(ConfigError::VotingWindowTooShort);
+                }
+                if window > max {
+                    return Err(ConfigError::VotingWindowTooLong);
+                }
+            }
+            if self.mode.ballots_per_voter() == 0 {
+                return Err(ConfigError::NoBallotsPerVoter);
+            }
+            if self.committee.is_some_and(|committee| committee.seats == 0)
+                || self.mode == VotingMode::SingleTransferable(0)
+            {
+                return Err(ConfigError::NoSeats);
+            }
+            let seats = match self.mode {
+                VotingMode::SingleTransferable(seats) => seats,
+                _ => self.committee.map_or(1, |committee| committee.seats),
+            };
+            if self.max_candidates > 0
+                && (self.max_candidates < self.min_candidates || self.max_candidates < seats)
+            {
+                return Err(ConfigError::CandidateCapTooLow);
+            }
+            if self.lottery_winners > MAX_LOTTERY_WINNERS {
+                return Err(ConfigError::TooManyLotteryWinners);
+            }
+            if self.outcome == Outcome::Sortition
+                && (self.committee.is_some()
+                    || matches!(self.mode, VotingMode::SingleTransferable(_)))
+            {
+                return Err(ConfigError::SortitionWithSeats);
+            }
+            if self
+                .tenure_boost
+                .is_some_and(|boost| boost.blocks_per_step == 0 || boost.max_multiplier == 0)
+            {
+                return Err(ConfigError::InvalidTenureBoost);
+            }
+            if self
+                .storage_sponsorship
+                .is_some_and(|sponsorship| sponsorship.per_ballot == 0)
+            {
+                return Err(ConfigError::InvalidSponsorship);
+            }
+            if self.deposit_scaling.is_some_and(|scaling| {
+                scaling.percent_per_candidate == 0 || self.candidacy_deposit == 0
+            }) {
+                return Err(ConfigError::InvalidDepositScaling);
+            }
+            if self.attendance_boost.len() > MAX_ATTENDANCE_TIERS
+                || self
+                    .attendance_boost
+                    .iter()
+                    .any(|tier| !(100..=MAX_ATTENDANCE_PERCENT).contains(&tier.percent))
+                || self
+                    .attendance_boost
+                    .windows(2)
+                    .any(|pair| pair[0].min_ballots >= pair[1].min_ballots)
+            {
+                return Err(ConfigError::InvalidAttendanceBoost);
+            }
+            if self.optimistic_tally.is_some()
+                && (self.voting_closes_at.is_none()
+                    || self.track_participation
+                    || matches!(self.mode, VotingMode::SingleTransferable(_)))
+            {
+                return Err(ConfigError::UnsupportedOptimisticTally);
+            }
+            if self.campaign.is_some_and(|campaign| {
+                campaign.ends_at <= campaign.starts_at || self.grace_period > 0
+            }) {
+                return Err(ConfigError::InvalidCampaign);
+            }
+            if self
+                .optimistic_tally
+                .as_ref()
+                .is_some_and(|optimistic| optimistic.challenge_period == 0)
+            {
+                return Err(ConfigError::NoChallengePeriod);
+            }
+            if self.anonymous
+                && (!self.closed_electorate
+                    || self.mode != VotingMode::Plurality
+                    || self.reputation_weighted
+                    || self.tenure_boost.is_some()
+                    || !self.attendance_boost.is_empty()
+                    || self.asset_weight.is_some()
+                    || self.weight_source.is_some()
+                    || self.token_weight.is_some()
+                    || self.encrypted_ballots.is_some()
+                    || self.track_participation
+                    || self.lottery_winners > 0
+                    || self.eligibility_source.is_some()
+                    || self.forbid_self_votes
+                    || self.candidates_barred)
+            {
+                return Err(ConfigError::UnsupportedAnonymity);
+            }
+            if self.anonymous && self.quorum as usize > MAX_RING {
+                return Err(ConfigError::QuorumUnreachable);
+            }
+            if self.tally_module.is_some()
+                && (matches!(self.mode, VotingMode::SingleTransferable(_))
+                    || self.optimistic_tally.is_some()
+                    || self.encrypted_ballots.is_some()
+                    || self.anonymous
+                    || self.track_participation)
+            {
+                return Err(ConfigError::UnsupportedTallyModule);
+            }
+            if let Some(encrypted) = &self.encrypted_ballots {
+                if encrypted.threshold == 0
+                    || encrypted.threshold as usize > encrypted.trustees.len()
+                    || encrypted.trustees.len() > MAX_TRUSTEES
+                    || self.voting_closes_at.is_none()
+                    || self.mode != VotingMode::Plurality
+                    || self.optimistic_tally.is_some()
+                    || self.forbid_self_votes
+                {
+                    return Err(ConfigError::UnsupportedEncryptedBallots);
+                }
+            }
+            if self.constituencies > 0
+                && (matches!(self.mode, VotingMode::SingleTransferable(_))
+                    || self.tally_module.is_some()
+                    || self.encrypted_ballots.is_some()
+                    || self.anonymous)
+            {
+                return Err(ConfigError::UnsupportedConstituencies);
+            }
+            if !self.electors.is_empty()
+                && (self.electors.len() != self.constituencies as usize
+                    || self.electors.len() > MAX_COLLEGE_CONSTITUENCIES)
+            {
+                return Err(ConfigError::InvalidElectors);
+            }
+            let weightings = [
+                self.asset_weight.is_some(),
+                self.weight_source.is_some(),
+                self.token_weight.is_some(),
+            ];
+            if weightings.into_iter().filter(|weighted| *weighted).count() > 1 {
+                return Err(ConfigError::ConflictingWeights);
+            }
+            if let Some(cap) = self.asset_weight.and_then(|weight| weight.max_weight) {
+                if matches!(cap, WeightCap::Votes(0) | WeightCap::SupplyPercent(0))
+                    || matches!(cap, WeightCap::SupplyPercent(percent) if percent > 100)
+                {
+                    return Err(ConfigError::InvalidWeightCap);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// Phase of a recall against an election's winner.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum RecallStatus {
+        /// Collecting petition signatures.
+        Petition,
+        /// Enough signatures: the yes/no recall ballot is open.
+        Voting,
+        /// The winner was recalled and the seat is vacant.
+        Succeeded,
+        /// The winner keeps the seat.
+        Failed,
+    }
+
+    /// A recall against the winner of a finalized election.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Recall {
+        pub target: AccountId,
+        pub status: RecallStatus,
+        pub signatures: u32,
+        pub yes: u32,
+        pub no: u32,
+    }
+
+    /// An open dispute against the result of a finalized election.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Dispute {
+        pub challenger: AccountId,
+        pub bond: Balance,
+        pub raised_at: BlockNumber,
+    }
+
+    /// Voter lottery of an election: a pool shared among voters drawn with a
+    /// seed the admin committed to before finalization.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Lottery {
+        /// Blake2x256 hash of the seed.
+        pub commitment: Option<Hash>,
+        pub pool: Balance,
+        /// Revealed with the draw.
+        pub seed: Option<Hash>,
+        pub winners: Vec<AccountId>,
+    }
+
+    /// How a dispute against an election's result is settled.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Ruling {
+        /// The result is voided and the bond returned to the challenger.
+        Upheld,
+        /// The result stands and the bond goes to the treasury.
+        Dismissed,
+        /// The candidate is banned and struck from the winners, forfeiting
+        /// any prize, and the bond is returned to the challenger. Its votes
+        /// stay in the totals; a disqualified winner's seat goes to the next
+        /// leader only if no other winner remains.
+        Disqualified(AccountId),
+    }
+
+    /// Phase of an upgrade referendum.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum ReferendumStatus {
+        /// The yes/no ballot is open.
+        Voting,
+        /// The code hash may be deployed.
+        Approved,
+        /// The code hash may not be deployed.
+        Rejected,
+    }
+
+    /// Public details a candidate publishes about itself.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct CandidateProfile {
+        /// Display name, in UTF-8.
+        pub name: Vec<u8>,
+        /// Hash of the candidate's manifesto, published off-chain.
+        pub manifesto: Hash,
+        pub updated_at: BlockNumber,
+        /// Earlier profiles, kept in `get_profile_history`.
+        pub revisions: u32,
+    }
+
+    /// A profile a candidate replaced, and when it was in effect.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ProfileRevision {
+        pub name: Vec<u8>,
+        pub manifesto: Hash,
+        pub updated_at: BlockNumber,
+        pub replaced_at: BlockNumber,
+    }
+
+    /// One ballot in an account's participation record.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct VoteRecord {
+        pub election_id: ElectionId,
+        pub ballot_id: BallotId,
+        /// Candidate voted for, recorded only for public-ballot elections
+        /// and ballots that are not split.
+        pub candidate: Option<AccountId>,
+    }
+
+    /// An account's record in participation-tracked elections, which count
+    /// as consecutive rounds in the order they are finalized.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Participation {
+        /// Tracked elections voted in.
+        pub elections: u32,
+        /// Rounds in a row voted in, up to `last_round`.
+        pub streak: u32,
+        pub best: u32,
+        pub last_round: u32,
+    }
+
+    /// How much an account has taken part in elections of this contract,
+    /// tracked or not.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct ParticipationScore {
+        /// Ballots cast, one per election voted in.
+        pub voted: u32,
+        /// Elections won outright.
+        pub won: u32,
+        /// Races entered as a candidate.
+        pub ran: u32,
+    }
+
+    /// A ballot for `candidate` signed off-chain by the voter's ECDSA key
+    /// over `signed_ballot_hash`. The voter is the account of the key: the
+    /// BLAKE2-256 hash of its compressed public key, as in Substrate.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct SignedBallot {
+        pub candidate: AccountId,
+        /// The voter's `get_ballot_nonce` when the ballot is settled.
+        pub nonce: u64,
+        pub signature: [u8; 65],
+    }
+
+    /// What a ballot is cast for. Each kind is only accepted by the
+    /// `VotingMode` it is named after, except that every mode takes a
+    /// `Single` ballot.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum BallotChoice {
+        /// A single candidate, given the whole ballot: a ranking of one, all
+        /// points of a score ballot, all votes of a cumulative one.
+        Single(AccountId),
+        /// Per-mille shares of a split ballot.
+        Split(Vec<(AccountId, u16)>),
+        /// Candidates in order of preference.
+        Ranked(Vec<AccountId>),
+        /// Votes bought for a candidate.
+        Quadratic(AccountId, u16),
+        /// Points given to each candidate.
+        Scored(Vec<(AccountId, u16)>),
+        /// Whole votes given to each candidate.
+        Cumulative(Vec<(AccountId, u16)>),
+    }
+
+    /// Where a candidate stands in a single-transferable-vote count.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    enum Standing {
+        Hopeful,
+        /// Elected with `total` votes, of which `surplus` pass on to next
+        /// preferences.
+        Elected {
+            surplus: u128,
+            total: u128,
+        },
+        Excluded,
+    }
+
+    /// Progress of a single-transferable-vote count across `finalize_step`
+    /// calls. Vote values are in `STV_UNIT`s.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    struct StvCount {
+        candidates: Vec<AccountId>,
+        standing: Vec<Standing>,
+        /// Votes of each candidate counted so far in the current pass.
+        tallies: Vec<u128>,
+        /// Next ballot to visit in the current pass.
+        cursor: u32,
+        quota: u128,
+        elected: Vec<AccountId>,
+        rounds: u32,
+    }
+
+    impl StvCount {
+        /// Starts a count of `totals`, with the Droop quota for `seats`.
+        fn new(totals: &[(AccountId, u32)], seats: u32) -> Self {
+            let total = totals
+                .iter()
+                .map(|(_, votes)| u128::from(*votes))
+                .sum::<u128>()
+                .saturating_mul(STV_UNIT);
+            Self {
+                candidates: totals.iter().map(|(candidate, _)| *candidate).collect(),
+                standing: ink::prelude::vec![Standing::Hopeful; totals.len()],
+                tallies: ink::prelude::vec![0; totals.len()],
+                cursor: 0,
+                quota: counting::droop_quota(total, seats),
+                elected: Vec::new(),
+                rounds: 0,
+            }
+        }
+
+        /// Settles the pass just completed: elects the leading candidate if
+        /// it reaches the quota and excludes the last one otherwise, ties
+        /// going against the later runner. Returns whether every seat is
+        /// filled.
+        fn settle(&mut self, seats: u32) -> bool {
+            self.rounds = self.rounds.saturating_add(1);
+            if self.fill_remaining(seats) {
+                return true;
+            }
+            match counting::settle_pass(&self.tallies, &self.hopeful(), self.quota) {
+                Some(Settlement::Elect(leader)) => {
+                    let total = self.tallies[leader];
+                    self.standing[leader] = Standing::Elected {
+                        surplus: total - self.quota,
+                        total,
+                    };
+                    self.elected.push(self.candidates[leader]);
+                }
+                Some(Settlement::Exclude(last)) => self.standing[last] = Standing::Excluded,
+                None => {}
+            }
+            self.fill_remaining(seats)
+        }
+
+        /// Elects every hopeful candidate, most votes first, once no more of
+        /// them are left than seats. Returns whether every seat is filled.
+        fn fill_remaining(&mut self, seats: u32) -> bool {
+            if self.elected.len() >= seats as usize {
+                return true;
+            }
+            let mut hopeful = self.hopeful();
+            if self.elected.len() + hopeful.len() > seats as usize {
+                return false;
+            }
+            hopeful.sort_by(|a, b| self.tallies[*b].cmp(&self.tallies[*a]));
+            for index in hopeful {
+                self.standing[index] = Standing::Elected {
+                    surplus: 0,
+                    total: self.tallies[index].max(1),
+                };
+                self.elected.push(self.candidates[index]);
+            }
+            true
+        }
+
+        fn hopeful(&self) -> Vec<usize> {
+            (0..self.candidates.len())
+                .filter(|index| self.standing[*index] == Standing::Hopeful)
+                .collect()
+        }
+    }
+
+    /// A yes/no ballot on upgrading the contract to `code_hash`.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Referendum {
+        pub code_hash: Hash,
+        pub status: ReferendumStatus,
+        pub yes: u32,
+        pub no: u32,
+    }
+
+    /// A signaling survey: answers are counted per option and nobody is
+    /// elected.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Survey {
+        /// The account that created the survey and may close it.
+        pub creator: AccountId,
+        /// Hash of the question, published elsewhere.
+        pub question: Hash,
+        /// Option labels, in UTF-8.
+        pub options: Vec<Vec<u8>>,
+        /// Answers given to each option, in option order.
+        pub counts: Vec<u32>,
+        pub open: bool,
+    }
+
+    /// An administrative operation that, while a timelock is set, must be
+    /// scheduled with `schedule_action` and wait out the delay.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum AdminAction {
+        /// `set_vote_fee`.
+        VoteFee(ElectionId, Balance),
+        /// `set_candidacy_deposit`.
+        CandidacyDeposit(ElectionId, Balance),
+        /// `set_creation_fee`.
+        CreationFee(Balance),
+        /// `ban_candidate`.
+        BanCandidate(ElectionId, AccountId),
+        /// `upgrade`.
+        Upgrade(Hash),
+        /// Lowers the timelock delay; raising it needs no scheduling.
+        TimelockDelay(BlockNumber),
+        /// `delegate_logic`.
+        DelegateLogic(Hash),
+    }
+
+    /// An administrative operation waiting out the timelock.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ScheduledAction {
+        pub action: AdminAction,
+        /// The account that scheduled it, the only one that may execute or
+        /// cancel it.
+        pub proposer: AccountId,
+        /// First block it may be executed in.
+        pub executable_at: BlockNumber,
+    }
+
+    /// Compact record of a finalized election, kept after its detailed
+    /// per-voter state is pruned.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ArchivedResult {
+        pub winners: Vec<AccountId>,
+        pub totals: Vec<(AccountId, u32)>,
+        pub turnout: u32,
+        pub finalized_at: BlockNumber,
+    }
+
+    /// Ballots cast in an election, counted by the block range they were cast
+    /// in. Ranges start `bucket_blocks` wide; whenever more than
+    /// `MAX_HISTOGRAM_BUCKETS` ranges hold ballots, the width doubles and
+    /// neighbouring ranges merge, so that long elections stay compact.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct TurnoutHistogram {
+        pub bucket_blocks: BlockNumber,
+        /// First block of every range holding ballots, with their number, in
+        /// block order.
+        pub buckets: Vec<(BlockNumber, u32)>,
+    }
+
+    impl Default for TurnoutHistogram {
+        fn default() -> Self {
+            Self {
+                bucket_blocks: HISTOGRAM_BUCKET_BLOCKS,
+                buckets: Vec::new(),
+            }
+        }
+    }
+
+    impl TurnoutHistogram {
+        /// Counts a ballot cast in `block`.
+        fn count(&mut self, block: BlockNumber) {
+            let start = block - block % self.bucket_blocks;
+            match self.buckets.last_mut() {
+                Some((last, ballots)) if *last == start => *ballots = ballots.saturating_add(1),
+                _ => self.buckets.push((start, 1)),
+            }
+            while self.buckets.len() > MAX_HISTOGRAM_BUCKETS {
+                self.bucket_blocks = self.bucket_blocks.saturating_mul(2);
+                let mut merged: Vec<(BlockNumber, u32)> = Vec::with_capacity(self.buckets.len());
+                for (start, ballots) in core::mem::take(&mut self.buckets) {
+                    let start = start - start % self.bucket_blocks;
+                    match merged.last_mut() {
+                        Some((last, total)) if *last == start => {
+                            *total = total.saturating_add(ballots)
+                        }
+                        _ => merged.push((start, ballots)),
+                    }
+                }
+                self.buckets = merged;
+            }
+        }
+    }
+
+    /// Per-election bookkeeping.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    struct Election {
+        admin: AccountId,
+        config: ElectionConfig,
+        status: ElectionStatus,
+        turnout: u32,
+        finalized_at: Option<BlockNumber>,
+        /// Vote fees collected so far.
+        fee_pot: Balance,
+        /// Funds paid out to the winners on finalization.
+        prize_pool: Balance,
+        /// Candidacy deposits collected so far.
+        deposit_pot: Balance,
+        /// Registered voters, for closed-electorate elections.
+        electorate: u32,
+        /// Tips added to the finalization bounty, in the native currency.
+        bounty_tips: Balance,
+        /// Block at which registration was closed, freezing the slate.
+        slate_frozen_at: Option<BlockNumber>,
+        /// Storage entries created by registrations and ballots, counted
+        /// against `storage_budget`: one per candidate, voter and ballot.
+        storage_entries: u32,
+        /// Bond the admin posted at creation and has not been released or
+        /// slashed yet.
+        admin_bond: Balance,
+        /// Block number and timestamp at which the election was paused, while
+        /// it is.
+        paused_at: Option<(BlockNumber, Timestamp)>,
+    }
+
+    impl Election {
+        /// Whether the election used up its storage budget.
+        fn storage_exhausted(&self) -> bool {
+            self.config.storage_budget > 0 && self.storage_entries >= self.config.storage_budget
+        }
+    }
+
+    /// Complete, self-describing outcome of an election.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct ElectionResult {
+        pub status: ElectionStatus,
+        /// Most votes first, then in ascending account order.
+        pub winners: Vec<AccountId>,
+        pub runner_up: Vec<AccountId>,
+        pub margin: u32,
+        /// Votes of every runner, in the order they entered the race.
+        pub totals: Vec<(AccountId, u32)>,
+        /// Number of ballots cast.
+        pub turnout: u32,
+        /// Block at which the election was finalized, if it was.
+        pub finalized_at: Option<BlockNumber>,
+    }
+
+    /// Aggregate statistics of an election's vote distribution.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct ElectionStats {
+        /// Number of ballots cast.
+        pub turnout: u32,
+        pub candidates: u32,
+        /// Votes of all runners together.
+        pub total_votes: u64,
+        /// Votes per runner, rounded down.
+        pub mean_votes: u32,
+        /// Middle of the runners' votes, the lower middle for an even count.
+        pub median_votes: u32,
+        /// The leader's part of all votes, in per-mille, rounded down.
+        pub leader_share: u16,
+        /// Runners without a single vote.
+        pub zero_vote_candidates: u32,
+    }
+
+    /// What a frontend shows of a candidate.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub struct CandidateInfo {
+        pub candidate: AccountId,
+        pub votes: u32,
+        /// Whether the moderator vetted the candidate and its profile.
+        pub verified: bool,
+        pub profile: Option<CandidateProfile>,
+        /// Who referred the candidate, when the election required it.
+        pub referred_by: Option<AccountId>,
+        /// The candidate's name, as `resolve_name` gives it.
+        pub display_name: Option<Vec<u8>>,
+    }
+
+    impl Default for Voting {
+        fn default() -> Self {
+            Voting::new()
+        }
+    }
+
+    /// Every message carries an explicit selector, the one ink! derived
+    /// from its name when it was added, and these selectors are the frozen
+    /// ABI: a renamed or moved message keeps its selector, the selector of a
+    /// removed message is never reused, and a changed signature comes as a
+    /// new message instead.
+    impl Voting {
+        /// Constructor that opens election `0` for voting right away.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut voting = Self::empty();
+            let election_id =
+                voting.insert_election(Self::env().caller(), ElectionConfig::default());
+            voting.update_election(election_id, |election| {
+                election.status = ElectionStatus::Voting
+            });
+            voting.elections_voting = 1;
+            Self::env().emit_event(PhaseChanged {
+                election_id,
+                from: ElectionStatus::Pending,
+                to: ElectionStatus::Voting,
+            });
+            voting
+        }
+
+        /// Constructor that opens election `0` for voting right away, with
+        /// `candidates` already on the ballot. Duplicates are ignored.
+        #[ink(constructor)]
+        pub fn new_with_candidates(candidates: Vec<AccountId>) -> Self {
+            let mut voting = Self::new();
+            let mut runners = Vec::new();
+            for candidate in candidates {
+                if !runners.contains(&candidate) {
+                    runners.push(candidate);
+                    voting.count_candidacy(candidate);
+                }
+            }
+            for candidate in &runners {
+                Self::env().emit_event(CandidateRegistered {
+                    election_id: 0,
+                    candidate: *candidate,
+                    deposit: 0,
+                });
+            }
+            voting.runners.insert(0, &runners);
+            voting
+        }
+
+        /// Constructor that creates election `0` as `Pending` under `config`,
+        /// refusing to instantiate if the configuration is invalid.
+        ///
+        /// `fee_limits` bounds every fee for the lifetime of the contract.
+        #[ink(constructor)]
+        pub fn new_with_config(
+            config: ElectionConfig,
+            fee_limits: FeeLimits,
+        ) -> Result<Self, ConfigError> {
+            let mut voting = Self::empty();
+            voting.fee_limits = fee_limits;
+            voting.validate_config(&config)?;
+            voting.insert_election(Self::env().caller(), config);
+            Ok(voting)
+        }
+
+        /// Constructor that creates election `0` as `Pending` under `config`,
+        /// as `new_with_config` does, to take over an election of another
+        /// deployment: until voting opens the owner loads its state with
+        /// `import_voters` and `import_tallies`.
+        #[ink(constructor)]
+        pub fn new_from_snapshot(
+            config: ElectionConfig,
+            fee_limits: FeeLimits,
+        ) -> Result<Self, ConfigError> {
+            let mut voting = Self::new_with_config(config, fee_limits)?;
+            voting.importing = true;
+            Ok(voting)
+        }
+
+        /// Constructor that creates election `0` as `Pending` under `config`,
+        /// as `new_with_config` does, taking value sent through `receive` as
+        /// `incoming_funds` says.
+        #[ink(constructor)]
+        pub fn new_with_funds_policy(
+            config: ElectionConfig,
+            fee_limits: FeeLimits,
+            incoming_funds: IncomingFunds,
+        ) -> Result<Self, ConfigError> {
+            let mut voting = Self::new_with_config(config, fee_limits)?;
+            voting.incoming_funds = incoming_funds;
+            Ok(voting)
+        }
+
+        /// Constructor that initializes the `bool` value to `false`.
+        ///
+        /// Constructors can delegate to other constructors.
+        #[ink(constructor)]
+        #[allow(clippy::should_implement_trait)]
+        pub fn default() -> Self {
+            Self::new()
+        }
+
+        /// Storage without any election.
+        fn empty() -> Self {
+            Self {
+                elections: Mapping::default(),
+                next_election_id: 0,
+                runners: Mapping::default(),
+                votes: Mapping::default(),
+                electorate: Mapping::default(),
+                electorate_at: Mapping::default(),
+                electorate_position: Mapping::default(),
+                slates: Mapping::default(),
+                profiles: Mapping::default(),
+                candidate_categories: Mapping::default(),
+                category_members: Mapping::default(),
+                constituency_of: Mapping::default(),
+                constituency_votes: Mapping::default(),
+                standings: Mapping::default(),
+                races: Mapping::default(),
+                race_of: Mapping::default(),
+                rerun_of: Mapping::default(),
+                latest_rerun: Mapping::default(),
+                profile_history: Mapping::default(),
+                statements: Mapping::default(),
+                vouchers_of: Mapping::default(),
+                vouching_barred: Mapping::default(),
+                banned_voters: Mapping::default(),
+                already_voted: Mapping::default(),
+                approvals_cast: Mapping::default(),
+                approved: Mapping::default(),
+                endorsements: Mapping::default(),
+                endorsed: Mapping::default(),
+                recalls: Mapping::default(),
+                disputes: Mapping::default(),
+                tally_claims: Mapping::default(),
+                refuted_tallies: Mapping::default(),
+                sealed_ballots: Mapping::default(),
+                key_shares: Mapping::default(),
+                revealed_ballots: Mapping::default(),
+                nullifiers: Mapping::default(),
+                lotteries: Mapping::default(),
+                sponsorships: Mapping::default(),
+                open_disputes: Vec::new(),
+                paying_out: Default::default(),
+                resolver: None,
+                moderator: None,
+                name_validator: None,
+                name_service: None,
+                entry_deposit: 0,
+                admin_bond: 0,
+                verified: Mapping::default(),
+                recall_signed: Mapping::default(),
+                recall_voted: Mapping::default(),
+                incumbent: None,
+                consecutive_terms: Mapping::default(),
+                win_history: Mapping::default(),
+                archive: Mapping::default(),
+                archived_count: 0,
+                archive_index: Mapping::default(),
+                tally_hashes: Mapping::default(),
+                mirrored: Mapping::default(),
+                ballot_branches: Mapping::default(),
+                ballot_roots: Mapping::default(),
+                rankings: Mapping::default(),
+                transfers: Mapping::default(),
+                stv_counts: Mapping::default(),
+                stv_elected: Mapping::default(),
+                sortition_draws: Mapping::default(),
+                deposits: Mapping::default(),
+                prizes: Mapping::default(),
+                campaign_funds: Mapping::default(),
+                campaign_pledges: Mapping::default(),
+                treasury: 0,
+                owner: Self::env().caller(),
+                protocol_fee: ProtocolFee {
+                    beneficiary: Self::env().caller(),
+                    basis_points: 0,
+                },
+                claimable: Mapping::default(),
+                token_claimable: Mapping::default(),
+                fee_limits: FeeLimits::default(),
+                funds: TreasuryReport::default(),
+                creation_fee: 0,
+                elections_voting: 0,
+                banned: Mapping::default(),
+                voters: Mapping::default(),
+                voters_for: Mapping::default(),
+                ballots_for: Mapping::default(),
+                vote_history: Mapping::default(),
+                vote_history_len: Mapping::default(),
+                reasons: Mapping::default(),
+                reputation_adjustments: Mapping::default(),
+                participation: Mapping::default(),
+                participation_progress: Mapping::default(),
+                candidacies: Mapping::default(),
+                tracked_rounds: 0,
+                storage_version: STORAGE_VERSION,
+                upgrades_governed: false,
+                referendums: Mapping::default(),
+                next_referendum_id: 0,
+                surveys: Mapping::default(),
+                next_survey_id: 0,
+                survey_responded: Mapping::default(),
+                timelock_delay: 0,
+                scheduled_actions: Mapping::default(),
+                next_action_id: 0,
+                importing: false,
+                incoming_funds: IncomingFunds::Reject,
+                roles: Mapping::default(),
+                block_ballots: Mapping::default(),
+                block_registrations: Mapping::default(),
+                last_registered: Mapping::default(),
+                role_permissions: Mapping::default(),
+                next_ballot_id: 0,
+                referendum_voted: Mapping::default(),
+                approved_upgrades: Mapping::default(),
+                partial_totals: Mapping::default(),
+                bridges: Mapping::default(),
+                relayed_from: Mapping::default(),
+                relayed_turnout: Mapping::default(),
+                identity_mode: false,
+                identities: Mapping::default(),
+                registered_since: Mapping::default(),
+                identity_voted: Mapping::default(),
+                ballot_nonces: Mapping::default(),
+                aggregators: Mapping::default(),
+                delegates: Mapping::default(),
+                delegators: Mapping::default(),
+                topic_delegates: Mapping::default(),
+                topic_delegators: Mapping::default(),
+                split_delegates: Mapping::default(),
+                split_delegators: Mapping::default(),
+                split_spent: Mapping::default(),
+                delegations: 0,
+                invitation_codes: Mapping::default(),
+                referrals: Mapping::default(),
+                display_names: Mapping::default(),
+                name_owners: Mapping::default(),
+                refund_cursors: Mapping::default(),
+                gc_cursors: Mapping::default(),
+                slash_petitions: Mapping::default(),
+                slash_signed: Mapping::default(),
+                turnout_histograms: Mapping::default(),
+            }
+        }
+
+        /// Creates a new `Pending` election administered by the caller.
+        /// The configured creation fee and admin bond must be transferred
+        /// along.
+        #[ink(message, payable, selector = 0x9e01_36d4)]
+        pub fn create_election(
+            &mut self,
+            config: ElectionConfig,
+        ) -> Result<ElectionId, ConfigError> {
+            if self.paying_out() {
+                return Err(ConfigError::Reentrancy);
+            }
+            self.validate_config(&config)?;
+            if !self.collect_creation_fee() {
+                return Err(ConfigError::IncorrectCreationFee);
+            }
+            Ok(self.insert_election(self.env().caller(), config))
+        }
+
+        /// Creates a new `Pending` election administered by the caller, run
+        /// under the same configuration as `source_id` but with fresh tallies.
+        /// The configured creation fee and admin bond must be transferred
+        /// along.
+        #[ink(message, payable, selector = 0xda36_9dd9)]
+        pub fn clone_election(&mut self, source_id: ElectionId) -> Result<ElectionId, VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(source_id);
+            let source = self
+                .elections
+                .get(source_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if !self.collect_creation_fee() {
+                return Err(VoteError::IncorrectFee);
+            }
+            Ok(self.insert_election(self.env().caller(), source.config))
+        }
+
+        /// Creates a new `Pending` election administered by the caller, run
+        /// under the configuration of the closed election `election_id` and
+        /// bound to its electorate, e.g. to repeat a voided or disputed vote.
+        /// The re-run admits exactly the voters of the original, who cannot
+        /// join or leave until the re-run closes, and weighs their ballots
+        /// the same, so configurations whose weights could shift in between
+        /// are refused. Only the election admin may call it, and the
+        /// configured creation fee and admin bond must be transferred along.
+        #[ink(message, payable, selector = 0xcf2a_7ad1)]
+        pub fn rerun_election(&mut self, election_id: ElectionId) -> Result<ElectionId, VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("rerun_election"))?;
+            let source = self.electorate_id(election_id);
+            if !matches!(
+                election.status,
+                ElectionStatus::Finalized | ElectionStatus::Cancelled | ElectionStatus::Expired
+            ) || self.rerun_pending(source)
+            {
+                return Err(VoteError::InvalidTransition);
+            }
+            let config = &election.config;
+            if config.eligibility_source.is_some()
+                || config.asset_weight.is_some()
+                || config.reputation_weighted
+                || config.tenure_boost.is_some()
+                || !config.attendance_boost.is_empty()
+                || config
+                    .weight_source
+                    .is_some_and(|source| source.snapshot_block.is_none())
+            {
+                return Err(VoteError::ElectorateNotPinned);
+            }
+            if !self.collect_creation_fee() {
+                return Err(VoteError::IncorrectFee);
+            }
+            let electorate = self
+                .elections
+                .get(source)
+                .map(|source| source.electorate)
+                .unwrap_or_default();
+            let mut config = election.config;
+            config.voter_deposit = None;
+            config.vouches_required = 0;
+            let rerun_id = self.insert_election(self.env().caller(), config);
+            self.update_election(rerun_id, |rerun| rerun.electorate = electorate);
+            self.rerun_of.insert(rerun_id, &source);
+            self.latest_rerun.insert(source, &rerun_id);
+            self.env().emit_event(ElectionRerun {
+                election_id,
+                rerun_id,
+                electorate_of: source,
+            });
+            Ok(rerun_id)
+        }
+
+        /// Returns the election whose electorate a re-run votes with.
+        #[ink(message, selector = 0xb224_b65d)]
+        pub fn get_rerun_of(&self, rerun_id: ElectionId) -> Option<ElectionId> {
+            self.rerun_of.get(rerun_id)
+        }
+
+        /// Returns the maximum fees fixed at instantiation.
+        #[ink(message, selector = 0x576c_341d)]
+        pub fn get_fee_limits(&self) -> FeeLimits {
+            self.fee_limits.clone()
+        }
+
+        /// Returns the fee charged for creating an election.
+        #[ink(message, selector = 0xfd96_b0ad)]
+        pub fn get_creation_fee(&self) -> Balance {
+            self.creation_fee
+        }
+
+        /// Changes the vote fee of an election, clamped to `max_vote_fee`,
+        /// until voting opens in it. Only the owner may call it, never while
+        /// any election is voting, and only through `schedule_action` while a
+        /// timelock is set.
+        ///
+        /// Returns the fee actually applied.
+        #[ink(message, selector = 0x3587_3415)]
+        pub fn set_vote_fee(
+            &mut self,
+            election_id: ElectionId,
+            fee: Balance,
+        ) -> Result<Balance, VoteError> {
+            self.ensure_phase(election_id);
+            self.ensure_fees_unlocked()?;
+            self.ensure_no_timelock()?;
+            self.apply_vote_fee(election_id, fee)
+        }
+
+        fn apply_vote_fee(
+            &mut self,
+            election_id: ElectionId,
+            fee: Balance,
+        ) -> Result<Balance, VoteError> {
+            let fee = fee.min(self.fee_limits.max_vote_fee);
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            Self::ensure_config_open(&election)?;
+            election.config.vote_fee = fee;
+            self.elections.insert(election_id, &election);
+            self.emit_fee_updated(Some(election_id), FeeKind::Vote, fee);
+            self.emit_config_changed(Some(election_id), ink::selector_bytes!("set_vote_fee"));
+            Ok(fee)
+        }
+
+        /// Changes the candidacy deposit of an election, clamped to
+        /// `max_candidacy_deposit`, until voting opens in it. Only the owner
+        /// may call it, never while any election is voting, and only through
+        /// `schedule_action` while a timelock is set.
+        ///
+        /// Returns the deposit actually applied.
+        #[ink(message, selector = 0x16fa_843d)]
+        pub fn set_candidacy_deposit(
+            &mut self,
+            election_id: ElectionId,
+            deposit: Balance,
+        ) -> Result<Balance, VoteError> {
+            self.ensure_phase(election_id);
+            self.ensure_fees_unlocked()?;
+            self.ensure_no_timelock()?;
+            self.apply_candidacy_deposit(election_id, deposit)
+        }
+
+        fn apply_candidacy_deposit(
+            &mut self,
+            election_id: ElectionId,
+            deposit: Balance,
+        ) -> Result<Balance, VoteError> {
+            let deposit = deposit.min(self.fee_limits.max_candidacy_deposit);
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            Self::ensure_config_open(&election)?;
+            election.config.candidacy_deposit = deposit;
+            self.elections.insert(election_id, &election);
+            self.emit_fee_updated(Some(election_id), FeeKind::CandidacyDeposit, deposit);
+            self.emit_config_changed(
+                Some(election_id),
+                ink::selector_bytes!("set_candidacy_deposit"),
+            );
+            Ok(deposit)
+        }
+
+        /// Changes the election creation fee, clamped to `max_creation_fee`.
+        /// Only the owner may call it, never while any election is voting, and
+        /// only through `schedule_action` while a timelock is set.
+        ///
+        /// Returns the fee actually applied.
+        #[ink(message, selector = 0x78bc_642f)]
+        pub fn set_creation_fee(&mut self, fee: Balance) -> Result<Balance, VoteError> {
+            self.ensure_fees_unlocked()?;
+            self.ensure_no_timelock()?;
+            Ok(self.apply_creation_fee(fee))
+        }
+
+        fn apply_creation_fee(&mut self, fee: Balance) -> Balance {
+            let fee = fee.min(self.fee_limits.max_creation_fee);
+            self.creation_fee = fee;
+            self.emit_fee_updated(None, FeeKind::Creation, fee);
+            self.emit_config_changed(None, ink::selector_bytes!("set_creation_fee"));
+            fee
+        }
+
+        /// Returns the current phase of an election.
+        #[ink(message, selector = 0x07e3_b8df)]
+        pub fn get_status(&self, election_id: ElectionId) -> Option<ElectionStatus> {
+            self.elections
+                .get(election_id)
+                .map(|election| election.status)
+        }
+
+        /// Returns the configuration an election currently runs under,
+        /// including fees changed since its creation.
+        #[ink(message, selector = 0xd070_7806)]
+        pub fn get_config(&self, election_id: ElectionId) -> Option<ElectionConfig> {
+            self.elections
+                .get(election_id)
+                .map(|election| election.config)
+        }
+
+        /// Subscribes `hook` to the votes and outcome of an election, or
+        /// unsubscribes the current hook with `None`, until voting opens.
+        /// Only the election admin may call it.
+        #[ink(message, selector = 0xb9f7_1bfd)]
+        pub fn set_hook(
+            &mut self,
+            election_id: ElectionId,
+            hook: Option<AccountId>,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let mut election =
+                self.election_as_admin(election_id, ink::selector_bytes!("set_hook"))?;
+            Self::ensure_config_open(&election)?;
+            election.config.hook = hook;
+            self.elections.insert(election_id, &election);
+            self.env().emit_event(HookUpdated { election_id, hook });
+            self.emit_config_changed(Some(election_id), ink::selector_bytes!("set_hook"));
+            Ok(())
+        }
+
+        /// Lets candidates register. Only the election admin may call it.
+        #[ink(message, selector = 0x901c_6fda)]
+        pub fn open_registration(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            self.transition(
+                election_id,
+                ink::selector_bytes!("open_registration"),
+                &[ElectionStatus::Pending],
+                ElectionStatus::Registration,
+            )
+        }
+
+        /// Ends candidate and voter registration, freezing the candidate list
+        /// until voting opens. Only the election admin may call it.
+        #[ink(message, selector = 0xaa9f_7bf3)]
+        pub fn close_registration(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            self.transition(
+                election_id,
+                ink::selector_bytes!("close_registration"),
+                &[ElectionStatus::Registration],
+                ElectionStatus::SlateFrozen,
+            )
+        }
+
+        /// Returns the block at which registration of an election was closed.
+        #[ink(message, selector = 0xa792_3a15)]
+        pub fn get_slate_frozen_at(&self, election_id: ElectionId) -> Option<BlockNumber> {
+            self.elections
+                .get(election_id)
+                .and_then(|election| election.slate_frozen_at)
+        }
+
+        /// Starts accepting ballots. Only the election admin may call it.
+        ///
+        /// Fails with `NotEnoughCandidates` while fewer candidates than the
+        /// configured `min_candidates` are running. An election with a
+        /// `grace_period` must have had its registration closed that many
+        /// blocks before; one with a campaign opens once it has ended. One
+        /// with a `voting_opens_at` opens on its own once that has passed,
+        /// and this call then only brings the opening forward.
+        #[ink(message, selector = 0x4927_cd0e)]
+        pub fn open_voting(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("open_voting"))?;
+            if election.config.campaign.is_some() {
+                self.ensure_phase(election_id);
+                return match self.get_status(election_id) {
+                    Some(ElectionStatus::Voting) => Ok(()),
+                    _ => Err(VoteError::VotingNotOpenYet),
+                };
+            }
+            if self.ensure_phase(election_id) {
+                return match self.get_status(election_id) {
+                    Some(ElectionStatus::Voting) => Ok(()),
+                    _ => Err(VoteError::InvalidTransition),
+                };
+            }
+            let from = self.opening_from(election_id, &election)?;
+            self.transition(
+                election_id,
+                ink::selector_bytes!("open_voting"),
+                from,
+                ElectionStatus::Voting,
+            )
+        }
+
+        /// Checks that an election without a campaign may open for voting
+        /// now, and returns the phases it may open from.
+        fn opening_from(
+            &self,
+            election_id: ElectionId,
+            election: &Election,
+        ) -> Result<&'static [ElectionStatus], VoteError> {
+            if election.paused_at.is_some() {
+                return Err(VoteError::ElectionPaused);
+            }
+            if election
+                .config
+                .voting_opens_at
+                .is_some_and(|opens_at| self.now_against(opens_at) == Ordering::Less)
+            {
+                return Err(VoteError::VotingNotOpenYet);
+            }
+            if election.slate_frozen_at.is_some_and(|frozen_at| {
+                self.env().block_number() < frozen_at.saturating_add(election.config.grace_period)
+            }) {
+                return Err(VoteError::VotingNotOpenYet);
+            }
+            let candidates = self.runners.get(election_id).unwrap_or_default().len();
+            if candidates < election.config.min_candidates as usize {
+                return Err(VoteError::NotEnoughCandidates);
+            }
+            Ok(if election.config.grace_period > 0 {
+                &[ElectionStatus::SlateFrozen]
+            } else {
+                &[
+                    ElectionStatus::Pending,
+                    ElectionStatus::Registration,
+                    ElectionStatus::SlateFrozen,
+                ]
+            })
+        }
+
+        /// Closes the election, freezing its outcome. Only the election admin,
+        /// or a role granted `finalize`, may call it.
+        ///
+        /// Waits for `voting_closes_at` and requires the configured quorum.
+        #[ink(message, selector = 0x4065_6e2b)]
+        pub fn finalize(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.finalize_step(election_id, u32::MAX).map(|_| ())
+        }
+
+        /// Counts up to `batch` more runners of the election, so that large
+        /// elections can be finalized over several calls. The first call
+        /// closes the ballot; the one counting the last runner finalizes it.
+        /// Whatever is left of `batch` then goes to visiting ballots: for the
+        /// transfer count of a `SingleTransferable` election, until every
+        /// seat is filled, and for booking participation streaks when they
+        /// are tracked. The tally posted for an optimistic election is adopted
+        /// instead, without counting, once its challenge period has passed.
+        /// An election with encrypted ballots waits for `reveal_ballots` to
+        /// have counted every ballot.
+        ///
+        /// The election admin, or a role granted `finalize` or `finalize_step`,
+        /// may call it at any time. Once `voting_closes_at` has passed anyone
+        /// may, and whoever finalizes the election is paid
+        /// its `finalization_bounty`.
+        ///
+        /// Returns whether the election is now finalized.
+        #[ink(message, selector = 0x4732_6f16)]
+        pub fn finalize_step(
+            &mut self,
+            election_id: ElectionId,
+            batch: u32,
+        ) -> Result<bool, VoteError> {
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if election.paused_at.is_some() {
+                return Err(VoteError::ElectionPaused);
+            }
+            let deadline = election.config.voting_closes_at;
+            if caller != election.admin
+                && deadline.is_none()
+                && !self.permitted(caller, ink::selector_bytes!("finalize"))
+                && !self.permitted(caller, ink::selector_bytes!("finalize_step"))
+            {
+                return Err(VoteError::Unauthorized);
+            }
+            if election.status != ElectionStatus::Tallying {
+                if deadline
+                    .is_some_and(|closes_at| self.now_against(closes_at) != Ordering::Greater)
+                {
+                    return Err(VoteError::VotingStillOpen);
+                }
+                if election.turnout < election.config.quorum {
+                    return Err(VoteError::QuorumNotReached);
+                }
+                if !matches!(
+                    election.status,
+                    ElectionStatus::Voting | ElectionStatus::RevealPhase
+                ) {
+                    return Err(VoteError::InvalidTransition);
+                }
+                if election.config.encrypted_ballots.is_some()
+                    && (election.status != ElectionStatus::RevealPhase
+                        || self.get_revealed_count(election_id) < election.turnout)
+                {
+                    return Err(VoteError::BallotsSealed);
+                }
+            }
+
+            let claim = match election.config.optimistic_tally {
+                Some(optimistic) => self.tally_claims.get(election_id).map(|claim| {
+                    let window_ends = claim
+                        .proposed_at
+                        .saturating_add(optimistic.challenge_period);
+                    (claim, self.env().block_number() < window_ends)
+                }),
+                None => None,
+            };
+            let totals = if let Some((claim, challengeable)) = &claim {
+                if *challengeable {
+                    return Err(VoteError::TallyUnderChallenge);
+                }
+                claim.totals.clone()
+            } else if let Some(module) = election.config.tally_module {
+                self.module_totals(module, election_id)?
+            } else {
+                let runners = self.runners.get(election_id).unwrap_or_default();
+                let mut totals = self.partial_totals.get(election_id).unwrap_or_default();
+                let counted = totals.len();
+                let end = counted.saturating_add(batch as usize).min(runners.len());
+                for runner in &runners[counted..end] {
+                    totals.push((
+                        *runner,
+                        self.votes.get((election_id, *runner)).unwrap_or_default(),
+                    ));
+                }
+                let mut budget = batch.saturating_sub((end - counted) as u32);
+                let mut counted_all = end == runners.len();
+                if let (true, VotingMode::SingleTransferable(seats)) =
+                    (counted_all, election.config.mode)
+                {
+                    counted_all = self.count_transferable_votes(
+                        election_id,
+                        election.turnout,
+                        seats,
+                        &totals,
+                        &mut budget,
+                    );
+                }
+                if counted_all && election.config.track_participation {
+                    counted_all =
+                        self.record_participation(election_id, election.turnout, &mut budget);
+                }
+                if !counted_all {
+                    if election.status != ElectionStatus::Tallying {
+                        self.enter_phase(
+                            election_id,
+                            election,
+                            &[ElectionStatus::Voting, ElectionStatus::RevealPhase],
+                            ElectionStatus::Tallying,
+                        )?;
+                    }
+                    if end > counted {
+                        self.partial_totals.insert(election_id, &totals);
+                    }
+                    return Ok(false);
+                }
+
+                if election.status == ElectionStatus::Tallying {
+                    self.partial_totals.remove(election_id);
+                }
+                if election.config.electors.is_empty() {
+                    totals
+                } else {
+                    self.elector_totals(election_id, &election.config, &totals)
+                }
+            };
+            let committee = election.config.committee.is_some();
+            let mirror = election.config.mirror_outcome;
+            let hook = election.config.hook;
+            self.enter_phase(
+                election_id,
+                election,
+                &[
+                    ElectionStatus::Voting,
+                    ElectionStatus::RevealPhase,
+                    ElectionStatus::Tallying,
+                ],
+                ElectionStatus::Finalized,
+            )?;
+            self.archive_result(election_id, totals);
+            if committee {
+                self.announce_committee(election_id);
+            }
+            self.award_prizes(election_id);
+            if deadline.is_some() {
+                self.pay_bounty(election_id, caller);
+            }
+            self.route_fees(election_id);
+            self.record_term(election_id);
+            if let Some((claim, _)) = claim {
+                self.release_tally_claim(election_id, claim);
+            }
+            // a failed note is retried with `mirror_outcome`
+            if mirror && self.is_result_effective(election_id) {
+                self.note_outcome(election_id);
+            }
+            if let Some(hook) = hook {
+                self.notify_finalize(hook, election_id);
+            }
+            Ok(true)
+        }
+
+        /// Calls off an election that has not been finalized yet.
+        /// Only the election admin may call it.
+        #[ink(message, selector = 0x9796_e9a7)]
+        pub fn cancel(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            self.transition(
+                election_id,
+                ink::selector_bytes!("cancel"),
+                &[
+                    ElectionStatus::Pending,
+                    ElectionStatus::Registration,
+                    ElectionStatus::SlateFrozen,
+                    ElectionStatus::Campaign,
+                    ElectionStatus::Voting,
+                    ElectionStatus::RevealPhase,
+                ],
+                ElectionStatus::Cancelled,
+            )?;
+            self.settle_cancellation(election_id);
+            Ok(())
+        }
+
+        /// Expires an election left unfinalized for `EXPIRY_BLOCKS` blocks,
+        /// or `EXPIRY_MILLIS` milliseconds, past its `voting_closes_at`, so
+        /// that its funds are refunded as for a cancelled one and no absent
+        /// admin can strand them. Anyone may call it, and is paid the
+        /// election's bounty tips and `EXPIRY_BOUNTY_PERCENT` of its admin
+        /// bond. Every message acting on the election expires it as well.
+        ///
+        /// Fails with `NotStale` for elections without a deadline, paused
+        /// ones and ones that never opened, which are cancelled instead.
+        ///
+        /// Returns the bounty credited.
+        #[ink(message, selector = 0xb2c6_8550)]
+        pub fn expire(&mut self, election_id: ElectionId) -> Result<Balance, VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if !self.is_stale(&election) {
+                return Err(VoteError::NotStale);
+            }
+            Ok(self.expire_election(election_id, election))
+        }
+
+        /// Whether `election` is still open or being counted `EXPIRY_BLOCKS`
+        /// or `EXPIRY_MILLIS` after its `voting_closes_at`, and not paused.
+        fn is_stale(&self, election: &Election) -> bool {
+            matches!(
+                election.status,
+                ElectionStatus::Campaign
+                    | ElectionStatus::Voting
+                    | ElectionStatus::RevealPhase
+                    | ElectionStatus::Tallying
+            ) && election.paused_at.is_none()
+                && election.config.voting_closes_at.is_some_and(|closes_at| {
+                    self.now_against(closes_at.delayed(EXPIRY_BLOCKS, EXPIRY_MILLIS))
+                        == Ordering::Greater
+                })
+        }
+
+        /// Moves a stale election into `Expired`, settling it as a cancelled
+        /// one, and pays the caller its expiry bounty.
+        ///
+        /// Returns the bounty credited.
+        fn expire_election(&mut self, election_id: ElectionId, mut election: Election) -> Balance {
+            let keeper = self.env().caller();
+            let tips = core::mem::take(&mut election.bounty_tips);
+            let share = election.admin_bond.saturating_mul(EXPIRY_BOUNTY_PERCENT) / 100;
+            election.admin_bond -= share;
+            let admin = election.admin;
+            let from = [election.status];
+            if self
+                .enter_phase(election_id, election, &from, ElectionStatus::Expired)
+                .is_err()
+            {
+                return 0;
+            }
+            self.settle_cancellation(election_id);
+            self.funds.prize_escrow = self.funds.prize_escrow.saturating_sub(tips);
+            self.funds.deposits = self.funds.deposits.saturating_sub(share);
+            if share > 0 {
+                self.env().emit_event(AdminBondSettled {
+                    election_id,
+                    admin,
+                    amount: share,
+                    slashed: true,
+                });
+            }
+            let bounty = tips.saturating_add(share);
+            if bounty > 0 {
+                self.credit(keeper, bounty);
+            }
+            self.env().emit_event(ElectionExpired {
+                election_id,
+                keeper,
+                bounty,
+            });
+            bounty
+        }
+
+        /// Releases what a just cancelled or expired election held on to: its
+        /// posted tally's bond, and its bounty tips, which go to the treasury.
+        fn settle_cancellation(&mut self, election_id: ElectionId) {
+            if let Some(claim) = self.tally_claims.get(election_id) {
+                self.release_tally_claim(election_id, claim);
+            }
+            let mut tips = 0;
+            self.update_election(election_id, |election| {
+                tips = core::mem::take(&mut election.bounty_tips)
+            });
+            if tips > 0 {
+                self.funds.prize_escrow = self.funds.prize_escrow.saturating_sub(tips);
+                self.forfeit(tips);
+            }
+        }
+
+        /// Unwinds a cancelled or expired election, going through at most `max_entries`
+        /// of its ballots, candidates and voters in that order and resuming
+        /// where the last call stopped. Every ballot's vote fee is credited
+        /// back to the voter, unless the election is quadratic, and every
+        /// candidacy and voter deposit to the account that made it, to be
+        /// withdrawn with `claim`, or `claim_tokens` when paid in the fee
+        /// token. An election cancelled once finalized already routed its
+        /// fees and candidacy deposits, and only refunds voter deposits.
+        /// Everything is refunded at most once, so calling it again once done
+        /// does nothing. Campaign pledges are refunded to each supporter with
+        /// `refund_campaign_pledge`. Anyone may call it.
+        ///
+        /// Returns whether every entry has been gone through.
+        #[ink(message, selector = 0x3968_f2d1)]
+        pub fn process_refunds(
+            &mut self,
+            election_id: ElectionId,
+            max_entries: u32,
+        ) -> Result<bool, VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if !matches!(
+                election.status,
+                ElectionStatus::Cancelled | ElectionStatus::Expired
+            ) {
+                return Err(VoteError::InvalidTransition);
+            }
+            let runners = self.runners.get(election_id).unwrap_or_default();
+            let ballots = election.turnout;
+            let candidates = ballots.saturating_add(runners.len() as u32);
+            let total = candidates.saturating_add(election.electorate);
+            let start = self.refund_cursors.get(election_id).unwrap_or_default();
+            let end = start.saturating_add(max_entries).min(total);
+            let routed = election.finalized_at.is_some();
+            let vote_fee = if routed || matches!(election.config.mode, VotingMode::Quadratic(_)) {
+                0
+            } else {
+                election.config.vote_fee
+            };
+            let mut refunded: Balance = 0;
+            for entry in start..end {
+                if entry < ballots {
+                    // imported ballots paid nothing, so the pot caps the refunds
+                    let refund = vote_fee.min(election.fee_pot);
+                    let Some(voter) = self.voters.get((election_id, entry)) else {
+                        continue;
+                    };
+                    if refund == 0 {
+                        continue;
+                    }
+                    election.fee_pot = election.fee_pot.saturating_sub(refund);
+                    self.funds.fee_income = self
+                        .funds
+                        .fee_income
+                        .saturating_sub(Self::native_due(&election.config, refund));
+                    self.repay(&election.config, voter, refund);
+                    refunded = refunded.saturating_add(refund);
+                } else if entry < candidates {
+                    if routed {
+                        continue;
+                    }
+                    let candidate = runners[(entry - ballots) as usize];
+                    let Some(deposit) = self.deposits.take((election_id, candidate)) else {
+                        continue;
+                    };
+                    election.deposit_pot = election.deposit_pot.saturating_sub(deposit);
+                    self.funds.deposits = self
+                        .funds
+                        .deposits
+                        .saturating_sub(Self::native_due(&election.config, deposit));
+                    self.repay(&election.config, candidate, deposit);
+                    refunded = refunded.saturating_add(deposit);
+                } else {
+                    let Some(voter) = self.electorate_at.get((election_id, entry - candidates))
+                    else {
+                        continue;
+                    };
+                    let deposit = self
+                        .electorate
+                        .get((election_id, voter))
+                        .unwrap_or_default();
+                    if deposit > 0 {
+                        self.electorate.insert((election_id, voter), &0);
+                        self.funds.deposits = self.funds.deposits.saturating_sub(deposit);
+                        self.credit(voter, deposit);
+                        refunded = refunded.saturating_add(deposit);
+                    }
+                }
+            }
+            if end > start {
+                self.refund_cursors.insert(election_id, &end);
+                self.elections.insert(election_id, &election);
+            }
+            let done = end >= total;
+            self.env().emit_event(RefundsProcessed {
+                election_id,
+                entries: end.saturating_sub(start),
+                refunded,
+                done,
+            });
+            Ok(done)
+        }
+
+        /// Posts the tally of an optimistic election once voting has closed,
+        /// listing every runner's total in the order of the `get_results`
+        /// totals. It must carry the configured bond, and only the designated
+        /// proposer may call it, once per election.
+        #[ink(message, payable, selector = 0x55dd_32df)]
+        pub fn propose_tally(
+            &mut self,
+            election_id: ElectionId,
+            totals: Vec<(AccountId, u32)>,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            let proposer = self.env().caller();
+            let optimistic = election
+                .config
+                .optimistic_tally
+                .filter(|optimistic| optimistic.proposer == proposer)
+                .ok_or(VoteError::Unauthorized)?;
+            if self.refuted_tallies.contains(election_id) {
+                return Err(VoteError::TallyRefuted);
+            }
+            if self.tally_claims.contains(election_id) {
+                return Err(VoteError::TallyAlreadyProposed);
+            }
+            if election.status != ElectionStatus::Voting {
+                return Err(VoteError::InvalidTransition);
+            }
+            if election
+                .config
+                .voting_closes_at
+                .is_some_and(|closes_at| self.now_against(closes_at) != Ordering::Greater)
+            {
+                return Err(VoteError::VotingStillOpen);
+            }
+            let bond = self.env().transferred_value();
+            if bond != optimistic.bond {
+                return Err(VoteError::IncorrectFee);
+            }
+            self.funds.deposits = self.funds.deposits.saturating_add(bond);
+            self.tally_claims.insert(
+                election_id,
+                &TallyClaim {
+                    proposer,
+                    bond,
+                    totals,
+                    proposed_at: self.env().block_number(),
+                },
+            );
+            self.env().emit_event(TallyProposed {
+                election_id,
+                proposer,
+                bond,
+            });
+            Ok(())
+        }
+
+        /// Proves the posted tally of an election wrong about the runner at
+        /// `index`: its entry names another account or another total than the
+        /// on-chain count, or the tally lists a different number of runners.
+        /// The caller receives the proposer's bond in their claimable balance,
+        /// and the election is then counted on-chain.
+        #[ink(message, selector = 0xd2a4_cdd8)]
+        pub fn challenge_tally(
+            &mut self,
+            election_id: ElectionId,
+            index: u32,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            let claim = self
+                .tally_claims
+                .get(election_id)
+                .ok_or(VoteError::NoTallyClaim)?;
+            let challenge_period = election
+                .config
+                .optimistic_tally
+                .map(|optimistic| optimistic.challenge_period)
+                .unwrap_or_default();
+            if self.env().block_number() >= claim.proposed_at.saturating_add(challenge_period) {
+                return Err(VoteError::ChallengeClosed);
+            }
+            let runners = self.runners.get(election_id).unwrap_or_default();
+            let miscounted = runners.get(index as usize).is_some_and(|runner| {
+                claim.totals.get(index as usize)
+                    != Some(&(
+                        *runner,
+                        self.votes.get((election_id, *runner)).unwrap_or_default(),
+                    ))
+            });
+            if !miscounted && claim.totals.len() == runners.len() {
+                return Err(VoteError::NoFraud);
+            }
+            let challenger = self.env().caller();
+            self.tally_claims.remove(election_id);
+            self.refuted_tallies.insert(election_id, &());
+            self.funds.deposits = self.funds.deposits.saturating_sub(claim.bond);
+            self.credit(challenger, claim.bond);
+            self.env().emit_event(TallyRefuted {
+                election_id,
+                challenger,
+                index,
+            });
+            Ok(())
+        }
+
+        /// Returns the tally posted for an election and not yet adopted or
+        /// refuted, if any.
+        #[ink(message, selector = 0x0923_8457)]
+        pub fn get_tally_claim(&self, election_id: ElectionId) -> Option<TallyClaim> {
+            self.tally_claims.get(election_id)
+        }
+
+        /// Posts the caller's share of the key of an encrypted election once
+        /// voting has closed. Only its trustees may call it, once each. The
+        /// share meeting the threshold moves the election to `RevealPhase`.
+        #[ink(message, selector = 0x635a_c0f3)]
+        pub fn post_key_share(
+            &mut self,
+            election_id: ElectionId,
+            share: Vec<u8>,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            let trustee = self.env().caller();
+            let threshold = match &election.config.encrypted_ballots {
+                Some(encrypted) if encrypted.trustees.contains(&trustee) => encrypted.threshold,
+                Some(_) => return Err(VoteError::Unauthorized),
+                None => return Err(VoteError::EncryptionDisabled),
+            };
+            if election.status != ElectionStatus::Voting {
+                return Err(VoteError::InvalidTransition);
+            }
+            if election
+                .config
+                .voting_closes_at
+                .is_some_and(|closes_at| self.now_against(closes_at) != Ordering::Greater)
+            {
+                return Err(VoteError::VotingStillOpen);
+            }
+            let mut shares = self.key_shares.get(election_id).unwrap_or_default();
+            if shares.iter().any(|(posted_by, _)| *posted_by == trustee) {
+                return Err(VoteError::ShareAlreadyPosted);
+            }
+            shares.push((trustee, share));
+            self.key_shares.insert(election_id, &shares);
+            self.env().emit_event(KeySharePosted {
+                election_id,
+                trustee,
+            });
+            if shares.len() >= threshold as usize {
+                self.enter_phase(
+                    election_id,
+                    election,
+                    &[ElectionStatus::Voting],
+                    ElectionStatus::RevealPhase,
+                )?;
+            }
+            Ok(())
+        }
+
+        /// Decrypts and counts up to `batch` more encrypted ballots of an
+        /// election in `RevealPhase`, in the order they were cast. Ballots
+        /// that do not decrypt to an account, or name one not running where
+        /// write-ins are refused, are spoiled and not counted. Anyone may
+        /// call it.
+        ///
+        /// Returns whether every ballot is now revealed, after which the
+        /// election can be finalized.
+        #[ink(message, selector = 0xad08_ef1f)]
+        pub fn reveal_ballots(
+            &mut self,
+            election_id: ElectionId,
+            batch: u32,
+        ) -> Result<bool, VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            let public_key = election
+                .config
+                .encrypted_ballots
+                .as_ref()
+                .map(|encrypted| encrypted.public_key)
+                .ok_or(VoteError::EncryptionDisabled)?;
+            if election.status != ElectionStatus::RevealPhase {
+                return Err(VoteError::InvalidTransition);
+            }
+            let revealed = self.get_revealed_count(election_id);
+            let end = revealed.saturating_add(batch).min(election.turnout);
+            let shares: Vec<Vec<u8>> = self
+                .key_shares
+                .get(election_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(_, share)| share)
+                .collect();
+
+            // decrypt the whole batch before counting any of it
+            let mut opened = Vec::new();
+            for index in revealed..end {
+                let Some(ballot) = self.sealed_ballots.get((election_id, index)) else {
+                    continue;
+                };
+                let plaintext = runtime::active()
+                    .threshold_decrypt(public_key, shares.clone(), ballot.ciphertext)
+                    .map_err(|_| VoteError::DecryptionFailed)?;
+                let candidate =
+                    <AccountId as ink::scale::DecodeAll>::decode_all(&mut &plaintext[..]).ok();
+                opened.push((index, ballot.voter, ballot.weight, candidate));
+            }
+
+            let write_ins = !election.config.candidacy_gated();
+            let mut runners = self.runners.get(election_id).unwrap_or_default();
+            let mut new_runners = false;
+            let (mut counted, mut spoiled) = (0u32, 0u32);
+            for (index, voter, weight, candidate) in opened {
+                self.sealed_ballots.remove((election_id, index));
+                let Some(candidate) =
+                    candidate.filter(|candidate| write_ins || runners.contains(candidate))
+                else {
+                    spoiled = spoiled.saturating_add(1);
+                    continue;
+                };
+                if !runners.contains(&candidate) {
+                    runners.push(candidate);
+                    self.count_candidacy(candidate);
+                    new_runners = true;
+                    self.env().emit_event(CandidateRegistered {
+                        election_id,
+                        candidate,
+                        deposit: 0,
+                    });
+                }
+                let new_total = self
+                    .votes
+                    .get((election_id, candidate))
+                    .unwrap_or_default()
+                    .saturating_add(weight);
+                self.votes.insert((election_id, candidate), &new_total);
+                self.update_standings(election_id, &election.config, candidate, new_total);
+                if election.config.public_ballot {
+                    let position = self
+                        .ballots_for
+                        .get((election_id, candidate))
+                        .unwrap_or_default();
+                    self.voters_for
+                        .insert((election_id, candidate, position), &voter);
+                    self.ballots_for
+                        .insert((election_id, candidate), &position.saturating_add(1));
+                }
+                if !election.config.blind_results {
+                    self.env().emit_event(TallyUpdated {
+                        election_id,
+                        candidate,
+                        new_total,
+                    });
+                }
+                self.notify_vote(&election.config, election_id, voter, candidate, weight);
+                counted = counted.saturating_add(1);
+            }
+            if new_runners {
+                self.runners.insert(election_id, &runners);
+            }
+            self.revealed_ballots.insert(election_id, &end);
+            self.env().emit_event(BallotsRevealed {
+                election_id,
+                counted,
+                spoiled,
+            });
+            Ok(end == election.turnout)
+        }
+
+        /// Returns how many trustees posted their key share for an election.
+        #[ink(message, selector = 0x9fbb_4682)]
+        pub fn get_key_share_count(&self, election_id: ElectionId) -> u32 {
+            self.key_shares
+                .get(election_id)
+                .map_or(0, |shares| shares.len() as u32)
+        }
+
+        /// Returns how many encrypted ballots of an election were revealed.
+        #[ink(message, selector = 0x0cb9_6ed0)]
+        pub fn get_revealed_count(&self, election_id: ElectionId) -> u32 {
+            self.revealed_ballots.get(election_id).unwrap_or_default()
+        }
+
+        /// Registers the caller as a candidate while registration is open.
+        ///
+        /// Requires the configured number of endorsements, if any, and exactly
+        /// the deposit `get_candidacy_deposit` quotes to be transferred along,
+        /// which grows with the field under a `deposit_scaling`. Fails
+        /// with `RateLimited` once `registrations_per_block` candidates
+        /// registered in the block, and with `CooldownActive` within
+        /// `registration_cooldown` blocks of the caller's last candidacy.
+        #[ink(message, payable, selector = 0x653d_424f)]
+        pub fn register_candidate(
+            &mut self,
+            election_id: ElectionId,
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let (mut election, mut runners) =
+                self.check_registration(caller, election_id, self.env().transferred_value())?;
+            let deposit = election.config.candidacy_deposit_for(runners.len());
+            if !self.pull_fee_token(&election.config, caller, deposit) {
+                return Err(RegistrationError::TransferFailed);
+            }
+            if deposit > 0 {
+                self.deposits.insert((election_id, caller), &deposit);
+                election.deposit_pot = election.deposit_pot.saturating_add(deposit);
+                self.funds.deposits = self
+                    .funds
+                    .deposits
+                    .saturating_add(Self::native_due(&election.config, deposit));
+            }
+            election.storage_entries = election.storage_entries.saturating_add(1);
+            self.elections.insert(election_id, &election);
+            runners.push(caller);
+            self.count_candidacy(caller);
+            self.runners.insert(election_id, &runners);
+            let now = self.env().block_number();
+            self.last_registered.insert(caller, &now);
+            if election.config.registrations_per_block > 0 {
+                let registered = self.registrations_this_block(election_id);
+                self.block_registrations
+                    .insert(election_id, &(now, registered.saturating_add(1)));
+            }
+            self.env().emit_event(CandidateRegistered {
+                election_id,
+                candidate: caller,
+                deposit,
+            });
+            Ok(())
+        }
+
+        /// Registers the caller as a party in a committee election, running
+        /// `members` in order. Ballots for the party count for its slate.
+        ///
+        /// Takes the same checks and deposit as `register_candidate`; the slate
+        /// must name between one and `seats` distinct members.
+        #[ink(message, payable, selector = 0xa5c4_b588)]
+        pub fn register_slate(
+            &mut self,
+            election_id: ElectionId,
+            members: Vec<AccountId>,
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            let Some(committee) = election.config.committee else {
+                return Err(RegistrationError::InvalidSlate);
+            };
+            let mut distinct = members.clone();
+            distinct.sort_unstable();
+            distinct.dedup();
+            if members.is_empty()
+                || members.len() > committee.seats as usize
+                || distinct.len() != members.len()
+            {
+                return Err(RegistrationError::InvalidSlate);
+            }
+            self.register_candidate(election_id)?;
+            self.slates
+                .insert((election_id, self.env().caller()), &members);
+            Ok(())
+        }
+
+        /// Returns the members a party runs in a committee election.
+        #[ink(message, selector = 0xaf78_3182)]
+        pub fn get_slate(&self, election_id: ElectionId, party: AccountId) -> Vec<AccountId> {
+            self.slates.get((election_id, party)).unwrap_or_default()
+        }
+
+        /// Sets the caller's profile as a candidate of the election, until
+        /// voting opens, and no sooner than `profile_cooldown` blocks after
+        /// the previous one, or it fails with `CooldownActive`. A replaced
+        /// profile is kept in the candidate's profile history, so that late
+        /// changes of platform stay visible, and changing it withdraws the
+        /// candidate's verified badge.
+        #[ink(message, selector = 0x0897_4eaf)]
+        pub fn update_profile(
+            &mut self,
+            election_id: ElectionId,
+            name: Vec<u8>,
+            manifesto: Hash,
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            if !matches!(
+                election.status,
+                ElectionStatus::Pending | ElectionStatus::Registration
+            ) {
+                return Err(RegistrationError::RegistrationClosed);
+            }
+            let candidate = self.env().caller();
+            if !self
+                .runners
+                .get(election_id)
+                .unwrap_or_default()
+                .contains(&candidate)
+            {
+                return Err(RegistrationError::NotRegistered);
+            }
+            if name.len() > MAX_NAME_LEN {
+                return Err(RegistrationError::NameTooLong);
+            }
+            let now = self.env().block_number();
+            let revisions = match self.profiles.get((election_id, candidate)) {
+                Some(previous)
+                    if now
+                        < previous
+                            .updated_at
+                            .saturating_add(election.config.profile_cooldown) =>
+                {
+                    return Err(RegistrationError::CooldownActive);
+                }
+                Some(previous) => {
+                    self.profile_history.insert(
+                        (election_id, candidate, previous.revisions),
+                        &ProfileRevision {
+                            name: previous.name,
+                            manifesto: previous.manifesto,
+                            updated_at: previous.updated_at,
+                            replaced_at: now,
+                        },
+                    );
+                    previous.revisions.saturating_add(1)
+                }
+                None => 0,
+            };
+            self.profiles.insert(
+                (election_id, candidate),
+                &CandidateProfile {
+                    name: name.clone(),
+                    manifesto,
+                    updated_at: now,
+                    revisions,
+                },
+            );
+            self.env().emit_event(CandidateProfileUpdated {
+                election_id,
+                candidate,
+                name,
+                manifesto,
+                revisions,
+            });
+            if self.verified.take((election_id, candidate)).is_some() {
+                self.env().emit_event(CandidateVerified {
+                    election_id,
+                    candidate,
+                    verified: false,
+                });
+            }
+            Ok(())
+        }
+
+        /// Returns the current profile of a candidate, if it set one.
+        #[ink(message, selector = 0x166e_7927)]
+        pub fn get_profile(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Option<CandidateProfile> {
+            self.profiles.get((election_id, candidate))
+        }
+
+        /// Returns the profiles a candidate replaced, oldest first, at most
+        /// `MAX_PAGE_SIZE` at a time.
+        #[ink(message, selector = 0xe4e4_4c44)]
+        pub fn get_profile_history(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<ProfileRevision> {
+            let len = self
+                .get_profile(election_id, candidate)
+                .map_or(0, |profile| profile.revisions);
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+            (offset..end)
+                .filter_map(|index| self.profile_history.get((election_id, candidate, index)))
+                .collect()
+        }
+
+        /// Tags a runner with `categories`, replacing its earlier tags, until
+        /// voting opens. Repeated ids count once, and at most `MAX_CATEGORIES`
+        /// remain. Only the election admin may call it.
+        #[ink(message, selector = 0x5e7a_c06d)]
+        pub fn set_categories(
+            &mut self,
+            election_id: ElectionId,
+            candidate: AccountId,
+            mut categories: Vec<CategoryId>,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("set_categories"))?;
+            if !matches!(
+                election.status,
+                ElectionStatus::Pending | ElectionStatus::Registration
+            ) {
+                return Err(VoteError::InvalidTransition);
+            }
+            if !self
+                .runners
+                .get(election_id)
+                .unwrap_or_default()
+                .contains(&candidate)
+            {
+                return Err(VoteError::NotACandidate);
+            }
+            categories.sort_unstable();
+            categories.dedup();
+            if categories.len() > MAX_CATEGORIES {
+                return Err(VoteError::TooManyCategories);
+            }
+            for category in self.get_categories(election_id, candidate) {
+                let mut members = self
+                    .category_members
+                    .get((election_id, category))
+                    .unwrap_or_default();
+                members.retain(|member| *member != candidate);
+                self.category_members
+                    .insert((election_id, category), &members);
+            }
+            for category in &categories {
+                let mut members = self
+                    .category_members
+                    .get((election_id, *category))
+                    .unwrap_or_default();
+                members.push(candidate);
+                self.category_members
+                    .insert((election_id, *category), &members);
+            }
+            self.candidate_categories
+                .insert((election_id, candidate), &categories);
+            self.env().emit_event(CandidateCategorized {
+                election_id,
+                candidate,
+                categories,
+            });
+            Ok(())
+        }
+
+        /// Returns the categories a candidate is tagged with, in ascending order.
+        #[ink(message, selector = 0x8df3_2454)]
+        pub fn get_categories(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Vec<CategoryId> {
+            self.candidate_categories
+                .get((election_id, candidate))
+                .unwrap_or_default()
+        }
+
+        /// Returns the candidates tagged with `category`, in the order they
+        /// were tagged, at most `MAX_PAGE_SIZE` at a time.
+        #[ink(message, selector = 0xd30b_403b)]
+        pub fn get_candidates_by_category(
+            &self,
+            election_id: ElectionId,
+            category: CategoryId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<AccountId> {
+            self.category_members
+                .get((election_id, category))
+                .unwrap_or_default()
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .collect()
+        }
+
+        /// Returns the current winner(s) among the candidates tagged with
+        /// `category`, as `get_current_winner` picks them among all runners,
+        /// e.g. to fill one seat per region.
+        #[ink(message, selector = 0x0e4e_d4e9)]
+        pub fn get_category_winner(
+            &self,
+            election_id: ElectionId,
+            category: CategoryId,
+        ) -> Vec<AccountId> {
+            if self.hides_results(election_id) {
+                return Vec::new();
+            }
+            let members = self
+                .category_members
+                .get((election_id, category))
+                .unwrap_or_default();
+            let totals: Vec<(AccountId, u32)> = self
+                .totals(election_id)
+                .into_iter()
+                .filter(|(runner, _)| members.contains(runner))
+                .collect();
+            Self::winners(&totals, self.tie_break(election_id)).0
+        }
+
+        /// Assigns `voters` to a constituency of the election, replacing their
+        /// earlier one, at most `MAX_VOTER_BATCH` at a time and until voting
+        /// closes. Ballots then count in the voter's constituency as well as
+        /// in the whole election, delegated weight included, so voters who
+        /// already voted cannot move. Only the election admin may call it.
+        #[ink(message, selector = 0xfdea_a38d)]
+        pub fn assign_constituency(
+            &mut self,
+            election_id: ElectionId,
+            constituency: ConstituencyId,
+            voters: Vec<AccountId>,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("assign_constituency"))?;
+            if voters.len() > MAX_VOTER_BATCH {
+                return Err(VoteError::BatchTooLarge);
+            }
+            if !matches!(
+                election.status,
+                ElectionStatus::Pending
+                    | ElectionStatus::Registration
+                    | ElectionStatus::SlateFrozen
+                    | ElectionStatus::Campaign
+                    | ElectionStatus::Voting
+            ) {
+                return Err(VoteError::InvalidTransition);
+            }
+            if constituency >= election.config.constituencies {
+                return Err(VoteError::InvalidConstituency);
+            }
+            if voters.iter().any(|voter| {
+                self.already_voted
+                    .get((election_id, *voter))
+                    .unwrap_or(false)
+                    || self.approvals_cast.contains((election_id, *voter))
+            }) {
+                return Err(VoteError::AlreadyVoted);
+            }
+            for voter in &voters {
+                self.constituency_of
+                    .insert((election_id, *voter), &constituency);
+            }
+            self.env().emit_event(VotersAssigned {
+                election_id,
+                constituency,
+                assigned: voters.len() as u32,
+            });
+            Ok(())
+        }
+
+        /// Returns the constituency a voter is assigned to.
+        #[ink(message, selector = 0xa597_ccd2)]
+        pub fn get_constituency(
+            &self,
+            election_id: ElectionId,
+            voter: AccountId,
+        ) -> Option<ConstituencyId> {
+            self.constituency_of.get((election_id, voter))
+        }
+
+        /// Returns the votes a candidate received from a constituency.
+        #[ink(message, selector = 0xc3fc_4875)]
+        pub fn get_constituency_votes(
+            &self,
+            election_id: ElectionId,
+            constituency: ConstituencyId,
+            candidate: AccountId,
+        ) -> u32 {
+            if self.hides_results(election_id) {
+                return 0;
+            }
+            self.constituency_votes
+                .get((election_id, constituency, candidate))
+                .unwrap_or_default()
+        }
+
+        /// Returns the current winner(s) of a constituency, picked among all
+        /// runners by the votes it cast as `get_current_winner` picks them
+        /// election-wide, e.g. to elect one regional representative each.
+        #[ink(message, selector = 0x17bd_4fa1)]
+        pub fn get_constituency_winner(
+            &self,
+            election_id: ElectionId,
+            constituency: ConstituencyId,
+        ) -> Vec<AccountId> {
+            if self.hides_results(election_id) {
+                return Vec::new();
+            }
+            let totals: RunnerTotals = self
+                .runners
+                .get(election_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|runner| {
+                    let votes = self
+                        .constituency_votes
+                        .get((election_id, constituency, runner))
+                        .unwrap_or_default();
+                    (runner, votes)
+                })
+                .collect();
+            Self::winners(&totals, self.tie_break(election_id)).0
+        }
+
+        /// Returns the standing of a runner, one for the most votes, with
+        /// tied runners sharing a rank and the next rank skipping as many.
+        /// Elections tracking standings answer from them in one read, others
+        /// by counting every runner; electoral colleges rank on electors.
+        ///
+        /// Returns `None` for an account not running, and while the
+        /// election's counts are hidden.
+        #[ink(message, selector = 0x7a33_4273)]
+        pub fn get_rank(&self, election_id: ElectionId, candidate: AccountId) -> Option<u32> {
+            let election = self.elections.get(election_id)?;
+            if self.results_hidden(&election) {
+                return None;
+            }
+            if election.config.track_standings
+                && election.config.electors.is_empty()
+                && !self.archive.contains(election_id)
+            {
+                let standings = self.standings.get(election_id).unwrap_or_default();
+                let votes = match standings.iter().find(|(runner, _)| *runner == candidate) {
+                    Some((_, votes)) => *votes,
+                    None if self
+                        .runners
+                        .get(election_id)
+                        .unwrap_or_default()
+                        .contains(&candidate) =>
+                    {
+                        0
+                    }
+                    None => return None,
+                };
+                let ahead = standings.partition_point(|(_, total)| *total > votes);
+                return Some(ahead as u32 + 1);
+            }
+            let totals = self.totals(election_id);
+            let (_, votes) = totals.iter().find(|(runner, _)| *runner == candidate)?;
+            let ahead = totals.iter().filter(|(_, total)| total > votes).count();
+            Some(ahead as u32 + 1)
+        }
+
+        /// Returns the electors each runner holds in an electoral college
+        /// election, in runner order, as counted from the constituency
+        /// winners so far.
+        #[ink(message, selector = 0xce19_8da8)]
+        pub fn get_elector_totals(&self, election_id: ElectionId) -> Vec<(AccountId, u32)> {
+            let Some(election) = self.elections.get(election_id) else {
+                return Vec::new();
+            };
+            if election.config.electors.is_empty() {
+                return Vec::new();
+            }
+            if let Some(archived) = self.archive.get(election_id) {
+                return archived.totals;
+            }
+            if self.results_hidden(&election) {
+                return self.hidden_totals(election_id);
+            }
+            self.live_totals(election_id, &election.config)
+        }
+
+        /// Adds the `Pending` election `race_id` as a race of `election_id`,
+        /// e.g. a treasurer race next to a presidential one. Each race keeps
+        /// its own candidates, phases, tallies and winners, and `vote_races`
+        /// covers all of them with one ballot. An election holds at most
+        /// `MAX_RACES` races besides its own, and races hold none. The
+        /// caller must administer both elections.
+        #[ink(message, selector = 0x3186_3221)]
+        pub fn add_race(
+            &mut self,
+            election_id: ElectionId,
+            race_id: ElectionId,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election = self.election_as_admin(election_id, ink::selector_bytes!("add_race"))?;
+            let race = self.election_as_admin(race_id, ink::selector_bytes!("add_race"))?;
+            if election.status != ElectionStatus::Pending || race.status != ElectionStatus::Pending
+            {
+                return Err(VoteError::InvalidTransition);
+            }
+            if race_id == election_id
+                || self.race_of.contains(election_id)
+                || self.race_of.contains(race_id)
+                || self.races.contains(race_id)
+            {
+                return Err(VoteError::AlreadyARace);
+            }
+            let mut races = self.races.get(election_id).unwrap_or_default();
+            if races.len() >= MAX_RACES {
+                return Err(VoteError::TooManyRaces);
+            }
+            races.push(race_id);
+            self.races.insert(election_id, &races);
+            self.race_of.insert(race_id, &election_id);
+            self.env().emit_event(RaceAdded {
+                election_id,
+                race_id,
+            });
+            Ok(())
+        }
+
+        /// Returns the races `vote_races` covers: the election itself, then
+        /// the races added to it, in the order they were added.
+        #[ink(message, selector = 0x2df9_fd47)]
+        pub fn get_races(&self, election_id: ElectionId) -> Vec<ElectionId> {
+            let mut races = ink::prelude::vec![election_id];
+            races.extend(self.races.get(election_id).unwrap_or_default());
+            races
+        }
+
+        /// Returns the election a race was added to, if any.
+        #[ink(message, selector = 0xba37_5217)]
+        pub fn get_race_of(&self, race_id: ElectionId) -> Option<ElectionId> {
+            self.race_of.get(race_id)
+        }
+
+        /// Casts the caller's ballot in every race of an election at once,
+        /// for one candidate per race in `get_races` order. The transferred
+        /// value must add up the fees of all races. Nothing is recorded
+        /// unless every race accepts its ballot.
+        ///
+        /// Returns the ballot id recorded in each race.
+        #[ink(message, payable, selector = 0xacaa_f946)]
+        pub fn vote_races(
+            &mut self,
+            election_id: ElectionId,
+            choices: Vec<AccountId>,
+        ) -> Result<Vec<BallotId>, VoteError> {
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let races = self.get_races(election_id);
+            if choices.len() != races.len() {
+                return Err(VoteError::WrongRaceCount);
+            }
+            let mut fees = Vec::with_capacity(races.len());
+            for (race_id, candidate) in races.iter().zip(&choices) {
+                let (race, _, _) = self.check_ballot(caller, *race_id, &[*candidate])?;
+                if race.config.encrypted_ballots.is_some() {
+                    return Err(VoteError::BallotsEncrypted);
+                }
+                if race.config.anonymous {
+                    return Err(VoteError::BallotsAnonymous);
+                }
+                fees.push(Self::native_due(&race.config, race.config.vote_fee));
+            }
+            let due = fees
+                .iter()
+                .fold(0, |due: Balance, fee| due.saturating_add(*fee));
+            if self.env().transferred_value() != due {
+                return Err(VoteError::IncorrectFee);
+            }
+            let mut ballots = Vec::with_capacity(races.len());
+            for ((race_id, candidate), fee) in races.into_iter().zip(choices).zip(fees) {
+                ballots.push(self.record_ballot(
+                    caller,
+                    race_id,
+                    BallotChoice::Single(candidate),
+                    Vec::new(),
+                    fee,
+                )?);
+            }
+            Ok(ballots)
+        }
+
+        /// Returns the candidates a finalized single-transferable-vote election
+        /// seated, in order of election.
+        #[ink(message, selector = 0x5509_025f)]
+        pub fn get_stv_elected(&self, election_id: ElectionId) -> Vec<AccountId> {
+            self.stv_elected.get(election_id).unwrap_or_default()
+        }
+
+        /// Returns the seats each party currently wins in a committee election,
+        /// in runner order, and the members elected to them.
+        #[ink(message, selector = 0x1156_d741)]
+        pub fn get_committee(
+            &self,
+            election_id: ElectionId,
+        ) -> (Vec<(AccountId, u32)>, Vec<AccountId>) {
+            let Some(committee) = self
+                .elections
+                .get(election_id)
+                .and_then(|election| election.config.committee)
+            else {
+                return (Vec::new(), Vec::new());
+            };
+            if self.hides_results(election_id) {
+                return (Vec::new(), Vec::new());
+            }
+            let totals = self.totals(election_id);
+            let slates: Vec<Vec<AccountId>> = totals
+                .iter()
+                .map(|(party, _)| self.get_slate(election_id, *party))
+                .collect();
+            let sizes: Vec<u32> = slates.iter().map(|slate| slate.len() as u32).collect();
+            let won = counting::apportion(&totals, &sizes, committee.seats, |seats| {
+                committee.method.divisor(seats)
+            });
+            let elected = slates
+                .into_iter()
+                .zip(&won)
+                .flat_map(|(slate, seats)| slate.into_iter().take(*seats as usize))
+                .collect();
+            let seats = totals.iter().map(|(party, _)| *party).zip(won).collect();
+            (seats, elected)
+        }
+
+        /// Returns how many storage entries the registrations and ballots of
+        /// an election created, as counted against its `storage_budget`.
+        #[ink(message, selector = 0x9e28_b940)]
+        pub fn get_storage_entries(&self, election_id: ElectionId) -> u32 {
+            self.elections
+                .get(election_id)
+                .map_or(0, |election| election.storage_entries)
+        }
+
+        /// Returns the deposit the next candidate of the election must make,
+        /// in the election's fee token if it has one.
+        #[ink(message, selector = 0x253e_eca4)]
+        pub fn get_candidacy_deposit(&self, election_id: ElectionId) -> Balance {
+            self.elections.get(election_id).map_or(0, |election| {
+                let running = self.runners.get(election_id).unwrap_or_default().len();
+                election.config.candidacy_deposit_for(running)
+            })
+        }
+
+        /// Tells whether `account` could currently register as a candidate
+        /// with `deposit` attached, running every check `register_candidate`
+        /// does without changing state.
+        #[ink(message, selector = 0x32a1_1999)]
+        pub fn can_register(
+            &self,
+            account: AccountId,
+            election_id: ElectionId,
+            deposit: Balance,
+        ) -> Result<(), RegistrationError> {
+            self.check_registration(account, election_id, deposit)
+                .map(|_| ())
+        }
+
+        /// Bars an account from registering as a candidate.
+        /// Only the election admin may call it, and only through
+        /// `schedule_action` while a timelock is set.
+        #[ink(message, selector = 0x66b1_1465)]
+        pub fn ban_candidate(
+            &mut self,
+            election_id: ElectionId,
+            account: AccountId,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            self.election_as_admin(election_id, ink::selector_bytes!("ban_candidate"))?;
+            self.ensure_no_timelock()?;
+            self.apply_ban(election_id, account);
+            Ok(())
+        }
+
+        fn apply_ban(&mut self, election_id: ElectionId, account: AccountId) {
+            self.banned.insert((election_id, account), &());
+            self.env().emit_event(CandidateBanned {
+                election_id,
+                candidate: account,
+            });
+        }
+
+        /// Registers `accounts` as voters of the election, at most
+        /// `MAX_VOTER_BATCH` at a time, until voting closes. Only the election
+        /// admin may call it.
+        ///
+        /// Returns, for every entry, whether it was newly registered; accounts
+        /// already in the electorate, or repeated in the batch, are skipped.
+        #[ink(message, selector = 0x7629_ec2b)]
+        pub fn add_voters(
+            &mut self,
+            election_id: ElectionId,
+            accounts: Vec<AccountId>,
+        ) -> Result<Vec<bool>, VoteError> {
+            self.ensure_phase(election_id);
+            let mut election =
+                self.election_as_admin(election_id, ink::selector_bytes!("add_voters"))?;
+            if accounts.len() > MAX_VOTER_BATCH {
+                return Err(VoteError::BatchTooLarge);
+            }
+            if !matches!(
+                election.status,
+                ElectionStatus::Pending
+                    | ElectionStatus::Registration
+                    | ElectionStatus::SlateFrozen
+                    | ElectionStatus::Campaign
+                    | ElectionStatus::Voting
+            ) || self.rerun_of.contains(election_id)
+            {
+                return Err(VoteError::InvalidTransition);
+            }
+            let added: Vec<bool> = accounts
+                .into_iter()
+                .map(|account| {
+                    let new = !self.electorate.contains((election_id, account));
+                    if new {
+                        self.enroll_voter(election_id, &mut election, account, 0);
+                    }
+                    new
+                })
+                .collect();
+            let count = added.iter().filter(|added| **added).count() as u32;
+            self.elections.insert(election_id, &election);
+            self.env().emit_event(VotersAdded {
+                election_id,
+                added: count,
+                electorate: election.electorate,
+            });
+            Ok(added)
+        }
+
+        /// Registers `candidates` as runners of a `Pending` election without a
+        /// deposit, at most `MAX_VOTER_BATCH` at a time, e.g. as exported by
+        /// the contract this one succeeds. Only the election admin may call it.
+        ///
+        /// Returns, for every entry, whether it was newly registered; runners,
+        /// banned accounts, repeated entries and entries beyond
+        /// `max_candidates` are skipped.
+        #[ink(message, selector = 0xb5cf_7a0b)]
+        pub fn import_candidates(
+            &mut self,
+            election_id: ElectionId,
+            candidates: Vec<AccountId>,
+        ) -> Result<Vec<bool>, VoteError> {
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("import_candidates"))?;
+            if candidates.len() > MAX_VOTER_BATCH {
+                return Err(VoteError::BatchTooLarge);
+            }
+            if election.status != ElectionStatus::Pending {
+                return Err(VoteError::InvalidTransition);
+            }
+            let mut runners = self.runners.get(election_id).unwrap_or_default();
+            let max = election.config.max_candidates as usize;
+            let added: Vec<bool> = candidates
+                .into_iter()
+                .map(|candidate| {
+                    let new = !runners.contains(&candidate)
+                        && !self.banned.contains((election_id, candidate))
+                        && (max == 0 || runners.len() < max);
+                    if new {
+                        runners.push(candidate);
+                        self.count_candidacy(candidate);
+                        self.env().emit_event(CandidateRegistered {
+                            election_id,
+                            candidate,
+                            deposit: 0,
+                        });
+                    }
+                    new
+                })
+                .collect();
+            self.runners.insert(election_id, &runners);
+            Ok(added)
+        }
+
+        /// Joins the closed electorate of the election by transferring exactly
+        /// its `voter_deposit`, before voting opens.
+        #[ink(message, payable, selector = 0x81b2_cf8b)]
+        pub fn register_voter(&mut self, election_id: ElectionId) -> Result<(), RegistrationError> {
+            if self.paying_out() {
+                return Err(RegistrationError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            let Some(required) = election.config.voter_deposit else {
+                return Err(RegistrationError::RegistrationClosed);
+            };
+            if !election.config.closed_electorate
+                || !matches!(
+                    election.status,
+                    ElectionStatus::Pending | ElectionStatus::Registration
+                )
+            {
+                return Err(RegistrationError::RegistrationClosed);
+            }
+            if self.electorate.contains((election_id, caller)) {
+                return Err(RegistrationError::AlreadyRegistered);
+            }
+            if self.banned_voters.contains((election_id, caller)) {
+                return Err(RegistrationError::Banned);
+            }
+            if election.storage_exhausted() {
+                return Err(RegistrationError::StorageBudgetExceeded);
+            }
+            let deposit = self.env().transferred_value();
+            if deposit != required {
+                return Err(RegistrationError::IncorrectDeposit);
+            }
+            self.enroll_voter(election_id, &mut election, caller, deposit);
+            self.funds.deposits = self.funds.deposits.saturating_add(deposit);
+            self.elections.insert(election_id, &election);
+            self.env().emit_event(VoterRegistered {
+                election_id,
+                voter: caller,
+                deposit,
+            });
+            Ok(())
+        }
+
+        /// Hands out invitation codes to the closed electorate of the
+        /// election, given as their `invitation_code_hash`, at most
+        /// `MAX_VOTER_BATCH` at a time. Whoever presents a code to
+        /// `register_with_code` joins the electorate, so the admin does not
+        /// need to know the voters' accounts in advance. Only the election
+        /// admin may call it, before voting opens.
+        ///
+        /// Returns how many codes were new.
+        #[ink(message, selector = 0x1e10_5d3e)]
+        pub fn add_invitation_codes(
+            &mut self,
+            election_id: ElectionId,
+            code_hashes: Vec<Hash>,
+        ) -> Result<u32, VoteError> {
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("add_invitation_codes"))?;
+            if code_hashes.len() > MAX_VOTER_BATCH {
+                return Err(VoteError::BatchTooLarge);
+            }
+            if !election.config.closed_electorate {
+                return Err(VoteError::ElectorateOpen);
+            }
+            if !matches!(
+                election.status,
+                ElectionStatus::Pending | ElectionStatus::Registration
+            ) {
+                return Err(VoteError::InvalidTransition);
+            }
+            let mut added = 0u32;
+            for code_hash in code_hashes {
+                if self
+                    .invitation_codes
+                    .insert((election_id, code_hash), &())
+                    .is_none()
+                {
+                    added = added.saturating_add(1);
+                }
+            }
+            Ok(added)
+        }
+
+        /// Joins the closed electorate of the election with an invitation
+        /// code handed out through `add_invitation_codes`, without a
+        /// deposit. Every code admits one voter, and only until voting opens.
+        #[ink(message, selector = 0x393d_be32)]
+        pub fn register_with_code(
+            &mut self,
+            election_id: ElectionId,
+            code: Vec<u8>,
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            if !election.config.closed_electorate
+                || !matches!(
+                    election.status,
+                    ElectionStatus::Pending | ElectionStatus::Registration
+                )
+            {
+                return Err(RegistrationError::RegistrationClosed);
+            }
+            if self.electorate.contains((election_id, caller)) {
+                return Err(RegistrationError::AlreadyRegistered);
+            }
+            if self.banned_voters.contains((election_id, caller)) {
+                return Err(RegistrationError::Banned);
+            }
+            if election.storage_exhausted() {
+                return Err(RegistrationError::StorageBudgetExceeded);
+            }
+            if code.len() > MAX_CODE_LEN {
+                return Err(RegistrationError::InvalidCode);
+            }
+            let code_hash = self.invitation_code_hash(code);
+            if self
+                .invitation_codes
+                .take((election_id, code_hash))
+                .is_none()
+            {
+                return Err(RegistrationError::InvalidCode);
+            }
+            self.enroll_voter(election_id, &mut election, caller, 0);
+            self.elections.insert(election_id, &election);
+            self.env().emit_event(VoterRegistered {
+                election_id,
+                voter: caller,
+                deposit: 0,
+            });
+            Ok(())
+        }
+
+        /// Returns the hash under which `add_invitation_codes` takes `code`.
+        #[ink(message, selector = 0x019a_8b5c)]
+        pub fn invitation_code_hash(&self, code: Vec<u8>) -> Hash {
+            let mut hash = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&code, &mut hash);
+            Hash::from(hash)
+        }
+
+        /// Returns whether the invitation code hashing to `code_hash` would
+        /// still admit a voter to the election.
+        #[ink(message, selector = 0x52db_b565)]
+        pub fn has_invitation(&self, election_id: ElectionId, code_hash: Hash) -> bool {
+            self.elections.get(election_id).is_some_and(|election| {
+                matches!(
+                    election.status,
+                    ElectionStatus::Pending | ElectionStatus::Registration
+                )
+            }) && self.invitation_codes.contains((election_id, code_hash))
+        }
+
+        /// Leaves the electorate of the election, refunding the caller's
+        /// deposit. Not possible while ballots are being cast or counted.
+        ///
+        /// Returns the refunded deposit.
+        #[ink(message, selector = 0xa427_9724)]
+        pub fn deregister_voter(
+            &mut self,
+            election_id: ElectionId,
+        ) -> Result<Balance, RegistrationError> {
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            if Self::electorate_frozen(&election) || self.rerun_pending(election_id) {
+                return Err(RegistrationError::RegistrationClosed);
+            }
+            if !self.electorate.contains((election_id, caller)) {
+                return Err(RegistrationError::NotRegistered);
+            }
+            Ok(self.drop_voter(election_id, election, caller))
+        }
+
+        /// Removes `account` from the electorate of the election, refunding
+        /// its deposit. Not possible while ballots are being cast or counted.
+        /// Only the election admin may call it.
+        ///
+        /// Returns the refunded deposit.
+        #[ink(message, selector = 0x7ffc_a5f2)]
+        pub fn remove_voter(
+            &mut self,
+            election_id: ElectionId,
+            account: AccountId,
+        ) -> Result<Balance, VoteError> {
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("remove_voter"))?;
+            if Self::electorate_frozen(&election) || self.rerun_pending(election_id) {
+                return Err(VoteError::InvalidTransition);
+            }
+            if !self.electorate.contains((election_id, account)) {
+                return Err(VoteError::NotInElectorate);
+            }
+            Ok(self.drop_voter(election_id, election, account))
+        }
+
+        /// Vouches for `account` to join the closed electorate of the election,
+        /// which it does once it holds `vouches_required` vouches. Only
+        /// registered voters not barred from vouching may vouch, before voting
+        /// opens, and each of them once per account.
+        #[ink(message, selector = 0x353d_cb22)]
+        pub fn vouch(
+            &mut self,
+            election_id: ElectionId,
+            account: AccountId,
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let mut election = self.vouching_election(election_id)?;
+            if !self.electorate.contains((election_id, caller))
+                || self.vouching_barred.contains((election_id, caller))
+            {
+                return Err(RegistrationError::NotAVoucher);
+            }
+            if self.electorate.contains((election_id, account)) {
+                return Err(RegistrationError::AlreadyRegistered);
+            }
+            if self.banned_voters.contains((election_id, account)) {
+                return Err(RegistrationError::Banned);
+            }
+            if election.storage_exhausted() {
+                return Err(RegistrationError::StorageBudgetExceeded);
+            }
+            let mut vouchers = self
+                .vouchers_of
+                .get((election_id, account))
+                .unwrap_or_default();
+            if vouchers.contains(&caller) {
+                return Err(RegistrationError::AlreadyVouched);
+            }
+            vouchers.push(caller);
+            self.vouchers_of.insert((election_id, account), &vouchers);
+            let vouches = vouchers.len() as u32;
+            self.env().emit_event(VouchUpdated {
+                election_id,
+                voucher: caller,
+                account,
+                vouches,
+            });
+            if vouches >= election.config.vouches_required {
+                self.enroll_voter(election_id, &mut election, account, 0);
+                self.elections.insert(election_id, &election);
+                self.env().emit_event(VoterRegistered {
+                    election_id,
+                    voter: account,
+                    deposit: 0,
+                });
+            }
+            Ok(())
+        }
+
+        /// Takes back the caller's vouch for `account`, before voting opens.
+        /// An account vouched into the electorate leaves it again.
+        #[ink(message, selector = 0x24d6_855c)]
+        pub fn revoke_vouch(
+            &mut self,
+            election_id: ElectionId,
+            account: AccountId,
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let election = self.vouching_election(election_id)?;
+            let mut vouchers = self
+                .vouchers_of
+                .get((election_id, account))
+                .unwrap_or_default();
+            let Some(position) = vouchers.iter().position(|voucher| *voucher == caller) else {
+                return Err(RegistrationError::NotAVoucher);
+            };
+            let vouched_in = vouchers.len() as u32 >= election.config.vouches_required;
+            vouchers.remove(position);
+            self.vouchers_of.insert((election_id, account), &vouchers);
+            self.env().emit_event(VouchUpdated {
+                election_id,
+                voucher: caller,
+                account,
+                vouches: vouchers.len() as u32,
+            });
+            if vouched_in && self.electorate.contains((election_id, account)) {
+                self.drop_voter(election_id, election, account);
+            }
+            Ok(())
+        }
+
+        /// Returns the voters vouching for `account`.
+        #[ink(message, selector = 0xeedf_6f40)]
+        pub fn get_vouchers(&self, election_id: ElectionId, account: AccountId) -> Vec<AccountId> {
+            self.vouchers_of
+                .get((election_id, account))
+                .unwrap_or_default()
+        }
+
+        /// Bars `account` from the electorate of the election, before voting
+        /// opens. Its deposit goes to the treasury, and the voters who vouched
+        /// for it may no longer vouch. Only the election admin may call it.
+        #[ink(message, selector = 0x4d44_dfb4)]
+        pub fn ban_voter(
+            &mut self,
+            election_id: ElectionId,
+            account: AccountId,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let mut election =
+                self.election_as_admin(election_id, ink::selector_bytes!("ban_voter"))?;
+            if Self::electorate_frozen(&election)
+                || self.rerun_pending(election_id)
+                || self.rerun_of.contains(election_id)
+            {
+                return Err(VoteError::InvalidTransition);
+            }
+            self.banned_voters.insert((election_id, account), &());
+            let forfeited = match self.unenroll_voter(election_id, &mut election, account) {
+                Some(deposit) => {
+                    self.elections.insert(election_id, &election);
+                    deposit
+                }
+                None => 0,
+            };
+            self.forfeit(forfeited);
+            self.funds.deposits = self.funds.deposits.saturating_sub(forfeited);
+            let vouchers = self
+                .vouchers_of
+                .take((election_id, account))
+                .unwrap_or_default();
+            for voucher in &vouchers {
+                self.vouching_barred.insert((election_id, *voucher), &());
+            }
+            self.env().emit_event(VoterBanned {
+                election_id,
+                voter: account,
+                forfeited,
+                vouchers_barred: vouchers.len() as u32,
+            });
+            Ok(())
+        }
+
+        /// Returns whether `account` is a registered voter of the election.
+        #[ink(message, selector = 0x52da_c2ec)]
+        pub fn is_voter(&self, election_id: ElectionId, account: AccountId) -> bool {
+            self.electorate
+                .contains((self.electorate_id(election_id), account))
+        }
+
+        /// Returns how many voters are registered for the election.
+        #[ink(message, selector = 0xe91e_4933)]
+        pub fn get_electorate_size(&self, election_id: ElectionId) -> u32 {
+            self.elections
+                .get(election_id)
+                .map(|election| election.electorate)
+                .unwrap_or_default()
+        }
+
+        /// Returns the registered voters of the election, at most
+        /// `MAX_PAGE_SIZE` at a time, e.g. for a successor contract to load
+        /// them with `add_voters`. The order only changes when voters leave,
+        /// which they cannot while ballots are being cast or counted.
+        #[ink(message, selector = 0x2b2d_7578)]
+        pub fn export_voters(
+            &self,
+            election_id: ElectionId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<AccountId> {
+            let electorate_id = self.electorate_id(election_id);
+            let len = self.get_electorate_size(electorate_id);
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+            (offset..end)
+                .filter_map(|index| self.electorate_at.get((electorate_id, index)))
+                .collect()
+        }
+
+        /// Returns the runners of the election in the order they entered the
+        /// race, at most `MAX_PAGE_SIZE` at a time, e.g. for a successor
+        /// contract to load them with `import_candidates`.
+        #[ink(message, selector = 0x19ac_50ab)]
+        pub fn export_candidates(
+            &self,
+            election_id: ElectionId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<AccountId> {
+            self.runners
+                .get(election_id)
+                .unwrap_or_default()
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .collect()
+        }
+
+        /// Endorses a prospective candidate while registration is open, or
+        /// a running one during the campaign. Every account may endorse a
+        /// given candidate once.
+        #[ink(message, selector = 0xcf0e_6d82)]
+        pub fn endorse(
+            &mut self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            if !matches!(
+                election.status,
+                ElectionStatus::Registration | ElectionStatus::Campaign
+            ) {
+                return Err(RegistrationError::RegistrationClosed);
+            }
+
+            let caller = self.env().caller();
+            if caller == candidate {
+                return Err(RegistrationError::SelfEndorsement);
+            }
+            if self.endorsed.contains((election_id, caller, candidate)) {
+                return Err(RegistrationError::AlreadyEndorsed);
+            }
+            self.endorsed.insert((election_id, caller, candidate), &());
+            let endorsements = self.get_endorsements(election_id, candidate);
+            self.endorsements
+                .insert((election_id, candidate), &endorsements.saturating_add(1));
+            self.env().emit_event(Endorsed {
+                election_id,
+                endorser: caller,
+                candidate,
+            });
+            Ok(())
+        }
+
+        /// Refers a prospective candidate of the election, which then may
+        /// register where `referral_required` is set. Only the election admin
+        /// and its verified candidates may refer, before voting opens, and
+        /// every candidate is referred once.
+        #[ink(message, selector = 0x5969_ffcf)]
+        pub fn refer(
+            &mut self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            self.record_referral(election_id, self.env().caller(), candidate)
+        }
+
+        /// Records the referral of the caller signed off-chain by `referrer`,
+        /// who must be allowed to `refer`. The signature is an ECDSA signature
+        /// over the caller's `referral_hash`, and the referrer is the account
+        /// its public key hashes to.
+        #[ink(message, selector = 0xbe0f_8a95)]
+        pub fn redeem_referral(
+            &mut self,
+            election_id: ElectionId,
+            referrer: AccountId,
+            signature: [u8; 65],
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let digest = self.referral_digest(election_id, caller);
+            let key = self
+                .env()
+                .ecdsa_recover(&signature, &digest)
+                .map_err(|_| RegistrationError::InvalidSignature)?;
+            let mut signer = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&key, &mut signer);
+            if AccountId::from(signer) != referrer {
+                return Err(RegistrationError::InvalidSignature);
+            }
+            self.record_referral(election_id, referrer, caller)
+        }
+
+        /// Returns the hash a referrer signs to refer `candidate` through
+        /// `redeem_referral`.
+        #[ink(message, selector = 0xf7b2_8595)]
+        pub fn referral_hash(&self, election_id: ElectionId, candidate: AccountId) -> Hash {
+            Hash::from(self.referral_digest(election_id, candidate))
+        }
+
+        /// Returns who referred a candidate of the election, if anyone did.
+        #[ink(message, selector = 0x84e0_74b5)]
+        pub fn get_referrer(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Option<AccountId> {
+            self.referrals.get((election_id, candidate))
+        }
+
+        /// Posts or replaces the caller's campaign statement as a running
+        /// candidate of the election, while registration is open or during
+        /// the campaign.
+        #[ink(message, selector = 0x8d90_17ba)]
+        pub fn post_statement(
+            &mut self,
+            election_id: ElectionId,
+            statement: Hash,
+        ) -> Result<(), RegistrationError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            if !matches!(
+                election.status,
+                ElectionStatus::Registration | ElectionStatus::Campaign
+            ) {
+                return Err(RegistrationError::RegistrationClosed);
+            }
+            let candidate = self.env().caller();
+            if !self
+                .runners
+                .get(election_id)
+                .unwrap_or_default()
+                .contains(&candidate)
+            {
+                return Err(RegistrationError::NotRegistered);
+            }
+            self.statements.insert((election_id, candidate), &statement);
+            self.env().emit_event(StatementPosted {
+                election_id,
+                candidate,
+                statement,
+            });
+            Ok(())
+        }
+
+        /// Returns the hash of a candidate's campaign statement.
+        #[ink(message, selector = 0xa931_1a0d)]
+        pub fn get_statement(&self, election_id: ElectionId, candidate: AccountId) -> Option<Hash> {
+            self.statements.get((election_id, candidate))
+        }
+
+        /// Moves an election into the phase its thresholds call for, as
+        /// every message acting on it does on its own first. Anyone may call
+        /// it.
+        ///
+        /// Returns the phase the election is in.
+        #[ink(message, selector = 0x8f94_3982)]
+        pub fn advance_phase(
+            &mut self,
+            election_id: ElectionId,
+        ) -> Result<ElectionStatus, VoteError> {
+            self.ensure_phase(election_id);
+            self.get_status(election_id)
+                .ok_or(VoteError::ElectionNotFound)
+        }
+
+        /// Returns how many endorsements a candidate collected so far.
+        #[ink(message, selector = 0x95c5_3e8d)]
+        pub fn get_endorsements(&self, election_id: ElectionId, candidate: AccountId) -> u32 {
+            self.endorsements
+                .get((election_id, candidate))
+                .unwrap_or_default()
+        }
+
+        /// Returns the votes a runner holds so far, or `0` while the
+        /// election's counts are hidden.
+        #[ink(message, selector = 0x5f9d_374c)]
+        pub fn get_votes(&self, election_id: ElectionId, address: AccountId) -> u32 {
+            if self.hides_results(election_id) {
+                return 0;
+            }
+            self.votes.get((election_id, address)).unwrap_or_default()
+        }
+
+        /// Casts the caller's ballot. It must carry exactly the configured `vote_fee`.
+        ///
+        /// In asset-weighted elections the ballot is worth one vote per `unit`
+        /// of the asset the caller holds.
+        ///
+        /// Returns the id of the ballot, one more than the previous accepted
+        /// ballot's in any election.
+        #[ink(message, payable, selector = 0x083b_e260)]
+        pub fn vote(
+            &mut self,
+            election_id: ElectionId,
+            address: AccountId,
+        ) -> Result<BallotId, VoteError> {
+            self.ensure_phase(election_id);
+            self.cast_ballot(
+                self.env().caller(),
+                election_id,
+                BallotChoice::Single(address),
+                Vec::new(),
+            )
+        }
+
+        /// Casts the caller's ballot like `vote`, publishing `memo` in the
+        /// `VoteCast` event. The memo is at most `MAX_MEMO_LEN` bytes long.
+        #[ink(message, payable, selector = 0x3bbb_1084)]
+        pub fn vote_with_memo(
+            &mut self,
+            election_id: ElectionId,
+            address: AccountId,
+            memo: Vec<u8>,
+        ) -> Result<BallotId, VoteError> {
+            self.ensure_phase(election_id);
+            if memo.len() > MAX_MEMO_LEN {
+                return Err(VoteError::MemoTooLong);
+            }
+            self.cast_ballot(
+                self.env().caller(),
+                election_id,
+                BallotChoice::Single(address),
+                memo,
+            )
+        }
+
+        /// Casts the caller's ballot like `vote`, committing to the 32-byte
+        /// hash of a rationale published elsewhere. The hash is kept with the
+        /// ballot id and returned by `get_reason`.
+        #[ink(message, payable, selector = 0x9720_adf8)]
+        pub fn vote_with_reason(
+            &mut self,
+            election_id: ElectionId,
+            address: AccountId,
+            reason: Hash,
+        ) -> Result<BallotId, VoteError> {
+            self.ensure_phase(election_id);
+            let voter = self.env().caller();
+            let ballot_id = self.cast_ballot(
+                voter,
+                election_id,
+                BallotChoice::Single(address),
+                Vec::new(),
+            )?;
+            self.reasons.insert(ballot_id, &reason);
+            if !self.quiet_events(election_id) {
+                self.env().emit_event(ReasonRecorded {
+                    election_id,
+                    ballot_id,
+                    voter,
+                    reason,
+                });
+            }
+            Ok(ballot_id)
+        }
+
+        /// Returns the rationale hash a ballot was cast with, if any.
+        #[ink(message, selector = 0x4c03_b721)]
+        pub fn get_reason(&self, ballot_id: BallotId) -> Option<Hash> {
+            self.reasons.get(ballot_id)
+        }
+
+        /// Casts the caller's ballot of any kind, which must suit the
+        /// election's `VotingMode` or fails with `UnsupportedBallotType`.
+        /// `Scored` and `Cumulative` ballots are cast through it; the other
+        /// kinds may also be cast through their own messages.
+        #[ink(message, payable, selector = 0xa6d0_cf65)]
+        pub fn vote_ballot(
+            &mut self,
+            election_id: ElectionId,
+            ballot: BallotChoice,
+        ) -> Result<BallotId, VoteError> {
+            self.ensure_phase(election_id);
+            self.cast_ballot(self.env().caller(), election_id, ballot, Vec::new())
+        }
+
+        /// Casts the caller's ballot in a `Split` election, giving each
+        /// candidate its per-mille share. The shares must be nonzero, go to
+        /// distinct candidates and add up to at most `SPLIT_BUDGET`; whatever
+        /// is left is not counted.
+        #[ink(message, payable, selector = 0x09eb_7f71)]
+        pub fn vote_split(
+            &mut self,
+            election_id: ElectionId,
+            allocations: Vec<(AccountId, u16)>,
+        ) -> Result<BallotId, VoteError> {
+            self.ensure_phase(election_id);
+            self.cast_ballot(
+                self.env().caller(),
+                election_id,
+                BallotChoice::Split(allocations),
+                Vec::new(),
+            )
+        }
+
+        /// Casts the caller's ranked ballot in a `SingleTransferable` election,
+        /// listing up to `MAX_RANKING` distinct candidates in order of
+        /// preference. A plain `vote` counts as a ranking of one.
+        #[ink(message, payable, selector = 0x66e0_5beb)]
+        pub fn vote_ranked(
+            &mut self,
+            election_id: ElectionId,
+            ranking: Vec<AccountId>,
+        ) -> Result<BallotId, VoteError> {
+            self.ensure_phase(election_id);
+            self.cast_ballot(
+                self.env().caller(),
+                election_id,
+                BallotChoice::Ranked(ranking),
+                Vec::new(),
+            )
+        }
+
+        /// Casts the caller's ballot in a `Quadratic` election, giving `votes`
+        /// votes to `address`. It must carry `vote_fee` times `votes` squared,
+        /// and does not carry the weight of delegators. A plain `vote` buys a
+        /// single vote.
+        #[ink(message, payable, selector = 0x782d_5e14)]
+        pub fn vote_quadratic(
+            &mut self,
+            election_id: ElectionId,
+            address: AccountId,
+            votes: u16,
+        ) -> Result<BallotId, VoteError> {
+            self.ensure_phase(election_id);
+            self.cast_ballot(
+                self.env().caller(),
+                election_id,
+                BallotChoice::Quadratic(address, votes),
+                Vec::new(),
+            )
+        }
+
+        /// Counts a batch of ballots signed off-chain in an election run with
+        /// `signed_ballots`, at most `MAX_SIGNED_BATCH` at a time. Anyone may
+        /// submit them, transferring `vote_fee` for every ballot of the batch.
+        ///
+        /// Returns, for every ballot, its id or why it was skipped. A ballot
+        /// whose signature cannot be recovered, whose nonce is stale, or which
+        /// its voter could not cast is skipped, its fee is credited back to
+        /// the caller, and `BallotSkipped` records its error code.
+        #[ink(message, payable, selector = 0x7293_513b)]
+        pub fn settle_ballots(
+            &mut self,
+            election_id: ElectionId,
+            ballots: Vec<SignedBallot>,
+        ) -> Result<Vec<Result<BallotId, VoteError>>, VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if !election.config.signed_ballots {
+                return Err(VoteError::SignedBallotsDisabled);
+            }
+            if ballots.len() > MAX_SIGNED_BATCH {
+                return Err(VoteError::BatchTooLarge);
+            }
+            let fee = Self::native_due(&election.config, election.config.vote_fee);
+            if self.env().transferred_value() != fee.saturating_mul(ballots.len() as Balance) {
+                return Err(VoteError::IncorrectFee);
+            }
+            let mut outcomes = Vec::with_capacity(ballots.len());
+            let mut refund: Balance = 0;
+            for (index, ballot) in ballots.into_iter().enumerate() {
+                let outcome = self.settle_ballot(election_id, ballot, fee);
+                if let Err(error) = &outcome {
+                    refund = refund.saturating_add(fee);
+                    self.env().emit_event(BallotSkipped {
+                        election_id,
+                        index: index as u32,
+                        code: error.code(),
+                    });
+                }
+                outcomes.push(outcome);
+            }
+            if refund > 0 {
+                self.credit(self.env().caller(), refund);
+            }
+            Ok(outcomes)
+        }
+
+        /// Returns the hash a voter signs to vote for `candidate` with the
+        /// given nonce through `settle_ballots`.
+        #[ink(message, selector = 0x3d3c_4383)]
+        pub fn signed_ballot_hash(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+            nonce: u64,
+        ) -> Hash {
+            Hash::from(self.signed_ballot_digest(election_id, candidate, nonce))
+        }
+
+        /// Returns the nonce the next signed ballot of `account` must carry.
+        #[ink(message, selector = 0x6c4c_7d3f)]
+        pub fn get_ballot_nonce(&self, account: AccountId) -> u64 {
+            self.ballot_nonces.get(account).unwrap_or_default()
+        }
+
+        /// Casts an anonymous ballot for `candidate`, signed with a linkable
+        /// ring signature over `ring`, which must list the whole electorate.
+        /// The signed message is `anonymous_ballot_hash`. The contract learns
+        /// only that some member voted, and the nullifier `key_image` spends,
+        /// `anonymous_nullifier`, rejects a second ballot from the same
+        /// member. Whoever submits it pays the
+        /// configured `vote_fee`, so a relayer may hide the voter's account.
+        #[ink(message, payable, selector = 0xb1b6_3414)]
+        pub fn vote_anonymous(
+            &mut self,
+            election_id: ElectionId,
+            candidate: AccountId,
+            ring: Vec<AccountId>,
+            key_image: Hash,
+            signature: Vec<u8>,
+        ) -> Result<BallotId, VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if election.paused_at.is_some() {
+                return Err(VoteError::ElectionPaused);
+            }
+            if !election.config.anonymous {
+                return Err(VoteError::AnonymityDisabled);
+            }
+            if election.status != ElectionStatus::Voting
+                || election
+                    .config
+                    .voting_closes_at
+                    .is_some_and(|closes_at| self.now_against(closes_at) == Ordering::Greater)
+            {
+                return Err(VoteError::VotingClosed);
+            }
+            if ring.len() > MAX_RING {
+                return Err(VoteError::BatchTooLarge);
+            }
+            let mut members = ring.clone();
+            members.sort_unstable();
+            members.dedup();
+            let electorate_id = self.electorate_id(election_id);
+            if members.len() != ring.len()
+                || ring.len() != election.electorate as usize
+                || ring
+                    .iter()
+                    .any(|member| !self.electorate.contains((electorate_id, *member)))
+            {
+                return Err(VoteError::InvalidRing);
+            }
+            let nullifier = self.anonymous_nullifier(election_id, key_image);
+            if self.is_nullifier_spent(nullifier) {
+                return Err(VoteError::NullifierSpent);
+            }
+            self.take_block_slot(election_id, &election.config)?;
+            let mut runners = self.runners.get(election_id).unwrap_or_default();
+            let running = runners.contains(&candidate);
+            if !running && election.config.candidacy_gated() {
+                return Err(VoteError::NotACandidate);
+            }
+            if !running && election.storage_exhausted() {
+                return Err(VoteError::StorageBudgetExceeded);
+            }
+            let fee = self.env().transferred_value();
+            let vote_fee = election.config.vote_fee;
+            if fee != Self::native_due(&election.config, vote_fee) {
+                return Err(VoteError::IncorrectFee);
+            }
+            let message = self.anonymous_ballot_digest(election_id, candidate);
+            if !runtime::active().verify_ring_signature(ring, message, key_image, signature) {
+                return Err(VoteError::InvalidSignature);
+            }
+            let new_total = self
+                .votes
+                .get((election_id, candidate))
+                .unwrap_or_default()
+                .checked_add(1)
+                .ok_or(VoteError::VoteOverflow)?;
+            if !self.pull_fee_token(&election.config, self.env().caller(), vote_fee) {
+                return Err(VoteError::TransferFailed);
+            }
+
+            self.nullifiers.insert(nullifier, &true);
+            if !running {
+                runners.push(candidate);
+                self.count_candidacy(candidate);
+                self.runners.insert(election_id, &runners);
+                election.storage_entries = election.storage_entries.saturating_add(1);
+                self.env().emit_event(CandidateRegistered {
+                    election_id,
+                    candidate,
+                    deposit: 0,
+                });
+            }
+            self.votes.insert((election_id, candidate), &new_total);
+            self.update_standings(election_id, &election.config, candidate, new_total);
+            let leaf = Self::compound_ballot_leaf(election_id, candidate, &[nullifier]);
+            self.insert_ballot_leaf(election_id, election.turnout, leaf);
+            election.turnout = election.turnout.saturating_add(1);
+            election.storage_entries = election.storage_entries.saturating_add(1);
+            self.count_in_histogram(election_id);
+            election.fee_pot = election.fee_pot.saturating_add(vote_fee);
+            self.funds.fee_income = self.funds.fee_income.saturating_add(fee);
+            self.elections.insert(election_id, &election);
+            let ballot_id = self.next_ballot_id;
+            self.next_ballot_id = ballot_id.saturating_add(1);
+            self.env().emit_event(AnonymousVoteCast {
+                election_id,
+                ballot_id,
+                candidate,
+                nullifier,
+                fee,
+            });
+            if !election.config.blind_results {
+                self.env().emit_event(TallyUpdated {
+                    election_id,
+                    candidate,
+                    new_total,
+                });
+            }
+            Ok(ballot_id)
+        }
+
+        /// Returns the message the ring signature of an anonymous ballot for
+        /// `candidate` signs.
+        #[ink(message, selector = 0x5142_2434)]
+        pub fn anonymous_ballot_hash(&self, election_id: ElectionId, candidate: AccountId) -> Hash {
+            Hash::from(self.anonymous_ballot_digest(election_id, candidate))
+        }
+
+        /// Returns the nullifier an anonymous ballot signed with `key_image`
+        /// spends in an election: their hash, so that one key votes once in
+        /// every election.
+        #[ink(message, selector = 0x82a1_c49a)]
+        pub fn anonymous_nullifier(&self, election_id: ElectionId, key_image: Hash) -> Hash {
+            let mut nullifier = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &(election_id, key_image),
+                &mut nullifier,
+            );
+            Hash::from(nullifier)
+        }
+
+        /// Returns whether a ballot already spent `nullifier`.
+        #[ink(message, selector = 0x5982_49ca)]
+        pub fn is_nullifier_spent(&self, nullifier: Hash) -> bool {
+            self.nullifiers.get(nullifier).unwrap_or_default()
+        }
+
+        /// Casts the caller's ballot in an election with encrypted ballots,
+        /// `ciphertext` being the SCALE-encoded candidate encrypted to the
+        /// election's public key. It must carry the configured `vote_fee`.
+        /// The ballot is worth the caller's weight when cast, and carries no
+        /// delegated weight.
+        #[ink(message, payable, selector = 0xee55_f612)]
+        pub fn vote_encrypted(
+            &mut self,
+            election_id: ElectionId,
+            ciphertext: Vec<u8>,
+        ) -> Result<BallotId, VoteError> {
+            self.ensure_phase(election_id);
+            let caller = self.env().caller();
+            let fee = self.env().transferred_value();
+            if ciphertext.len() > MAX_CIPHERTEXT_LEN {
+                return Err(VoteError::CiphertextTooLong);
+            }
+            let (mut election, _, weight) = self.check_ballot(caller, election_id, &[])?;
+            if election.config.encrypted_ballots.is_none() {
+                return Err(VoteError::EncryptionDisabled);
+            }
+            self.take_block_slot(election_id, &election.config)?;
+            let vote_fee = election.config.vote_fee;
+            if fee != Self::native_due(&election.config, vote_fee) {
+                return Err(VoteError::IncorrectFee);
+            }
+            if !self.pull_fee_token(&election.config, caller, vote_fee) {
+                return Err(VoteError::TransferFailed);
+            }
+
+            self.already_voted.insert((election_id, caller), &true);
+            if self.identity_mode {
+                if let Some(identity) = self.identities.get(caller) {
+                    self.identity_voted.insert((election_id, identity), &caller);
+                }
+            }
+            let index = election.turnout;
+            self.voters.insert((election_id, index), &caller);
+            let leaf = Self::compound_ballot_leaf(election_id, caller, &ciphertext);
+            self.insert_ballot_leaf(election_id, index, leaf);
+            self.sealed_ballots.insert(
+                (election_id, index),
+                &SealedBallot {
+                    voter: caller,
+                    weight,
+                    ciphertext,
+                },
+            );
+            election.turnout = election.turnout.saturating_add(1);
+            election.storage_entries = election.storage_entries.saturating_add(1);
+            self.count_in_histogram(election_id);
+            election.fee_pot = election.fee_pot.saturating_add(vote_fee);
+            self.funds.fee_income = self.funds.fee_income.saturating_add(fee);
+            self.elections.insert(election_id, &election);
+            let ballot_id = self.next_ballot_id;
+            self.next_ballot_id = ballot_id.saturating_add(1);
+            self.push_vote_record(
+                caller,
+                VoteRecord {
+                    election_id,
+                    ballot_id,
+                    candidate: None,
+                },
+            );
+            if election.config.quiet_events {
+                self.env().emit_event(BallotCounted {
+                    election_id,
+                    turnout: election.turnout,
+                });
+            } else {
+                self.env().emit_event(EncryptedVoteCast {
+                    election_id,
+                    ballot_id,
+                    voter: caller,
+                    fee,
+                });
+            }
+            Ok(ballot_id)
+        }
+
+        /// Casts a ballot attested by a trusted bridge on behalf of `voter`, an
+        /// account on the chain the bridge relays from. It counts like the
+        /// voter's own ballot and must carry the configured `vote_fee`.
+        #[ink(message, payable, selector = 0xb9b1_d73c)]
+        pub fn relay_vote(
+            &mut self,
+            election_id: ElectionId,
+            voter: AccountId,
+            address: AccountId,
+        ) -> Result<BallotId, VoteError> {
+            self.ensure_phase(election_id);
+            let bridge = self.env().caller();
+            let chain = self.bridges.get(bridge).ok_or(VoteError::Unauthorized)?;
+            let ballot_id = self.cast_ballot(
+                voter,
+                election_id,
+                BallotChoice::Single(address),
+                Vec::new(),
+            )?;
+            self.relayed_from.insert((election_id, voter), &chain);
+            let relayed = self.get_relayed_turnout(election_id, chain);
+            self.relayed_turnout
+                .insert((election_id, chain), &relayed.saturating_add(1));
+            if !self.quiet_events(election_id) {
+                self.env().emit_event(VoteRelayed {
+                    election_id,
+                    chain,
+                    voter,
+                    bridge,
+                });
+            }
+            Ok(ballot_id)
+        }
+
+        /// Delegates the caller's vote to `delegate`, replacing any earlier
+        /// delegation, split or not. Until the caller votes in an election, a ballot cast by
+        /// the delegate, or by whoever the delegate delegates to, carries the
+        /// caller's weight and uses up the caller's ballot.
+        #[ink(message, selector = 0xc596_54fe)]
+        pub fn delegate(&mut self, delegate: AccountId) -> Result<(), VoteError> {
+            let caller = self.env().caller();
+            let mut next = Some(delegate);
+            for _ in 0..=MAX_DELEGATION_DEPTH {
+                let Some(account) = next else {
+                    break;
+                };
+                if account == caller {
+                    return Err(VoteError::DelegationCycle);
+                }
+                next = self.delegates.get(account);
+            }
+            let mut delegators = self.delegators.get(delegate).unwrap_or_default();
+            if delegators.len() >= MAX_DELEGATORS {
+                return Err(VoteError::TooManyDelegators);
+            }
+            if !self.withdraw_delegation(caller) && !self.withdraw_split_delegation(caller) {
+                self.delegations = self.delegations.saturating_add(1);
+            }
+            delegators.push(caller);
+            self.delegators.insert(delegate, &delegators);
+            self.delegates.insert(caller, &delegate);
+            self.env().emit_event(DelegationChanged {
+                delegator: caller,
+                delegate: Some(delegate),
+            });
+            Ok(())
+        }
+
+        /// Takes back the caller's delegation.
+        #[ink(message, selector = 0x2456_de34)]
+        pub fn undelegate(&mut self) -> Result<(), VoteError> {
+            let caller = self.env().caller();
+            if !self.withdraw_delegation(caller) {
+                return Err(VoteError::NotDelegating);
+            }
+            self.delegations = self.delegations.saturating_sub(1);
+            self.env().emit_event(DelegationChanged {
+                delegator: caller,
+                delegate: None,
+            });
+            Ok(())
+        }
+
+        /// Returns the account `account` delegates its vote to, if any.
+        #[ink(message, selector = 0xf346_b980)]
+        pub fn get_delegate(&self, account: AccountId) -> Option<AccountId> {
+            self.delegates.get(account)
+        }
+
+        /// Delegates the caller's vote in elections about `topic` to
+        /// `delegate`, replacing any earlier delegation on the topic. In those
+        /// elections it takes precedence over the caller's general
+        /// delegation, which keeps applying everywhere else; on the way, each
+        /// account further down the chain follows its own topic delegate
+        /// first, then its general one.
+        #[ink(message, selector = 0x83ce_1c44)]
+        pub fn delegate_topic(
+            &mut self,
+            topic: TopicId,
+            delegate: AccountId,
+        ) -> Result<(), VoteError> {
+            let caller = self.env().caller();
+            let mut next = Some(delegate);
+            for _ in 0..=MAX_DELEGATION_DEPTH {
+                let Some(account) = next else {
+                    break;
+                };
+                if account == caller {
+                    return Err(VoteError::DelegationCycle);
+                }
+                next = self
+                    .topic_delegates
+                    .get((topic, account))
+                    .or_else(|| self.delegates.get(account));
+            }
+            let mut delegators = self
+                .topic_delegators
+                .get((topic, delegate))
+                .unwrap_or_default();
+            if delegators.len() >= MAX_DELEGATORS {
+                return Err(VoteError::TooManyDelegators);
+            }
+            if !self.withdraw_topic_delegation(topic, caller) {
+                self.delegations = self.delegations.saturating_add(1);
+            }
+            delegators.push(caller);
+            self.topic_delegators.insert((topic, delegate), &delegators);
+            self.topic_delegates.insert((topic, caller), &delegate);
+            self.env().emit_event(TopicDelegationChanged {
+                topic,
+                delegator: caller,
+                delegate: Some(delegate),
+            });
+            Ok(())
+        }
+
+        /// Takes back the caller's delegation on `topic`, leaving its general
+        /// delegation, if any, to apply there again.
+        #[ink(message, selector = 0xa1f7_3b53)]
+        pub fn undelegate_topic(&mut self, topic: TopicId) -> Result<(), VoteError> {
+            let caller = self.env().caller();
+            if !self.withdraw_topic_delegation(topic, caller) {
+                return Err(VoteError::NotDelegating);
+            }
+            self.delegations = self.delegations.saturating_sub(1);
+            self.env().emit_event(TopicDelegationChanged {
+                topic,
+                delegator: caller,
+                delegate: None,
+            });
+            Ok(())
+        }
+
+        /// Returns the account `account` delegates its vote on `topic` to, if
+        /// any, not counting its general delegate.
+        #[ink(message, selector = 0xc6b6_7c91)]
+        pub fn get_topic_delegate(&self, topic: TopicId, account: AccountId) -> Option<AccountId> {
+            self.topic_delegates.get((topic, account))
+        }
+
+        /// Returns the accounts delegating directly to `account` on `topic`.
+        #[ink(message, selector = 0xab66_eff9)]
+        pub fn get_topic_delegators(&self, topic: TopicId, account: AccountId) -> Vec<AccountId> {
+            self.topic_delegators
+                .get((topic, account))
+                .unwrap_or_default()
+        }
+
+        /// Splits the caller's vote among `shares` of delegates, in per-mille
+        /// shares adding up to a whole vote, replacing any earlier delegation
+        /// but those on a topic, which keep precedence. Until the caller
+        /// votes in an election, a ballot cast by one of the delegates
+        /// carries its share of the caller's weight, rounded down, and uses
+        /// up the caller's own ballot; the other delegates keep their shares.
+        /// Shares are not passed further down a delegation chain.
+        #[ink(message, selector = 0xf32f_5021)]
+        pub fn delegate_split(&mut self, shares: Vec<(AccountId, u16)>) -> Result<(), VoteError> {
+            let caller = self.env().caller();
+            if shares.len() > MAX_SPLIT_DELEGATES {
+                return Err(VoteError::BatchTooLarge);
+            }
+            let mut total: u16 = 0;
+            for (position, (delegate, share)) in shares.iter().enumerate() {
+                if *delegate == caller {
+                    return Err(VoteError::DelegationCycle);
+                }
+                if *share == 0 || shares[..position].iter().any(|(seen, _)| seen == delegate) {
+                    return Err(VoteError::InvalidSplit);
+                }
+                total = total.saturating_add(*share);
+            }
+            if total != SPLIT_BUDGET {
+                return Err(VoteError::InvalidSplit);
+            }
+            let previous = self.split_delegates.get(caller).unwrap_or_default();
+            for (delegate, _) in &shares {
+                let delegators = self.split_delegators.get(delegate).unwrap_or_default();
+                if delegators.len() >= MAX_DELEGATORS
+                    && !previous.iter().any(|(previous, _)| previous == delegate)
+                {
+                    return Err(VoteError::TooManyDelegators);
+                }
+            }
+            if !self.withdraw_delegation(caller) && !self.withdraw_split_delegation(caller) {
+                self.delegations = self.delegations.saturating_add(1);
+            }
+            for (delegate, _) in &shares {
+                let mut delegators = self.split_delegators.get(delegate).unwrap_or_default();
+                delegators.push(caller);
+                self.split_delegators.insert(delegate, &delegators);
+            }
+            self.split_delegates.insert(caller, &shares);
+            self.env().emit_event(SplitDelegationChanged {
+                delegator: caller,
+                shares,
+            });
+            Ok(())
+        }
+
+        /// Takes back the caller's split delegation.
+        #[ink(message, selector = 0x70a0_1505)]
+        pub fn undelegate_split(&mut self) -> Result<(), VoteError> {
+            let caller = self.env().caller();
+            if !self.withdraw_split_delegation(caller) {
+                return Err(VoteError::NotDelegating);
+            }
+            self.delegations = self.delegations.saturating_sub(1);
+            self.env().emit_event(SplitDelegationChanged {
+                delegator: caller,
+                shares: Vec::new(),
+            });
+            Ok(())
+        }
+
+        /// Returns the delegates `account` splits its vote among, with their
+        /// per-mille shares.
+        #[ink(message, selector = 0xf5a1_6dbb)]
+        pub fn get_split_delegation(&self, account: AccountId) -> Vec<(AccountId, u16)> {
+            self.split_delegates.get(account).unwrap_or_default()
+        }
+
+        /// Returns the accounts delegating a share of their vote to `account`.
+        #[ink(message, selector = 0xb52e_6536)]
+        pub fn get_split_delegators(&self, account: AccountId) -> Vec<AccountId> {
+            self.split_delegators.get(account).unwrap_or_default()
+        }
+
+        /// Returns the accounts delegating directly to `account`.
+        #[ink(message, selector = 0x588d_d10d)]
+        pub fn get_delegators(&self, account: AccountId) -> Vec<AccountId> {
+            self.delegators.get(account).unwrap_or_default()
+        }
+
+        /// Returns the votes a ballot of `account` would carry in the
+        /// election: its own weight plus that of every account delegating to
+        /// it, directly or through up to `MAX_DELEGATION_DEPTH` links, that
+        /// has not voted yet, and the shares split to it by such accounts.
+        #[ink(message, selector = 0xda6e_12a9)]
+        pub fn effective_weight(&self, election_id: ElectionId, account: AccountId) -> u128 {
+            let Some(election) = self.elections.get(election_id) else {
+                return 0;
+            };
+            let whole: u128 = core::iter::once(account)
+                .chain(self.delegated_voters(&election, election_id, account))
+                .filter_map(|voter| self.ballot_weight(&election.config, voter).ok())
+                .map(u128::from)
+                .sum();
+            let split: u128 = self
+                .split_voters(&election, election_id, account)
+                .into_iter()
+                .filter_map(|(voter, share)| {
+                    let votes = self.ballot_weight(&election.config, voter).ok()?;
+                    Some(u128::from(Self::split_share(votes, share)))
+                })
+                .sum();
+            whole.saturating_add(split)
+        }
+
+        /// Returns the record of `account` in participation-tracked elections.
+        #[ink(message, selector = 0xfc45_9ceb)]
+        pub fn get_participation(&self, account: AccountId) -> Participation {
+            self.participation.get(account).unwrap_or_default()
+        }
+
+        /// Returns how many elections `account` voted in, won and ran in,
+        /// for reputation systems and eligibility rules built on top.
+        #[ink(message, selector = 0x2cc3_6d13)]
+        pub fn participation_of(&self, account: AccountId) -> ParticipationScore {
+            ParticipationScore {
+                voted: self.vote_history_len.get(account).unwrap_or_default(),
+                won: self.get_win_history(account).len() as u32,
+                ran: self.candidacies.get(account).unwrap_or_default(),
+            }
+        }
+
+        /// Returns how many tracked rounds in a row `account` voted in, up to
+        /// the latest one; zero if it missed the latest.
+        #[ink(message, selector = 0x7d25_9030)]
+        pub fn get_streak(&self, account: AccountId) -> u32 {
+            let participation = self.get_participation(account);
+            if participation.elections > 0
+                && participation.last_round.saturating_add(1) == self.tracked_rounds
+            {
+                participation.streak
+            } else {
+                0
+            }
+        }
+
+        /// Returns the reputation of `account`: one point per ballot it cast,
+        /// plus the owner's adjustment, between zero and `MAX_REPUTATION`.
+        #[ink(message, selector = 0xf566_ad78)]
+        pub fn get_reputation(&self, account: AccountId) -> u32 {
+            let earned = i64::from(self.vote_history_len.get(account).unwrap_or_default());
+            let adjustment = i64::from(self.get_reputation_adjustment(account));
+            (earned + adjustment).clamp(0, i64::from(MAX_REPUTATION)) as u32
+        }
+
+        /// Returns the reputation the owner added to or took from `account`.
+        #[ink(message, selector = 0xe889_894d)]
+        pub fn get_reputation_adjustment(&self, account: AccountId) -> i32 {
+            self.reputation_adjustments.get(account).unwrap_or_default()
+        }
+
+        /// Moves the reputation adjustment of `account` by `delta`. The
+        /// adjustment must stay within `MAX_REPUTATION_ADJUSTMENT` either
+        /// way. Only the owner may call it.
+        #[ink(message, selector = 0x3cd6_5d95)]
+        pub fn adjust_reputation(
+            &mut self,
+            account: AccountId,
+            delta: i32,
+        ) -> Result<u32, VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            let adjustment = self
+                .get_reputation_adjustment(account)
+                .checked_add(delta)
+                .filter(|adjustment| adjustment.abs() <= MAX_REPUTATION_ADJUSTMENT)
+                .ok_or(VoteError::ReputationOutOfBounds)?;
+            self.reputation_adjustments.insert(account, &adjustment);
+            let reputation = self.get_reputation(account);
+            self.env().emit_event(ReputationChanged {
+                account,
+                adjustment,
+                reputation,
+            });
+            Ok(reputation)
+        }
+
+        /// Trusts `bridge` to relay ballots from `chain`, or stops trusting it
+        /// when `chain` is `None`. Only the owner may call it.
+        #[ink(message, selector = 0xc5bd_7c99)]
+        pub fn set_bridge(
+            &mut self,
+            bridge: AccountId,
+            chain: Option<ChainId>,
+        ) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if let Some(chain) = chain {
+                self.bridges.insert(bridge, &chain);
+            } else {
+                self.bridges.remove(bridge);
+            }
+            self.env().emit_event(BridgeUpdated { bridge, chain });
+            Ok(())
+        }
+
+        /// Returns how many ballots of an election were relayed from `chain`.
+        #[ink(message, selector = 0x08a7_dd2d)]
+        pub fn get_relayed_turnout(&self, election_id: ElectionId, chain: ChainId) -> u32 {
+            self.relayed_turnout
+                .get((election_id, chain))
+                .unwrap_or_default()
+        }
+
+        /// Returns the chain a voter's ballot was relayed from, if it was.
+        #[ink(message, selector = 0xc174_237a)]
+        pub fn get_relay_origin(
+            &self,
+            election_id: ElectionId,
+            voter: AccountId,
+        ) -> Option<ChainId> {
+            self.relayed_from.get((election_id, voter))
+        }
+
+        /// Registers `account` under `identity`, or clears its identity when
+        /// `None`. Only the owner may call it.
+        #[ink(message, selector = 0xc308_7309)]
+        pub fn set_identity(
+            &mut self,
+            account: AccountId,
+            identity: Option<IdentityId>,
+        ) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if let Some(identity) = identity {
+                if self.identities.insert(account, &identity).is_none() {
+                    self.registered_since
+                        .insert(account, &self.env().block_number());
+                }
+            } else {
+                self.identities.remove(account);
+                self.registered_since.remove(account);
+            }
+            self.env().emit_event(IdentityUpdated { account, identity });
+            Ok(())
+        }
+
+        /// Approves `aggregator` to vote in elections rejecting contract
+        /// ballots, or withdraws the approval. Only the owner may call it.
+        #[ink(message, selector = 0x9494_4f43)]
+        pub fn set_aggregator(
+            &mut self,
+            aggregator: AccountId,
+            approved: bool,
+        ) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if approved {
+                self.aggregators.insert(aggregator, &());
+            } else {
+                self.aggregators.remove(aggregator);
+            }
+            self.env().emit_event(AggregatorUpdated {
+                aggregator,
+                approved,
+            });
+            Ok(())
+        }
+
+        /// Returns whether `account` is an approved aggregator contract.
+        #[ink(message, selector = 0x078c_5d99)]
+        pub fn is_aggregator(&self, account: AccountId) -> bool {
+            self.aggregators.contains(account)
+        }
+
+        /// Returns the identity `account` is registered under.
+        #[ink(message, selector = 0xd4d3_671f)]
+        pub fn get_identity(&self, account: AccountId) -> Option<IdentityId> {
+            self.identities.get(account)
+        }
+
+        /// Returns the block since which `account` has been registered under
+        /// an identity. Accounts registered before it was tracked have none.
+        #[ink(message, selector = 0xf941_68bb)]
+        pub fn get_registered_since(&self, account: AccountId) -> Option<BlockNumber> {
+            self.registered_since.get(account)
+        }
+
+        /// Switches identity mode on or off for every election. While it is
+        /// on, only accounts registered under an identity may vote, and all the
+        /// accounts of one identity count as a single voter. Only the owner
+        /// may call it, never while any election is voting.
+        #[ink(message, selector = 0x4318_5cc3)]
+        pub fn set_identity_mode(&mut self, enabled: bool) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if self.elections_voting > 0 {
+                return Err(VoteError::ConfigFrozen);
+            }
+            self.identity_mode = enabled;
+            self.env().emit_event(IdentityModeChanged { enabled });
+            self.emit_config_changed(None, ink::selector_bytes!("set_identity_mode"));
+            Ok(())
+        }
+
+        /// Returns whether ballots are deduplicated per identity.
+        #[ink(message, selector = 0x38e3_d8a7)]
+        pub fn is_identity_mode(&self) -> bool {
+            self.identity_mode
+        }
+
+        /// Tells whether `account` could currently vote for `candidate`, running
+        /// every check `vote` does except for the fee, without changing state.
+        #[ink(message, selector = 0x91f4_19ce)]
+        pub fn can_vote(
+            &self,
+            account: AccountId,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Result<(), VoteError> {
+            self.check_ballot(account, election_id, &[candidate])
+                .map(|_| ())
+        }
+
+        /// Returns the accounts that voted in an election in the order they
+        /// cast their ballot, once per ballot, at most `MAX_PAGE_SIZE` at a time.
+        #[ink(message, selector = 0x0cc0_bbf3)]
+        pub fn get_voters(
+            &self,
+            election_id: ElectionId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<AccountId> {
+            let turnout = self
+                .elections
+                .get(election_id)
+                .map(|election| election.turnout)
+                .unwrap_or_default();
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(turnout);
+            (offset..end)
+                .filter_map(|index| self.voters.get((election_id, index)))
+                .collect()
+        }
+
+        /// Returns the accounts that voted for `candidate`, in order, at most
+        /// `MAX_PAGE_SIZE` at a time. Empty unless the election has a public ballot.
+        #[ink(message, selector = 0x9630_c216)]
+        pub fn get_voters_for(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<AccountId> {
+            let ballots = self.get_voter_count_for(election_id, candidate);
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(ballots);
+            (offset..end)
+                .filter_map(|index| self.voters_for.get((election_id, candidate, index)))
+                .collect()
+        }
+
+        /// Returns how many accounts `get_voters_for` lists for `candidate`,
+        /// so that clients know how many pages to fetch. Zero unless the
+        /// election has a public ballot.
+        #[ink(message, selector = 0x7085_1cce)]
+        pub fn get_voter_count_for(&self, election_id: ElectionId, candidate: AccountId) -> u32 {
+            let public = self
+                .elections
+                .get(election_id)
+                .is_some_and(|election| election.config.public_ballot);
+            if !public {
+                return 0;
+            }
+            self.ballots_for
+                .get((election_id, candidate))
+                .unwrap_or_default()
+        }
+
+        /// Returns the ballots `account` cast, oldest first, at most
+        /// `MAX_PAGE_SIZE` at a time.
+        #[ink(message, selector = 0x5a77_1b57)]
+        pub fn get_vote_history(
+            &self,
+            account: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<VoteRecord> {
+            let len = self.vote_history_len.get(account).unwrap_or_default();
+            let end = offset.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+            (offset..end)
+                .filter_map(|index| self.vote_history.get((account, index)))
+                .collect()
+        }
+
+        /// Returns the full outcome of an election in one call. While its
+        /// counts are hidden only the runners and the turnout are reported.
+        #[ink(message, selector = 0x72a2_e82e)]
+        pub fn get_results(&self, election_id: ElectionId) -> Option<ElectionResult> {
+            let election = self.elections.get(election_id)?;
+            let (totals, winners) = match self.archive.get(election_id) {
+                Some(archived) => (archived.totals, archived.winners),
+                None if self.results_hidden(&election) => {
+                    let totals = self.hidden_totals(election_id);
+                    let turnout = election.turnout;
+                    return Some(ElectionResult {
+                        runner_up: Vec::new(),
+                        ..Self::election_result(&election, totals, Vec::new(), turnout)
+                    });
+                }
+                None => {
+                    let totals = self.live_totals(election_id, &election.config);
+                    let winners = Self::winners(&totals, election.config.tie_break).0;
+                    (totals, winners)
+                }
+            };
+            let turnout = election.turnout;
+            Some(Self::election_result(&election, totals, winners, turnout))
+        }
+
+        /// Returns the totals `get_results` reports, a page at a time: the
+        /// runners in the order they entered the race, with their votes, at
+        /// most `MAX_PAGE_SIZE` at a time. Unlike `get_results`, a page reads
+        /// the votes of its own runners only, so that elections with
+        /// thousands of write-in candidates can be enumerated in full.
+        #[ink(message, selector = 0x480d_7805)]
+        pub fn get_results_page(
+            &self,
+            election_id: ElectionId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(AccountId, u32)> {
+            let Some(election) = self.elections.get(election_id) else {
+                return Vec::new();
+            };
+            let limit = limit.min(MAX_PAGE_SIZE) as usize;
+            if let Some(archived) = self.archive.get(election_id) {
+                return archived
+                    .totals
+                    .into_iter()
+                    .skip(offset as usize)
+                    .take(limit)
+                    .collect();
+            }
+            let hidden = self.results_hidden(&election);
+            if !hidden && !election.config.electors.is_empty() {
+                return self
+                    .live_totals(election_id, &election.config)
+                    .into_iter()
+                    .skip(offset as usize)
+                    .take(limit)
+                    .collect();
+            }
+            self.runners
+                .get(election_id)
+                .unwrap_or_default()
+                .into_iter()
+                .skip(offset as usize)
+                .take(limit)
+                .map(|runner| {
+                    let votes = if hidden {
+                        0
+                    } else {
+                        self.votes.get((election_id, runner)).unwrap_or_default()
+                    };
+                    (runner, votes)
+                })
+                .collect()
+        }
+
+        /// Returns how many entries `get_results_page` goes through.
+        #[ink(message, selector = 0x9d02_7dc0)]
+        pub fn results_len(&self, election_id: ElectionId) -> u32 {
+            let len = match self.archive.get(election_id) {
+                Some(archived) => archived.totals.len(),
+                None => self.runners.get(election_id).unwrap_or_default().len(),
+            };
+            len as u32
+        }
+
+        /// Returns statistics of the election's vote distribution, over the
+        /// totals `get_results` reports, or `None` while its counts are hidden.
+        #[ink(message, selector = 0x0b58_aa38)]
+        pub fn get_stats(&self, election_id: ElectionId) -> Option<ElectionStats> {
+            let election = self.elections.get(election_id)?;
+            if self.results_hidden(&election) {
+                return None;
+            }
+            let mut votes: Vec<u32> = self
+                .totals(election_id)
+                .into_iter()
+                .map(|(_, votes)| votes)
+                .collect();
+            votes.sort_unstable();
+            let candidates = votes.len() as u32;
+            let total_votes = votes
+                .iter()
+                .fold(0u64, |total, votes| total.saturating_add(u64::from(*votes)));
+            let leader = u64::from(votes.last().copied().unwrap_or_default());
+            let leader_share = leader
+                .saturating_mul(u64::from(SPLIT_BUDGET))
+                .checked_div(total_votes)
+                .unwrap_or_default();
+            Some(ElectionStats {
+                turnout: election.turnout,
+                candidates,
+                total_votes,
+                mean_votes: total_votes
+                    .checked_div(u64::from(candidates))
+                    .unwrap_or_default() as u32,
+                median_votes: votes
+                    .get(votes.len().saturating_sub(1) / 2)
+                    .copied()
+                    .unwrap_or_default(),
+                leader_share: leader_share as u16,
+                zero_vote_candidates: votes.iter().filter(|votes| **votes == 0).count() as u32,
+            })
+        }
+
+        /// Returns the outcome the election would have if `extra_ballots`,
+        /// each a candidate and the votes it brings, were added to the
+        /// current count, e.g. to show how many votes would flip the result.
+        /// Candidates not running yet join as write-ins; nothing is recorded.
+        ///
+        /// Returns `None` for an election that does not exist or is
+        /// finalized, for an electoral college, whose electors ballots from
+        /// no constituency cannot move, and while its counts are hidden.
+        #[ink(message, selector = 0x270c_0d75)]
+        pub fn simulate_tally(
+            &self,
+            election_id: ElectionId,
+            extra_ballots: Vec<(AccountId, u32)>,
+        ) -> Option<ElectionResult> {
+            let election = self.elections.get(election_id)?;
+            if self.archive.contains(election_id)
+                || !election.config.electors.is_empty()
+                || self.results_hidden(&election)
+            {
+                return None;
+            }
+            let mut totals = self.live_totals(election_id, &election.config);
+            for (candidate, votes) in &extra_ballots {
+                match totals.iter_mut().find(|(runner, _)| runner == candidate) {
+                    Some((_, total)) => *total = total.saturating_add(*votes),
+                    None => totals.push((*candidate, *votes)),
+                }
+            }
+            let winners = Self::winners(&totals, election.config.tie_break).0;
+            let turnout = election.turnout.saturating_add(extra_ballots.len() as u32);
+            Some(Self::election_result(&election, totals, winners, turnout))
+        }
+
+        /// Assembles the outcome of `election` from its totals and winners.
+        fn election_result(
+            election: &Election,
+            totals: RunnerTotals,
+            winners: Vec<AccountId>,
+            turnout: u32,
+        ) -> ElectionResult {
+            let winner_votes = winners
+                .first()
+                .and_then(|winner| totals.iter().find(|(runner, _)| runner == winner))
+                .map_or(0, |(_, votes)| *votes);
+            let (runner_up, runner_up_votes) = counting::leaders(&totals, &winners);
+            let margin = if winners.len() == 1 {
+                winner_votes.saturating_sub(runner_up_votes)
+            } else {
+                0
+            };
+            ElectionResult {
+                status: election.status,
+                winners,
+                runner_up,
+                margin,
+                totals,
+                turnout,
+                finalized_at: election.finalized_at,
+            }
+        }
+
+        /// Returns the runner(s) with the most votes, or the winners recorded
+        /// at finalization once the election is finalized. There is none
+        /// while the election's counts are hidden. Winners come in canonical
+        /// order: most votes first, then in ascending account order.
+        #[ink(message, selector = 0x85ad_16b7)]
+        pub fn get_current_winner(&self, election_id: ElectionId) -> Vec<AccountId> {
+            if let Some(archived) = self.archive.get(election_id) {
+                return archived.winners;
+            }
+            let Some(election) = self.elections.get(election_id) else {
+                return Vec::new();
+            };
+            if self.results_hidden(&election) {
+                return Vec::new();
+            }
+            let totals = self.live_totals(election_id, &election.config);
+            Self::winners(&totals, election.config.tie_break).0
+        }
+
+        /// Returns the seed and the draw that picked the winner of a
+        /// finalized sortition election. The draw is the position of the
+        /// winning vote among all votes, counted in runner order.
+        #[ink(message, selector = 0x0c87_0a7b)]
+        pub fn get_sortition_draw(&self, election_id: ElectionId) -> Option<(Hash, u32)> {
+            self.sortition_draws.get(election_id)
+        }
+
+        /// Returns the runner(s) placed right behind the current winner(s).
+        ///
+        /// Ties are returned together, same as in `get_current_winner`.
+        #[ink(message, selector = 0x720f_3e78)]
+        pub fn get_runner_up(&self, election_id: ElectionId) -> Vec<AccountId> {
+            if self.hides_results(election_id) {
+                return Vec::new();
+            }
+            let totals = self.totals(election_id);
+            let (winners, _) = Self::winners(&totals, self.tie_break(election_id));
+            counting::leaders(&totals, &winners).0
+        }
+
+        /// Returns how many votes the winner leads the runner-up by.
+        ///
+        /// It is `0` when there is no winner or the lead is shared by a tie.
+        #[ink(message, selector = 0x8ae1_2791)]
+        pub fn get_margin(&self, election_id: ElectionId) -> u32 {
+            self.get_results(election_id)
+                .map(|result| result.margin)
+                .unwrap_or(0)
+        }
+
+        /// Starts a recall petition against the sole winner of a finalized
+        /// election. The caller's signature is the first one collected.
+        #[ink(message, selector = 0x9307_9220)]
+        pub fn start_recall(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            let winners = self.get_current_winner(election_id);
+            if election.status != ElectionStatus::Finalized
+                || election.config.recall_threshold == 0
+                || winners.len() != 1
+            {
+                return Err(VoteError::RecallUnavailable);
+            }
+            if self.recalls.contains(election_id) {
+                return Err(VoteError::RecallAlreadyStarted);
+            }
+            self.recalls.insert(
+                election_id,
+                &Recall {
+                    target: winners[0],
+                    status: RecallStatus::Petition,
+                    signatures: 0,
+                    yes: 0,
+                    no: 0,
+                },
+            );
+            self.sign_recall(election_id)
+        }
+
+        /// Signs a running recall petition. Once the election's
+        /// `recall_threshold` is reached the recall ballot opens.
+        #[ink(message, selector = 0x245e_0c33)]
+        pub fn sign_recall(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let mut recall = self
+                .recalls
+                .get(election_id)
+                .ok_or(VoteError::RecallNotFound)?;
+            if recall.status != RecallStatus::Petition {
+                return Err(VoteError::RecallClosed);
+            }
+            let caller = self.env().caller();
+            if self.recall_signed.contains((election_id, caller)) {
+                return Err(VoteError::AlreadySigned);
+            }
+            self.recall_signed.insert((election_id, caller), &());
+
+            recall.signatures = recall.signatures.saturating_add(1);
+            let threshold = self
+                .elections
+                .get(election_id)
+                .map(|election| election.config.recall_threshold)
+                .unwrap_or_default();
+            if recall.signatures >= threshold {
+                recall.status = RecallStatus::Voting;
+            }
+            self.recalls.insert(election_id, &recall);
+            self.emit_recall_updated(election_id, Some(caller), recall);
+            Ok(())
+        }
+
+        /// Casts a yes (`remove = true`) or no ballot in an open recall.
+        #[ink(message, selector = 0x95f6_9b61)]
+        pub fn vote_recall(
+            &mut self,
+            election_id: ElectionId,
+            remove: bool,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let mut recall = self
+                .recalls
+                .get(election_id)
+                .ok_or(VoteError::RecallNotFound)?;
+            if recall.status != RecallStatus::Voting {
+                return Err(VoteError::RecallClosed);
+            }
+            let caller = self.env().caller();
+            if self.recall_voted.contains((election_id, caller)) {
+                return Err(VoteError::AlreadyVoted);
+            }
+            self.recall_voted.insert((election_id, caller), &());
+
+            if remove {
+                recall.yes = recall.yes.saturating_add(1);
+            } else {
+                recall.no = recall.no.saturating_add(1);
+            }
+            self.recalls.insert(election_id, &recall);
+            self.emit_recall_updated(election_id, Some(caller), recall);
+            Ok(())
+        }
+
+        /// Closes the recall ballot. A strict majority of yes ballots vacates
+        /// the seat, after which a new election can be scheduled.
+        /// Only the election admin may call it.
+        #[ink(message, selector = 0x81ac_8f48)]
+        pub fn close_recall(&mut self, election_id: ElectionId) -> Result<RecallStatus, VoteError> {
+            self.ensure_phase(election_id);
+            self.election_as_admin(election_id, ink::selector_bytes!("close_recall"))?;
+            let mut recall = self
+                .recalls
+                .get(election_id)
+                .ok_or(VoteError::RecallNotFound)?;
+            if recall.status != RecallStatus::Voting {
+                return Err(VoteError::RecallClosed);
+            }
+            recall.status = if recall.yes > recall.no {
+                RecallStatus::Succeeded
+            } else {
+                RecallStatus::Failed
+            };
+            self.recalls.insert(election_id, &recall);
+            let status = recall.status;
+            self.emit_recall_updated(election_id, None, recall);
+            Ok(status)
+        }
+
+        /// Returns the recall raised against an election's winner, if any.
+        #[ink(message, selector = 0x30b4_bd3e)]
+        pub fn get_recall(&self, election_id: ElectionId) -> Option<Recall> {
+            self.recalls.get(election_id)
+        }
+
+        /// Returns whether the winner of an election was recalled.
+        #[ink(message, selector = 0x7a45_cf05)]
+        pub fn is_seat_vacated(&self, election_id: ElectionId) -> bool {
+            self.recalls
+                .get(election_id)
+                .is_some_and(|recall| recall.status == RecallStatus::Succeeded)
+        }
+
+        /// Disputes the result of a finalized election within its challenge
+        /// period, holding back its payouts until the dispute is ruled on. The
+        /// configured `dispute_bond` must be transferred along, and only one
+        /// dispute may be open at a time. An election with an arbitrator has
+        /// the dispute escalated to it, and fails with `ArbitrationFailed`
+        /// should the arbitrator not accept the case.
+        #[ink(message, payable, selector = 0x973c_7100)]
+        pub fn raise_dispute(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            let now = self.env().block_number();
+            let challenge_ends = election
+                .finalized_at
+                .map(|at| at.saturating_add(election.config.challenge_period));
+            if election.config.challenge_period == 0
+                || challenge_ends.is_none_or(|ends| now >= ends)
+            {
+                return Err(VoteError::ChallengeClosed);
+            }
+            if self.disputes.contains(election_id) {
+                return Err(VoteError::DisputeOpen);
+            }
+            let bond = self.env().transferred_value();
+            if bond != election.config.dispute_bond {
+                return Err(VoteError::IncorrectFee);
+            }
+            let challenger = self.env().caller();
+            self.funds.deposits = self.funds.deposits.saturating_add(bond);
+            self.disputes.insert(
+                election_id,
+                &Dispute {
+                    challenger,
+                    bond,
+                    raised_at: now,
+                },
+            );
+            self.open_disputes.push(election_id);
+            if let Some(arbitrator) = election.config.arbitrator {
+                let mut arbitrator: ink::contract_ref!(Arbitrator) = arbitrator.into();
+                if !matches!(
+                    arbitrator
+                        .call_mut()
+                        .create_dispute(election_id, challenger)
+                        .try_invoke(),
+                    Ok(Ok(true))
+                ) {
+                    return Err(VoteError::ArbitrationFailed);
+                }
+            }
+            self.env().emit_event(DisputeRaised {
+                election_id,
+                challenger,
+                bond,
+            });
+            Ok(())
+        }
+
+        /// Rules on the open dispute against an election. Upholding it voids
+        /// the result, sending unclaimed prizes to the treasury, and returns
+        /// the bond to the challenger along with the slashed admin bond;
+        /// dismissing it lets the result stand and
+        /// moves the bond to the treasury; disqualifying a candidate strikes
+        /// it from the result and returns the bond. Only the resolver may call
+        /// it, or the election admin while no resolver is appointed, and never
+        /// for an election with an arbitrator.
+        #[ink(message, selector = 0x539b_8b08)]
+        pub fn resolve_dispute(
+            &mut self,
+            election_id: ElectionId,
+            ruling: Ruling,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if election.config.arbitrator.is_some()
+                || self.env().caller() != self.resolver.unwrap_or(election.admin)
+            {
+                return Err(VoteError::Unauthorized);
+            }
+            let dispute = self.disputes.get(election_id).ok_or(VoteError::NoDispute)?;
+            self.settle_dispute(election_id, election, dispute, ruling);
+            Ok(())
+        }
+
+        /// Applies the ruling the election's arbitrator gave on its open
+        /// dispute, as `resolve_dispute` would. Anyone may call it; it fails
+        /// with `RulingPending` until the arbitrator has ruled.
+        #[ink(message, selector = 0x4671_e22f)]
+        pub fn enforce_ruling(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            let arbitrator = election.config.arbitrator.ok_or(VoteError::Unauthorized)?;
+            let dispute = self.disputes.get(election_id).ok_or(VoteError::NoDispute)?;
+            let arbitrator: ink::contract_ref!(Arbitrator) = arbitrator.into();
+            let ruling = match arbitrator.call().ruling_of(election_id).try_invoke() {
+                Ok(Ok(Some(ruling))) => ruling,
+                Ok(Ok(None)) => return Err(VoteError::RulingPending),
+                _ => return Err(VoteError::ArbitrationFailed),
+            };
+            self.settle_dispute(election_id, election, dispute, ruling);
+            Ok(())
+        }
+
+        /// Carries out a ruling on the election's open dispute and closes it.
+        fn settle_dispute(
+            &mut self,
+            election_id: ElectionId,
+            mut election: Election,
+            dispute: Dispute,
+            ruling: Ruling,
+        ) {
+            self.funds.deposits = self.funds.deposits.saturating_sub(dispute.bond);
+            match ruling {
+                Ruling::Upheld => {
+                    let admin_bond = self.slash_admin_bond(election_id, &mut election);
+                    self.credit(dispute.challenger, dispute.bond.saturating_add(admin_bond));
+                    let mut awarded = self.get_current_winner(election_id);
+                    awarded.extend(self.get_lottery(election_id).winners);
+                    for winner in awarded {
+                        if let Some(prize) = self.prizes.take((election_id, winner)) {
+                            self.funds.prize_escrow = self.funds.prize_escrow.saturating_sub(prize);
+                            self.forfeit(prize);
+                        }
+                    }
+                    self.env().emit_event(PhaseChanged {
+                        election_id,
+                        from: election.status,
+                        to: ElectionStatus::Cancelled,
+                    });
+                    election.status = ElectionStatus::Cancelled;
+                    self.elections.insert(election_id, &election);
+                }
+                Ruling::Dismissed => {
+                    self.forfeit(dispute.bond);
+                }
+                Ruling::Disqualified(candidate) => {
+                    self.credit(dispute.challenger, dispute.bond);
+                    self.apply_ban(election_id, candidate);
+                    if let Some(prize) = self.prizes.take((election_id, candidate)) {
+                        self.funds.prize_escrow = self.funds.prize_escrow.saturating_sub(prize);
+                        self.forfeit(prize);
+                    }
+                    if let Some(mut archived) = self.archive.get(election_id) {
+                        archived.winners.retain(|winner| *winner != candidate);
+                        if archived.winners.is_empty() {
+                            archived.winners = counting::leaders(&archived.totals, &[candidate]).0;
+                            if election.config.tie_break == TieBreakPolicy::EarliestRunner {
+                                archived.winners.truncate(1);
+                            }
+                            counting::canonical_order(&mut archived.winners, &archived.totals);
+                        }
+                        self.archive.insert(election_id, &archived);
+                    }
+                }
+            }
+            self.disputes.remove(election_id);
+            self.open_disputes
+                .retain(|disputed| *disputed != election_id);
+            self.env().emit_event(DisputeResolved {
+                election_id,
+                challenger: dispute.challenger,
+                ruling,
+            });
+        }
+
+        /// Appoints the account ruling on every dispute, or leaves disputes
+        /// to election admins when `None`. Only the owner may call it.
+        #[ink(message, selector = 0xb797_e560)]
+        pub fn set_resolver(&mut self, resolver: Option<AccountId>) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            self.resolver = resolver;
+            self.env().emit_event(ResolverUpdated { resolver });
+            self.emit_config_changed(None, ink::selector_bytes!("set_resolver"));
+            Ok(())
+        }
+
+        /// Returns the appointed dispute resolver, if any.
+        #[ink(message, selector = 0x08ac_c51b)]
+        pub fn get_resolver(&self) -> Option<AccountId> {
+            self.resolver
+        }
+
+        /// Sets the bond admins post when creating an election, from then
+        /// on. Only the owner may call it.
+        #[ink(message, selector = 0xf377_fc0d)]
+        pub fn set_admin_bond(&mut self, bond: Balance) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            self.admin_bond = bond;
+            self.emit_config_changed(None, ink::selector_bytes!("set_admin_bond"));
+            Ok(())
+        }
+
+        /// Returns the bond admins post when creating an election.
+        #[ink(message, selector = 0x5980_a7e9)]
+        pub fn get_admin_bond(&self) -> Balance {
+            self.admin_bond
+        }
+
+        /// Returns the bond the admin of an election still has posted.
+        #[ink(message, selector = 0xac17_e555)]
+        pub fn get_election_bond(&self, election_id: ElectionId) -> Balance {
+            self.elections
+                .get(election_id)
+                .map(|election| election.admin_bond)
+                .unwrap_or(0)
+        }
+
+        /// Signs the petition to slash the admin bond of an election over
+        /// the admin's misconduct, e.g. a wrongful disqualification. Only
+        /// accounts that voted in the election may sign, once each, from the
+        /// close of voting for as long as the bond is posted. Once two thirds
+        /// of the final turnout, and at least `MIN_SLASH_SIGNERS`, have
+        /// signed, the bond goes to the treasury.
+        #[ink(message, selector = 0x8b5d_4c84)]
+        pub fn sign_slash_petition(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if election.admin_bond == 0 {
+                return Err(VoteError::NoAdminBond);
+            }
+            if matches!(
+                election.status,
+                ElectionStatus::Pending
+                    | ElectionStatus::Registration
+                    | ElectionStatus::SlateFrozen
+                    | ElectionStatus::Campaign
+                    | ElectionStatus::Voting
+            ) {
+                return Err(VoteError::VotingStillOpen);
+            }
+            let caller = self.env().caller();
+            if !self.already_voted.contains((election_id, caller)) {
+                return Err(VoteError::Unauthorized);
+            }
+            if self.slash_signed.contains((election_id, caller)) {
+                return Err(VoteError::AlreadySigned);
+            }
+            self.slash_signed.insert((election_id, caller), &());
+            let signatures = self
+                .slash_petitions
+                .get(election_id)
+                .unwrap_or(0)
+                .saturating_add(1);
+            self.slash_petitions.insert(election_id, &signatures);
+            if signatures >= MIN_SLASH_SIGNERS
+                && u64::from(signatures) * 3 >= u64::from(election.turnout) * 2
+            {
+                let bond = self.slash_admin_bond(election_id, &mut election);
+                self.forfeit(bond);
+                self.elections.insert(election_id, &election);
+            }
+            Ok(())
+        }
+
+        /// Returns the signatures collected by the petition to slash the
+        /// admin bond of an election.
+        #[ink(message, selector = 0x0b33_4df4)]
+        pub fn get_slash_petition(&self, election_id: ElectionId) -> u32 {
+            self.slash_petitions.get(election_id).unwrap_or(0)
+        }
+
+        /// Releases the admin bond of an election to its admin, once the
+        /// result has taken effect or the election was cancelled or expired.
+        /// Only the election admin may call it.
+        #[ink(message, selector = 0x6118_98b0)]
+        pub fn reclaim_admin_bond(
+            &mut self,
+            election_id: ElectionId,
+        ) -> Result<Balance, VoteError> {
+            self.ensure_phase(election_id);
+            let mut election =
+                self.election_as_admin(election_id, ink::selector_bytes!("reclaim_admin_bond"))?;
+            if election.admin_bond == 0 {
+                return Err(VoteError::NoAdminBond);
+            }
+            if !matches!(
+                election.status,
+                ElectionStatus::Cancelled | ElectionStatus::Expired
+            ) && !self.is_result_effective(election_id)
+            {
+                return Err(VoteError::ResultNotEffective);
+            }
+            let bond = core::mem::take(&mut election.admin_bond);
+            self.elections.insert(election_id, &election);
+            self.funds.deposits = self.funds.deposits.saturating_sub(bond);
+            self.credit(election.admin, bond);
+            self.env().emit_event(AdminBondSettled {
+                election_id,
+                admin: election.admin,
+                amount: bond,
+                slashed: false,
+            });
+            Ok(bond)
+        }
+
+        /// Takes the admin bond off the election, which the caller stores,
+        /// and out of the deposits, returning it for the caller to pay out.
+        fn slash_admin_bond(
+            &mut self,
+            election_id: ElectionId,
+            election: &mut Election,
+        ) -> Balance {
+            let bond = core::mem::take(&mut election.admin_bond);
+            if bond == 0 {
+                return 0;
+            }
+            self.funds.deposits = self.funds.deposits.saturating_sub(bond);
+            self.env().emit_event(AdminBondSettled {
+                election_id,
+                admin: election.admin,
+                amount: bond,
+                slashed: true,
+            });
+            bond
+        }
+
+        /// Appoints the account vetting candidates of every election, or
+        /// leaves vetting to election admins when `None`. Only the owner may
+        /// call it.
+        #[ink(message, selector = 0xcacc_a6a9)]
+        pub fn set_moderator(&mut self, moderator: Option<AccountId>) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            self.moderator = moderator;
+            self.env().emit_event(ModeratorUpdated { moderator });
+            self.emit_config_changed(None, ink::selector_bytes!("set_moderator"));
+            Ok(())
+        }
+
+        /// Returns the appointed candidate moderator, if any.
+        #[ink(message, selector = 0xc04d_9493)]
+        pub fn get_moderator(&self) -> Option<AccountId> {
+            self.moderator
+        }
+
+        /// Sets the contract vetting display names, or leaves them to the
+        /// built-in checks when `None`. Only the owner may call it.
+        #[ink(message, selector = 0xef60_eaa4)]
+        pub fn set_name_validator(
+            &mut self,
+            validator: Option<AccountId>,
+        ) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            self.name_validator = validator;
+            self.env().emit_event(NameValidatorUpdated { validator });
+            self.emit_config_changed(None, ink::selector_bytes!("set_name_validator"));
+            Ok(())
+        }
+
+        /// Returns the contract vetting display names, if any.
+        #[ink(message, selector = 0xe3d4_47aa)]
+        pub fn get_name_validator(&self) -> Option<AccountId> {
+            self.name_validator
+        }
+
+        /// Sets the name service `resolve_name` asks first, or leaves names to
+        /// the display-name registry when `None`. Only the owner may call it.
+        #[ink(message, selector = 0x996d_bdf6)]
+        pub fn set_name_service(
+            &mut self,
+            name_service: Option<AccountId>,
+        ) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            self.name_service = name_service;
+            self.env().emit_event(NameServiceUpdated { name_service });
+            self.emit_config_changed(None, ink::selector_bytes!("set_name_service"));
+            Ok(())
+        }
+
+        /// Returns the name service, if any.
+        #[ink(message, selector = 0x7ede_ef67)]
+        pub fn get_name_service(&self) -> Option<AccountId> {
+            self.name_service
+        }
+
+        /// Returns the name of an account: its primary name with the name
+        /// service, if one is set, or else its display name. A failed call to
+        /// the name service falls back to the display name as well.
+        #[ink(message, selector = 0x01da_76aa)]
+        pub fn resolve_name(&self, account: AccountId) -> Option<Vec<u8>> {
+            self.name_service
+                .and_then(|name_service| {
+                    let name_service: ink::contract_ref!(NameService) = name_service.into();
+                    match name_service.call().get_primary_name(account).try_invoke() {
+                        Ok(Ok(name)) => name.filter(|name| !name.is_empty()),
+                        _ => None,
+                    }
+                })
+                .or_else(|| self.display_names.get(account))
+        }
+
+        /// Returns the names of up to `MAX_PAGE_SIZE` accounts, in the order
+        /// given, as `resolve_name` would.
+        #[ink(message, selector = 0x6e83_1ecf)]
+        pub fn resolve_names(&self, accounts: Vec<AccountId>) -> Vec<Option<Vec<u8>>> {
+            accounts
+                .into_iter()
+                .take(MAX_PAGE_SIZE as usize)
+                .map(|account| self.resolve_name(account))
+                .collect()
+        }
+
+        /// Registers or replaces the caller's display name, releasing the
+        /// previous one. A name is valid UTF-8 without control characters, of
+        /// at most `MAX_DISPLAY_NAME_LEN` bytes, not taken by another account
+        /// in any letter case, and accepted by the name validator, if set; a
+        /// failed call to the validator counts as a refusal.
+        #[ink(message, selector = 0x1654_52a4)]
+        pub fn set_display_name(&mut self, name: Vec<u8>) -> Result<(), RegistrationError> {
+            let caller = self.env().caller();
+            if name.len() > MAX_DISPLAY_NAME_LEN {
+                return Err(RegistrationError::NameTooLong);
+            }
+            if name.is_empty()
+                || core::str::from_utf8(&name)
+                    .map_or(true, |name| name.chars().any(char::is_control))
+            {
+                return Err(RegistrationError::InvalidName);
+            }
+            let key = Self::display_name_key(&name);
+            if self
+                .name_owners
+                .get(key)
+                .is_some_and(|owner| owner != caller)
+            {
+                return Err(RegistrationError::NameTaken);
+            }
+            if let Some(validator) = self.name_validator {
+                let validator: ink::contract_ref!(NameValidator) = validator.into();
+                let acceptable = validator
+                    .call()
+                    .is_acceptable(caller, name.clone())
+                    .try_invoke();
+                if !matches!(acceptable, Ok(Ok(true))) {
+                    return Err(RegistrationError::NameRejected);
+                }
+            }
+            if let Some(previous) = self.display_names.get(caller) {
+                self.name_owners.remove(Self::display_name_key(&previous));
+            }
+            self.name_owners.insert(key, &caller);
+            self.display_names.insert(caller, &name);
+            self.env().emit_event(DisplayNameSet {
+                account: caller,
+                name: Some(name),
+            });
+            Ok(())
+        }
+
+        /// Gives up the caller's display name, if it has one, so that anyone
+        /// may take it.
+        #[ink(message, selector = 0x5f01_66cb)]
+        pub fn clear_display_name(&mut self) {
+            let caller = self.env().caller();
+            let Some(name) = self.display_names.take(caller) else {
+                return;
+            };
+            self.name_owners.remove(Self::display_name_key(&name));
+            self.env().emit_event(DisplayNameSet {
+                account: caller,
+                name: None,
+            });
+        }
+
+        /// Returns the display name of an account, if it registered one.
+        #[ink(message, selector = 0x088c_9985)]
+        pub fn display_name_of(&self, account: AccountId) -> Option<Vec<u8>> {
+            self.display_names.get(account)
+        }
+
+        /// Returns the display names of up to `MAX_PAGE_SIZE` accounts, in the
+        /// order given, e.g. to render the runners or winners of an election.
+        #[ink(message, selector = 0xdf4a_633f)]
+        pub fn display_names_of(&self, accounts: Vec<AccountId>) -> Vec<Option<Vec<u8>>> {
+            accounts
+                .into_iter()
+                .take(MAX_PAGE_SIZE as usize)
+                .map(|account| self.display_names.get(account))
+                .collect()
+        }
+
+        /// Returns the account going by a display name, in any letter case.
+        #[ink(message, selector = 0xcc42_4ca4)]
+        pub fn account_of_name(&self, name: Vec<u8>) -> Option<AccountId> {
+            self.name_owners.get(Self::display_name_key(&name))
+        }
+
+        /// Grants or withdraws the verified badge of a runner, telling voters
+        /// the moderator vetted it and its profile. Only the moderator may
+        /// call it, or the election admin while no moderator is appointed.
+        #[ink(message, selector = 0xe5c8_fe25)]
+        pub fn set_verified(
+            &mut self,
+            election_id: ElectionId,
+            candidate: AccountId,
+            verified: bool,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if self.env().caller() != self.moderator.unwrap_or(election.admin) {
+                return Err(VoteError::Unauthorized);
+            }
+            if !self
+                .runners
+                .get(election_id)
+                .unwrap_or_default()
+                .contains(&candidate)
+            {
+                return Err(VoteError::NotACandidate);
+            }
+            if verified {
+                self.verified.insert((election_id, candidate), &());
+            } else {
+                self.verified.remove((election_id, candidate));
+            }
+            self.env().emit_event(CandidateVerified {
+                election_id,
+                candidate,
+                verified,
+            });
+            Ok(())
+        }
+
+        /// Returns whether a candidate holds the verified badge.
+        #[ink(message, selector = 0x1d9b_495f)]
+        pub fn is_verified(&self, election_id: ElectionId, candidate: AccountId) -> bool {
+            self.verified.contains((election_id, candidate))
+        }
+
+        /// Suspends a single election: until it is resumed it takes no
+        /// registration or ballot, does not change phase and cannot be
+        /// finalized, while other elections go on. Only the moderator may
+        /// call it, or the election admin while no moderator is appointed.
+        ///
+        /// Fails with `InvalidTransition` once the election is being tallied
+        /// or is over, including once it is stale, so that a pause never
+        /// holds off `expire`.
+        #[ink(message, selector = 0x0696_3f37)]
+        pub fn pause_election(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if self.env().caller() != self.moderator.unwrap_or(election.admin) {
+                return Err(VoteError::Unauthorized);
+            }
+            if matches!(
+                election.status,
+                ElectionStatus::Tallying
+                    | ElectionStatus::Finalized
+                    | ElectionStatus::Cancelled
+                    | ElectionStatus::Expired
+            ) {
+                return Err(VoteError::InvalidTransition);
+            }
+            if election.paused_at.is_some() {
+                return Err(VoteError::ElectionPaused);
+            }
+            election.paused_at = Some((self.env().block_number(), self.env().block_timestamp()));
+            self.elections.insert(election_id, &election);
+            self.env().emit_event(ElectionPauseChanged {
+                election_id,
+                paused: true,
+            });
+            Ok(())
+        }
+
+        /// Resumes a paused election, pushing its voting window, campaign and
+        /// grace period back by as long as it was paused, so that none of
+        /// them lost any time. Only the moderator may call it, or the
+        /// election admin while no moderator is appointed.
+        #[ink(message, selector = 0xd929_c34a)]
+        pub fn resume_election(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if self.env().caller() != self.moderator.unwrap_or(election.admin) {
+                return Err(VoteError::Unauthorized);
+            }
+            let Some((block, time)) = election.paused_at.take() else {
+                return Err(VoteError::InvalidTransition);
+            };
+            let blocks = self.env().block_number().saturating_sub(block);
+            let millis = self.env().block_timestamp().saturating_sub(time);
+            let config = &mut election.config;
+            config.voting_opens_at = config
+                .voting_opens_at
+                .map(|opens_at| opens_at.delayed(blocks, millis));
+            config.voting_closes_at = config
+                .voting_closes_at
+                .map(|closes_at| closes_at.delayed(blocks, millis));
+            if let Some(campaign) = config.campaign.as_mut() {
+                campaign.starts_at = campaign.starts_at.saturating_add(blocks);
+                campaign.ends_at = campaign.ends_at.saturating_add(blocks);
+            }
+            election.slate_frozen_at = election
+                .slate_frozen_at
+                .map(|frozen_at| frozen_at.saturating_add(blocks));
+            self.elections.insert(election_id, &election);
+            self.env().emit_event(ElectionPauseChanged {
+                election_id,
+                paused: false,
+            });
+            Ok(())
+        }
+
+        /// Returns whether an election is paused.
+        #[ink(message, selector = 0x9a48_5636)]
+        pub fn is_election_paused(&self, election_id: ElectionId) -> bool {
+            self.elections
+                .get(election_id)
+                .is_some_and(|election| election.paused_at.is_some())
+        }
+
+        /// Returns the votes, badge and profile of a runner.
+        #[ink(message, selector = 0xb7cd_b16a)]
+        pub fn get_candidate_info(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Option<CandidateInfo> {
+            if !self
+                .totals(election_id)
+                .iter()
+                .any(|(runner, _)| *runner == candidate)
+            {
+                return None;
+            }
+            Some(CandidateInfo {
+                candidate,
+                votes: self.get_votes(election_id, candidate),
+                verified: self.is_verified(election_id, candidate),
+                profile: self.get_profile(election_id, candidate),
+                referred_by: self.get_referrer(election_id, candidate),
+                display_name: self.resolve_name(candidate),
+            })
+        }
+
+        /// Returns the elections with an open dispute, oldest dispute first.
+        #[ink(message, selector = 0x7579_9beb)]
+        pub fn get_open_disputes(&self) -> Vec<ElectionId> {
+            self.open_disputes.clone()
+        }
+
+        /// Returns the open dispute against an election's result, if any.
+        #[ink(message, selector = 0x4cfd_8cc7)]
+        pub fn get_dispute(&self, election_id: ElectionId) -> Option<Dispute> {
+            self.disputes.get(election_id)
+        }
+
+        /// Returns whether the result of an election has taken effect: it is
+        /// finalized, its challenge period has lapsed and no dispute is open.
+        #[ink(message, selector = 0x25c7_61e4)]
+        pub fn is_result_effective(&self, election_id: ElectionId) -> bool {
+            let Some(election) = self.elections.get(election_id) else {
+                return false;
+            };
+            election.status == ElectionStatus::Finalized
+                && election.finalized_at.is_some_and(|at| {
+                    self.env().block_number() >= at.saturating_add(election.config.challenge_period)
+                })
+                && !self.disputes.contains(election_id)
+        }
+
+        /// Notes the outcome of an election run with `mirror_outcome` with the
+        /// runtime's governance, when it could not be noted at finalization
+        /// because the result had not taken effect yet or the runtime refused
+        /// it. Anyone may call it, and an outcome is noted only once.
+        #[ink(message, selector = 0xdff3_6eac)]
+        pub fn mirror_outcome(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if !election.config.mirror_outcome {
+                return Err(VoteError::MirrorDisabled);
+            }
+            if !self.is_result_effective(election_id) {
+                return Err(VoteError::ResultNotEffective);
+            }
+            if self.mirrored.contains(election_id) {
+                return Err(VoteError::AlreadyMirrored);
+            }
+            if !self.note_outcome(election_id) {
+                return Err(VoteError::MirrorFailed);
+            }
+            Ok(())
+        }
+
+        /// Returns whether the outcome of an election was noted with the
+        /// runtime's governance.
+        #[ink(message, selector = 0x8ffc_aae7)]
+        pub fn is_outcome_mirrored(&self, election_id: ElectionId) -> bool {
+            self.mirrored.contains(election_id)
+        }
+
+        /// Returns how many term-limited elections in a row an account has won.
+        /// The streak resets as soon as someone else wins a term.
+        #[ink(message, selector = 0x6dc4_a3d6)]
+        pub fn get_consecutive_terms(&self, account: AccountId) -> u32 {
+            if self.incumbent != Some(account) {
+                return 0;
+            }
+            self.consecutive_terms.get(account).unwrap_or_default()
+        }
+
+        /// Returns every election an account has won outright, oldest first.
+        #[ink(message, selector = 0xa824_666d)]
+        pub fn get_win_history(&self, account: AccountId) -> Vec<ElectionId> {
+            self.win_history.get(account).unwrap_or_default()
+        }
+
+        /// Adds the transferred value to the prize pool of an election that
+        /// has not been closed yet.
+        #[ink(message, payable, selector = 0xda6d_3c8e)]
+        pub fn fund_prize(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if matches!(
+                election.status,
+                ElectionStatus::Finalized | ElectionStatus::Cancelled | ElectionStatus::Expired
+            ) {
+                return Err(VoteError::VotingClosed);
+            }
+            let amount = self.env().transferred_value();
+            election.prize_pool = election.prize_pool.saturating_add(amount);
+            self.funds.prize_escrow = self.funds.prize_escrow.saturating_add(amount);
+            self.elections.insert(election_id, &election);
+            self.env().emit_event(PrizeFunded {
+                election_id,
+                funder: self.env().caller(),
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Adds the transferred value to the finalization bounty of an
+        /// election with a deadline that has not been closed yet. Tips are
+        /// paid with the bounty to whoever finalizes it, and go to the
+        /// treasury if it is cancelled.
+        #[ink(message, payable, selector = 0xa776_eaad)]
+        pub fn tip_bounty(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let mut election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if matches!(
+                election.status,
+                ElectionStatus::Finalized | ElectionStatus::Cancelled | ElectionStatus::Expired
+            ) {
+                return Err(VoteError::VotingClosed);
+            }
+            if election.config.voting_closes_at.is_none() {
+                return Err(VoteError::BountyUnavailable);
+            }
+            let amount = self.env().transferred_value();
+            election.bounty_tips = election.bounty_tips.saturating_add(amount);
+            self.funds.prize_escrow = self.funds.prize_escrow.saturating_add(amount);
+            self.elections.insert(election_id, &election);
+            self.env().emit_event(BountyTipped {
+                election_id,
+                tipper: self.env().caller(),
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Returns the tips added to the finalization bounty of an election.
+        #[ink(message, selector = 0x85f1_f7ca)]
+        pub fn get_bounty_tips(&self, election_id: ElectionId) -> Balance {
+            self.elections
+                .get(election_id)
+                .map_or(0, |election| election.bounty_tips)
+        }
+
+        /// Adds the transferred value to the voter lottery pool of an election
+        /// that runs a lottery, until it is drawn.
+        #[ink(message, payable, selector = 0x1d9b_d15a)]
+        pub fn fund_lottery(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            let mut lottery = self.get_lottery(election_id);
+            if election.config.lottery_winners == 0 || lottery.seed.is_some() {
+                return Err(VoteError::LotteryUnavailable);
+            }
+            let amount = self.env().transferred_value();
+            lottery.pool = lottery.pool.saturating_add(amount);
+            self.funds.prize_escrow = self.funds.prize_escrow.saturating_add(amount);
+            self.lotteries.insert(election_id, &lottery);
+            self.env().emit_event(LotteryFunded {
+                election_id,
+                funder: self.env().caller(),
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Commits to the seed of an election's voter lottery by its Blake2x256
+        /// hash, before the election is finalized. Only the election admin
+        /// may call it.
+        #[ink(message, selector = 0xce46_ca28)]
+        pub fn commit_lottery_seed(
+            &mut self,
+            election_id: ElectionId,
+            commitment: Hash,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("commit_lottery_seed"))?;
+            let mut lottery = self.get_lottery(election_id);
+            if election.config.lottery_winners == 0
+                || lottery.commitment.is_some()
+                || matches!(
+                    election.status,
+                    ElectionStatus::Finalized | ElectionStatus::Cancelled | ElectionStatus::Expired
+                )
+            {
+                return Err(VoteError::LotteryUnavailable);
+            }
+            lottery.commitment = Some(commitment);
+            self.lotteries.insert(election_id, &lottery);
+            self.env().emit_event(LotterySeedCommitted {
+                election_id,
+                commitment,
+            });
+            Ok(())
+        }
+
+        /// Reveals the committed seed of a finalized election's voter lottery
+        /// and draws up to `lottery_winners` distinct voters, each ballot
+        /// equally likely. Draw `i` hashes `(seed, ballot_root, i)` into a
+        /// ballot index, moving on to the next index if it is taken. The
+        /// drawn voters share the pool as prizes; the rounding remainder goes
+        /// to the treasury. Only the election admin may call it.
+        #[ink(message, selector = 0x4886_4b12)]
+        pub fn draw_lottery(
+            &mut self,
+            election_id: ElectionId,
+            seed: Hash,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("draw_lottery"))?;
+            let mut lottery = self.get_lottery(election_id);
+            if election.status != ElectionStatus::Finalized
+                || lottery.seed.is_some()
+                || election.turnout == 0
+            {
+                return Err(VoteError::LotteryUnavailable);
+            }
+            let mut hashed = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&seed, &mut hashed);
+            if lottery.commitment != Some(Hash::from(hashed)) {
+                return Err(VoteError::SeedMismatch);
+            }
+
+            let ballot_root = self.get_ballot_root(election_id).unwrap_or_default();
+            let draws = election.config.lottery_winners.min(election.turnout);
+            let mut drawn: Vec<u32> = Vec::new();
+            for draw in 0..draws {
+                let mut hash = [0u8; 32];
+                ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                    &(seed, ballot_root, draw),
+                    &mut hash,
+                );
+                let mut bytes = [0u8; 4];
+                bytes.copy_from_slice(&hash[..4]);
+                let mut index = u32::from_le_bytes(bytes) % election.turnout;
+                while drawn.contains(&index) {
+                    index = (index + 1) % election.turnout;
+                }
+                drawn.push(index);
+            }
+            lottery.winners = drawn
+                .into_iter()
+                .filter_map(|index| self.voters.get((election_id, index)))
+                .collect();
+            let share = lottery.pool / Balance::from(draws);
+            for winner in &lottery.winners {
+                let prize = self.get_prize(election_id, *winner).saturating_add(share);
+                self.prizes.insert((election_id, *winner), &prize);
+            }
+            let paid = share.saturating_mul(lottery.winners.len() as Balance);
+            let remainder = lottery.pool.saturating_sub(paid);
+            self.funds.prize_escrow = self.funds.prize_escrow.saturating_sub(remainder);
+            self.forfeit(remainder);
+            lottery.seed = Some(seed);
+            self.lotteries.insert(election_id, &lottery);
+            self.env().emit_event(LotteryDrawn {
+                election_id,
+                seed,
+                winners: lottery.winners,
+                share,
+            });
+            Ok(())
+        }
+
+        /// Adds the transferred value to the pool paying back the storage
+        /// deposit of an election's ballots, until it closes. Only the
+        /// election admin may call it, for an election configured with a
+        /// `storage_sponsorship`.
+        #[ink(message, payable, selector = 0xd4ec_c0b4)]
+        pub fn fund_sponsorship(&mut self, election_id: ElectionId) -> Result<(), VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("fund_sponsorship"))?;
+            if election.config.storage_sponsorship.is_none() {
+                return Err(VoteError::SponsorshipDisabled);
+            }
+            if matches!(
+                election.status,
+                ElectionStatus::Finalized | ElectionStatus::Cancelled | ElectionStatus::Expired
+            ) {
+                return Err(VoteError::VotingClosed);
+            }
+            let amount = self.env().transferred_value();
+            let mut sponsorship = self.get_sponsorship(election_id);
+            sponsorship.pool = sponsorship.pool.saturating_add(amount);
+            self.funds.deposits = self.funds.deposits.saturating_add(amount);
+            self.sponsorships.insert(election_id, &sponsorship);
+            self.env().emit_event(SponsorshipFunded {
+                election_id,
+                amount,
+                pool: sponsorship.pool,
+            });
+            Ok(())
+        }
+
+        /// Returns the storage sponsorship pool of an election and what it
+        /// paid out so far.
+        #[ink(message, selector = 0xd3bf_7ebe)]
+        pub fn get_sponsorship(&self, election_id: ElectionId) -> Sponsorship {
+            self.sponsorships.get(election_id).unwrap_or_default()
+        }
+
+        /// Credits what is left of an election's storage sponsorship pool to
+        /// the caller once the election is finalized, cancelled or expired.
+        /// Only the election admin may call it.
+        ///
+        /// Returns the amount credited.
+        #[ink(message, selector = 0x4c2f_5f81)]
+        pub fn reclaim_sponsorship(
+            &mut self,
+            election_id: ElectionId,
+        ) -> Result<Balance, VoteError> {
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("reclaim_sponsorship"))?;
+            if !matches!(
+                election.status,
+                ElectionStatus::Finalized | ElectionStatus::Cancelled | ElectionStatus::Expired
+            ) {
+                return Err(VoteError::InvalidTransition);
+            }
+            let mut sponsorship = self.get_sponsorship(election_id);
+            let remainder = core::mem::take(&mut sponsorship.pool);
+            if remainder > 0 {
+                self.sponsorships.insert(election_id, &sponsorship);
+                self.funds.deposits = self.funds.deposits.saturating_sub(remainder);
+                self.credit(self.env().caller(), remainder);
+            }
+            Ok(remainder)
+        }
+
+        /// Returns the voter lottery of an election.
+        #[ink(message, selector = 0x02a3_a86a)]
+        pub fn get_lottery(&self, election_id: ElectionId) -> Lottery {
+            self.lotteries.get(election_id).unwrap_or_default()
+        }
+
+        /// Returns the prize share an account may claim from an election.
+        #[ink(message, selector = 0x2e06_559a)]
+        pub fn get_prize(&self, election_id: ElectionId, account: AccountId) -> Balance {
+            self.prizes.get((election_id, account)).unwrap_or_default()
+        }
+
+        /// Releases the caller's prize share of an election into their
+        /// claimable balance, once the result has taken effect.
+        #[ink(message, selector = 0xd336_4c0a)]
+        pub fn claim_prize(&mut self, election_id: ElectionId) -> Result<Balance, VoteError> {
+            self.ensure_phase(election_id);
+            if !self.is_result_effective(election_id) {
+                return Err(VoteError::ResultNotEffective);
+            }
+            let caller = self.env().caller();
+            let prize = self
+                .prizes
+                .take((election_id, caller))
+                .ok_or(VoteError::NothingToClaim)?;
+            self.funds.prize_escrow = self.funds.prize_escrow.saturating_sub(prize);
+            self.credit(caller, prize);
+            Ok(prize)
+        }
+
+        /// Adds the transferred value to the campaign fund of `candidate`, a
+        /// runner of an election that has not been closed yet. The fund is
+        /// escrowed until the result takes effect, recorded per supporter.
+        #[ink(message, payable, selector = 0xf661_8e80)]
+        pub fn fund_campaign(
+            &mut self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Result<(), VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if election.config.campaign_threshold == 0 {
+                return Err(VoteError::CampaignFundsDisabled);
+            }
+            if !matches!(
+                election.status,
+                ElectionStatus::Pending
+                    | ElectionStatus::Registration
+                    | ElectionStatus::SlateFrozen
+                    | ElectionStatus::Campaign
+                    | ElectionStatus::Voting
+            ) {
+                return Err(VoteError::VotingClosed);
+            }
+            if !self
+                .runners
+                .get(election_id)
+                .unwrap_or_default()
+                .contains(&candidate)
+            {
+                return Err(VoteError::NotACandidate);
+            }
+            let supporter = self.env().caller();
+            let amount = self.env().transferred_value();
+            let fund = self
+                .get_campaign_fund(election_id, candidate)
+                .saturating_add(amount);
+            self.campaign_funds.insert((election_id, candidate), &fund);
+            let pledge = self
+                .get_campaign_pledge(election_id, candidate, supporter)
+                .saturating_add(amount);
+            self.campaign_pledges
+                .insert((election_id, candidate, supporter), &pledge);
+            self.funds.deposits = self.funds.deposits.saturating_add(amount);
+            self.env().emit_event(CampaignFunded {
+                election_id,
+                candidate,
+                supporter,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Pays the campaign fund of `candidate` into its claimable balance,
+        /// once the result has taken effect with the candidate at or above
+        /// the campaign threshold. Anyone may call it.
+        #[ink(message, selector = 0xa2f0_1467)]
+        pub fn release_campaign_fund(
+            &mut self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Result<Balance, VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if !self.is_result_effective(election_id) {
+                return Err(VoteError::ResultNotEffective);
+            }
+            if self.votes.get((election_id, candidate)).unwrap_or_default()
+                < election.config.campaign_threshold
+            {
+                return Err(VoteError::CampaignThresholdMissed);
+            }
+            let amount = self
+                .campaign_funds
+                .take((election_id, candidate))
+                .ok_or(VoteError::NothingToClaim)?;
+            self.funds.deposits = self.funds.deposits.saturating_sub(amount);
+            self.credit(candidate, amount);
+            self.env().emit_event(CampaignFundReleased {
+                election_id,
+                candidate,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Refunds the caller's pledge to the campaign fund of `candidate`
+        /// into their claimable balance, once the election was cancelled or
+        /// expired or its result has taken effect with the candidate short of the
+        /// campaign threshold.
+        #[ink(message, selector = 0x7589_ac1a)]
+        pub fn refund_campaign_pledge(
+            &mut self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> Result<Balance, VoteError> {
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if !matches!(
+                election.status,
+                ElectionStatus::Cancelled | ElectionStatus::Expired
+            ) {
+                if !self.is_result_effective(election_id) {
+                    return Err(VoteError::ResultNotEffective);
+                }
+                if self.votes.get((election_id, candidate)).unwrap_or_default()
+                    >= election.config.campaign_threshold
+                {
+                    return Err(VoteError::CampaignThresholdReached);
+                }
+            }
+            let supporter = self.env().caller();
+            let amount = self
+                .campaign_pledges
+                .take((election_id, candidate, supporter))
+                .ok_or(VoteError::NothingToClaim)?;
+            let fund = self
+                .get_campaign_fund(election_id, candidate)
+                .saturating_sub(amount);
+            self.campaign_funds.insert((election_id, candidate), &fund);
+            self.funds.deposits = self.funds.deposits.saturating_sub(amount);
+            self.credit(supporter, amount);
+            self.env().emit_event(CampaignPledgeRefunded {
+                election_id,
+                candidate,
+                supporter,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Returns the campaign fund escrowed for a candidate.
+        #[ink(message, selector = 0x8c9f_4f85)]
+        pub fn get_campaign_fund(&self, election_id: ElectionId, candidate: AccountId) -> Balance {
+            self.campaign_funds
+                .get((election_id, candidate))
+                .unwrap_or_default()
+        }
+
+        /// Returns what `supporter` pledged to the campaign fund of a
+        /// candidate. The pledge stays on record once the fund is released.
+        #[ink(message, selector = 0x427a_a5d7)]
+        pub fn get_campaign_pledge(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+            supporter: AccountId,
+        ) -> Balance {
+            self.campaign_pledges
+                .get((election_id, candidate, supporter))
+                .unwrap_or_default()
+        }
+
+        /// Returns the balance an account may withdraw with `claim`.
+        #[ink(message, selector = 0xeba9_b9f0)]
+        pub fn get_claimable(&self, account: AccountId) -> Balance {
+            self.claimable.get(account).unwrap_or_default()
+        }
+
+        /// Pays everything the contract owes the caller out to them. This is the
+        /// only message that pays participants; the balance is cleared before
+        /// the transfer and restored if it fails, and no payable message nor
+        /// `claim` itself may run while the transfer is underway.
+        #[ink(message, selector = 0xb388_803f)]
+        pub fn claim(&mut self) -> Result<Balance, VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            let caller = self.env().caller();
+            let amount = self
+                .claimable
+                .take(caller)
+                .ok_or(VoteError::NothingToClaim)?;
+            self.paying_out.set(&true);
+            let paid = self.env().transfer(caller, amount);
+            self.paying_out.set(&false);
+            if paid.is_err() {
+                self.claimable.insert(caller, &amount);
+                return Err(VoteError::TransferFailed);
+            }
+            self.funds.payouts = self.funds.payouts.saturating_sub(amount);
+            self.env().emit_event(Claimed {
+                account: caller,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Returns the fee tokens of `token` an account may withdraw with
+        /// `claim_tokens`.
+        #[ink(message, selector = 0xb9c9_daf5)]
+        pub fn get_token_claimable(&self, token: AccountId, account: AccountId) -> Balance {
+            self.token_claimable
+                .get((token, account))
+                .unwrap_or_default()
+        }
+
+        /// Pays the fee tokens of `token` the contract owes the caller out to
+        /// them, under the same guard as `claim`.
+        #[ink(message, selector = 0xfbcf_bf73)]
+        pub fn claim_tokens(&mut self, token: AccountId) -> Result<Balance, VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            let caller = self.env().caller();
+            let amount = self
+                .token_claimable
+                .take((token, caller))
+                .ok_or(VoteError::NothingToClaim)?;
+            self.paying_out.set(&true);
+            let mut psp22: ink::contract_ref!(Psp22) = token.into();
+            let paid = psp22
+                .call_mut()
+                .transfer(caller, amount, Vec::new())
+                .try_invoke();
+            self.paying_out.set(&false);
+            if !matches!(paid, Ok(Ok(Ok(())))) {
+                self.token_claimable.insert((token, caller), &amount);
+                return Err(VoteError::TransferFailed);
+            }
+            self.env().emit_event(TokensClaimed {
+                token,
+                account: caller,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Returns the current protocol fee split.
+        #[ink(message, selector = 0x9b8e_863e)]
+        pub fn get_protocol_fee(&self) -> ProtocolFee {
+            self.protocol_fee.clone()
+        }
+
+        /// Changes the protocol fee split. Only the owner may call it, never
+        /// while any election is voting.
+        #[ink(message, selector = 0x774a_c12e)]
+        pub fn set_protocol_fee(&mut self, protocol_fee: ProtocolFee) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if self.elections_voting > 0 {
+                return Err(VoteError::ConfigFrozen);
+            }
+            if protocol_fee.basis_points > MAX_PROTOCOL_FEE_BPS {
+                return Err(VoteError::FeeTooHigh);
+            }
+            self.env().emit_event(ProtocolFeeUpdated {
+                beneficiary: protocol_fee.beneficiary,
+                basis_points: protocol_fee.basis_points,
+            });
+            self.emit_config_changed(None, ink::selector_bytes!("set_protocol_fee"));
+            self.protocol_fee = protocol_fee;
+            Ok(())
+        }
+
+        /// Returns the version of the deployed code as `(major, minor, patch)`.
+        #[ink(message, selector = 0xec6d_41e1)]
+        pub fn version(&self) -> (u16, u16, u16) {
+            VERSION
+        }
+
+        /// Returns the optional features the deployed code supports, one bit
+        /// each as listed in `capabilities`, so that callers can check for a
+        /// feature before using it.
+        #[ink(message, selector = 0x9c8b_ae98)]
+        pub fn capabilities(&self) -> u64 {
+            CAPABILITIES
+        }
+
+        /// Returns whether the contract implements an interface listed in
+        /// `interfaces`, given its identifier, or a message of one, given its
+        /// selector.
+        #[ink(message, selector = 0x8085_95e8)]
+        pub fn supports(&self, selector_or_interface_id: u32) -> bool {
+            crate::interfaces::ALL.iter().any(|selectors| {
+                crate::interfaces::id(selectors) == selector_or_interface_id
+                    || selectors.contains(&selector_or_interface_id)
+            })
+        }
+
+        /// Returns the version of the storage layout.
+        #[ink(message, selector = 0x3e15_0c47)]
+        pub fn get_storage_version(&self) -> u8 {
+            self.storage_version
+        }
+
+        /// Brings storage written by older code up to `STORAGE_VERSION`, one
+        /// version at a time. Running it again once up to date does nothing.
+        /// Only the owner may call it.
+        ///
+        /// Returns the version the storage was migrated from.
+        #[ink(message, selector = 0x5ada_be8b)]
+        pub fn migrate_storage(&mut self) -> Result<u8, VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            let from = self.storage_version;
+            if from > STORAGE_VERSION {
+                return Err(VoteError::UnsupportedStorageVersion);
+            }
+            // One step per layout change goes here, each moving
+            // `storage_version` up by one. Version 1 is the first layout.
+            self.storage_version = STORAGE_VERSION;
+            if from != STORAGE_VERSION {
+                self.env().emit_event(StorageMigrated {
+                    from,
+                    to: STORAGE_VERSION,
+                    code_hash: self.env().own_code_hash().unwrap_or_default(),
+                });
+            }
+            Ok(from)
+        }
+
+        /// Imports voters of the election moved into a snapshot deployment,
+        /// at most `MAX_VOTER_BATCH` at a time, each with whether it already
+        /// voted. Voters join the electorate of a closed-electorate election,
+        /// and ones that voted count in the turnout and cannot vote again;
+        /// their ballots are loaded with `import_tallies`. Only the owner may
+        /// call it, before voting opens.
+        ///
+        /// Returns how many entries were newly imported; voters already in,
+        /// or already marked as voted, are skipped.
+        #[ink(message, selector = 0x376f_1241)]
+        pub fn import_voters(
+            &mut self,
+            election_id: ElectionId,
+            voters: Vec<(AccountId, bool)>,
+        ) -> Result<u32, VoteError> {
+            self.ensure_phase(election_id);
+            let mut election = self.importing_election(election_id)?;
+            if voters.len() > MAX_VOTER_BATCH {
+                return Err(VoteError::BatchTooLarge);
+            }
+            let mut imported = 0u32;
+            for (voter, voted) in voters {
+                let mut new = false;
+                if election.config.closed_electorate
+                    && !self.electorate.contains((election_id, voter))
+                {
+                    self.enroll_voter(election_id, &mut election, voter, 0);
+                    new = true;
+                }
+                if voted && !self.already_voted.contains((election_id, voter)) {
+                    self.already_voted.insert((election_id, voter), &true);
+                    self.voters.insert((election_id, election.turnout), &voter);
+                    let leaf = Self::compound_ballot_leaf::<AccountId>(election_id, voter, &[]);
+                    self.insert_ballot_leaf(election_id, election.turnout, leaf);
+                    election.turnout = election.turnout.saturating_add(1);
+                    new = true;
+                }
+                if new {
+                    imported = imported.saturating_add(1);
+                }
+            }
+            self.elections.insert(election_id, &election);
+            self.env().emit_event(VotersImported {
+                election_id,
+                imported,
+                electorate: election.electorate,
+                turnout: election.turnout,
+            });
+            Ok(imported)
+        }
+
+        /// Adds imported votes to the runners of the election moved into a
+        /// snapshot deployment, at most `MAX_VOTER_BATCH` runners at a time,
+        /// registering the ones not running yet. Only the owner may call it,
+        /// before voting opens.
+        #[ink(message, selector = 0x103d_577b)]
+        pub fn import_tallies(
+            &mut self,
+            election_id: ElectionId,
+            tallies: Vec<(AccountId, u32)>,
+        ) -> Result<(), VoteError> {
+            self.ensure_phase(election_id);
+            let election = self.importing_election(election_id)?;
+            if tallies.len() > MAX_VOTER_BATCH {
+                return Err(VoteError::BatchTooLarge);
+            }
+            let mut runners = self.runners.get(election_id).unwrap_or_default();
+            for (candidate, votes) in tallies {
+                if !runners.contains(&candidate) {
+                    runners.push(candidate);
+                    self.count_candidacy(candidate);
+                    self.env().emit_event(CandidateRegistered {
+                        election_id,
+                        candidate,
+                        deposit: 0,
+                    });
+                }
+                let new_total = self
+                    .votes
+                    .get((election_id, candidate))
+                    .unwrap_or_default()
+                    .checked_add(votes)
+                    .ok_or(VoteError::VoteOverflow)?;
+                self.votes.insert((election_id, candidate), &new_total);
+                self.update_standings(election_id, &election.config, candidate, new_total);
+                if !election.config.blind_results {
+                    self.env().emit_event(TallyUpdated {
+                        election_id,
+                        candidate,
+                        new_total,
+                    });
+                }
+            }
+            self.runners.insert(election_id, &runners);
+            Ok(())
+        }
+
+        /// Returns the election if the caller may import state into it: the
+        /// owner, into election `0` of a snapshot deployment before voting
+        /// opens.
+        fn importing_election(&self, election_id: ElectionId) -> Result<Election, VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if !self.importing
+                || election_id != 0
+                || !matches!(
+                    election.status,
+                    ElectionStatus::Pending | ElectionStatus::Registration
+                )
+            {
+                return Err(VoteError::InvalidTransition);
+            }
+            Ok(election)
+        }
+
+        /// Opens a survey for signaling, e.g. a temperature check, offering
+        /// between two and `MAX_SURVEY_OPTIONS` options labelled in at most
+        /// `MAX_NAME_LEN` bytes each. Answers are counted per option and
+        /// nobody is elected. Anyone may create one.
+        #[ink(message, selector = 0x8d0e_b290)]
+        pub fn create_survey(
+            &mut self,
+            question: Hash,
+            options: Vec<Vec<u8>>,
+        ) -> Result<SurveyId, VoteError> {
+            if !(2..=MAX_SURVEY_OPTIONS).contains(&options.len())
+                || options.iter().any(|label| label.len() > MAX_NAME_LEN)
+            {
+                return Err(VoteError::InvalidSurvey);
+            }
+            let survey_id = self.next_survey_id;
+            self.next_survey_id = survey_id.saturating_add(1);
+            let survey = Survey {
+                creator: self.env().caller(),
+                question,
+                counts: ink::prelude::vec![0; options.len()],
+                options,
+                open: true,
+            };
+            self.surveys.insert(survey_id, &survey);
+            self.env().emit_event(SurveyUpdated {
+                survey_id,
+                account: None,
+                counts: survey.counts,
+                open: true,
+            });
+            Ok(survey_id)
+        }
+
+        /// Answers an open survey with the option at index `option`. Each
+        /// account answers once.
+        #[ink(message, selector = 0x8897_7afc)]
+        pub fn answer_survey(&mut self, survey_id: SurveyId, option: u32) -> Result<(), VoteError> {
+            let mut survey = self
+                .surveys
+                .get(survey_id)
+                .ok_or(VoteError::SurveyNotFound)?;
+            if !survey.open {
+                return Err(VoteError::SurveyClosed);
+            }
+            let caller = self.env().caller();
+            if self.survey_responded.contains((survey_id, caller)) {
+                return Err(VoteError::AlreadyVoted);
+            }
+            let count = survey
+                .counts
+                .get_mut(option as usize)
+                .ok_or(VoteError::InvalidOption)?;
+            *count = count.saturating_add(1);
+            self.survey_responded.insert((survey_id, caller), &());
+            self.surveys.insert(survey_id, &survey);
+            self.env().emit_event(SurveyUpdated {
+                survey_id,
+                account: Some(caller),
+                counts: survey.counts,
+                open: true,
+            });
+            Ok(())
+        }
+
+        /// Stops a survey from taking answers, leaving its counts as they
+        /// are. Only its creator may call it.
+        #[ink(message, selector = 0x1711_2578)]
+        pub fn close_survey(&mut self, survey_id: SurveyId) -> Result<(), VoteError> {
+            let mut survey = self
+                .surveys
+                .get(survey_id)
+                .ok_or(VoteError::SurveyNotFound)?;
+            if self.env().caller() != survey.creator {
+                return Err(VoteError::Unauthorized);
+            }
+            if !survey.open {
+                return Err(VoteError::SurveyClosed);
+            }
+            survey.open = false;
+            self.surveys.insert(survey_id, &survey);
+            self.env().emit_event(SurveyUpdated {
+                survey_id,
+                account: None,
+                counts: survey.counts,
+                open: false,
+            });
+            Ok(())
+        }
+
+        /// Returns a survey with its answer counts.
+        #[ink(message, selector = 0x6262_0717)]
+        pub fn get_survey(&self, survey_id: SurveyId) -> Option<Survey> {
+            self.surveys.get(survey_id)
+        }
+
+        /// Returns each option's share of a survey's answers, in per-mille and
+        /// option order. Shares are rounded down, and all zero before the
+        /// first answer.
+        #[ink(message, selector = 0xccfe_ff76)]
+        pub fn get_survey_distribution(&self, survey_id: SurveyId) -> Vec<u16> {
+            let counts = self
+                .surveys
+                .get(survey_id)
+                .map(|survey| survey.counts)
+                .unwrap_or_default();
+            let answers = counts.iter().fold(0u64, |answers, count| {
+                answers.saturating_add(u64::from(*count))
+            });
+            counts
+                .iter()
+                .map(|count| {
+                    let share = u64::from(*count)
+                        .saturating_mul(u64::from(SPLIT_BUDGET))
+                        .checked_div(answers)
+                        .unwrap_or_default();
+                    share as u16
+                })
+                .collect()
+        }
+
+        /// Makes every later `upgrade` depend on an approving referendum.
+        /// There is no way back. Only the owner may call it.
+        #[ink(message, selector = 0xe9f8_8983)]
+        pub fn govern_upgrades(&mut self) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            self.upgrades_governed = true;
+            Ok(())
+        }
+
+        /// Gives up ownership for good, freezing every contract-wide setting:
+        /// fees, the protocol fee, the resolver and moderator, upgrades and
+        /// storage migrations. Refused while any election is voting,
+        /// revealing or being counted. Only the owner may call it.
+        #[ink(message, selector = 0x8c90_065b)]
+        pub fn renounce_ownership(&mut self) -> Result<(), VoteError> {
+            let previous_owner = self.env().caller();
+            if previous_owner != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            let in_flight = (0..self.next_election_id).any(|election_id| {
+                self.elections.get(election_id).is_some_and(|election| {
+                    matches!(
+                        election.status,
+                        ElectionStatus::Voting
+                            | ElectionStatus::RevealPhase
+                            | ElectionStatus::Tallying
+                    )
+                })
+            });
+            if in_flight {
+                return Err(VoteError::ElectionsInFlight);
+            }
+            self.owner = AccountId::from(NO_OWNER);
+            self.env().emit_event(OwnershipRenounced { previous_owner });
+            Ok(())
+        }
+
+        /// Returns the owner, or `None` once ownership was renounced.
+        #[ink(message, selector = 0x07fc_d0b1)]
+        pub fn get_owner(&self) -> Option<AccountId> {
+            (self.owner != AccountId::from(NO_OWNER)).then_some(self.owner)
+        }
+
+        /// Sets how many blocks fee changes, candidate bans and upgrades wait
+        /// between being scheduled and running; zero lets them run directly.
+        /// Raising the delay takes effect at once, lowering it must itself be
+        /// scheduled with `AdminAction::TimelockDelay`.
+        /// Only the owner may call it.
+        #[ink(message, selector = 0xc29c_4d99)]
+        pub fn set_timelock_delay(&mut self, delay: BlockNumber) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if delay < self.timelock_delay {
+                return Err(VoteError::Timelocked);
+            }
+            self.apply_timelock_delay(delay);
+            Ok(())
+        }
+
+        fn apply_timelock_delay(&mut self, delay: BlockNumber) {
+            self.timelock_delay = delay;
+            self.env().emit_event(TimelockDelayUpdated { delay });
+            self.emit_config_changed(None, ink::selector_bytes!("set_timelock_delay"));
+        }
+
+        /// Returns the timelock delay, in blocks.
+        #[ink(message, selector = 0xe6eb_10b1)]
+        pub fn get_timelock_delay(&self) -> BlockNumber {
+            self.timelock_delay
+        }
+
+        /// Schedules an administrative action to run once the timelock delay
+        /// has passed. The caller must be allowed to perform the action
+        /// directly: the owner for fees, upgrades and the delay, the election
+        /// admin for bans.
+        ///
+        /// Returns the id to execute or cancel it with.
+        #[ink(message, selector = 0x6cff_d00d)]
+        pub fn schedule_action(&mut self, action: AdminAction) -> Result<u32, VoteError> {
+            self.authorize_action(&action)?;
+            let action_id = self.next_action_id;
+            self.next_action_id = action_id.saturating_add(1);
+            let proposer = self.env().caller();
+            let executable_at = self
+                .env()
+                .block_number()
+                .saturating_add(self.timelock_delay);
+            self.scheduled_actions.insert(
+                action_id,
+                &ScheduledAction {
+                    action: action.clone(),
+                    proposer,
+                    executable_at,
+                },
+            );
+            self.env().emit_event(ActionScheduled {
+                action_id,
+                proposer,
+                action,
+                executable_at,
+            });
+            Ok(action_id)
+        }
+
+        /// Runs a scheduled action once its delay has passed. Only its
+        /// proposer may call it, and only while still allowed to perform the
+        /// action; fee changes still wait until no election is voting.
+        #[ink(message, selector = 0xde77_5c14)]
+        pub fn execute_action(&mut self, action_id: u32) -> Result<(), VoteError> {
+            let scheduled = self
+                .scheduled_actions
+                .get(action_id)
+                .ok_or(VoteError::ActionNotFound)?;
+            if self.env().caller() != scheduled.proposer {
+                return Err(VoteError::Unauthorized);
+            }
+            if self.env().block_number() < scheduled.executable_at {
+                return Err(VoteError::TimelockPending);
+            }
+            self.authorize_action(&scheduled.action)?;
+            let fee_change = matches!(
+                scheduled.action,
+                AdminAction::VoteFee(..)
+                    | AdminAction::CandidacyDeposit(..)
+                    | AdminAction::CreationFee(_)
+            );
+            if fee_change && self.elections_voting > 0 {
+                return Err(VoteError::FeesLocked);
+            }
+            match scheduled.action {
+                AdminAction::VoteFee(election_id, fee) => {
+                    self.apply_vote_fee(election_id, fee)?;
+                }
+                AdminAction::CandidacyDeposit(election_id, deposit) => {
+                    self.apply_candidacy_deposit(election_id, deposit)?;
+                }
+                AdminAction::CreationFee(fee) => {
+                    self.apply_creation_fee(fee);
+                }
+                AdminAction::BanCandidate(election_id, account) => {
+                    self.apply_ban(election_id, account)
+                }
+                AdminAction::Upgrade(code_hash) => self.apply_upgrade(code_hash)?,
+                AdminAction::TimelockDelay(delay) => self.apply_timelock_delay(delay),
+                AdminAction::DelegateLogic(code_hash) => self.apply_delegation(code_hash)?,
+            }
+            self.scheduled_actions.remove(action_id);
+            self.env().emit_event(ActionExecuted { action_id });
+            Ok(())
+        }
+
+        /// Drops a scheduled action without running it. Only its proposer may
+        /// call it.
+        #[ink(message, selector = 0x35c2_10a8)]
+        pub fn cancel_action(&mut self, action_id: u32) -> Result<(), VoteError> {
+            let scheduled = self
+                .scheduled_actions
+                .get(action_id)
+                .ok_or(VoteError::ActionNotFound)?;
+            if self.env().caller() != scheduled.proposer {
+                return Err(VoteError::Unauthorized);
+            }
+            self.scheduled_actions.remove(action_id);
+            self.env().emit_event(ActionCancelled { action_id });
+            Ok(())
+        }
+
+        /// Returns an action waiting out the timelock.
+        #[ink(message, selector = 0x5d3d_e425)]
+        pub fn get_scheduled_action(&self, action_id: u32) -> Option<ScheduledAction> {
+            self.scheduled_actions.get(action_id)
+        }
+
+        /// Gives `account` a role, replacing the one it held, or takes its
+        /// role away with `None`. Only the owner may call it.
+        #[ink(message, selector = 0xdb8c_051e)]
+        pub fn assign_role(
+            &mut self,
+            account: AccountId,
+            role: Option<RoleId>,
+        ) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            match role {
+                Some(role) => self.roles.insert(account, &role),
+                None => {
+                    self.roles.remove(account);
+                    None
+                }
+            };
+            self.env().emit_event(RoleAssigned { account, role });
+            Ok(())
+        }
+
+        /// Lets holders of `role` call the message with `selector` in any
+        /// election as if they were its admin, or revokes it. Covers every
+        /// message restricted to the election admin; either `finalize` or
+        /// `finalize_step` grants both. Owner-only messages are
+        /// never delegated. Only the owner may call it.
+        #[ink(message, selector = 0x3b02_0220)]
+        pub fn set_permission(
+            &mut self,
+            role: RoleId,
+            selector: Selector,
+            granted: bool,
+        ) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if granted {
+                self.role_permissions.insert((role, selector), &());
+            } else {
+                self.role_permissions.remove((role, selector));
+            }
+            self.env().emit_event(PermissionUpdated {
+                role,
+                selector,
+                granted,
+            });
+            Ok(())
+        }
+
+        /// Returns the role an account holds.
+        #[ink(message, selector = 0x85f6_5d55)]
+        pub fn get_role(&self, account: AccountId) -> Option<RoleId> {
+            self.roles.get(account)
+        }
+
+        /// Returns whether holders of `role` may call the message with
+        /// `selector`.
+        #[ink(message, selector = 0xe094_2492)]
+        pub fn has_permission(&self, role: RoleId, selector: Selector) -> bool {
+            self.role_permissions.contains((role, selector))
+        }
+
+        /// Returns whether upgrades need an approving referendum.
+        #[ink(message, selector = 0xf10d_1318)]
+        pub fn are_upgrades_governed(&self) -> bool {
+            self.upgrades_governed
+        }
+
+        /// Opens a referendum on upgrading to `code_hash`.
+        /// Only the owner may call it.
+        #[ink(message, selector = 0xc00b_1291)]
+        pub fn propose_upgrade(&mut self, code_hash: Hash) -> Result<u32, VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            let referendum_id = self.next_referendum_id;
+            self.next_referendum_id = referendum_id.saturating_add(1);
+            let referendum = Referendum {
+                code_hash,
+                status: ReferendumStatus::Voting,
+                yes: 0,
+                no: 0,
+            };
+            self.referendums.insert(referendum_id, &referendum);
+            self.emit_referendum_updated(referendum_id, None, referendum);
+            Ok(referendum_id)
+        }
+
+        /// Casts a yes (`approve = true`) or no ballot in an open referendum.
+        #[ink(message, selector = 0xf5a7_91d9)]
+        pub fn vote_referendum(
+            &mut self,
+            referendum_id: u32,
+            approve: bool,
+        ) -> Result<(), VoteError> {
+            let mut referendum = self
+                .referendums
+                .get(referendum_id)
+                .ok_or(VoteError::ReferendumNotFound)?;
+            if referendum.status != ReferendumStatus::Voting {
+                return Err(VoteError::ReferendumClosed);
+            }
+            let caller = self.env().caller();
+            if self.referendum_voted.contains((referendum_id, caller)) {
+                return Err(VoteError::AlreadyVoted);
+            }
+            self.referendum_voted.insert((referendum_id, caller), &());
+
+            if approve {
+                referendum.yes = referendum.yes.saturating_add(1);
+            } else {
+                referendum.no = referendum.no.saturating_add(1);
+            }
+            self.referendums.insert(referendum_id, &referendum);
+            self.emit_referendum_updated(referendum_id, Some(caller), referendum);
+            Ok(())
+        }
+
+        /// Closes a referendum. A strict majority of yes ballots approves its
+        /// code hash for `upgrade`. Only the owner may call it.
+        #[ink(message, selector = 0x7431_fda6)]
+        pub fn close_referendum(
+            &mut self,
+            referendum_id: u32,
+        ) -> Result<ReferendumStatus, VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            let mut referendum = self
+                .referendums
+                .get(referendum_id)
+                .ok_or(VoteError::ReferendumNotFound)?;
+            if referendum.status != ReferendumStatus::Voting {
+                return Err(VoteError::ReferendumClosed);
+            }
+            referendum.status = if referendum.yes > referendum.no {
+                self.approved_upgrades
+                    .insert(referendum.code_hash, &referendum_id);
+                ReferendumStatus::Approved
+            } else {
+                ReferendumStatus::Rejected
+            };
+            self.referendums.insert(referendum_id, &referendum);
+            let status = referendum.status;
+            self.emit_referendum_updated(referendum_id, None, referendum);
+            Ok(status)
+        }
+
+        /// Returns an upgrade referendum.
+        #[ink(message, selector = 0xf6a4_e9a6)]
+        pub fn get_referendum(&self, referendum_id: u32) -> Option<Referendum> {
+            self.referendums.get(referendum_id)
+        }
+
+        /// Switches the contract to `code_hash`, keeping its storage. Once
+        /// upgrades are governed, a closed referendum must have approved that
+        /// exact hash; each approval is used up by one upgrade.
+        /// Only the owner may call it, and only through `schedule_action`
+        /// while a timelock is set.
+        #[ink(message, selector = 0x9852_f7b0)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            self.ensure_no_timelock()?;
+            self.apply_upgrade(code_hash)
+        }
+
+        /// Switches the contract to `delegator_code`, the code of a
+        /// `voting_delegator`, keeping its storage: from then on the current
+        /// code only runs through `delegate_call`, and the owner, as the
+        /// delegator's admin, may swap it for a fixed one without any
+        /// migration, even while elections are voting. Approval, timelock and
+        /// authorization are those of `upgrade`.
+        #[ink(message, selector = 0x7c7b_929d)]
+        pub fn delegate_logic(&mut self, delegator_code: Hash) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            self.ensure_no_timelock()?;
+            self.apply_delegation(delegator_code)
+        }
+
+        fn apply_delegation(&mut self, delegator_code: Hash) -> Result<(), VoteError> {
+            let logic = self
+                .env()
+                .own_code_hash()
+                .map_err(|_| VoteError::UpgradeFailed)?;
+            self.apply_upgrade(delegator_code)?;
+            ink::env::set_contract_storage(&crate::delegator::LOGIC_KEY, &logic);
+            ink::env::set_contract_storage(&crate::delegator::ADMIN_KEY, &self.owner);
+            Ok(())
+        }
+
+        fn apply_upgrade(&mut self, code_hash: Hash) -> Result<(), VoteError> {
+            if self.upgrades_governed && self.approved_upgrades.take(code_hash).is_none() {
+                return Err(VoteError::UpgradeNotApproved);
+            }
+            let old_code_hash = self.env().own_code_hash().unwrap_or_default();
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| VoteError::UpgradeFailed)?;
+            self.env().emit_event(CodeHashUpdated {
+                old_code_hash,
+                new_code_hash: code_hash,
+                storage_version: self.storage_version,
+            });
+            Ok(())
+        }
+
+        /// Takes value sent to the contract without naming what it is for,
+        /// under the policy fixed at instantiation: it is refused with
+        /// `TransferRejected`, booked as treasury income, or added to a
+        /// prize pool, so that it never ends up as untracked balance.
+        #[ink(message, payable, selector = 0x5e58_3c21)]
+        pub fn receive(&mut self) -> Result<(), VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            match self.incoming_funds {
+                IncomingFunds::Reject => Err(VoteError::TransferRejected),
+                IncomingFunds::Treasury => {
+                    let amount = self.env().transferred_value();
+                    self.forfeit(amount);
+                    self.env().emit_event(FundsReceived {
+                        from: self.env().caller(),
+                        amount,
+                    });
+                    Ok(())
+                }
+                IncomingFunds::PrizePool(election_id) => self.fund_prize(election_id),
+            }
+        }
+
+        /// Returns what `receive` does with the value sent along.
+        #[ink(message, selector = 0x63df_aaaa)]
+        pub fn get_incoming_funds(&self) -> IncomingFunds {
+            self.incoming_funds
+        }
+
+        /// Returns the funds owned by the contract itself.
+        #[ink(message, selector = 0x6899_c3c5)]
+        pub fn get_treasury(&self) -> Balance {
+            self.treasury
+        }
+
+        /// Returns the funds the contract holds, split into fee income, prize
+        /// escrow, deposits and pending payouts, to reconcile its balance
+        /// against what it owes.
+        #[ink(message, selector = 0x0fbb_5eb5)]
+        pub fn treasury_report(&self) -> TreasuryReport {
+            self.funds
+        }
+
+        /// Returns the part of the contract's balance that exceeds its
+        /// obligations: prize escrow, deposits, pending payouts and the vote
+        /// fees of elections not finalized yet, which may still be routed.
+        #[ink(message, selector = 0xbacb_9ea8)]
+        pub fn get_surplus(&self) -> Balance {
+            let TreasuryReport {
+                fee_income,
+                prize_escrow,
+                deposits,
+                payouts,
+            } = self.funds;
+            let unsettled_fees = fee_income.saturating_sub(self.treasury);
+            self.env().balance().saturating_sub(
+                unsettled_fees
+                    .saturating_add(prize_escrow)
+                    .saturating_add(deposits)
+                    .saturating_add(payouts),
+            )
+        }
+
+        /// Moves `amount` of the surplus to `to`, taking it out of the
+        /// treasury first. Only the owner may call it.
+        #[ink(message, selector = 0x802b_6b8d)]
+        pub fn withdraw_surplus(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            if amount > self.get_surplus() {
+                return Err(VoteError::ExceedsSurplus);
+            }
+            self.paying_out.set(&true);
+            let paid = self.env().transfer(to, amount);
+            self.paying_out.set(&false);
+            paid.map_err(|_| VoteError::TransferFailed)?;
+            // anything beyond the treasury was never booked to a bucket
+            let booked = amount.min(self.treasury);
+            self.treasury = self.treasury.saturating_sub(booked);
+            self.funds.fee_income = self.funds.fee_income.saturating_sub(booked);
+            self.env().emit_event(SurplusWithdrawn { to, amount });
+            Ok(())
+        }
+
+        /// Moves the whole treasury into `treasury`, a contract implementing
+        /// `Treasury`, and returns the amount moved. Only the owner may call
+        /// it.
+        #[ink(message, selector = 0x865b_0a79)]
+        pub fn sweep_treasury(&mut self, treasury: AccountId) -> Result<Balance, VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            let amount = self.treasury;
+            if amount == 0 {
+                return Err(VoteError::NothingToClaim);
+            }
+            let mut target: ink::contract_ref!(Treasury) = treasury.into();
+            self.paying_out.set(&true);
+            let paid = target
+                .call_mut()
+                .deposit()
+                .transferred_value(amount)
+                .try_invoke();
+            self.paying_out.set(&false);
+            if !matches!(paid, Ok(Ok(()))) {
+                return Err(VoteError::TransferFailed);
+            }
+            self.treasury = 0;
+            self.funds.fee_income = self.funds.fee_income.saturating_sub(amount);
+            self.env().emit_event(SurplusWithdrawn {
+                to: treasury,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Returns the archived outcome of a finalized election.
+        #[ink(message, selector = 0x69e3_3000)]
+        pub fn get_archived_result(&self, election_id: ElectionId) -> Option<ArchivedResult> {
+            self.archive.get(election_id)
+        }
+
+        /// Returns the hash committing to the final tally of a finalized
+        /// election: the Blake2x256 hash of the SCALE-encoded `(candidate,
+        /// votes)` pairs sorted by candidate.
+        #[ink(message, selector = 0x4c7e_4eb6)]
+        pub fn get_tally_hash(&self, election_id: ElectionId) -> Option<Hash> {
+            self.tally_hashes.get(election_id)
+        }
+
+        /// Returns the root of the Merkle tree of ballots of a finalized election.
+        ///
+        /// Leaf `i` is the Blake2x256 hash of the SCALE-encoded `(election_id,
+        /// voter, candidate)` of the `i`-th ballot, inner nodes hash the
+        /// encoded `(left, right)` pair and missing leaves are zero. The tree
+        /// has a fixed depth of 32.
+        #[ink(message, selector = 0xc739_f5f0)]
+        pub fn get_ballot_root(&self, election_id: ElectionId) -> Option<Hash> {
+            self.ballot_roots.get(election_id)
+        }
+
+        /// Checks that `voter` cast the `index`-th ballot of a finalized
+        /// election for `candidate`, given the siblings on the path from that
+        /// leaf to the root, bottom first.
+        #[ink(message, selector = 0xc764_c28a)]
+        pub fn verify_ballot(
+            &self,
+            election_id: ElectionId,
+            index: u32,
+            voter: AccountId,
+            candidate: AccountId,
+            proof: Vec<Hash>,
+        ) -> bool {
+            let Some(root) = self.ballot_roots.get(election_id) else {
+                return false;
+            };
+            if proof.len() != BALLOT_TREE_DEPTH {
+                return false;
+            }
+            let mut node = Self::ballot_leaf(election_id, voter, candidate);
+            let mut index = index;
+            for sibling in &proof {
+                node = if index & 1 == 0 {
+                    Self::hash_pair(&node, sibling)
+                } else {
+                    Self::hash_pair(sibling, &node)
+                };
+                index >>= 1;
+            }
+            node == root
+        }
+
+        /// Returns archived elections in order of finalization, at most
+        /// `MAX_PAGE_SIZE` at a time.
+        #[ink(message, selector = 0xceec_56d2)]
+        pub fn get_archived_results(
+            &self,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<(ElectionId, ArchivedResult)> {
+            let end = offset
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.archived_count);
+            (offset..end)
+                .filter_map(|index| self.archive_index.get(index))
+                .filter_map(|election_id| Some((election_id, self.archive.get(election_id)?)))
+                .collect()
+        }
+
+        /// Returns the election closed in the `round`-th finalization, counting
+        /// from zero, with its final tally. Rounds are numbered across all
+        /// elections, so recurring votes can be compared round over round.
+        #[ink(message, selector = 0xad17_f63c)]
+        pub fn get_round_result(&self, round: u32) -> Option<(ElectionId, ArchivedResult)> {
+            let election_id = self.archive_index.get(round)?;
+            Some((election_id, self.archive.get(election_id)?))
+        }
+
+        /// Returns how many elections have been archived.
+        #[ink(message, selector = 0xa870_a62e)]
+        pub fn get_archived_count(&self) -> u32 {
+            self.archived_count
+        }
+
+        /// Deletes the detailed state of a finalized election (runner list,
+        /// per-runner counts and the given voters' ballot flags), keeping only
+        /// its archived record. Only the election admin may call it.
+        ///
+        /// Returns the number of voter entries removed.
+        #[ink(message, selector = 0x8e62_921c)]
+        pub fn prune_election(
+            &mut self,
+            election_id: ElectionId,
+            voters: Vec<AccountId>,
+        ) -> Result<u32, VoteError> {
+            self.ensure_phase(election_id);
+            let election =
+                self.election_as_admin(election_id, ink::selector_bytes!("prune_election"))?;
+            if election.status != ElectionStatus::Finalized {
+                return Err(VoteError::InvalidTransition);
+            }
+            let runners = self.runners.take(election_id).unwrap_or_default();
+            for runner in &runners {
+                self.votes.remove((election_id, *runner));
+            }
+            let approval = election.config.mode.ballots_per_voter() > 1;
+            let mut removed = 0u32;
+            for voter in voters {
+                let mut voted = self.already_voted.take((election_id, voter)).is_some();
+                if let Some(identity) = self.identities.get(voter) {
+                    self.identity_voted.remove((election_id, identity));
+                }
+                if approval {
+                    voted |= self.approvals_cast.take((election_id, voter)).is_some();
+                    for runner in &runners {
+                        self.approved.remove((election_id, voter, *runner));
+                    }
+                }
+                if voted {
+                    removed = removed.saturating_add(1);
+                }
+            }
+            self.env().emit_event(ElectionPruned {
+                election_id,
+                voters_removed: removed,
+            });
+            Ok(removed)
+        }
+
+        /// Deletes the per-ballot and per-voter records of an archived
+        /// election, at most `max_entries` ballots at a time and resuming
+        /// where the last call stopped, keeping only its archived record.
+        /// The last call drops the runner list and counts as well. Anyone may
+        /// call it once the result has taken effect and `RETENTION_BLOCKS`
+        /// have passed since finalization.
+        ///
+        /// The runtime releases the storage deposit of the deleted entries to
+        /// the caller, who shares it with the treasury: the call must carry
+        /// half the `entry_deposit` for every ballot gone through, and what
+        /// it carries beyond that is credited back.
+        ///
+        /// Returns whether every ballot has been gone through.
+        #[ink(message, payable, selector = 0xa1d9_c9eb)]
+        pub fn gc(&mut self, election_id: ElectionId, max_entries: u32) -> Result<bool, VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            self.ensure_phase(election_id);
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if !self.is_result_effective(election_id) || !self.archive.contains(election_id) {
+                return Err(VoteError::InvalidTransition);
+            }
+            if election
+                .finalized_at
+                .is_some_and(|at| self.env().block_number() < at.saturating_add(RETENTION_BLOCKS))
+            {
+                return Err(VoteError::RetentionPending);
+            }
+            let start = self.gc_cursors.get(election_id).unwrap_or_default();
+            let end = start.saturating_add(max_entries).min(election.turnout);
+            let treasury_share =
+                Balance::from(end.saturating_sub(start)).saturating_mul(self.entry_deposit) / 2;
+            let paid = self.env().transferred_value();
+            if paid < treasury_share {
+                return Err(VoteError::IncorrectFee);
+            }
+
+            let runners = self.runners.get(election_id).unwrap_or_default();
+            let approval = election.config.mode.ballots_per_voter() > 1;
+            for index in start..end {
+                self.rankings.remove((election_id, index));
+                self.transfers.remove((election_id, index));
+                self.sealed_ballots.remove((election_id, index));
+                let Some(voter) = self.voters.take((election_id, index)) else {
+                    continue;
+                };
+                self.already_voted.remove((election_id, voter));
+                if let Some(identity) = self.identities.get(voter) {
+                    self.identity_voted.remove((election_id, identity));
+                }
+                if approval && self.approvals_cast.take((election_id, voter)).is_some() {
+                    for runner in &runners {
+                        self.approved.remove((election_id, voter, *runner));
+                    }
+                }
+            }
+            let done = end >= election.turnout;
+            if done {
+                for runner in self.runners.take(election_id).unwrap_or_default() {
+                    self.votes.remove((election_id, runner));
+                }
+            }
+            self.gc_cursors.insert(election_id, &end);
+
+            self.forfeit(treasury_share);
+            if paid > treasury_share {
+                self.credit(self.env().caller(), paid - treasury_share);
+            }
+            self.env().emit_event(ElectionCollected {
+                election_id,
+                entries: end.saturating_sub(start),
+                treasury_share,
+                done,
+            });
+            Ok(done)
+        }
+
+        /// Sets the storage deposit one entry is reckoned to hold, which
+        /// `gc` callers share with the treasury. Only the owner may call it.
+        #[ink(message, selector = 0x30c4_185b)]
+        pub fn set_entry_deposit(&mut self, entry_deposit: Balance) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            self.entry_deposit = entry_deposit;
+            self.emit_config_changed(None, ink::selector_bytes!("set_entry_deposit"));
+            Ok(())
+        }
+
+        /// Returns the storage deposit one entry is reckoned to hold.
+        #[ink(message, selector = 0x4f17_9195)]
+        pub fn get_entry_deposit(&self) -> Balance {
+            self.entry_deposit
+        }
+
+        /// Returns how the ballots of an election were paced: how many were
+        /// cast in each block range, for organizers weighing a deadline
+        /// extension.
+        #[ink(message, selector = 0x3205_f8c8)]
+        pub fn get_turnout_histogram(&self, election_id: ElectionId) -> TurnoutHistogram {
+            self.turnout_histograms.get(election_id).unwrap_or_default()
+        }
+
+        /// Counts a ballot accepted in the current block in the election's
+        /// turnout histogram.
+        fn count_in_histogram(&mut self, election_id: ElectionId) {
+            let mut histogram = self.get_turnout_histogram(election_id);
+            histogram.count(self.env().block_number());
+            self.turnout_histograms.insert(election_id, &histogram);
+        }
+
+        /// Stores the compact record of a freshly finalized election.
+        fn archive_result(&mut self, election_id: ElectionId, totals: RunnerTotals) {
+            let Some(election) = self.elections.get(election_id) else {
+                return;
+            };
+            let mut sorted = totals.clone();
+            sorted.sort_unstable();
+            let mut tally_hash = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&sorted, &mut tally_hash);
+            let tally_hash = Hash::from(tally_hash);
+            self.tally_hashes.insert(election_id, &tally_hash);
+            let ballot_root = self.ballot_root(election_id, election.turnout);
+            self.ballot_roots.insert(election_id, &ballot_root);
+
+            let total_votes = totals
+                .iter()
+                .fold(0u32, |sum, (_, votes)| sum.saturating_add(*votes));
+            let mut winners = match (election.config.mode, election.config.outcome) {
+                (VotingMode::SingleTransferable(_), _) => {
+                    self.stv_elected.get(election_id).unwrap_or_default()
+                }
+                (_, Outcome::Sortition) => {
+                    let mut seed = [0u8; 32];
+                    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                        &(
+                            election_id,
+                            ballot_root,
+                            tally_hash,
+                            election.finalized_at.unwrap_or_default(),
+                        ),
+                        &mut seed,
+                    );
+                    let seed = Hash::from(seed);
+                    let (draw, winner) = counting::draw(&totals, seed.as_ref());
+                    self.sortition_draws.insert(election_id, &(seed, draw));
+                    self.env().emit_event(SortitionDrawn {
+                        election_id,
+                        seed,
+                        draw,
+                        winner,
+                    });
+                    winner.into_iter().collect()
+                }
+                _ => Self::winners(&totals, election.config.tie_break).0,
+            };
+            counting::canonical_order(&mut winners, &totals);
+            let archived = ArchivedResult {
+                winners,
+                totals,
+                turnout: election.turnout,
+                finalized_at: election.finalized_at.unwrap_or_default(),
+            };
+            self.env().emit_event(RoundFinalized {
+                election_id,
+                round: self.archived_count,
+                winners: archived.winners.clone(),
+                turnout: archived.turnout,
+                total_votes,
+                tally_hash,
+                ballot_root,
+            });
+            self.archive.insert(election_id, &archived);
+            self.archive_index.insert(self.archived_count, &election_id);
+            self.archived_count = self.archived_count.saturating_add(1);
+        }
+
+        /// Runs the single-transferable-vote count of an election for up to
+        /// `budget` ballot visits, resuming where the last call stopped. Each
+        /// pass over the ballots is settled by electing or excluding one
+        /// candidate; ballots then move on to their next continuing
+        /// preference in the following pass. Returns whether every seat is
+        /// filled.
+        fn count_transferable_votes(
+            &mut self,
+            election_id: ElectionId,
+            turnout: u32,
+            seats: u32,
+            totals: &[(AccountId, u32)],
+            budget: &mut u32,
+        ) -> bool {
+            if self.stv_elected.contains(election_id) {
+                return true;
+            }
+            let mut count = self
+                .stv_counts
+                .get(election_id)
+                .unwrap_or_else(|| StvCount::new(totals, seats));
+            loop {
+                while count.cursor < turnout {
+                    if *budget == 0 {
+                        self.stv_counts.insert(election_id, &count);
+                        return false;
+                    }
+                    self.transfer_ballot(election_id, &mut count);
+                    count.cursor += 1;
+                    *budget -= 1;
+                }
+                if count.settle(seats) {
+                    break;
+                }
+                count.cursor = 0;
+                count.tallies.fill(0);
+            }
+            self.stv_counts.remove(election_id);
+            self.stv_elected.insert(election_id, &count.elected);
+            self.env().emit_event(SeatsFilled {
+                election_id,
+                elected: count.elected,
+                quota: count.quota,
+                rounds: count.rounds,
+            });
+            true
+        }
+
+        /// Books the ballots of a participation-tracked election into its
+        /// voters' streaks, up to `budget` of them, resuming where the last
+        /// call stopped. The election takes the next tracked round when the
+        /// first ballot is booked. Returns whether every ballot is booked.
+        fn record_participation(
+            &mut self,
+            election_id: ElectionId,
+            turnout: u32,
+            budget: &mut u32,
+        ) -> bool {
+            let progress = self.participation_progress.get(election_id);
+            let (round, mut cursor) = progress.unwrap_or_else(|| {
+                let round = self.tracked_rounds;
+                self.tracked_rounds = round.saturating_add(1);
+                (round, 0)
+            });
+            while cursor < turnout {
+                if *budget == 0 {
+                    self.participation_progress
+                        .insert(election_id, &(round, cursor));
+                    return false;
+                }
+                if let Some(voter) = self.voters.get((election_id, cursor)) {
+                    let mut participation = self.get_participation(voter);
+                    participation.streak = if participation.elections > 0
+                        && participation.last_round.saturating_add(1) == round
+                    {
+                        participation.streak.saturating_add(1)
+                    } else {
+                        1
+                    };
+                    participation.best = participation.best.max(participation.streak);
+                    participation.elections = participation.elections.saturating_add(1);
+                    participation.last_round = round;
+                    self.participation.insert(voter, &participation);
+                    if STREAK_MILESTONES.contains(&participation.streak) {
+                        self.env().emit_event(StreakMilestone {
+                            account: voter,
+                            streak: participation.streak,
+                        });
+                    }
+                }
+                cursor += 1;
+                *budget -= 1;
+            }
+            if progress.is_some() {
+                self.participation_progress.remove(election_id);
+            }
+            true
+        }
+
+        /// Counts the ranked ballot at `count.cursor` for its first continuing
+        /// preference. A ballot resting on a candidate elected at the end of
+        /// the last pass moves on at that candidate's share of surplus; one
+        /// resting on an excluded candidate moves on at full value.
+        fn transfer_ballot(&mut self, election_id: ElectionId, count: &mut StvCount) {
+            let key = (election_id, count.cursor);
+            let Some((position, value)) = self.transfers.get(key) else {
+                return;
+            };
+            let ranking = self.rankings.get(key).unwrap_or_default();
+            let (mut next, mut carried) = (position, value);
+            while let Some(candidate) = ranking.get(next as usize) {
+                let standing = count
+                    .candidates
+                    .iter()
+                    .position(|runner| runner == candidate)
+                    .map(|index| (index, count.standing[index]));
+                match standing {
+                    Some((index, Standing::Hopeful)) => {
+                        count.tallies[index] = count.tallies[index].saturating_add(carried);
+                        break;
+                    }
+                    Some((_, Standing::Elected { surplus, total })) if next == position => {
+                        carried = carried.saturating_mul(surplus) / total;
+                    }
+                    _ => {}
+                }
+                next += 1;
+            }
+            if (next, carried) != (position, value) {
+                self.transfers.insert(key, &(next, carried));
+            }
+        }
+
+        /// Emits the committee a freshly finalized committee election elected.
+        fn announce_committee(&mut self, election_id: ElectionId) {
+            let (seats, elected) = self.get_committee(election_id);
+            self.env().emit_event(CommitteeElected {
+                election_id,
+                seats,
+                elected,
+            });
+        }
+
+        /// Splits the prize pool of a freshly finalized election evenly among
+        /// its winners. The rounding remainder, or the whole pool when nobody
+        /// won, goes to the treasury.
+        fn award_prizes(&mut self, election_id: ElectionId) {
+            let Some(election) = self.elections.get(election_id) else {
+                return;
+            };
+            if election.prize_pool == 0 {
+                return;
+            }
+            let winners = self.get_current_winner(election_id);
+            let mut remainder = election.prize_pool;
+            if !winners.is_empty() {
+                let share = election.prize_pool / winners.len() as Balance;
+                for winner in winners {
+                    self.prizes.insert((election_id, winner), &share);
+                    remainder = remainder.saturating_sub(share);
+                    self.env().emit_event(PrizeAwarded {
+                        election_id,
+                        winner,
+                        amount: share,
+                    });
+                }
+            }
+            self.funds.prize_escrow = self.funds.prize_escrow.saturating_sub(remainder);
+            self.forfeit(remainder);
+        }
+
+        /// Pays the finalization bounty of a freshly finalized election out of
+        /// its vote fees to `keeper`, before they are routed, along with the
+        /// tips added to it.
+        fn pay_bounty(&mut self, election_id: ElectionId, keeper: AccountId) {
+            let Some(mut election) = self.elections.get(election_id) else {
+                return;
+            };
+            let amount = election.config.finalization_bounty.min(election.fee_pot);
+            let tips = election.bounty_tips;
+            if amount == 0 && tips == 0 {
+                return;
+            }
+            election.fee_pot = election.fee_pot.saturating_sub(amount);
+            election.bounty_tips = 0;
+            self.elections.insert(election_id, &election);
+            if amount > 0 {
+                match election.config.fee_token {
+                    Some(token) => self.credit_tokens(token, keeper, amount),
+                    None => {
+                        self.funds.fee_income = self.funds.fee_income.saturating_sub(amount);
+                        self.credit(keeper, amount);
+                    }
+                }
+            }
+            if tips > 0 {
+                self.funds.prize_escrow = self.funds.prize_escrow.saturating_sub(tips);
+                self.credit(keeper, tips);
+            }
+            self.env().emit_event(BountyPaid {
+                election_id,
+                keeper,
+                amount,
+                tips,
+            });
+        }
+
+        /// Routes the protocol's share of a freshly finalized election's vote
+        /// fees and candidacy deposits to the beneficiary; the rest goes to the
+        /// treasury, or to the election admin when they were paid in a token.
+        fn route_fees(&mut self, election_id: ElectionId) {
+            let Some(election) = self.elections.get(election_id) else {
+                return;
+            };
+            let collected = election.fee_pot.saturating_add(election.deposit_pot);
+            if collected == 0 {
+                return;
+            }
+            let ProtocolFee {
+                beneficiary,
+                basis_points,
+            } = self.protocol_fee.clone();
+            let routed = collected.saturating_mul(Balance::from(basis_points)) / 10_000;
+            if let Some(token) = election.config.fee_token {
+                self.credit_tokens(token, beneficiary, routed);
+                if routed > 0 {
+                    self.env().emit_event(FeeRouted {
+                        election_id,
+                        beneficiary,
+                        amount: routed,
+                    });
+                }
+                self.credit_tokens(token, election.admin, collected.saturating_sub(routed));
+                return;
+            }
+            // forfeited candidacy deposits become fee income
+            self.funds.deposits = self.funds.deposits.saturating_sub(election.deposit_pot);
+            self.funds.fee_income = self
+                .funds
+                .fee_income
+                .saturating_add(election.deposit_pot)
+                .saturating_sub(routed);
+            if routed > 0 {
+                self.credit(beneficiary, routed);
+                self.env().emit_event(FeeRouted {
+                    election_id,
+                    beneficiary,
+                    amount: routed,
+                });
+            }
+            self.treasury = self
+                .treasury
+                .saturating_add(collected.saturating_sub(routed));
+        }
+
+        /// Books the outcome of a freshly finalized election into the
+        /// per-account win history and, for term-limited elections, the
+        /// incumbent's streak.
+        fn record_term(&mut self, election_id: ElectionId) {
+            let winners = self.get_current_winner(election_id);
+            let winner = match winners.as_slice() {
+                [winner] => Some(*winner),
+                _ => None,
+            };
+            if let Some(winner) = winner {
+                let mut history = self.get_win_history(winner);
+                history.push(election_id);
+                self.win_history.insert(winner, &history);
+            }
+
+            let term_limited = self
+                .elections
+                .get(election_id)
+                .is_some_and(|election| election.config.term_limit > 0);
+            if !term_limited {
+                return;
+            }
+            if let Some(winner) = winner {
+                let terms = self.get_consecutive_terms(winner).saturating_add(1);
+                self.consecutive_terms.insert(winner, &terms);
+            }
+            self.incumbent = winner;
+        }
+
+        /// Returns the election if its electorate can currently grow by vouching.
+        fn vouching_election(
+            &self,
+            election_id: ElectionId,
+        ) -> Result<Election, RegistrationError> {
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            if !election.config.closed_electorate
+                || election.config.vouches_required == 0
+                || !matches!(
+                    election.status,
+                    ElectionStatus::Pending | ElectionStatus::Registration
+                )
+            {
+                return Err(RegistrationError::RegistrationClosed);
+            }
+            Ok(election)
+        }
+
+        /// Returns the election whose electorate `election_id` votes with,
+        /// itself unless it is a re-run.
+        fn electorate_id(&self, election_id: ElectionId) -> ElectionId {
+            self.rerun_of.get(election_id).unwrap_or(election_id)
+        }
+
+        /// Whether a re-run voting with the electorate of `election_id` has
+        /// not closed yet, which keeps that electorate as it is.
+        fn rerun_pending(&self, election_id: ElectionId) -> bool {
+            self.latest_rerun
+                .get(election_id)
+                .and_then(|rerun_id| self.elections.get(rerun_id))
+                .is_some_and(|rerun| {
+                    !matches!(
+                        rerun.status,
+                        ElectionStatus::Finalized
+                            | ElectionStatus::Cancelled
+                            | ElectionStatus::Expired
+                    )
+                })
+        }
+
+        /// Whether voters may not join or leave the electorate because ballots
+        /// are being cast or counted.
+        fn electorate_frozen(election: &Election) -> bool {
+            matches!(
+                election.status,
+                ElectionStatus::Voting | ElectionStatus::RevealPhase | ElectionStatus::Tallying
+            )
+        }
+
+        /// Adds `voter` to the electorate of `election`, holding `deposit`,
+        /// at the end of its exported order.
+        fn enroll_voter(
+            &mut self,
+            election_id: ElectionId,
+            election: &mut Election,
+            voter: AccountId,
+            deposit: Balance,
+        ) {
+            let position = election.electorate;
+            self.electorate.insert((election_id, voter), &deposit);
+            self.electorate_at.insert((election_id, position), &voter);
+            self.electorate_position
+                .insert((election_id, voter), &position);
+            election.electorate = position.saturating_add(1);
+            election.storage_entries = election.storage_entries.saturating_add(1);
+        }
+
+        /// Takes `voter` out of the electorate of `election`, moving the last
+        /// voter into its position, and returns the deposit it held.
+        fn unenroll_voter(
+            &mut self,
+            election_id: ElectionId,
+            election: &mut Election,
+            voter: AccountId,
+        ) -> Option<Balance> {
+            let deposit = self.electorate.take((election_id, voter))?;
+            election.electorate = election.electorate.saturating_sub(1);
+            election.storage_entries = election.storage_entries.saturating_sub(1);
+            let last = election.electorate;
+            let position = self
+                .electorate_position
+                .take((election_id, voter))
+                .unwrap_or(last);
+            if let Some(moved) = self.electorate_at.take((election_id, last)) {
+                if position != last {
+                    self.electorate_at.insert((election_id, position), &moved);
+                    self.electorate_position
+                        .insert((election_id, moved), &position);
+                }
+            }
+            Some(deposit)
+        }
+
+        /// Takes `voter` out of the electorate and credits its deposit back,
+        /// returning the amount refunded.
+        fn drop_voter(
+            &mut self,
+            election_id: ElectionId,
+            mut election: Election,
+            voter: AccountId,
+        ) -> Balance {
+            let refund = self
+                .unenroll_voter(election_id, &mut election, voter)
+                .unwrap_or_default();
+            self.elections.insert(election_id, &election);
+            if refund > 0 {
+                self.funds.deposits = self.funds.deposits.saturating_sub(refund);
+                self.credit(voter, refund);
+            }
+            self.env().emit_event(VoterRemoved {
+                election_id,
+                voter,
+                refund,
+            });
+            refund
+        }
+
+        /// Adds `amount` to the fee tokens of `token` that `account` may
+        /// withdraw with `claim_tokens`.
+        fn credit_tokens(&mut self, token: AccountId, account: AccountId, amount: Balance) {
+            if amount == 0 {
+                return;
+            }
+            let owed = self
+                .get_token_claimable(token, account)
+                .saturating_add(amount);
+            self.token_claimable.insert((token, account), &owed);
+            self.env().emit_event(TokensCredited {
+                token,
+                account,
+                amount,
+            });
+        }
+
+        /// Credits `amount` back to `account` in what the election takes
+        /// payments in: its fee token if it has one, native currency if not.
+        fn repay(&mut self, config: &ElectionConfig, account: AccountId, amount: Balance) {
+            match config.fee_token {
+                Some(token) => self.credit_tokens(token, account, amount),
+                None => self.credit(account, amount),
+            }
+        }
+
+        /// Returns the native value that must be transferred along to pay
+        /// `amount`, which is nothing when the election takes a fee token.
+        fn native_due(config: &ElectionConfig, amount: Balance) -> Balance {
+            if config.fee_token.is_some() {
+                0
+            } else {
+                amount
+            }
+        }
+
+        /// Pulls `amount` of the election's fee token from `from` into the
+        /// contract. Succeeds without a call when the election takes native
+        /// currency or nothing is due.
+        fn pull_fee_token(
+            &self,
+            config: &ElectionConfig,
+            from: AccountId,
+            amount: Balance,
+        ) -> bool {
+            let Some(token) = config.fee_token else {
+                return true;
+            };
+            if amount == 0 {
+                return true;
+            }
+            let mut psp22: ink::contract_ref!(Psp22) = token.into();
+            let pulled = psp22
+                .call_mut()
+                .transfer_from(from, self.env().account_id(), amount, Vec::new())
+                .try_invoke();
+            matches!(pulled, Ok(Ok(Ok(()))))
+        }
+
+        /// Moves `amount`, taken out of another bucket by the caller, to the
+        /// treasury.
+        fn forfeit(&mut self, amount: Balance) {
+            self.treasury = self.treasury.saturating_add(amount);
+            self.funds.fee_income = self.funds.fee_income.saturating_add(amount);
+        }
+
+        /// Whether a `claim` transfer is underway.
+        fn paying_out(&self) -> bool {
+            self.paying_out.get().unwrap_or_default()
+        }
+
+        /// Returns the storage deposit the sponsorship pool of an election
+        /// pays back for the next ballot: nothing without a sponsorship or,
+        /// unless sponsorship is required, once the pool runs dry.
+        fn sponsored_deposit(
+            &self,
+            election_id: ElectionId,
+            config: &ElectionConfig,
+        ) -> Result<Balance, VoteError> {
+            let Some(terms) = config.storage_sponsorship else {
+                return Ok(0);
+            };
+            if self.get_sponsorship(election_id).pool >= terms.per_ballot {
+                Ok(terms.per_ballot)
+            } else if terms.required {
+                Err(VoteError::SponsorshipExhausted)
+            } else {
+                Ok(0)
+            }
+        }
+
+        /// Credits `amount` out of the sponsorship pool of an election to the
+        /// caller, who paid the storage deposit of its ballot.
+        fn reimburse_storage(&mut self, election_id: ElectionId, amount: Balance) {
+            let mut sponsorship = self.get_sponsorship(election_id);
+            sponsorship.pool = sponsorship.pool.saturating_sub(amount);
+            sponsorship.spent = sponsorship.spent.saturating_add(amount);
+            sponsorship.ballots = sponsorship.ballots.saturating_add(1);
+            self.sponsorships.insert(election_id, &sponsorship);
+            self.funds.deposits = self.funds.deposits.saturating_sub(amount);
+            self.credit(self.env().caller(), amount);
+        }
+
+        /// Adds `amount` to what `account` may withdraw with `claim`.
+        fn credit(&mut self, account: AccountId, amount: Balance) {
+            let claimable = self.get_claimable(account).saturating_add(amount);
+            self.claimable.insert(account, &claimable);
+            self.funds.payouts = self.funds.payouts.saturating_add(amount);
+            self.env().emit_event(Credited {
+                account,
+                amount,
+                claimable,
+            });
+        }
+
+        /// Submits the winners and tally hash of a finalized election to the
+        /// runtime. Returns whether the runtime accepted them.
+        fn note_outcome(&mut self, election_id: ElectionId) -> bool {
+            let winners = self.get_current_winner(election_id);
+            let tally_hash = self.tally_hashes.get(election_id).unwrap_or_default();
+            let noted = runtime::active().note_outcome(election_id, winners.clone(), tally_hash);
+            if noted.is_err() {
+                return false;
+            }
+            self.mirrored.insert(election_id, &());
+            self.env().emit_event(OutcomeMirrored {
+                election_id,
+                winners,
+                tally_hash,
+            });
+            true
+        }
+
+        /// Counts one signed ballot of a `settle_ballots` batch, paid with `fee`.
+        fn settle_ballot(
+            &mut self,
+            election_id: ElectionId,
+            ballot: SignedBallot,
+            fee: Balance,
+        ) -> Result<BallotId, VoteError> {
+            let digest = self.signed_ballot_digest(election_id, ballot.candidate, ballot.nonce);
+            let key = self
+                .env()
+                .ecdsa_recover(&ballot.signature, &digest)
+                .map_err(|_| VoteError::InvalidSignature)?;
+            let mut voter = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&key, &mut voter);
+            let voter = AccountId::from(voter);
+            let nonce = self.get_ballot_nonce(voter);
+            if ballot.nonce != nonce {
+                return Err(VoteError::StaleNonce);
+            }
+            let ballot_id = self.record_ballot(
+                voter,
+                election_id,
+                BallotChoice::Single(ballot.candidate),
+                Vec::new(),
+                fee,
+            )?;
+            self.ballot_nonces.insert(voter, &nonce.saturating_add(1));
+            Ok(ballot_id)
+        }
+
+        /// Returns the digest a signed ballot signs: the hash of the contract
+        /// account, the election, the candidate and the nonce.
+        fn signed_ballot_digest(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+            nonce: u64,
+        ) -> [u8; 32] {
+            let mut digest = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &(self.env().account_id(), election_id, candidate, nonce),
+                &mut digest,
+            );
+            digest
+        }
+
+        /// Returns the key a display name is held under: the hash of the name
+        /// in ASCII lowercase, so that names differing in case collide.
+        fn display_name_key(name: &[u8]) -> Hash {
+            let mut hash = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(
+                &name.to_ascii_lowercase(),
+                &mut hash,
+            );
+            Hash::from(hash)
+        }
+
+        /// Records that `referrer` referred `candidate` to the election.
+        fn record_referral(
+            &mut self,
+            election_id: ElectionId,
+            referrer: AccountId,
+            candidate: AccountId,
+        ) -> Result<(), RegistrationError> {
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            if !matches!(
+                election.status,
+                ElectionStatus::Pending | ElectionStatus::Registration
+            ) {
+                return Err(RegistrationError::RegistrationClosed);
+            }
+            if referrer != election.admin && !self.is_verified(election_id, referrer) {
+                return Err(RegistrationError::NotAReferrer);
+            }
+            if self.referrals.contains((election_id, candidate)) {
+                return Err(RegistrationError::AlreadyReferred);
+            }
+            self.referrals.insert((election_id, candidate), &referrer);
+            self.env().emit_event(Referred {
+                election_id,
+                referrer,
+                candidate,
+            });
+            Ok(())
+        }
+
+        /// Returns the digest a signed referral signs: the hash of the
+        /// `referral` tag, the contract account, the election and the
+        /// candidate.
+        fn referral_digest(&self, election_id: ElectionId, candidate: AccountId) -> [u8; 32] {
+            let mut digest = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &(b"referral", self.env().account_id(), election_id, candidate),
+                &mut digest,
+            );
+            digest
+        }
+
+        /// Returns the digest an anonymous ballot signs: the hash of the
+        /// contract account, the election and the candidate.
+        fn anonymous_ballot_digest(
+            &self,
+            election_id: ElectionId,
+            candidate: AccountId,
+        ) -> [u8; 32] {
+            let mut digest = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &(self.env().account_id(), election_id, candidate),
+                &mut digest,
+            );
+            digest
+        }
+
+        /// Drops the tally claim of an election, returning the bond to its
+        /// proposer.
+        fn release_tally_claim(&mut self, election_id: ElectionId, claim: TallyClaim) {
+            self.tally_claims.remove(election_id);
+            self.funds.deposits = self.funds.deposits.saturating_sub(claim.bond);
+            self.credit(claim.proposer, claim.bond);
+        }
+
+        /// Stores a new `Pending` election and returns its id.
+        fn insert_election(&mut self, admin: AccountId, config: ElectionConfig) -> ElectionId {
+            let election_id = self.next_election_id;
+            self.next_election_id = election_id.saturating_add(1);
+            self.env().emit_event(ElectionCreated {
+                election_id,
+                admin,
+                config: config.clone(),
+            });
+            self.elections.insert(
+                election_id,
+                &Election {
+                    admin,
+                    config,
+                    status: ElectionStatus::Pending,
+                    turnout: 0,
+                    finalized_at: None,
+                    fee_pot: 0,
+                    prize_pool: 0,
+                    deposit_pot: 0,
+                    electorate: 0,
+                    bounty_tips: 0,
+                    slate_frozen_at: None,
+                    storage_entries: 0,
+                    admin_bond: self.admin_bond,
+                    paused_at: None,
+                },
+            );
+            election_id
+        }
+
+        /// Applies `f` to a stored election, if it exists.
+        fn update_election(&mut self, election_id: ElectionId, f: impl FnOnce(&mut Election)) {
+            if let Some(mut election) = self.elections.get(election_id) {
+                f(&mut election);
+                self.elections.insert(election_id, &election);
+            }
+        }
+
+        /// Records the ballot of `caller`, paid with the transferred value.
+        fn cast_ballot(
+            &mut self,
+            caller: AccountId,
+            election_id: ElectionId,
+            choice: BallotChoice,
+            memo: Vec<u8>,
+        ) -> Result<BallotId, VoteError> {
+            let fee = self.env().transferred_value();
+            self.record_ballot(caller, election_id, choice, memo, fee)
+        }
+
+        /// Records the ballot of `caller`, paid with `fee` of the transferred
+        /// value.
+        fn record_ballot(
+            &mut self,
+            caller: AccountId,
+            election_id: ElectionId,
+            choice: BallotChoice,
+            memo: Vec<u8>,
+            fee: Balance,
+        ) -> Result<BallotId, VoteError> {
+            let candidates: Vec<AccountId> = match &choice {
+                BallotChoice::Single(address) | BallotChoice::Quadratic(address, _) => {
+                    ink::prelude::vec![*address]
+                }
+                BallotChoice::Split(allocations)
+                | BallotChoice::Scored(allocations)
+                | BallotChoice::Cumulative(allocations) => allocations
+                    .iter()
+                    .map(|(candidate, _)| *candidate)
+                    .collect(),
+                BallotChoice::Ranked(ranking) => ranking.clone(),
+            };
+            let (mut election, mut runners, mut weight) =
+                match self.check_ballot(caller, election_id, &candidates) {
+                    Err(VoteError::VotingClosed) if self.ensure_phase(election_id) => {
+                        self.check_ballot(caller, election_id, &candidates)?
+                    }
+                    checked => checked?,
+                };
+            if election.config.encrypted_ballots.is_some() {
+                return Err(VoteError::BallotsEncrypted);
+            }
+            if election.config.anonymous {
+                return Err(VoteError::BallotsAnonymous);
+            }
+            self.take_block_slot(election_id, &election.config)?;
+            let mode = election.config.mode;
+            let allotted = mode.allots();
+            let ranked = matches!(mode, VotingMode::SingleTransferable(_));
+            let quadratic = matches!(mode, VotingMode::Quadratic(_));
+            let forwarded = election
+                .config
+                .tally_module
+                .map(|module| (module, choice.clone()));
+            let allocations = match (choice, mode) {
+                (BallotChoice::Single(address), VotingMode::Split) => {
+                    ink::prelude::vec![(address, SPLIT_BUDGET)]
+                }
+                (
+                    BallotChoice::Single(address),
+                    VotingMode::Score(most) | VotingMode::Cumulative(most),
+                ) => ink::prelude::vec![(address, most)],
+                (BallotChoice::Single(address), _) => ink::prelude::vec![(address, 1)],
+                (BallotChoice::Split(allocations), VotingMode::Split) => {
+                    if !Self::allotment_fits(&allocations, SPLIT_BUDGET, SPLIT_BUDGET.into()) {
+                        return Err(VoteError::InvalidSplit);
+                    }
+                    allocations
+                }
+                (BallotChoice::Ranked(ranking), VotingMode::SingleTransferable(_)) => {
+                    Self::check_ranking(&ranking)?;
+                    ink::prelude::vec![(ranking[0], 1)]
+                }
+                (BallotChoice::Quadratic(address, votes), VotingMode::Quadratic(max_votes)) => {
+                    if !(1..=max_votes).contains(&votes) {
+                        return Err(VoteError::InvalidVoteCount);
+                    }
+                    ink::prelude::vec![(address, votes)]
+                }
+                (BallotChoice::Scored(scores), VotingMode::Score(most)) => {
+                    if !Self::allotment_fits(&scores, most, u32::MAX) {
+                        return Err(VoteError::InvalidAllotment);
+                    }
+                    scores
+                }
+                (BallotChoice::Cumulative(votes), VotingMode::Cumulative(budget)) => {
+                    if !Self::allotment_fits(&votes, budget, budget.into()) {
+                        return Err(VoteError::InvalidAllotment);
+                    }
+                    votes
+                }
+                _ => return Err(VoteError::UnsupportedBallotType),
+            };
+            let vote_fee = if quadratic {
+                let votes = Balance::from(allocations[0].1);
+                election
+                    .config
+                    .vote_fee
+                    .saturating_mul(votes.saturating_mul(votes))
+            } else {
+                election.config.vote_fee
+            };
+            if fee != Self::native_due(&election.config, vote_fee) {
+                return Err(VoteError::IncorrectFee);
+            }
+            let reimbursed = self.sponsored_deposit(election_id, &election.config)?;
+
+            // carry the weight of voters delegating to the caller
+            let allowed = election.config.mode.ballots_per_voter();
+            let mut delegated = Vec::new();
+            let mut split = Vec::new();
+            if allowed == 1 && !quadratic {
+                for delegator in self.delegated_voters(&election, election_id, caller) {
+                    if let Ok(votes) = self.ballot_weight(&election.config, delegator) {
+                        weight = weight.saturating_add(votes);
+                        delegated.push(delegator);
+                    }
+                }
+                for (delegator, share) in self.split_voters(&election, election_id, caller) {
+                    if let Ok(votes) = self.ballot_weight(&election.config, delegator) {
+                        weight = weight.saturating_add(Self::split_share(votes, share));
+                        split.push((delegator, share));
+                    }
+                }
+            }
+
+            // Issue: Potential overflow
+            // Could use saturating_add so it wont return an error.
+            // a tally module keeps the count instead
+            let mut new_totals = Vec::with_capacity(allocations.len());
+            let counted = if forwarded.is_some() {
+                &[][..]
+            } else {
+                &allocations[..]
+            };
+            for (candidate, share) in counted {
+                let current_votes = self
+                    .votes
+                    .get((election_id, *candidate))
+                    .unwrap_or_default();
+                let new_total = weight
+                    .checked_mul(u32::from(*share))
+                    .and_then(|votes| current_votes.checked_add(votes))
+                    .ok_or(VoteError::VoteOverflow)?;
+                new_totals.push((*candidate, new_total));
+            }
+            if let Some((module, ballot)) = forwarded {
+                let mut tally: ink::contract_ref!(TallyModule) = module.into();
+                let ingested = tally
+                    .call_mut()
+                    .ingest_ballot(election_id, caller, ballot, weight)
+                    .try_invoke();
+                if !matches!(ingested, Ok(Ok(true))) {
+                    return Err(VoteError::TallyModuleFailed);
+                }
+            }
+            if !self.pull_fee_token(&election.config, self.env().caller(), vote_fee) {
+                return Err(VoteError::TransferFailed);
+            }
+
+            // tag caller as already voted once out of ballots
+            if allowed > 1 {
+                let cast = self
+                    .approvals_cast
+                    .get((election_id, caller))
+                    .unwrap_or_default()
+                    .saturating_add(1);
+                self.approvals_cast.insert((election_id, caller), &cast);
+                for candidate in &candidates {
+                    self.approved.insert((election_id, caller, *candidate), &());
+                }
+                if cast >= allowed {
+                    self.already_voted.insert((election_id, caller), &true);
+                }
+            } else {
+                self.already_voted.insert((election_id, caller), &true);
+            }
+            for delegator in delegated {
+                self.already_voted.insert((election_id, delegator), &true);
+            }
+            for (delegator, share) in split {
+                let spent = self
+                    .split_spent
+                    .get((election_id, delegator))
+                    .unwrap_or_default()
+                    .saturating_add(share);
+                self.split_spent.insert((election_id, delegator), &spent);
+                self.already_voted.insert((election_id, delegator), &true);
+            }
+
+            if self.identity_mode {
+                if let Some(identity) = self.identities.get(caller) {
+                    self.identity_voted.insert((election_id, identity), &caller);
+                }
+            }
+
+            // if not running yet, add the candidates to runners
+            let mut new_runners = false;
+            for candidate in &candidates {
+                if !runners.contains(candidate) {
+                    runners.push(*candidate);
+                    self.count_candidacy(*candidate);
+                    new_runners = true;
+                    election.storage_entries = election.storage_entries.saturating_add(1);
+                    self.env().emit_event(CandidateRegistered {
+                        election_id,
+                        candidate: *candidate,
+                        deposit: 0,
+                    });
+                }
+            }
+            if new_runners {
+                self.runners.insert(election_id, &runners);
+            }
+
+            // store votes
+            for (candidate, new_total) in &new_totals {
+                self.votes.insert((election_id, *candidate), new_total);
+                self.update_standings(election_id, &election.config, *candidate, *new_total);
+            }
+            if election.config.constituencies > 0 {
+                if let Some(constituency) = self.constituency_of.get((election_id, caller)) {
+                    for (candidate, share) in counted {
+                        let key = (election_id, constituency, *candidate);
+                        let votes = self
+                            .constituency_votes
+                            .get(key)
+                            .unwrap_or_default()
+                            .saturating_add(weight.saturating_mul(u32::from(*share)));
+                        self.constituency_votes.insert(key, &votes);
+                    }
+                }
+            }
+            for (candidate, _) in &allocations {
+                if election.config.public_ballot {
+                    let position = self
+                        .ballots_for
+                        .get((election_id, *candidate))
+                        .unwrap_or_default();
+                    self.voters_for
+                        .insert((election_id, *candidate, position), &caller);
+                    self.ballots_for
+                        .insert((election_id, *candidate), &position.saturating_add(1));
+                }
+            }
+            self.voters.insert((election_id, election.turnout), &caller);
+            let leaf = if allotted || quadratic {
+                Self::compound_ballot_leaf(election_id, caller, &allocations)
+            } else if ranked {
+                self.rankings
+                    .insert((election_id, election.turnout), &candidates);
+                self.transfers.insert(
+                    (election_id, election.turnout),
+                    &(0, u128::from(weight) * STV_UNIT),
+                );
+                Self::compound_ballot_leaf(election_id, caller, &candidates)
+            } else {
+                Self::ballot_leaf(election_id, caller, candidates[0])
+            };
+            self.insert_ballot_leaf(election_id, election.turnout, leaf);
+            election.turnout = election.turnout.saturating_add(1);
+            election.storage_entries = election.storage_entries.saturating_add(1);
+            self.count_in_histogram(election_id);
+            election.fee_pot = election.fee_pot.saturating_add(vote_fee);
+            self.funds.fee_income = self.funds.fee_income.saturating_add(fee);
+            self.elections.insert(election_id, &election);
+            if reimbursed > 0 {
+                self.reimburse_storage(election_id, reimbursed);
+            }
+            let ballot_id = self.next_ballot_id;
+            self.next_ballot_id = ballot_id.saturating_add(1);
+            self.push_vote_record(
+                caller,
+                VoteRecord {
+                    election_id,
+                    ballot_id,
+                    candidate: (election.config.public_ballot && !allotted)
+                        .then_some(candidates[0]),
+                },
+            );
+            for (candidate, share) in &allocations {
+                let votes = weight.saturating_mul(u32::from(*share));
+                self.notify_vote(&election.config, election_id, caller, *candidate, votes);
+            }
+            if election.config.quiet_events {
+                self.env().emit_event(BallotCounted {
+                    election_id,
+                    turnout: election.turnout,
+                });
+            } else if allotted {
+                self.env().emit_event(SplitVoteCast {
+                    election_id,
+                    ballot_id,
+                    voter: caller,
+                    allocations,
+                    fee,
+                    memo,
+                });
+            } else if ranked {
+                self.env().emit_event(RankedVoteCast {
+                    election_id,
+                    ballot_id,
+                    voter: caller,
+                    ranking: candidates,
+                    fee,
+                    memo,
+                });
+            } else {
+                self.env().emit_event(VoteCast {
+                    election_id,
+                    ballot_id,
+                    voter: caller,
+                    candidate: candidates[0],
+                    fee,
+                    memo,
+                });
+            }
+            if !election.config.blind_results {
+                for (candidate, new_total) in new_totals {
+                    self.env().emit_event(TallyUpdated {
+                        election_id,
+                        candidate,
+                        new_total,
+                    });
+                }
+            }
+
+            Ok(ballot_id)
+        }
+
+        /// Whether the ballot events of an election leave out the voter.
+        fn quiet_events(&self, election_id: ElectionId) -> bool {
+            self.elections
+                .get(election_id)
+                .is_some_and(|election| election.config.quiet_events)
+        }
+
+        /// Tells the hook of an election, if any, that `voter` gave `votes` to
+        /// `candidate`. The callback runs under the `HOOK_REF_TIME` and
+        /// `HOOK_PROOF_SIZE` caps, and its failure is ignored.
+        fn notify_vote(
+            &self,
+            config: &ElectionConfig,
+            election_id: ElectionId,
+            voter: AccountId,
+            candidate: AccountId,
+            votes: u32,
+        ) {
+            let Some(hook) = config.hook else {
+                return;
+            };
+            let mut hook: ink::contract_ref!(ElectionHook) = hook.into();
+            let _ = hook
+                .call_mut()
+                .on_vote(election_id, voter, candidate, votes)
+                .ref_time_limit(HOOK_REF_TIME)
+                .proof_size_limit(HOOK_PROOF_SIZE)
+                .try_invoke();
+        }
+
+        /// Tells `hook` the outcome of a finalized election, under the same
+        /// caps as `notify_vote`.
+        fn notify_finalize(&self, hook: AccountId, election_id: ElectionId) {
+            let Some(result) = self.get_results(election_id) else {
+                return;
+            };
+            let mut hook: ink::contract_ref!(ElectionHook) = hook.into();
+            let _ = hook
+                .call_mut()
+                .on_finalize(election_id, result)
+                .ref_time_limit(HOOK_REF_TIME)
+                .proof_size_limit(HOOK_PROOF_SIZE)
+                .try_invoke();
+        }
+
+        /// Counts a race `candidate` entered into its participation score.
+        fn count_candidacy(&mut self, candidate: AccountId) {
+            let ran = self.candidacies.get(candidate).unwrap_or_default();
+            self.candidacies.insert(candidate, &ran.saturating_add(1));
+        }
+
+        /// Appends `record` to the vote history of `voter`.
+        fn push_vote_record(&mut self, voter: AccountId, record: VoteRecord) {
+            let history_len = self.vote_history_len.get(voter).unwrap_or_default();
+            self.vote_history.insert((voter, history_len), &record);
+            self.vote_history_len
+                .insert(voter, &history_len.saturating_add(1));
+        }
+
+        /// Checks that the shares of a split, score or cumulative ballot are
+        /// nonzero, at most `most` each and `budget` altogether, and go to
+        /// distinct candidates.
+        fn allotment_fits(allocations: &[(AccountId, u16)], most: u16, budget: u32) -> bool {
+            let spent = allocations.iter().fold(0u32, |spent, (_, share)| {
+                spent.saturating_add(u32::from(*share))
+            });
+            let mut candidates: Vec<AccountId> = allocations
+                .iter()
+                .map(|(candidate, _)| *candidate)
+                .collect();
+            candidates.sort_unstable();
+            candidates.dedup();
+            !allocations.is_empty()
+                && allocations
+                    .iter()
+                    .all(|(_, share)| (1..=most).contains(share))
+                && spent <= budget
+                && candidates.len() == allocations.len()
+        }
+
+        /// Checks that a ranking lists between one and `MAX_RANKING` distinct
+        /// candidates.
+        fn check_ranking(ranking: &[AccountId]) -> Result<(), VoteError> {
+            let mut candidates = ranking.to_vec();
+            candidates.sort_unstable();
+            candidates.dedup();
+            if ranking.is_empty()
+                || ranking.len() > MAX_RANKING
+                || candidates.len() != ranking.len()
+            {
+                return Err(VoteError::InvalidRanking);
+            }
+            Ok(())
+        }
+
+        /// Runs the checks a candidacy of `account` with `deposit` attached must
+        /// pass, returning the election and its runners.
+        fn check_registration(
+            &self,
+            account: AccountId,
+            election_id: ElectionId,
+            deposit: Balance,
+        ) -> Result<(Election, Vec<AccountId>), RegistrationError> {
+            if self.paying_out() {
+                return Err(RegistrationError::Reentrancy);
+            }
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(RegistrationError::ElectionNotFound)?;
+            if election.paused_at.is_some() {
+                return Err(RegistrationError::ElectionPaused);
+            }
+            if election.status != ElectionStatus::Registration
+                || election
+                    .config
+                    .campaign
+                    .is_some_and(|campaign| self.env().block_number() >= campaign.starts_at)
+            {
+                return Err(RegistrationError::RegistrationClosed);
+            }
+            if self.banned.contains((election_id, account)) {
+                return Err(RegistrationError::Banned);
+            }
+            if election.storage_exhausted() {
+                return Err(RegistrationError::StorageBudgetExceeded);
+            }
+
+            let runners = self.runners.get(election_id).unwrap_or_default();
+            if runners.contains(&account) {
+                return Err(RegistrationError::AlreadyRegistered);
+            }
+            if election.config.max_candidates > 0
+                && runners.len() >= election.config.max_candidates as usize
+            {
+                return Err(RegistrationError::CandidateCapReached);
+            }
+            if self.get_endorsements(election_id, account) < election.config.endorsements_required {
+                return Err(RegistrationError::NotEnoughEndorsements);
+            }
+            if election.config.referral_required && !self.referrals.contains((election_id, account))
+            {
+                return Err(RegistrationError::NotReferred);
+            }
+            if election.config.term_limit > 0
+                && self.get_consecutive_terms(account) >= election.config.term_limit
+            {
+                return Err(RegistrationError::TermLimitReached);
+            }
+            let now = self.env().block_number();
+            if election.config.registration_cooldown > 0
+                && self.last_registered.get(account).is_some_and(|block| {
+                    now < block.saturating_add(election.config.registration_cooldown)
+                })
+            {
+                return Err(RegistrationError::CooldownActive);
+            }
+            if election.config.registrations_per_block > 0
+                && self.registrations_this_block(election_id)
+                    >= election.config.registrations_per_block
+            {
+                return Err(RegistrationError::RateLimited);
+            }
+            let due = election.config.candidacy_deposit_for(runners.len());
+            if deposit != Self::native_due(&election.config, due) {
+                return Err(RegistrationError::IncorrectDeposit);
+            }
+            Ok((election, runners))
+        }
+
+        /// Candidates the election registered in the current block.
+        fn registrations_this_block(&self, election_id: ElectionId) -> u32 {
+            match self.block_registrations.get(election_id) {
+                Some((block, registered)) if block == self.env().block_number() => registered,
+                _ => 0,
+            }
+        }
+
+        /// Runs the checks a ballot from `voter` for `candidate` must pass,
+        /// returning the election and its runners.
+        fn check_ballot(
+            &self,
+            voter: AccountId,
+            election_id: ElectionId,
+            candidates: &[AccountId],
+        ) -> Result<(Election, Vec<AccountId>, u32), VoteError> {
+            if self.paying_out() {
+                return Err(VoteError::Reentrancy);
+            }
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            if election.paused_at.is_some() {
+                return Err(VoteError::ElectionPaused);
+            }
+            if election.status != ElectionStatus::Voting
+                || election
+                    .config
+                    .voting_closes_at
+                    .is_some_and(|closes_at| self.now_against(closes_at) == Ordering::Greater)
+            {
+                return Err(VoteError::VotingClosed);
+            }
+
+            if election.config.closed_electorate
+                && !self
+                    .electorate
+                    .contains((self.electorate_id(election_id), voter))
+            {
+                return Err(VoteError::NotInElectorate);
+            }
+            if election.config.forbid_self_votes && candidates.contains(&voter) {
+                return Err(VoteError::SelfVoteForbidden);
+            }
+
+            // a failed call to the eligibility source counts as a refusal
+            if let Some(source) = election.config.eligibility_source {
+                let source: ink::contract_ref!(EligibilitySource) = source.into();
+                let eligible = source.call().is_eligible(voter, election_id).try_invoke();
+                if !matches!(eligible, Ok(Ok(true))) {
+                    return Err(VoteError::NotEligible);
+                }
+            }
+
+            if election.config.reject_contracts
+                && self.env().is_contract(&voter)
+                && !self.aggregators.contains(voter)
+            {
+                return Err(VoteError::ContractVoter);
+            }
+
+            if self.identity_mode {
+                let identity = self.identities.get(voter).ok_or(VoteError::NoIdentity)?;
+                if self
+                    .identity_voted
+                    .get((election_id, identity))
+                    .is_some_and(|account| account != voter)
+                {
+                    return Err(VoteError::AlreadyVoted);
+                }
+            }
+
+            // check if voter already voted
+            if self
+                .already_voted
+                .get((election_id, voter))
+                .unwrap_or(false)
+                || (election.config.mode.ballots_per_voter() > 1
+                    && candidates
+                        .iter()
+                        .any(|candidate| self.approved.contains((election_id, voter, *candidate))))
+            {
+                return Err(VoteError::AlreadyVoted);
+            }
+
+            // write-ins are only accepted when candidacy is unrestricted
+            let runners = self.runners.get(election_id).unwrap_or_default();
+            if election.config.candidates_barred && runners.contains(&voter) {
+                return Err(VoteError::CandidateBarred);
+            }
+            if election.config.candidacy_gated()
+                && candidates
+                    .iter()
+                    .any(|candidate| !runners.contains(candidate))
+            {
+                return Err(VoteError::NotACandidate);
+            }
+            if election.storage_exhausted()
+                && candidates
+                    .iter()
+                    .any(|candidate| !runners.contains(candidate))
+            {
+                return Err(VoteError::StorageBudgetExceeded);
+            }
+            let weight = self.ballot_weight(&election.config, voter)?;
+            Ok((election, runners, weight))
+        }
+
+        /// Books one of the ballots the election accepts in the current
+        /// block, failing with `RateLimited` once they are all taken.
+        fn take_block_slot(
+            &mut self,
+            election_id: ElectionId,
+            config: &ElectionConfig,
+        ) -> Result<(), VoteError> {
+            if config.ballots_per_block == 0 {
+                return Ok(());
+            }
+            let now = self.env().block_number();
+            let accepted = match self.block_ballots.get(election_id) {
+                Some((block, accepted)) if block == now => accepted,
+                _ => 0,
+            };
+            if accepted >= config.ballots_per_block {
+                return Err(VoteError::RateLimited);
+            }
+            self.block_ballots
+                .insert(election_id, &(now, accepted.saturating_add(1)));
+            Ok(())
+        }
+
+        /// Removes the delegation of `delegator`, if any. Returns whether
+        /// there was one.
+        fn withdraw_delegation(&mut self, delegator: AccountId) -> bool {
+            let Some(delegate) = self.delegates.take(delegator) else {
+                return false;
+            };
+            let mut delegators = self.delegators.get(delegate).unwrap_or_default();
+            delegators.retain(|account| *account != delegator);
+            self.delegators.insert(delegate, &delegators);
+            true
+        }
+
+        /// Removes the split delegation of `delegator`, if any. Returns
+        /// whether there was one.
+        fn withdraw_split_delegation(&mut self, delegator: AccountId) -> bool {
+            let Some(shares) = self.split_delegates.take(delegator) else {
+                return false;
+            };
+            for (delegate, _) in shares {
+                let mut delegators = self.split_delegators.get(delegate).unwrap_or_default();
+                delegators.retain(|account| *account != delegator);
+                self.split_delegators.insert(delegate, &delegators);
+            }
+            true
+        }
+
+        /// Removes the delegation of `delegator` on `topic`, if any. Returns
+        /// whether there was one.
+        fn withdraw_topic_delegation(&mut self, topic: TopicId, delegator: AccountId) -> bool {
+            let Some(delegate) = self.topic_delegates.take((topic, delegator)) else {
+                return false;
+            };
+            let mut delegators = self
+                .topic_delegators
+                .get((topic, delegate))
+                .unwrap_or_default();
+            delegators.retain(|account| *account != delegator);
+            self.topic_delegators.insert((topic, delegate), &delegators);
+            true
+        }
+
+        /// Returns the accounts delegating directly to `delegate` in an
+        /// election about `topic`: those delegating on the topic, and those
+        /// delegating generally without a delegate on the topic.
+        fn direct_delegators(&self, topic: Option<TopicId>, delegate: AccountId) -> Vec<AccountId> {
+            let general = self.delegators.get(delegate).unwrap_or_default();
+            let Some(topic) = topic else {
+                return general;
+            };
+            let mut delegators = self
+                .topic_delegators
+                .get((topic, delegate))
+                .unwrap_or_default();
+            delegators.extend(
+                general
+                    .into_iter()
+                    .filter(|delegator| !self.topic_delegates.contains((topic, *delegator))),
+            );
+            delegators
+        }
+
+        /// Returns the accounts whose weight a ballot of `voter` carries:
+        /// those delegating to it, directly or through up to
+        /// `MAX_DELEGATION_DEPTH` links, that may vote and have not. The
+        /// search stops at accounts that voted, whose ballot already spent
+        /// their delegators' weight.
+        fn delegated_voters(
+            &self,
+            election: &Election,
+            election_id: ElectionId,
+            voter: AccountId,
+        ) -> Vec<AccountId> {
+            let mut found = Vec::new();
+            if self.delegations == 0 {
+                return found;
+            }
+            let electorate_id = if election.config.closed_electorate {
+                self.electorate_id(election_id)
+            } else {
+                election_id
+            };
+            let mut frontier = ink::prelude::vec![voter];
+            for _ in 0..MAX_DELEGATION_DEPTH {
+                let mut next = Vec::new();
+                for delegate in frontier {
+                    for delegator in self.direct_delegators(election.config.topic, delegate) {
+                        if delegator == voter
+                            || found.contains(&delegator)
+                            || self.already_voted.contains((election_id, delegator))
+                            || (election.config.closed_electorate
+                                && !self.electorate.contains((electorate_id, delegator)))
+                        {
+                            continue;
+                        }
+                        found.push(delegator);
+                        next.push(delegator);
+                    }
+                }
+                if next.is_empty() {
+                    break;
+                }
+                frontier = next;
+            }
+            found
+        }
+
+        /// Returns the accounts splitting a share of their vote to `voter`
+        /// that may vote, with that share: those that have not voted, or
+        /// whose vote only other delegates have spent shares of. Accounts
+        /// with a delegate on the election's topic follow that one instead.
+        fn split_voters(
+            &self,
+            election: &Election,
+            election_id: ElectionId,
+            voter: AccountId,
+        ) -> Vec<(AccountId, u16)> {
+            if self.delegations == 0 {
+                return Vec::new();
+            }
+            let electorate_id = if election.config.closed_electorate {
+                self.electorate_id(election_id)
+            } else {
+                election_id
+            };
+            self.split_delegators
+                .get(voter)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|delegator| {
+                    (!self.already_voted.contains((election_id, *delegator))
+                        || self.split_spent.contains((election_id, *delegator)))
+                        && election
+                            .config
+                            .topic
+                            .is_none_or(|topic| !self.topic_delegates.contains((topic, *delegator)))
+                        && (!election.config.closed_electorate
+                            || self.electorate.contains((electorate_id, *delegator)))
+                })
+                .filter_map(|delegator| {
+                    let shares = self.split_delegates.get(delegator)?;
+                    let (_, share) = shares.iter().find(|(delegate, _)| *delegate == voter)?;
+                    Some((delegator, *share))
+                })
+                .collect()
+        }
+
+        /// Returns the votes a per-mille `share` of `votes` is worth,
+        /// rounded down.
+        fn split_share(votes: u32, share: u16) -> u32 {
+            let votes = u64::from(votes) * u64::from(share) / u64::from(SPLIT_BUDGET);
+            u32::try_from(votes).unwrap_or(u32::MAX)
+        }
+
+        /// Returns how many votes a ballot from `voter` is worth.
+        fn ballot_weight(
+            &self,
+            config: &ElectionConfig,
+            voter: AccountId,
+        ) -> Result<u32, VoteError> {
+            let mut votes = self.holding_weight(config, voter)?;
+            if config.reputation_weighted {
+                votes = votes.saturating_mul(self.get_reputation(voter).saturating_add(1));
+            }
+            if let Some(boost) = config.tenure_boost {
+                votes = votes.saturating_mul(self.tenure_multiplier(boost, voter));
+            }
+            if !config.attendance_boost.is_empty() {
+                votes = self.attendance_weight(&config.attendance_boost, voter, votes);
+            }
+            Ok(votes)
+        }
+
+        /// Returns `votes` raised by the highest attendance tier the past
+        /// ballots of `voter` reach.
+        fn attendance_weight(&self, tiers: &[AttendanceTier], voter: AccountId, votes: u32) -> u32 {
+            let ballots = self.vote_history_len.get(voter).unwrap_or_default();
+            let Some(tier) = tiers.iter().rev().find(|tier| ballots >= tier.min_ballots) else {
+                return votes;
+            };
+            let boosted = u64::from(votes) * u64::from(tier.percent) / 100;
+            u32::try_from(boosted).unwrap_or(u32::MAX)
+        }
+
+        /// Returns the multiplier `boost` grants `voter`, one for accounts
+        /// not registered under an identity.
+        fn tenure_multiplier(&self, boost: TenureBoost, voter: AccountId) -> u32 {
+            let Some(since) = self.registered_since.get(voter) else {
+                return 1;
+            };
+            let steps = self.env().block_number().saturating_sub(since) / boost.blocks_per_step;
+            steps.saturating_add(1).min(boost.max_multiplier)
+        }
+
+        /// Returns how many votes the asset holding of `voter` is worth, or
+        /// what the weight source says it is worth, or what its token balance
+        /// at the snapshot is worth. One when ballots are not weighted any of
+        /// these ways.
+        fn holding_weight(
+            &self,
+            config: &ElectionConfig,
+            voter: AccountId,
+        ) -> Result<u32, VoteError> {
+            if let Some(source) = config.weight_source {
+                let snapshot_block = source
+                    .snapshot_block
+                    .unwrap_or_else(|| self.env().block_number());
+                let calculator: ink::contract_ref!(WeightCalculator) = source.calculator.into();
+                let weight = calculator
+                    .call()
+                    .weight_of(voter, snapshot_block)
+                    .try_invoke();
+                let Ok(Ok(weight)) = weight else {
+                    return Err(VoteError::WeightSourceFailed);
+                };
+                return Ok(u32::try_from(weight).unwrap_or(u32::MAX));
+            }
+            if let Some(token_weight) = config.token_weight {
+                let token: ink::contract_ref!(Psp22Checkpoints) = token_weight.token.into();
+                let holding = token
+                    .call()
+                    .balance_of_at(voter, token_weight.snapshot_block)
+                    .try_invoke();
+                let Ok(Ok(holding)) = holding else {
+                    return Err(VoteError::WeightSourceFailed);
+                };
+                if holding < token_weight.min_holding {
+                    return Err(VoteError::InsufficientHolding);
+                }
+                let votes = holding / token_weight.unit.max(1);
+                return Ok(u32::try_from(votes).unwrap_or(u32::MAX));
+            }
+            let Some(asset_weight) = config.asset_weight else {
+                return Ok(1);
+            };
+            let holding = runtime::active().balance_of(asset_weight.asset_id, voter);
+            if holding < asset_weight.min_holding {
+                return Err(VoteError::InsufficientHolding);
+            }
+            let unit = asset_weight.unit.max(1);
+            let mut votes = holding / unit;
+            match asset_weight.max_weight {
+                Some(WeightCap::Votes(cap)) => votes = votes.min(cap.into()),
+                Some(WeightCap::SupplyPercent(percent)) => {
+                    let supply = runtime::active().total_supply(asset_weight.asset_id);
+                    // split so that a huge supply cannot overflow
+                    let cap = supply / 100 * Balance::from(percent)
+                        + supply % 100 * Balance::from(percent) / 100;
+                    votes = votes.min(cap / unit);
+                }
+                None => {}
+            }
+            Ok(u32::try_from(votes).unwrap_or(u32::MAX))
+        }
+
+        /// Validates `config` against the current block and the fee limits.
+        fn validate_config(&self, config: &ElectionConfig) -> Result<(), ConfigError> {
+            config.validate(
+                &self.fee_limits,
+                self.env().block_number(),
+                self.env().block_timestamp(),
+            )
+        }
+
+        /// Moves the transferred value into the treasury, but for the admin
+        /// bond, if it matches the creation fee plus the admin bond.
+        fn collect_creation_fee(&mut self) -> bool {
+            let paid = self.env().transferred_value();
+            if paid != self.creation_fee.saturating_add(self.admin_bond) {
+                return false;
+            }
+            self.treasury = self.treasury.saturating_add(self.creation_fee);
+            self.funds.fee_income = self.funds.fee_income.saturating_add(self.creation_fee);
+            self.funds.deposits = self.funds.deposits.saturating_add(self.admin_bond);
+            true
+        }
+
+        /// Makes sure the caller may perform `action` directly.
+        fn authorize_action(&self, action: &AdminAction) -> Result<(), VoteError> {
+            match action {
+                AdminAction::BanCandidate(election_id, _) => self
+                    .election_as_admin(*election_id, ink::selector_bytes!("ban_candidate"))
+                    .map(|_| ()),
+                _ if self.env().caller() != self.owner => Err(VoteError::Unauthorized),
+                _ => Ok(()),
+            }
+        }
+
+        /// Makes sure sensitive actions are not delayed by a timelock.
+        fn ensure_no_timelock(&self) -> Result<(), VoteError> {
+            if self.timelock_delay > 0 {
+                return Err(VoteError::Timelocked);
+            }
+            Ok(())
+        }
+
+        /// Makes sure the caller is the owner and no election is voting.
+        fn ensure_fees_unlocked(&self) -> Result<(), VoteError> {
+            if self.env().caller() != self.owner {
+                return Err(VoteError::Unauthorized);
+            }
+            if self.elections_voting > 0 {
+                return Err(VoteError::FeesLocked);
+            }
+            Ok(())
+        }
+
+        /// Fails with `ConfigFrozen` once voting has opened in `election`,
+        /// so that its rules stay those its ballots are cast under.
+        fn ensure_config_open(election: &Election) -> Result<(), VoteError> {
+            if matches!(
+                election.status,
+                ElectionStatus::Pending
+                    | ElectionStatus::Registration
+                    | ElectionStatus::SlateFrozen
+                    | ElectionStatus::Campaign
+            ) {
+                Ok(())
+            } else {
+                Err(VoteError::ConfigFrozen)
+            }
+        }
+
+        fn emit_config_changed(&self, election_id: Option<ElectionId>, setting: [u8; 4]) {
+            self.env().emit_event(ConfigChanged {
+                election_id,
+                setting,
+            });
+        }
+
+        fn emit_fee_updated(
+            &self,
+            election_id: Option<ElectionId>,
+            kind: FeeKind,
+            amount: Balance,
+        ) {
+            self.env().emit_event(FeeUpdated {
+                election_id,
+                kind,
+                amount,
+            });
+        }
+
+        fn emit_recall_updated(
+            &self,
+            election_id: ElectionId,
+            account: Option<AccountId>,
+            recall: Recall,
+        ) {
+            self.env().emit_event(RecallUpdated {
+                election_id,
+                account,
+                recall,
+            });
+        }
+
+        fn emit_referendum_updated(
+            &self,
+            referendum_id: u32,
+            account: Option<AccountId>,
+            referendum: Referendum,
+        ) {
+            self.env().emit_event(ReferendumUpdated {
+                referendum_id,
+                account,
+                referendum,
+            });
+        }
+
+        /// Loads an election, making sure the caller administers it.
+        fn election_as_admin(
+            &self,
+            election_id: ElectionId,
+            selector: Selector,
+        ) -> Result<Election, VoteError> {
+            let election = self
+                .elections
+                .get(election_id)
+                .ok_or(VoteError::ElectionNotFound)?;
+            let caller = self.env().caller();
+            if caller != election.admin && !self.permitted(caller, selector) {
+                return Err(VoteError::Unauthorized);
+            }
+            Ok(election)
+        }
+
+        /// Whether `account` holds a role granted the message with `selector`.
+        fn permitted(&self, account: AccountId, selector: Selector) -> bool {
+            self.roles
+                .get(account)
+                .is_some_and(|role| self.role_permissions.contains((role, selector)))
+        }
+
+        /// Moves an election from one of the `from` phases into `to`,
+        /// on behalf of its admin.
+        fn transition(
+            &mut self,
+            election_id: ElectionId,
+            selector: Selector,
+            from: &[ElectionStatus],
+            to: ElectionStatus,
+        ) -> Result<(), VoteError> {
+            let election = self.election_as_admin(election_id, selector)?;
+            self.enter_phase(election_id, election, from, to)
+        }
+
+        /// Moves an election into the phase its thresholds call for, so that
+        /// no dedicated transaction is ever needed: one with a campaign
+        /// through it, and one with a `voting_opens_at` into voting once
+        /// that has passed and it may open. One whose `voting_closes_at`
+        /// passes before voting opened expires, and is cancelled; one left
+        /// unfinalized too long after it goes to `Expired`, paying the caller
+        /// as `expire` does. A paused election stays put.
+        ///
+        /// Every message taking an election runs it first, with two
+        /// exceptions: `expire`, which expires a stale election itself and
+        /// would otherwise find it already expired, and read-only messages,
+        /// which report the phase last stored.
+        ///
+        /// Returns whether the election changed phase.
+        fn ensure_phase(&mut self, election_id: ElectionId) -> bool {
+            let Some(election) = self.elections.get(election_id) else {
+                return false;
+            };
+            if election.paused_at.is_some() {
+                return false;
+            }
+            if self.is_stale(&election) {
+                self.expire_election(election_id, election);
+                return true;
+            }
+            if election.config.campaign.is_some() {
+                return self.advance_campaign(election_id);
+            }
+            let waiting = [
+                ElectionStatus::Pending,
+                ElectionStatus::Registration,
+                ElectionStatus::SlateFrozen,
+            ];
+            if !waiting.contains(&election.status) {
+                return false;
+            }
+            if election
+                .config
+                .voting_closes_at
+                .is_some_and(|closes_at| self.now_against(closes_at) == Ordering::Greater)
+            {
+                let expired = self
+                    .enter_phase(election_id, election, &waiting, ElectionStatus::Cancelled)
+                    .is_ok();
+                if expired {
+                    self.settle_cancellation(election_id);
+                }
+                return expired;
+            }
+            if election.config.voting_opens_at.is_none() {
+                return false;
+            }
+            match self.opening_from(election_id, &election) {
+                Ok(from) => self
+                    .enter_phase(election_id, election, from, ElectionStatus::Voting)
+                    .is_ok(),
+                Err(_) => false,
+            }
+        }
+
+        /// Moves an election with a campaign into the campaign once it has
+        /// started, and on to voting once it has ended with enough
+        /// candidates running.
+        ///
+        /// Returns whether the election changed phase.
+        fn advance_campaign(&mut self, election_id: ElectionId) -> bool {
+            let Some(election) = self.elections.get(election_id) else {
+                return false;
+            };
+            let Some(campaign) = election.config.campaign else {
+                return false;
+            };
+            let now = self.env().block_number();
+            let min_candidates = election.config.min_candidates as usize;
+            let mut advanced = now >= campaign.starts_at
+                && self
+                    .enter_phase(
+                        election_id,
+                        election,
+                        &[ElectionStatus::Pending, ElectionStatus::Registration],
+                        ElectionStatus::Campaign,
+                    )
+                    .is_ok();
+            let candidates = self.runners.get(election_id).unwrap_or_default().len();
+            if now >= campaign.ends_at && candidates >= min_candidates {
+                if let Some(election) = self.elections.get(election_id) {
+                    advanced |= self
+                        .enter_phase(
+                            election_id,
+                            election,
+                            &[ElectionStatus::Campaign],
+                            ElectionStatus::Voting,
+                        )
+                        .is_ok();
+                }
+            }
+            advanced
+        }
+
+        /// Moves `election` from one of the `from` phases into `to`.
+        fn enter_phase(
+            &mut self,
+            election_id: ElectionId,
+            mut election: Election,
+            from: &[ElectionStatus],
+            to: ElectionStatus,
+        ) -> Result<(), VoteError> {
+            if !from.contains(&election.status) {
+                return Err(VoteError::InvalidTransition);
+            }
+            if election.status == ElectionStatus::Voting {
+                self.elections_voting = self.elections_voting.saturating_sub(1);
+            }
+            if to == ElectionStatus::Voting {
+                self.elections_voting = self.elections_voting.saturating_add(1);
+            }
+            self.env().emit_event(PhaseChanged {
+                election_id,
+                from: election.status,
+                to,
+            });
+            election.status = to;
+            if to == ElectionStatus::Finalized {
+                election.finalized_at = Some(self.env().block_number());
+            }
+            if to == ElectionStatus::SlateFrozen {
+                election.slate_frozen_at = Some(self.env().block_number());
+            }
+            self.elections.insert(election_id, &election);
+            Ok(())
+        }
+
+        /// Returns the votes of every runner, in the order they entered the race.
+        ///
+        /// Finalized elections are read from the archive, which survives
+        /// pruning; blind ones report no votes while their counts are hidden.
+        fn totals(&self, election_id: ElectionId) -> Vec<(AccountId, u32)> {
+            if let Some(archived) = self.archive.get(election_id) {
+                return archived.totals;
+            }
+            match self.elections.get(election_id) {
+                Some(election) if self.results_hidden(&election) => self.hidden_totals(election_id),
+                Some(election) => self.live_totals(election_id, &election.config),
+                None => Vec::new(),
+            }
+        }
+
+        /// Asks the tally module of an election for the votes of every runner.
+        fn module_totals(
+            &self,
+            module: AccountId,
+            election_id: ElectionId,
+        ) -> Result<RunnerTotals, VoteError> {
+            let runners = self.runners.get(election_id).unwrap_or_default();
+            let tally: ink::contract_ref!(TallyModule) = module.into();
+            let result = tally
+                .call()
+                .compute_result(election_id, runners.clone())
+                .try_invoke();
+            match result {
+                Ok(Ok(votes)) if votes.len() == runners.len() => {
+                    Ok(runners.into_iter().zip(votes).collect())
+                }
+                _ => Err(VoteError::TallyModuleFailed),
+            }
+        }
+
+        /// Returns the current votes of every runner, ignoring the archive,
+        /// or their electors in an electoral college.
+        fn live_totals(
+            &self,
+            election_id: ElectionId,
+            config: &ElectionConfig,
+        ) -> Vec<(AccountId, u32)> {
+            let totals: RunnerTotals = self
+                .runners
+                .get(election_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|runner| {
+                    (
+                        runner,
+                        self.votes.get((election_id, runner)).unwrap_or_default(),
+                    )
+                })
+                .collect();
+            if config.electors.is_empty() {
+                totals
+            } else {
+                self.elector_totals(election_id, config, &totals)
+            }
+        }
+
+        /// Moves `candidate` to its place in the standings of an election
+        /// tracking them, now that it holds `votes`.
+        fn update_standings(
+            &mut self,
+            election_id: ElectionId,
+            config: &ElectionConfig,
+            candidate: AccountId,
+            votes: u32,
+        ) {
+            if !config.track_standings || !config.electors.is_empty() {
+                return;
+            }
+            let mut standings = self.standings.get(election_id).unwrap_or_default();
+            standings.retain(|(runner, _)| *runner != candidate);
+            let place = standings.partition_point(|(_, total)| *total >= votes);
+            standings.insert(place, (candidate, votes));
+            self.standings.insert(election_id, &standings);
+        }
+
+        /// Hands the electors of each constituency to its sole winner, for
+        /// the runners of `totals`.
+        fn elector_totals(
+            &self,
+            election_id: ElectionId,
+            config: &ElectionConfig,
+            totals: &[(AccountId, u32)],
+        ) -> RunnerTotals {
+            let mut electors: RunnerTotals =
+                totals.iter().map(|(runner, _)| (*runner, 0)).collect();
+            for (constituency, weight) in (0..).zip(&config.electors) {
+                let votes: RunnerTotals = totals
+                    .iter()
+                    .map(|(runner, _)| {
+                        let votes = self.get_constituency_votes(election_id, constituency, *runner);
+                        (*runner, votes)
+                    })
+                    .collect();
+                let (winners, most) = Self::winners(&votes, config.tie_break);
+                if let ([winner], true) = (&winners[..], most > 0) {
+                    if let Some((_, won)) = electors.iter_mut().find(|(runner, _)| runner == winner)
+                    {
+                        *won = won.saturating_add(*weight);
+                    }
+                }
+            }
+            electors
+        }
+
+        /// Returns the leaf committing to a ballot in the ballot Merkle tree.
+        fn ballot_leaf(election_id: ElectionId, voter: AccountId, candidate: AccountId) -> Hash {
+            let mut leaf = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &(election_id, voter, candidate),
+                &mut leaf,
+            );
+            Hash::from(leaf)
+        }
+
+        /// Returns the leaf committing to a split, ranked, quadratic,
+        /// encrypted or anonymous ballot in the ballot Merkle tree: the hash
+        /// of its `(election_id, voter, choice)`, `choice` being the
+        /// allocations, the ranking or the ciphertext. Anonymous ballots stand
+        /// the candidate in for the voter and the nullifier for the choice;
+        /// imported ballots commit to no choice.
+        fn compound_ballot_leaf<T: ink::scale::Encode>(
+            election_id: ElectionId,
+            voter: AccountId,
+            choice: &[T],
+        ) -> Hash {
+            let mut leaf = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &(election_id, voter, choice),
+                &mut leaf,
+            );
+            Hash::from(leaf)
+        }
+
+        /// Returns the parent of two nodes of the ballot Merkle tree.
+        fn hash_pair(left: &Hash, right: &Hash) -> Hash {
+            let mut parent = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&(left, right), &mut parent);
+            Hash::from(parent)
+        }
+
+        /// Appends the `index`-th leaf to the ballot Merkle tree of an election,
+        /// folding it into the pending left-hand nodes.
+        fn insert_ballot_leaf(&mut self, election_id: ElectionId, index: u32, leaf: Hash) {
+            let mut branch = self.ballot_branches.get(election_id).unwrap_or_default();
+            let mut node = leaf;
+            let mut index = index;
+            for level in 0..BALLOT_TREE_DEPTH {
+                if index & 1 == 0 {
+                    if branch.len() <= level {
+                        branch.resize(level + 1, Hash::from([0; 32]));
+                    }
+                    branch[level] = node;
+                    break;
+                }
+                let left = branch.get(level).copied().unwrap_or(Hash::from([0; 32]));
+                node = Self::hash_pair(&left, &node);
+                index >>= 1;
+            }
+            self.ballot_branches.insert(election_id, &branch);
+        }
+
+        /// Returns the root of the ballot Merkle tree of an election holding
+        /// `leaves` ballots.
+        fn ballot_root(&self, election_id: ElectionId, leaves: u32) -> Hash {
+            let branch = self.ballot_branches.get(election_id).unwrap_or_default();
+            let mut node = Hash::from([0; 32]);
+            let mut zero = Hash::from([0; 32]);
+            let mut size = leaves;
+            for level in 0..BALLOT_TREE_DEPTH {
+                node = if size & 1 == 1 {
+                    let left = branch.get(level).copied().unwrap_or(zero);
+                    Self::hash_pair(&left, &node)
+                } else {
+                    Self::hash_pair(&node, &zero)
+                };
+                zero = Self::hash_pair(&zero, &zero);
+                size >>= 1;
+            }
+            node
+        }
+
+        /// Orders the current block against `moment`.
+        fn now_against(&self, moment: Moment) -> Ordering {
+            moment.compare_now(self.env().block_number(), self.env().block_timestamp())
+        }
+
+        /// Returns whether the counts of `election` are hidden, as blind
+        /// elections keep them until voting closes.
+        fn results_hidden(&self, election: &Election) -> bool {
+            election.config.blind_results
+                && matches!(
+                    election.status,
+                    ElectionStatus::Pending
+                        | ElectionStatus::Registration
+                        | ElectionStatus::SlateFrozen
+                        | ElectionStatus::Campaign
+                        | ElectionStatus::Voting
+                )
+                && election
+                    .config
+                    .voting_closes_at
+                    .is_none_or(|closes_at| self.now_against(closes_at) != Ordering::Greater)
+        }
+
+        /// Returns whether the counts of an election are hidden, see
+        /// `results_hidden`.
+        fn hides_results(&self, election_id: ElectionId) -> bool {
+            self.elections
+                .get(election_id)
+                .is_some_and(|election| self.results_hidden(&election))
+        }
+
+        /// Returns the runners of an election, each with no votes, as
+        /// reported while its counts are hidden.
+        fn hidden_totals(&self, election_id: ElectionId) -> RunnerTotals {
+            self.runners
+                .get(election_id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|runner| (runner, 0))
+                .collect()
+        }
+
+        /// Returns the tie-break policy of an election.
+        fn tie_break(&self, election_id: ElectionId) -> TieBreakPolicy {
+            self.elections
+                .get(election_id)
+                .map(|election| election.config.tie_break)
+                .unwrap_or_default()
+        }
+
+        /// Returns the winners, in canonical order, and their vote count,
+        /// settling a tie for first place according to `tie_break`.
+        fn winners(
+            totals: &[(AccountId, u32)],
+            tie_break: TieBreakPolicy,
+        ) -> (Vec<AccountId>, u32) {
+            let (mut winners, votes) =
+                counting::winners(totals, tie_break == TieBreakPolicy::EarliestRunner);
+            counting::canonical_order(&mut winners, totals);
+            (winners, votes)
+        }
+    }
+
+    impl VotingQueries for Voting {
+        #[ink(message)]
+        fn winner(&self, election_id: ElectionId) -> Vec<AccountId> {
+            self.get_current_winner(election_id)
+        }
+
+        #[ink(message)]
+        fn status(&self, election_id: ElectionId) -> Option<ElectionStatus> {
+            self.get_status(election_id)
+        }
+
+        #[ink(message)]
+        fn votes_of(&self, election_id: ElectionId, candidate: AccountId) -> u32 {
+            self.get_votes(election_id, candidate)
+        }
+    }
+
+    impl VotingStandard for Voting {
+        #[ink(message)]
+        fn standard_version(&self) -> u16 {
+            STANDARD_VERSION
+        }
+
+        #[ink(message, payable)]
+        fn cast_vote(&mut self, election_id: ElectionId, candidate: AccountId) -> Result<u64, u32> {
+            self.vote(election_id, candidate)
+                .map_err(|error| error.code())
+        }
+
+        #[ink(message)]
+        fn results(&self, election_id: ElectionId) -> Option<(RunnerTotals, Vec<AccountId>)> {
+            self.get_results(election_id)
+                .map(|result| (result.totals, result.winners))
+        }
+
+        #[ink(message)]
+        fn election_status(&self, election_id: ElectionId) -> Option<ElectionStatus> {
+            self.get_status(election_id)
+        }
+
+        #[ink(message)]
+        fn delegate_vote(&mut self, delegate: AccountId) -> Result<(), u32> {
+            self.delegate(delegate).map_err(|error| error.code())
+        }
+    }
+
+    // TODO:
+    // Write unitary tests
+    // Write integration tests
+    // e2e tests?
+    // README file
+    // upload github
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use crate::runtime::TestRuntime;
+        use fixtures::*;
+
+        /// Environment helpers and ready-made contracts for the tests.
+        mod fixtures {
+            use super::*;
+
+            /// Returns the recorded events of type `E`, oldest first.
+            pub fn events_of<E: ink::env::Event>() -> Vec<ink::env::test::EmittedEvent> {
+                ink::env::test::recorded_events()
+                    .filter(|event| {
+                        event.topics.first().map(|t| t.as_slice())
+                            == E::SIGNATURE_TOPIC.as_ref().map(|t| t.as_slice())
+                    })
+                    .collect()
+            }
+
+            /// Counts the recorded events of type `E`.
+            pub fn emitted<E: ink::env::Event>() -> usize {
+                events_of::<E>().len()
+            }
+
+            /// Fee limits high enough for any fee used in the tests.
+            pub fn generous_limits() -> FeeLimits {
+                FeeLimits {
+                    max_vote_fee: 1_000_000,
+                    max_candidacy_deposit: 1_000_000,
+                    max_creation_fee: 1_000_000,
+                }
+            }
+
+            /// Makes `account` the caller of the next messages.
+            pub fn set_caller(account: AccountId) {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
+            }
+
+            /// Makes the next messages carry `value`.
+            pub fn set_value(value: Balance) {
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(value);
+            }
+
+            /// Moves the chain `blocks` blocks forward.
+            pub fn advance_blocks(blocks: u32) {
+                for _ in 0..blocks {
+                    ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+                }
+            }
+
+            /// Sets the timestamp of the current block.
+            pub fn set_timestamp(time: Timestamp) {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(time);
+            }
+
+            /// The `n`th voter of `populated`, distinct from the default
+            /// accounts.
+            pub fn voter(n: u32) -> AccountId {
+                let mut account = [0x10; 32];
+                account[..4].copy_from_slice(&n.to_le_bytes());
+                AccountId::from(account)
+            }
+
+            /// The `n`th candidate of `populated`, distinct from the default
+            /// accounts and every voter.
+            pub fn candidate(n: u32) -> AccountId {
+                let mut account = [0xc0; 32];
+                account[..4].copy_from_slice(&n.to_le_bytes());
+                AccountId::from(account)
+            }
+
+            /// A contract created by the default account with election `0`
+            /// open for voting, `candidates` candidates on its ballot and
+            /// `ballots` ballots cast, the `n`th by `voter(n)` for candidate
+            /// `n` modulo `candidates`. The default account calls next.
+            pub fn populated(candidates: u32, ballots: u32) -> Voting {
+                let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+                set_caller(accounts.alice);
+                let mut voting =
+                    Voting::new_with_candidates((0..candidates).map(candidate).collect());
+                for n in 0..ballots {
+                    set_caller(voter(n));
+                    voting.vote(0, candidate(n % candidates)).unwrap();
+                }
+                set_caller(accounts.alice);
+                voting
+            }
+        }
+
+        /// We test if the default constructor does its job.
+        #[ink::test]
+        fn default_works() {
+            let voting = Voting::default();
+
+            assert_eq!(voting.get_current_winner(0), Vec::new());
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Voting));
+            assert_eq!(voting.runners.get(0), None);
+        }
+
+        /// We test a simple use case of our contract.
+        #[ink::test]
+        fn accept_new_vote() {
+            let mut voting = populated(3, 4);
+            assert_eq!(voting.get_votes(0, candidate(0)), 2);
+
+            set_caller(voter(4));
+            assert_eq!(voting.vote(0, candidate(2)), Ok(4));
+            assert_eq!(voting.get_votes(0, candidate(2)), 2);
+            assert_eq!(voting.get_results(0).unwrap().turnout, 5);
+            assert_eq!(emitted::<VoteCast>(), 5);
+        }
+
+        /// We test that the runner-up and the margin follow the standings.
+        #[ink::test]
+        fn runner_up_and_margin_work() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+
+            for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(0, accounts.django).unwrap();
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting.vote(0, accounts.frank).unwrap();
+
+            assert_eq!(voting.get_current_winner(0), vec![accounts.django]);
+            assert_eq!(voting.get_runner_up(0), vec![accounts.frank]);
+            assert_eq!(voting.get_margin(0), 2);
+        }
+
+        /// We test that a tie at the top leaves no margin.
+        #[ink::test]
+        fn tied_winners_have_no_margin() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.frank).unwrap();
+
+            assert_eq!(voting.get_current_winner(0).len(), 2);
+            assert_eq!(voting.get_runner_up(0), Vec::new());
+            assert_eq!(voting.get_margin(0), 0);
+        }
+
+        /// We test that the result reports the whole outcome and freezes on finalize.
+        #[ink::test]
+        fn results_are_complete_and_final() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(voting.finalize(0), Err(VoteError::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::VotingClosed)
+            );
+
+            let result = voting.get_results(0).unwrap();
+            assert_eq!(result.status, ElectionStatus::Finalized);
+            assert_eq!(result.winners, vec![accounts.django]);
+            assert_eq!(result.margin, 1);
+            assert_eq!(result.totals, vec![(accounts.django, 1)]);
+            assert_eq!(result.turnout, 1);
+            assert_eq!(result.finalized_at, Some(0));
+        }
+
+        /// We test that elections move through their phases in order.
+        #[ink::test]
+        fn status_follows_the_lifecycle() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting.create_election(ElectionConfig::default()).unwrap();
+            assert_eq!(election_id, 1);
+            assert_eq!(
+                voting.get_status(election_id),
+                Some(ElectionStatus::Pending)
+            );
+            assert_eq!(voting.get_status(2), None);
+
+            assert_eq!(
+                voting.register_candidate(election_id),
+                Err(RegistrationError::RegistrationClosed)
+            );
+            voting.open_registration(election_id).unwrap();
+            assert_eq!(
+                voting.get_status(election_id),
+                Some(ElectionStatus::Registration)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.register_candidate(election_id).unwrap();
+            assert_eq!(
+                voting.register_candidate(election_id),
+                Err(RegistrationError::AlreadyRegistered)
+            );
+            assert_eq!(
+                voting.vote(election_id, accounts.django),
+                Err(VoteError::VotingClosed)
+            );
+            assert_eq!(
+                voting.open_voting(election_id),
+                Err(VoteError::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(election_id).unwrap();
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(voting.get_votes(election_id, accounts.django), 1);
+            assert_eq!(voting.get_votes(0, accounts.django), 0);
+
+            voting.finalize(election_id).unwrap();
+            assert_eq!(
+                voting.get_status(election_id),
+                Some(ElectionStatus::Finalized)
+            );
+            assert_eq!(
+                voting.cancel(election_id),
+                Err(VoteError::InvalidTransition)
+            );
+
+            voting.cancel(0).unwrap();
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Cancelled));
+        }
+
+        /// We test that voting only opens once enough candidates registered.
+        #[ink::test]
+        fn voting_needs_minimum_candidates() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    min_candidates: 2,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_registration(election_id).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.register_candidate(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.open_voting(election_id),
+                Err(VoteError::NotEnoughCandidates)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting.register_candidate(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(election_id).unwrap();
+        }
+
+        /// We test that closed-list elections only take ballots for registered
+        /// candidates.
+        #[ink::test]
+        fn closed_lists_reject_write_ins() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    candidate_list: CandidateList::ClosedList,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_registration(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.register_candidate(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(election_id).unwrap();
+
+            assert_eq!(
+                voting.vote(election_id, accounts.eve),
+                Err(VoteError::NotACandidate)
+            );
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(voting.get_votes(election_id, accounts.django), 1);
+        }
+
+        /// We test that the admin can load a closed electorate in batches.
+        #[ink::test]
+        fn admin_registers_voters_in_bulk() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    closed_electorate: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(
+                voting.add_voters(election_id, vec![accounts.bob; MAX_VOTER_BATCH + 1]),
+                Err(VoteError::BatchTooLarge)
+            );
+            assert_eq!(
+                voting.add_voters(
+                    election_id,
+                    vec![accounts.bob, accounts.charlie, accounts.bob]
+                ),
+                Ok(vec![true, true, false])
+            );
+            assert_eq!(
+                voting.add_voters(election_id, vec![accounts.charlie, accounts.eve]),
+                Ok(vec![false, true])
+            );
+            assert_eq!(voting.get_electorate_size(election_id), 3);
+            assert!(voting.is_voter(election_id, accounts.eve));
+            voting.open_voting(election_id).unwrap();
+
+            assert_eq!(
+                voting.vote(election_id, accounts.django),
+                Err(VoteError::NotInElectorate)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(
+                voting.add_voters(election_id, vec![accounts.frank]),
+                Err(VoteError::Unauthorized)
+            );
+        }
+
+        /// We test that voters can leave, or be removed from, the electorate
+        /// before voting opens and get their deposit back.
+        #[ink::test]
+        fn voters_deregister_with_refund() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    closed_electorate: true,
+                    voter_deposit: Some(500),
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.add_voters(election_id, vec![accounts.eve]).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.register_voter(election_id),
+                Err(RegistrationError::IncorrectDeposit)
+            );
+            set_value(500);
+            voting.register_voter(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.register_voter(election_id).unwrap();
+            set_value(0);
+            assert_eq!(voting.get_electorate_size(election_id), 3);
+
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 2_000_000,
+            );
+            let before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                accounts.charlie,
+            )
+            .unwrap();
+            assert_eq!(voting.deregister_voter(election_id), Ok(500));
+            assert_eq!(voting.get_claimable(accounts.charlie), 500);
+            assert_eq!(voting.claim(), Ok(500));
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    accounts.charlie
+                ),
+                Ok(before + 500)
+            );
+            assert_eq!(
+                voting.deregister_voter(election_id),
+                Err(RegistrationError::NotRegistered)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(voting.remove_voter(election_id, accounts.eve), Ok(0));
+            assert_eq!(voting.get_electorate_size(election_id), 1);
+            voting.open_voting(election_id).unwrap();
+            assert_eq!(
+                voting.remove_voter(election_id, accounts.bob),
+                Err(VoteError::InvalidTransition)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.deregister_voter(election_id),
+                Err(RegistrationError::RegistrationClosed)
+            );
+        }
+
+        /// We test that candidates must be referred by the admin or a verified
+        /// candidate, on-chain or with a signed referral, where the election
+        /// requires it.
+        #[ink::test]
+        fn referrals_gate_candidacy() {
+            use secp256k1::{Message, SecretKey, SECP256K1};
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    referral_required: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_registration(election_id).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                voting.register_candidate(election_id),
+                Err(RegistrationError::NotReferred)
+            );
+            assert_eq!(
+                voting.refer(election_id, accounts.charlie),
+                Err(RegistrationError::NotAReferrer)
+            );
+            set_caller(accounts.alice);
+            voting.refer(election_id, accounts.bob).unwrap();
+            assert_eq!(
+                voting.refer(election_id, accounts.bob),
+                Err(RegistrationError::AlreadyReferred)
+            );
+            set_caller(accounts.bob);
+            voting.register_candidate(election_id).unwrap();
+            set_caller(accounts.alice);
+            voting
+                .set_verified(election_id, accounts.bob, true)
+                .unwrap();
+            set_caller(accounts.bob);
+            voting.refer(election_id, accounts.charlie).unwrap();
+            set_caller(accounts.charlie);
+            voting.register_candidate(election_id).unwrap();
+            assert_eq!(
+                voting
+                    .get_candidate_info(election_id, accounts.charlie)
+                    .and_then(|info| info.referred_by),
+                Some(accounts.bob)
+            );
+
+            // a signed referral only counts for the referrer whose key signed it
+            let secret = SecretKey::from_slice(&[7; 32]).unwrap();
+            let mut referrer = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(
+                &secret.public_key(SECP256K1).serialize(),
+                &mut referrer,
+            );
+            let referrer = AccountId::from(referrer);
+            let hash = voting.referral_hash(election_id, accounts.django);
+            let (recovery_id, compact) = SECP256K1
+                .sign_ecdsa_recoverable(
+                    &Message::from_digest_slice(hash.as_ref()).unwrap(),
+                    &secret,
+                )
+                .serialize_compact();
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            set_caller(accounts.django);
+            assert_eq!(
+                voting.redeem_referral(election_id, accounts.alice, signature),
+                Err(RegistrationError::InvalidSignature)
+            );
+            assert_eq!(
+                voting.redeem_referral(election_id, referrer, signature),
+                Err(RegistrationError::NotAReferrer)
+            );
+            set_caller(accounts.eve);
+            assert_eq!(
+                voting.redeem_referral(election_id, referrer, signature),
+                Err(RegistrationError::InvalidSignature)
+            );
+            set_caller(accounts.alice);
+            voting.refer(election_id, referrer).unwrap();
+            set_caller(referrer);
+            voting.register_candidate(election_id).unwrap();
+            set_caller(accounts.alice);
+            voting.set_verified(election_id, referrer, true).unwrap();
+            set_caller(accounts.django);
+            voting
+                .redeem_referral(election_id, referrer, signature)
+                .unwrap();
+            assert_eq!(
+                voting.get_referrer(election_id, accounts.django),
+                Some(referrer)
+            );
+            assert_eq!(emitted::<Referred>(), 4);
+
+            // ballots may only name registered candidates
+            set_caller(accounts.alice);
+            voting.open_voting(election_id).unwrap();
+            set_caller(accounts.eve);
+            assert_eq!(
+                voting.vote(election_id, accounts.frank),
+                Err(VoteError::NotACandidate)
+            );
+            assert_eq!(
+                voting.refer(election_id, accounts.frank),
+                Err(RegistrationError::RegistrationClosed)
+            );
+        }
+
+        /// We test that display names are validated, unique in any letter case,
+        /// and released when changed or cleared.
+        #[ink::test]
+        fn display_names_are_unique() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.set_display_name(Vec::new()),
+                Err(RegistrationError::InvalidName)
+            );
+            assert_eq!(
+                voting.set_display_name(b"tab\there".to_vec()),
+                Err(RegistrationError::InvalidName)
+            );
+            assert_eq!(
+                voting.set_display_name(vec![0xff, 0xfe]),
+                Err(RegistrationError::InvalidName)
+            );
+            assert_eq!(
+                voting.set_display_name(vec![b'a'; MAX_DISPLAY_NAME_LEN + 1]),
+                Err(RegistrationError::NameTooLong)
+            );
+            voting.set_display_name(b"Alice".to_vec()).unwrap();
+            assert_eq!(
+                voting.account_of_name(b"ALICE".to_vec()),
+                Some(accounts.alice)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                voting.set_display_name(b"alice".to_vec()),
+                Err(RegistrationError::NameTaken)
+            );
+            voting.set_display_name(b"Bob".to_vec()).unwrap();
+            assert_eq!(
+                voting.display_names_of(vec![accounts.alice, accounts.charlie, accounts.bob]),
+                vec![Some(b"Alice".to_vec()), None, Some(b"Bob".to_vec())]
+            );
+
+            // renaming releases the old name, clearing releases the new one
+            set_caller(accounts.alice);
+            voting.set_display_name(b"ALICE".to_vec()).unwrap();
+            voting.set_display_name(b"Carol".to_vec()).unwrap();
+            assert_eq!(voting.account_of_name(b"alice".to_vec()), None);
+            voting.clear_display_name();
+            assert_eq!(voting.display_name_of(accounts.alice), None);
+            set_caller(accounts.bob);
+            voting.set_display_name(b"carol".to_vec()).unwrap();
+            assert_eq!(voting.account_of_name(b"Bob".to_vec()), None);
+            assert_eq!(emitted::<DisplayNameSet>(), 6);
+
+            assert_eq!(
+                voting.set_name_validator(Some(accounts.django)),
+                Err(VoteError::Unauthorized)
+            );
+        }
+
+        /// We test that names resolve to display names while no name service
+        /// is set, and that only the owner may set one.
+        #[ink::test]
+        fn names_fall_back_to_display_names() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            set_caller(accounts.bob);
+            voting.set_display_name(b"Bob".to_vec()).unwrap();
+            assert_eq!(voting.resolve_name(accounts.bob), Some(b"Bob".to_vec()));
+            assert_eq!(
+                voting.resolve_names(vec![accounts.charlie, accounts.bob]),
+                vec![None, Some(b"Bob".to_vec())]
+            );
+            assert_eq!(
+                voting.set_name_service(Some(accounts.django)),
+                Err(VoteError::Unauthorized)
+            );
+            set_caller(accounts.alice);
+            voting.set_name_service(Some(accounts.django)).unwrap();
+            assert_eq!(voting.get_name_service(), Some(accounts.django));
+            assert_eq!(emitted::<NameServiceUpdated>(), 1);
+        }
+
+        /// We test that invitation codes admit one voter each, and only until
+        /// voting opens.
+        #[ink::test]
+        fn invitation_codes_admit_voters() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.add_invitation_codes(0, vec![Hash::from([1; 32])]),
+                Err(VoteError::ElectorateOpen)
+            );
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    closed_electorate: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            let codes = [b"first".to_vec(), b"second".to_vec(), b"third".to_vec()];
+            let hashes: Vec<Hash> = codes
+                .iter()
+                .map(|code| voting.invitation_code_hash(code.clone()))
+                .collect();
+            assert_eq!(
+                voting.add_invitation_codes(election_id, hashes.clone()),
+                Ok(3)
+            );
+            assert_eq!(
+                voting.add_invitation_codes(election_id, hashes[..1].to_vec()),
+                Ok(0)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                voting.add_invitation_codes(election_id, hashes.clone()),
+                Err(VoteError::Unauthorized)
+            );
+
+            assert_eq!(
+                voting.register_with_code(election_id, b"fourth".to_vec()),
+                Err(RegistrationError::InvalidCode)
+            );
+            voting
+                .register_with_code(election_id, codes[0].clone())
+                .unwrap();
+            assert!(voting.is_voter(election_id, accounts.bob));
+            assert!(!voting.has_invitation(election_id, hashes[0]));
+            assert_eq!(
+                voting.register_with_code(election_id, codes[1].clone()),
+                Err(RegistrationError::AlreadyRegistered)
+            );
+            set_caller(accounts.charlie);
+            assert_eq!(
+                voting.register_with_code(election_id, codes[0].clone()),
+                Err(RegistrationError::InvalidCode)
+            );
+            voting
+                .register_with_code(election_id, codes[1].clone())
+                .unwrap();
+            assert_eq!(voting.get_electorate_size(election_id), 2);
+            assert_eq!(emitted::<VoterRegistered>(), 2);
+
+            // the third code lapses once voting opens
+            set_caller(accounts.alice);
+            assert!(voting.has_invitation(election_id, hashes[2]));
+            voting.open_voting(election_id).unwrap();
+            assert!(!voting.has_invitation(election_id, hashes[2]));
+            set_caller(accounts.django);
+            assert_eq!(
+                voting.register_with_code(election_id, codes[2].clone()),
+                Err(RegistrationError::RegistrationClosed)
+            );
+        }
+
+        /// We test that accounts join the electorate with enough vouches, and
+        /// that vouching for a later-banned account costs the right to vouch.
+        #[ink::test]
+        fn voters_vouch_newcomers_in() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    closed_electorate: true,
+                    vouches_required: 2,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting
+                .add_voters(election_id, vec![accounts.bob, accounts.charlie])
+                .unwrap();
+
+            assert_eq!(
+                voting.vouch(election_id, accounts.eve),
+                Err(RegistrationError::NotAVoucher)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vouch(election_id, accounts.eve).unwrap();
+            assert_eq!(
+                voting.vouch(election_id, accounts.eve),
+                Err(RegistrationError::AlreadyVouched)
+            );
+            assert!(!voting.is_voter(election_id, accounts.eve));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vouch(election_id, accounts.eve).unwrap();
+            assert!(voting.is_voter(election_id, accounts.eve));
+            assert_eq!(voting.get_electorate_size(election_id), 3);
+
+            voting.revoke_vouch(election_id, accounts.eve).unwrap();
+            assert!(!voting.is_voter(election_id, accounts.eve));
+            assert_eq!(
+                voting.get_vouchers(election_id, accounts.eve),
+                [accounts.bob]
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.ban_voter(election_id, accounts.eve).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.vouch(election_id, accounts.frank),
+                Err(RegistrationError::NotAVoucher)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                voting.vouch(election_id, accounts.eve),
+                Err(RegistrationError::Banned)
+            );
+            voting.vouch(election_id, accounts.frank).unwrap();
+        }
+
+        /// We test that parties register slates and seat their members in
+        /// order of the seats they win.
+        #[ink::test]
+        fn committees_are_elected_from_slates() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    committee: Some(Committee {
+                        seats: 3,
+                        method: Apportionment::DHondt,
+                    }),
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_registration(election_id).unwrap();
+            let red = [accounts.alice, accounts.bob];
+            let blue = [accounts.charlie, accounts.django];
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                voting.register_slate(election_id, vec![accounts.alice, accounts.alice]),
+                Err(RegistrationError::InvalidSlate)
+            );
+            voting.register_slate(election_id, red.to_vec()).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            voting.register_slate(election_id, blue.to_vec()).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(election_id).unwrap();
+
+            for (n, party) in [accounts.eve, accounts.eve, accounts.frank]
+                .into_iter()
+                .enumerate()
+            {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(AccountId::from(
+                    [0x10 + n as u8; 32],
+                ));
+                voting.vote(election_id, party).unwrap();
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(election_id).unwrap();
+
+            assert_eq!(
+                voting.get_committee(election_id),
+                (
+                    vec![(accounts.eve, 2), (accounts.frank, 1)],
+                    vec![accounts.alice, accounts.bob, accounts.charlie]
+                )
+            );
+            assert_eq!(emitted::<CommitteeElected>(), 1);
+        }
+
+        /// We test that a single-transferable-vote count, resumed over several
+        /// steps, passes the surplus of an elected candidate to next
+        /// preferences.
+        #[ink::test]
+        fn transferable_votes_fill_seats() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    mode: VotingMode::SingleTransferable(0),
+                    ..Default::default()
+                }),
+                Err(ConfigError::NoSeats)
+            );
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    mode: VotingMode::SingleTransferable(2),
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            assert_eq!(
+                voting.vote_ranked(0, vec![accounts.alice]),
+                Err(VoteError::UnsupportedBallotType)
+            );
+            assert_eq!(
+                voting.vote_ranked(election_id, vec![accounts.bob, accounts.bob]),
+                Err(VoteError::InvalidRanking)
+            );
+
+            // alice leads on first preferences, charlie comes second
+            let rankings = [
+                (6, vec![accounts.alice, accounts.bob]),
+                (2, vec![accounts.bob]),
+                (3, vec![accounts.charlie]),
+            ];
+            let mut n = 0u8;
+            for (ballots, ranking) in rankings {
+                for _ in 0..ballots {
+                    ink::env::test::set_caller::<ink::env::DefaultEnvironment>(AccountId::from(
+                        [0x10 + n; 32],
+                    ));
+                    voting.vote_ranked(election_id, ranking.clone()).unwrap();
+                    n += 1;
+                }
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut steps = 1;
+            while !voting.finalize_step(election_id, 4).unwrap() {
+                steps += 1;
+            }
+
+            // alice's surplus carries bob past the quota
+            assert!(steps > 1);
+            assert_eq!(
+                voting.get_stv_elected(election_id),
+                [accounts.alice, accounts.bob]
+            );
+            assert_eq!(
+                voting.get_archived_result(election_id).unwrap().winners,
+                [accounts.alice, accounts.bob]
+            );
+            let filled = events_of::<SeatsFilled>();
+            assert_eq!(filled.len(), 1);
+            let filled =
+                <SeatsFilled as ink::scale::Decode>::decode(&mut &filled[0].data[..]).unwrap();
+            assert_eq!(filled.rounds, 2);
+        }
+
+        /// We test that a sortition winner is drawn from a seed anyone can
+        /// recompute, in proportion to the votes.
+        #[ink::test]
+        fn sortition_draws_the_winner() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    mode: VotingMode::SingleTransferable(2),
+                    outcome: Outcome::Sortition,
+                    ..Default::default()
+                }),
+                Err(ConfigError::SortitionWithSeats)
+            );
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    outcome: Outcome::Sortition,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            for (n, candidate) in [accounts.alice, accounts.alice, accounts.alice, accounts.bob]
+                .into_iter()
+                .enumerate()
+            {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(AccountId::from(
+                    [0x10 + n as u8; 32],
+                ));
+                voting.vote(election_id, candidate).unwrap();
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(election_id).unwrap();
+
+            let (seed, draw) = voting.get_sortition_draw(election_id).unwrap();
+            let mut expected = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &(
+                    election_id,
+                    voting.get_ballot_root(election_id).unwrap(),
+                    voting.get_tally_hash(election_id).unwrap(),
+                    voting
+                        .get_results(election_id)
+                        .unwrap()
+                        .finalized_at
+                        .unwrap(),
+                ),
+                &mut expected,
+            );
+            assert_eq!(seed, Hash::from(expected));
+            assert!(draw < 4);
+            let winner = if draw < 3 {
+                accounts.alice
+            } else {
+                accounts.bob
+            };
+            assert_eq!(voting.get_current_winner(election_id), [winner]);
+            assert_eq!(voting.get_results(election_id).unwrap().winners, [winner]);
+            assert_eq!(emitted::<SortitionDrawn>(), 1);
+        }
+
+        /// We test that recounting the ballots off-chain with the counting
+        /// module reproduces the totals, winner and runner-up of the contract.
+        #[ink::test]
+        fn counting_module_recounts_the_results() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting.create_election(Default::default()).unwrap();
+            voting.open_voting(election_id).unwrap();
+            let ballots = [accounts.bob, accounts.alice, accounts.bob, accounts.charlie];
+            for (n, candidate) in ballots.into_iter().enumerate() {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(AccountId::from(
+                    [0x10 + n as u8; 32],
+                ));
+                voting.vote(election_id, candidate).unwrap();
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(election_id).unwrap();
+
+            let result = voting.get_results(election_id).unwrap();
+            let totals = crate::counting::totals(ballots.into_iter().map(|ballot| (ballot, 1)));
+            assert_eq!(totals, result.totals);
+            let (winners, votes) = crate::counting::winners(&totals, false);
+            assert_eq!((winners.clone(), votes), (result.winners, 2));
+            assert_eq!(
+                crate::counting::leaders(&totals, &winners).0,
+                result.runner_up
+            );
+        }
+
+        /// We test the highest-averages methods of the counting module against
+        /// the textbook example of eight seats shared among four parties.
+        #[ink::test]
+        fn seats_are_apportioned_by_highest_averages() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let totals = [
+                (accounts.alice, 100_000),
+                (accounts.bob, 80_000),
+                (accounts.charlie, 30_000),
+                (accounts.django, 20_000),
+            ];
+            let apportion = |sizes: &[u32], method: Apportionment| {
+                crate::counting::apportion(&totals, sizes, 8, |seats| method.divisor(seats))
+            };
+            assert_eq!(apportion(&[8; 4], Apportionment::DHondt), [4, 3, 1, 0]);
+            assert_eq!(apportion(&[8; 4], Apportionment::SainteLague), [3, 3, 1, 1]);
+            // seats a short slate cannot fill go to the next quotients
+            assert_eq!(
+                apportion(&[2, 8, 8, 8], Apportionment::DHondt),
+                [2, 4, 1, 1]
+            );
+        }
+
+        /// We test that a ballot carries the weight delegated to the voter down
+        /// a chain, except that of delegators who already voted.
+        #[ink::test]
+        fn delegated_weight_is_spent_by_delegates() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.delegate(accounts.bob).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.delegate(accounts.charlie).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.delegate(accounts.django),
+                Err(VoteError::DelegationCycle)
+            );
+            assert_eq!(voting.undelegate(), Err(VoteError::NotDelegating));
+            assert_eq!(voting.get_delegators(accounts.bob), [accounts.charlie]);
+            assert_eq!(voting.effective_weight(0, accounts.bob), 3);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.vote(0, accounts.alice).unwrap();
+            assert_eq!(voting.effective_weight(0, accounts.bob), 2);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.bob).unwrap();
+            assert_eq!(voting.get_votes(0, accounts.bob), 2);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(voting.vote(0, accounts.alice), Err(VoteError::AlreadyVoted));
+        }
+
+        /// We test that ballots are counted by block range, and that ranges
+        /// widen once there are too many of them.
+        #[ink::test]
+        fn turnout_histogram_paces_ballots() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.vote(0, accounts.django).unwrap();
+            advance_blocks(150);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(0, accounts.django).unwrap();
+            let histogram = voting.get_turnout_histogram(0);
+            assert_eq!(histogram.bucket_blocks, 100);
+            assert_eq!(histogram.buckets, [(0, 1), (100, 2)]);
+
+            for voter in 10..73 {
+                advance_blocks(100);
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(AccountId::from(
+                    [voter; 32],
+                ));
+                voting.vote(0, accounts.django).unwrap();
+            }
+            let histogram = voting.get_turnout_histogram(0);
+            assert_eq!(histogram.bucket_blocks, 200);
+            assert!(histogram.buckets.len() <= 64);
+            let counted: u32 = histogram.buckets.iter().map(|(_, ballots)| ballots).sum();
+            assert_eq!(counted, 66);
+            assert_eq!(voting.get_turnout_histogram(1), TurnoutHistogram::default());
+        }
+
+        /// We test that a split vote carries its shares to each delegate that
+        /// votes, and that the delegator may no longer vote once one did.
+        #[ink::test]
+        fn split_delegation_shares_the_weight() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    reputation_weighted: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            voting.adjust_reputation(accounts.django, 9).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(
+                voting.delegate_split(vec![(accounts.bob, 600), (accounts.charlie, 300)]),
+                Err(VoteError::InvalidSplit)
+            );
+            assert_eq!(
+                voting.delegate_split(vec![(accounts.django, 1_000)]),
+                Err(VoteError::DelegationCycle)
+            );
+            voting.delegate(accounts.eve).unwrap();
+            voting
+                .delegate_split(vec![(accounts.bob, 600), (accounts.charlie, 400)])
+                .unwrap();
+            assert_eq!(voting.get_delegate(accounts.django), None);
+            assert_eq!(voting.get_split_delegators(accounts.bob), [accounts.django]);
+            assert_eq!(voting.effective_weight(election_id, accounts.bob), 7);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(election_id, accounts.alice).unwrap();
+            assert_eq!(voting.get_votes(election_id, accounts.alice), 7);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(
+                voting.vote(election_id, accounts.frank),
+                Err(VoteError::AlreadyVoted)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(election_id, accounts.frank).unwrap();
+            assert_eq!(voting.get_votes(election_id, accounts.frank), 5);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.undelegate_split().unwrap();
+            assert!(voting.get_split_delegators(accounts.bob).is_empty());
+            assert_eq!(voting.undelegate_split(), Err(VoteError::NotDelegating));
+        }
+
+        /// We test that prize payouts wait out the challenge period and any
+        /// dispute raised within it.
+        #[ink::test]
+        fn disputes_hold_back_payouts() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    challenge_period: 5,
+                    dispute_bond: 10,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            set_value(100);
+            voting.fund_prize(election_id).unwrap();
+            set_value(0);
+            voting.vote(election_id, accounts.django).unwrap();
+            voting.finalize(election_id).unwrap();
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 1_000_000,
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                voting.raise_dispute(election_id),
+                Err(VoteError::IncorrectFee)
+            );
+            set_value(10);
+            voting.raise_dispute(election_id).unwrap();
+            assert_eq!(
+                voting.raise_dispute(election_id),
+                Err(VoteError::DisputeOpen)
+            );
+            set_value(0);
+            advance_blocks(5);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(
+                voting.claim_prize(election_id),
+                Err(VoteError::ResultNotEffective)
+            );
+            assert_eq!(
+                voting.resolve_dispute(election_id, Ruling::Dismissed),
+                Err(VoteError::Unauthorized)
+            );
+            assert_eq!(voting.get_open_disputes(), [election_id]);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let treasury = voting.get_treasury();
+            voting
+                .resolve_dispute(election_id, Ruling::Dismissed)
+                .unwrap();
+            assert_eq!(voting.get_treasury(), treasury + 10);
+            assert!(voting.get_open_disputes().is_empty());
+            assert!(voting.is_result_effective(election_id));
+            assert_eq!(
+                voting.raise_dispute(election_id),
+                Err(VoteError::ChallengeClosed)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(voting.claim_prize(election_id), Ok(100));
+        }
+
+        /// We test that disqualifying the winner hands the seat to the runner-up
+        /// and refunds the challenger, and that the admin may not rule on a
+        /// dispute left to an arbitrator.
+        #[ink::test]
+        fn disqualification_strikes_the_winner() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    challenge_period: 5,
+                    dispute_bond: 10,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            voting.vote(election_id, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(election_id, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(election_id, accounts.frank).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(election_id).unwrap();
+            assert_eq!(voting.get_current_winner(election_id), [accounts.django]);
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 1_000_000,
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            set_value(10);
+            voting.raise_dispute(election_id).unwrap();
+            set_value(0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let treasury = voting.get_treasury();
+            voting
+                .resolve_dispute(election_id, Ruling::Disqualified(accounts.django))
+                .unwrap();
+            assert_eq!(voting.get_treasury(), treasury);
+            assert_eq!(voting.get_current_winner(election_id), [accounts.frank]);
+            assert_eq!(emitted::<CandidateBanned>(), 1);
+            assert!(voting.get_open_disputes().is_empty());
+
+            let arbitrated = voting
+                .create_election(ElectionConfig {
+                    challenge_period: 5,
+                    dispute_bond: 10,
+                    arbitrator: Some(accounts.frank),
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(
+                voting.resolve_dispute(arbitrated, Ruling::Dismissed),
+                Err(VoteError::Unauthorized)
+            );
+            assert_eq!(voting.enforce_ruling(arbitrated), Err(VoteError::NoDispute));
+            assert_eq!(
+                voting.enforce_ruling(election_id),
+                Err(VoteError::Unauthorized)
+            );
+        }
+
+        /// We test that the admin bond is posted at creation, slashed by a
+        /// supermajority of voters and otherwise released once the result
+        /// takes effect.
+        #[ink::test]
+        fn admin_bond_is_slashable() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.set_admin_bond(50).unwrap();
+            let config = ElectionConfig {
+                challenge_period: 5,
+                ..Default::default()
+            };
+            assert_eq!(
+                voting.create_election(config.clone()),
+                Err(ConfigError::IncorrectCreationFee)
+            );
+            set_value(50);
+            let slashed = voting.create_election(config.clone()).unwrap();
+            let released = voting.create_election(config).unwrap();
+            set_value(0);
+            assert_eq!(voting.get_election_bond(slashed), 50);
+            assert_eq!(voting.treasury_report().deposits, 100);
+
+            for election_id in [slashed, released] {
+                voting.open_voting(election_id).unwrap();
+                for voter in [accounts.alice, accounts.bob, accounts.charlie] {
+                    ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                    voting.vote(election_id, accounts.django).unwrap();
+                }
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+                voting.finalize(election_id).unwrap();
+            }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                voting.sign_slash_petition(slashed),
+                Err(VoteError::Unauthorized)
+            );
+            let treasury = voting.get_treasury();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.sign_slash_petition(slashed).unwrap();
+            assert_eq!(
+                voting.sign_slash_petition(slashed),
+                Err(VoteError::AlreadySigned)
+            );
+            assert_eq!(voting.get_treasury(), treasury);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.sign_slash_petition(slashed).unwrap();
+            assert_eq!(voting.get_treasury(), treasury);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.sign_slash_petition(slashed).unwrap();
+            assert_eq!(voting.get_slash_petition(slashed), 3);
+            assert_eq!(voting.get_treasury(), treasury + 50);
+            assert_eq!(voting.get_election_bond(slashed), 0);
+            assert_eq!(
+                voting.sign_slash_petition(slashed),
+                Err(VoteError::NoAdminBond)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.reclaim_admin_bond(released),
+                Err(VoteError::ResultNotEffective)
+            );
+            advance_blocks(5);
+            assert_eq!(voting.reclaim_admin_bond(released), Ok(50));
+            assert_eq!(voting.get_claimable(accounts.alice), 50);
+            assert_eq!(voting.treasury_report().deposits, 0);
+            assert_eq!(emitted::<AdminBondSettled>(), 2);
+        }
+
+        /// We test that a lone voter cannot slash the admin bond, neither
+        /// while voting is open nor on their own once it has closed.
+        #[ink::test]
+        fn lone_voter_cannot_slash_admin_bond() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.set_admin_bond(50).unwrap();
+            set_value(50);
+            let election_id = voting.create_election(ElectionConfig::default()).unwrap();
+            set_value(0);
+            voting.open_voting(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(
+                voting.sign_slash_petition(election_id),
+                Err(VoteError::VotingStillOpen)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(election_id).unwrap();
+            let treasury = voting.get_treasury();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.sign_slash_petition(election_id).unwrap();
+            assert_eq!(voting.get_slash_petition(election_id), 1);
+            assert_eq!(voting.get_election_bond(election_id), 50);
+            assert_eq!(voting.get_treasury(), treasury);
+        }
+
+        /// We test that an upheld dispute voids the result and refunds the
+        /// challenger, and that only the appointed resolver may uphold it.
+        #[ink::test]
+        fn upheld_disputes_void_the_result() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    challenge_period: 5,
+                    dispute_bond: 10,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            set_value(100);
+            voting.fund_prize(election_id).unwrap();
+            set_value(0);
+            voting.vote(election_id, accounts.django).unwrap();
+            voting.finalize(election_id).unwrap();
+            voting.set_resolver(Some(accounts.frank)).unwrap();
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 1_000_000,
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            set_value(10);
+            voting.raise_dispute(election_id).unwrap();
+            set_value(0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.resolve_dispute(election_id, Ruling::Upheld),
+                Err(VoteError::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            let treasury = voting.get_treasury();
+            voting.resolve_dispute(election_id, Ruling::Upheld).unwrap();
+
+            assert_eq!(
+                voting.get_status(election_id),
+                Some(ElectionStatus::Cancelled)
+            );
+            assert_eq!(voting.get_treasury(), treasury + 100);
+            assert_eq!(voting.get_claimable(accounts.eve), 10);
+            assert!(!voting.is_result_effective(election_id));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(
+                voting.claim_prize(election_id),
+                Err(VoteError::ResultNotEffective)
+            );
+        }
+
+        /// We test that reputation grows with every ballot, moves with the
+        /// owner's bounded adjustments and scales reputation-weighted ballots.
+        #[ink::test]
+        fn reputation_weights_ballots() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    reputation_weighted: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(voting.get_reputation(accounts.bob), 1);
+            assert_eq!(
+                voting.adjust_reputation(accounts.bob, 2),
+                Err(VoteError::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(voting.adjust_reputation(accounts.bob, 2), Ok(3));
+            assert_eq!(
+                voting.adjust_reputation(accounts.bob, MAX_REPUTATION_ADJUSTMENT),
+                Err(VoteError::ReputationOutOfBounds)
+            );
+            assert_eq!(voting.adjust_reputation(accounts.charlie, -5), Ok(0));
+            assert_eq!(emitted::<ReputationChanged>(), 2);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(voting.get_votes(election_id, accounts.django), 4);
+            assert_eq!(voting.get_reputation(accounts.bob), 4);
+        }
+
+        /// We test that participation streaks run over consecutive tracked
+        /// elections, break on a missed one and announce milestones.
+        #[ink::test]
+        fn participation_streaks_are_tracked() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let tracked = ElectionConfig {
+                track_participation: true,
+                ..Default::default()
+            };
+            for round in 0..6 {
+                let election_id = voting.create_election(tracked.clone()).unwrap();
+                voting.open_voting(election_id).unwrap();
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+                voting.vote(election_id, accounts.django).unwrap();
+                if round != 2 {
+                    ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+                    voting.vote(election_id, accounts.django).unwrap();
+                }
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+                // one ballot booked per step
+                while !voting.finalize_step(election_id, 1).unwrap() {}
+            }
+
+            assert_eq!(voting.get_streak(accounts.bob), 6);
+            assert_eq!(voting.get_streak(accounts.charlie), 3);
+            assert_eq!(
+                voting.get_participation(accounts.charlie),
+                Participation {
+                    elections: 5,
+                    streak: 3,
+                    best: 3,
+                    last_round: 5,
+                }
+            );
+            let milestones = events_of::<StreakMilestone>();
+            assert_eq!(milestones.len(), 1);
+            let milestone =
+                <StreakMilestone as ink::scale::Decode>::decode(&mut &milestones[0].data[..])
+                    .unwrap();
+            assert_eq!((milestone.account, milestone.streak), (accounts.bob, 5));
+
+            // an untracked election does not break the streak
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_streak(accounts.bob), 6);
+        }
+
+        /// We test that the voter lottery draws distinct voters with the seed
+        /// committed to before finalization, and pays them from its pool.
+        #[ink::test]
+        fn lottery_draws_committed_voters() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    lottery_winners: 2,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            let seed = Hash::from([3; 32]);
+            let mut commitment = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&seed, &mut commitment);
+            voting
+                .commit_lottery_seed(election_id, Hash::from(commitment))
+                .unwrap();
+            set_value(100);
+            voting.fund_lottery(election_id).unwrap();
+            set_value(0);
+            let voters = [accounts.bob, accounts.charlie, accounts.django];
+            for voter in voters {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(election_id, accounts.eve).unwrap();
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.draw_lottery(election_id, seed),
+                Err(VoteError::LotteryUnavailable)
+            );
+            voting.finalize(election_id).unwrap();
+            assert_eq!(
+                voting.draw_lottery(election_id, Hash::from([4; 32])),
+                Err(VoteError::SeedMismatch)
+            );
+            voting.draw_lottery(election_id, seed).unwrap();
+
+            let lottery = voting.get_lottery(election_id);
+            assert_eq!(lottery.seed, Some(seed));
+            assert_eq!(lottery.winners.len(), 2);
+            assert_ne!(lottery.winners[0], lottery.winners[1]);
+            for winner in &lottery.winners {
+                assert!(voters.contains(winner));
+                assert_eq!(voting.get_prize(election_id, *winner), 50);
+            }
+            assert_eq!(
+                voting.draw_lottery(election_id, seed),
+                Err(VoteError::LotteryUnavailable)
+            );
+            assert_eq!(emitted::<LotteryDrawn>(), 1);
+        }
+
+        /// We test that the sponsorship pool pays back the storage deposit of
+        /// ballots until it runs dry, and that its remainder returns to the
+        /// admin.
+        #[ink::test]
+        fn sponsorship_reimburses_ballots() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.fund_sponsorship(0),
+                Err(VoteError::SponsorshipDisabled)
+            );
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    storage_sponsorship: Some(StorageSponsorship {
+                        per_ballot: 10,
+                        required: true,
+                    }),
+                    ..Default::default()
+                })
+                .unwrap();
+            set_value(15);
+            voting.fund_sponsorship(election_id).unwrap();
+            set_value(0);
+            voting.open_voting(election_id).unwrap();
+
+            set_caller(accounts.bob);
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(voting.get_claimable(accounts.bob), 10);
+            assert_eq!(
+                voting.get_sponsorship(election_id),
+                Sponsorship {
+                    pool: 5,
+                    spent: 10,
+                    ballots: 1,
+                }
+            );
+            set_caller(accounts.charlie);
+            assert_eq!(
+                voting.vote(election_id, accounts.django),
+                Err(VoteError::SponsorshipExhausted)
+            );
+            assert_eq!(
+                voting.reclaim_sponsorship(election_id),
+                Err(VoteError::Unauthorized)
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                voting.reclaim_sponsorship(election_id),
+                Err(VoteError::InvalidTransition)
+            );
+            voting.cancel(election_id).unwrap();
+            assert_eq!(voting.reclaim_sponsorship(election_id), Ok(5));
+            assert_eq!(voting.get_claimable(accounts.alice), 5);
+            assert_eq!(voting.treasury_report().deposits, 0);
+            assert_eq!(voting.treasury_report().payouts, 15);
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    storage_sponsorship: Some(StorageSponsorship {
+                        per_ballot: 0,
+                        required: false,
+                    }),
+                    ..Default::default()
+                }),
+                Err(ConfigError::InvalidSponsorship)
+            );
+        }
+
+        /// We test that a scaled candidacy deposit grows with every runner past
+        /// the free ones.
+        #[ink::test]
+        fn candidacy_deposit_scales_with_the_field() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(Default::default(), generous_limits()).unwrap();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    candidacy_deposit: 100,
+                    deposit_scaling: Some(DepositScaling {
+                        free_candidates: 1,
+                        percent_per_candidate: 50,
+                    }),
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_registration(election_id).unwrap();
+            for (candidate, deposit) in [
+                (accounts.bob, 100),
+                (accounts.charlie, 100),
+                (accounts.django, 150),
+                (accounts.eve, 200),
+            ] {
+                assert_eq!(voting.get_candidacy_deposit(election_id), deposit);
+                assert_eq!(
+                    voting.can_register(candidate, election_id, deposit - 1),
+                    Err(RegistrationError::IncorrectDeposit)
+                );
+                set_caller(candidate);
+                set_value(deposit);
+                voting.register_candidate(election_id).unwrap();
+            }
+            assert_eq!(voting.get_candidacy_deposit(election_id), 250);
+            assert_eq!(voting.treasury_report().deposits, 550);
+
+            set_caller(accounts.alice);
+            set_value(0);
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    deposit_scaling: Some(DepositScaling {
+                        free_candidates: 0,
+                        percent_per_candidate: 10,
+                    }),
+                    ..Default::default()
+                }),
+                Err(ConfigError::InvalidDepositScaling)
+            );
+        }
+
+        /// We test that an election past its storage budget refuses new
+        /// candidates and voters, and write-ins, but still counts ballots for
+        /// its runners.
+        #[ink::test]
+        fn storage_budget_caps_growth() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    storage_budget: 3,
+                    vouches_required: 1,
+                    closed_electorate: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting
+                .add_voters(election_id, vec![accounts.bob, accounts.charlie])
+                .unwrap();
+            voting.open_registration(election_id).unwrap();
+            set_caller(accounts.django);
+            voting.register_candidate(election_id).unwrap();
+            assert_eq!(voting.get_storage_entries(election_id), 3);
+            set_caller(accounts.eve);
+            assert_eq!(
+                voting.register_candidate(election_id),
+                Err(RegistrationError::StorageBudgetExceeded)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                voting.vouch(election_id, accounts.frank),
+                Err(RegistrationError::StorageBudgetExceeded)
+            );
+
+            set_caller(accounts.alice);
+            voting.open_voting(election_id).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                voting.vote(election_id, accounts.eve),
+                Err(VoteError::StorageBudgetExceeded)
+            );
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(voting.get_storage_entries(election_id), 4);
+        }
+
+        /// We test that a cancelled election is unwound in chunks, refunding
+        /// every vote fee and deposit once.
+        #[ink::test]
+        fn cancelled_election_is_refunded_in_chunks() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(Default::default(), generous_limits()).unwrap();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    vote_fee: 10,
+                    candidacy_deposit: 100,
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(
+                voting.process_refunds(election_id, 10),
+                Err(VoteError::InvalidTransition)
+            );
+            voting.open_registration(election_id).unwrap();
+            set_caller(accounts.django);
+            set_value(100);
+            voting.register_candidate(election_id).unwrap();
+            set_caller(accounts.alice);
+            set_value(0);
+            voting.open_voting(election_id).unwrap();
+            set_value(10);
+            for voter in [accounts.bob, accounts.charlie] {
+                set_caller(voter);
+                voting.vote(election_id, accounts.django).unwrap();
+            }
+            set_value(0);
+            set_caller(accounts.alice);
+            voting.cancel(election_id).unwrap();
+
+            set_caller(accounts.eve);
+            assert_eq!(voting.process_refunds(election_id, 2), Ok(false));
+            assert_eq!(voting.get_claimable(accounts.bob), 10);
+            assert_eq!(voting.get_claimable(accounts.charlie), 10);
+            assert_eq!(voting.get_claimable(accounts.django), 0);
+            assert_eq!(voting.process_refunds(election_id, 2), Ok(true));
+            assert_eq!(voting.get_claimable(accounts.django), 100);
+            assert_eq!(voting.process_refunds(election_id, 2), Ok(true));
+            assert_eq!(voting.get_claimable(accounts.django), 100);
+            assert_eq!(voting.treasury_report().deposits, 0);
+            assert_eq!(voting.treasury_report().payouts, 120);
+        }
+
+        /// We test that anyone may garbage-collect an archived election's
+        /// ballots in chunks after the retention period, paying the treasury
+        /// its share, while its archived result stays readable.
+        #[ink::test]
+        fn gc_deletes_archived_ballots() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.set_entry_deposit(10).unwrap();
+            for voter in [accounts.bob, accounts.charlie, accounts.django] {
+                set_caller(voter);
+                voting.vote(0, accounts.eve).unwrap();
+            }
+            set_caller(accounts.alice);
+            assert_eq!(voting.gc(0, 2), Err(VoteError::InvalidTransition));
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.gc(0, 2), Err(VoteError::RetentionPending));
+            advance_blocks(RETENTION_BLOCKS);
+
+            set_caller(accounts.frank);
+            set_value(9);
+            assert_eq!(voting.gc(0, 2), Err(VoteError::IncorrectFee));
+            set_value(15);
+            assert_eq!(voting.gc(0, 2), Ok(false));
+            assert_eq!(voting.get_claimable(accounts.frank), 5);
+            assert!(!voting.already_voted.contains((0, accounts.bob)));
+            assert!(voting.already_voted.contains((0, accounts.django)));
+            set_value(5);
+            assert_eq!(voting.gc(0, 2), Ok(true));
+            assert!(!voting.already_voted.contains((0, accounts.django)));
+            assert_eq!(voting.get_treasury(), 15);
+            assert_eq!(voting.get_current_winner(0), vec![accounts.eve]);
+            assert_eq!(
+                voting
+                    .get_archived_result(0)
+                    .map(|archived| archived.totals),
+                Some(vec![(accounts.eve, 3)])
+            );
+            set_value(0);
+            assert_eq!(voting.gc(0, 2), Ok(true));
+            assert_eq!(emitted::<ElectionCollected>(), 3);
+        }
+
+        /// We test that a receiver called back while `claim` pays it out can
+        /// neither claim again nor vote, fund or register, and that the guard
+        /// is lifted once the payout is over.
+        #[ink::test]
+        fn payouts_cannot_be_reentered() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            set_value(100);
+            voting.fund_prize(0).unwrap();
+            set_value(0);
+            voting.vote(0, accounts.django).unwrap();
+            voting.finalize(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.claim_prize(0).unwrap();
+            let election_id = voting.create_election(Default::default()).unwrap();
+            voting.open_voting(election_id).unwrap();
+
+            // what a receiving contract sees when it calls back mid-transfer
+            voting.paying_out.set(&true);
+            assert_eq!(voting.claim(), Err(VoteError::Reentrancy));
+            assert_eq!(
+                voting.vote(election_id, accounts.eve),
+                Err(VoteError::Reentrancy)
+            );
+            assert_eq!(voting.fund_prize(election_id), Err(VoteError::Reentrancy));
+            assert_eq!(
+                voting.create_election(Default::default()),
+                Err(ConfigError::Reentrancy)
+            );
+            assert_eq!(
+                voting.register_candidate(election_id),
+                Err(RegistrationError::Reentrancy)
+            );
+            assert_eq!(voting.get_claimable(accounts.django), 100);
+
+            voting.paying_out.set(&false);
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 1_000_000,
+            );
+            assert_eq!(voting.claim(), Ok(100));
+            assert!(!voting.paying_out());
+            assert!(voting.vote(election_id, accounts.eve).is_ok());
+        }
+
+        /// We test that every field outside the root keeps the storage key
+        /// derived from its original name, and that no two keys collide.
+        #[ink::test]
+        fn storage_keys_are_pinned() {
+            use ink::{primitives::KeyComposer, storage::traits::StorageKey};
+            let voting = Voting::new();
+            let keys = [
+                (voting.elections.key(), "elections"),
+                (voting.runners.key(), "runners"),
+                (voting.votes.key(), "votes"),
+                (voting.electorate.key(), "electorate"),
+                (voting.electorate_at.key(), "electorate_at"),
+                (voting.electorate_position.key(), "electorate_position"),
+                (voting.slates.key(), "slates"),
+                (voting.profiles.key(), "profiles"),
+                (voting.candidate_categories.key(), "candidate_categories"),
+                (voting.category_members.key(), "category_members"),
+                (voting.constituency_of.key(), "constituency_of"),
+                (voting.constituency_votes.key(), "constituency_votes"),
+                (voting.standings.key(), "standings"),
+                (voting.races.key(), "races"),
+                (voting.race_of.key(), "race_of"),
+                (voting.rerun_of.key(), "rerun_of"),
+                (voting.latest_rerun.key(), "latest_rerun"),
+                (voting.profile_history.key(), "profile_history"),
+                (voting.statements.key(), "statements"),
+                (voting.verified.key(), "verified"),
+                (voting.vouchers_of.key(), "vouchers_of"),
+                (voting.vouching_barred.key(), "vouching_barred"),
+                (voting.banned_voters.key(), "banned_voters"),
+                (voting.already_voted.key(), "already_voted"),
+                (voting.approvals_cast.key(), "approvals_cast"),
+                (voting.approved.key(), "approved"),
+                (voting.endorsements.key(), "endorsements"),
+                (voting.endorsed.key(), "endorsed"),
+                (voting.recalls.key(), "recalls"),
+                (voting.disputes.key(), "disputes"),
+                (voting.tally_claims.key(), "tally_claims"),
+                (voting.refuted_tallies.key(), "refuted_tallies"),
+                (voting.sealed_ballots.key(), "sealed_ballots"),
+                (voting.key_shares.key(), "key_shares"),
+                (voting.revealed_ballots.key(), "revealed_ballots"),
+                (voting.nullifiers.key(), "nullifiers"),
+                (voting.lotteries.key(), "lotteries"),
+                (voting.sponsorships.key(), "sponsorships"),
+                (voting.paying_out.key(), "paying_out"),
+                (voting.recall_signed.key(), "recall_signed"),
+                (voting.recall_voted.key(), "recall_voted"),
+                (voting.consecutive_terms.key(), "consecutive_terms"),
+                (voting.win_history.key(), "win_history"),
+                (voting.archive.key(), "archive"),
+                (voting.archive_index.key(), "archive_index"),
+                (voting.tally_hashes.key(), "tally_hashes"),
+                (voting.mirrored.key(), "mirrored"),
+                (voting.ballot_branches.key(), "ballot_branches"),
+                (voting.ballot_roots.key(), "ballot_roots"),
+                (voting.rankings.key(), "rankings"),
+                (voting.transfers.key(), "transfers"),
+                (voting.stv_counts.key(), "stv_counts"),
+                (voting.stv_elected.key(), "stv_elected"),
+                (voting.sortition_draws.key(), "sortition_draws"),
+                (voting.deposits.key(), "deposits"),
+                (voting.prizes.key(), "prizes"),
+                (voting.campaign_funds.key(), "campaign_funds"),
+                (voting.campaign_pledges.key(), "campaign_pledges"),
+                (voting.claimable.key(), "claimable"),
+                (voting.token_claimable.key(), "token_claimable"),
+                (voting.banned.key(), "banned"),
+                (voting.voters.key(), "voters"),
+                (voting.voters_for.key(), "voters_for"),
+                (voting.ballots_for.key(), "ballots_for"),
+                (voting.vote_history.key(), "vote_history"),
+                (voting.vote_history_len.key(), "vote_history_len"),
+                (voting.reasons.key(), "reasons"),
+                (
+                    voting.reputation_adjustments.key(),
+                    "reputation_adjustments",
+                ),
+                (voting.participation.key(), "participation"),
+                (
+                    voting.participation_progress.key(),
+                    "participation_progress",
+                ),
+                (voting.candidacies.key(), "candidacies"),
+                (voting.referendums.key(), "referendums"),
+                (voting.referendum_voted.key(), "referendum_voted"),
+                (voting.surveys.key(), "surveys"),
+                (voting.survey_responded.key(), "survey_responded"),
+                (voting.scheduled_actions.key(), "scheduled_actions"),
+                (voting.roles.key(), "roles"),
+                (voting.block_ballots.key(), "block_ballots"),
+                (voting.block_registrations.key(), "block_registrations"),
+                (voting.last_registered.key(), "last_registered"),
+                (voting.role_permissions.key(), "role_permissions"),
+                (voting.approved_upgrades.key(), "approved_upgrades"),
+                (voting.partial_totals.key(), "partial_totals"),
+                (voting.bridges.key(), "bridges"),
+                (voting.relayed_from.key(), "relayed_from"),
+                (voting.relayed_turnout.key(), "relayed_turnout"),
+                (voting.identities.key(), "identities"),
+                (voting.registered_since.key(), "registered_since"),
+                (voting.identity_voted.key(), "identity_voted"),
+                (voting.ballot_nonces.key(), "ballot_nonces"),
+                (voting.aggregators.key(), "aggregators"),
+                (voting.delegates.key(), "delegates"),
+                (voting.delegators.key(), "delegators"),
+                (voting.topic_delegates.key(), "topic_delegates"),
+                (voting.topic_delegators.key(), "topic_delegators"),
+                (voting.split_delegates.key(), "split_delegates"),
+                (voting.split_delegators.key(), "split_delegators"),
+                (voting.split_spent.key(), "split_spent"),
+                (voting.invitation_codes.key(), "invitation_codes"),
+                (voting.referrals.key(), "referrals"),
+                (voting.display_names.key(), "display_names"),
+                (voting.name_owners.key(), "name_owners"),
+                (voting.refund_cursors.key(), "refund_cursors"),
+                (voting.gc_cursors.key(), "gc_cursors"),
+                (voting.slash_petitions.key(), "slash_petitions"),
+                (voting.slash_signed.key(), "slash_signed"),
+                (voting.turnout_histograms.key(), "turnout_histograms"),
+            ];
+            for (key, name) in keys {
+                assert_eq!(
+                    KeyComposer::compute_key("Voting", "", name),
+                    Ok(key),
+                    "{name}"
+                );
+            }
+            let mut distinct: Vec<u32> = keys.iter().map(|(key, _)| *key).collect();
+            distinct.sort_unstable();
+            distinct.dedup();
+            assert_eq!(distinct.len(), keys.len());
+            assert!(!distinct.contains(&0), "the root cell lives at key 0");
+            for key in [crate::delegator::LOGIC_KEY, crate::delegator::ADMIN_KEY] {
+                assert!(!distinct.contains(&key), "delegator cells stay apart");
+            }
+        }
+
+        /// We test that only the owner sweeps the treasury, and only once it
+        /// holds funds.
+        #[ink::test]
+        fn treasury_sweep_needs_owner_and_funds() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.sweep_treasury(accounts.frank),
+                Err(VoteError::NothingToClaim)
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                voting.sweep_treasury(accounts.frank),
+                Err(VoteError::Unauthorized)
+            );
+        }
+
+        /// We test that the code reports the crate version and its features.
+        #[ink::test]
+        fn version_and_capabilities_are_reported() {
+            use crate::capabilities::{DELEGATION, FEE_TOKENS, MULTI_ELECTION};
+            let voting = Voting::new();
+            assert_eq!(
+                voting.version(),
+                (
+                    env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap(),
+                    env!("CARGO_PKG_VERSION_MINOR").parse().unwrap(),
+                    env!("CARGO_PKG_VERSION_PATCH").parse().unwrap(),
+                )
+            );
+            let capabilities = voting.capabilities();
+            assert_eq!(capabilities & DELEGATION, DELEGATION);
+            assert_eq!(
+                capabilities & (MULTI_ELECTION | FEE_TOKENS),
+                MULTI_ELECTION | FEE_TOKENS
+            );
+            assert_eq!(capabilities >> 63, 0);
+        }
+
+        /// We test that interfaces are recognised by identifier and by selector.
+        #[ink::test]
+        fn interfaces_are_recognised() {
+            use crate::interfaces::{id, DELEGATION, REFERENDUM, VOTING_QUERIES};
+            let voting = Voting::new();
+            assert!(voting.supports(id(VOTING_QUERIES)));
+            assert!(voting.supports(id(DELEGATION)));
+            assert!(voting.supports(id(REFERENDUM)));
+            assert!(voting.supports(ink::selector_id!("delegate")));
+            assert!(voting.supports(0x5654_0002));
+            assert!(!voting.supports(ink::selector_id!("commit_vote")));
+            assert!(!voting.supports(0));
+            // these only compile if the contract dispatches the selectors
+            let _ = <Voting as ink::reflect::DispatchableMessageInfo<
+                { ink::selector_id!("delegate") },
+            >>::SELECTOR;
+            let _ = <Voting as ink::reflect::DispatchableMessageInfo<
+                { ink::selector_id!("claim_tokens") },
+            >>::SELECTOR;
+        }
+
+        /// We test that the standard selector set drives an election and
+        /// reports errors by code.
+        #[ink::test]
+        fn voting_standard_drives_an_election() {
+            use crate::interfaces::{id, VOTING_STANDARD};
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert!(voting.supports(id(VOTING_STANDARD)));
+            assert_eq!(VotingStandard::standard_version(&voting), 1);
+            assert_eq!(
+                VotingStandard::election_status(&voting, 0),
+                Some(ElectionStatus::Voting)
+            );
+
+            assert_eq!(
+                VotingStandard::cast_vote(&mut voting, 0, accounts.eve),
+                Ok(0)
+            );
+            assert_eq!(
+                VotingStandard::cast_vote(&mut voting, 0, accounts.eve),
+                Err(VoteError::AlreadyVoted.code())
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                VotingStandard::delegate_vote(&mut voting, accounts.charlie),
+                Ok(())
+            );
+            assert_eq!(
+                VotingStandard::delegate_vote(&mut voting, accounts.bob),
+                Err(VoteError::DelegationCycle.code())
+            );
+            assert_eq!(
+                VotingStandard::results(&voting, 0),
+                Some((vec![(accounts.eve, 1)], vec![accounts.eve]))
+            );
+            assert_eq!(VotingStandard::results(&voting, 9), None);
+        }
+
+        /// We test that contract ballots are rejected where configured, unless
+        /// the contract is an approved aggregator.
+        #[ink::test]
+        fn contract_ballots_need_an_approved_aggregator() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    reject_contracts: true,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_voting(0).unwrap();
+            ink::env::test::set_contract::<ink::env::DefaultEnvironment>(accounts.eve);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::ContractVoter)
+            );
+            assert_eq!(
+                voting.set_aggregator(accounts.eve, true),
+                Err(VoteError::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.set_aggregator(accounts.eve, true).unwrap();
+            assert!(voting.is_aggregator(accounts.eve));
+            assert_eq!(emitted::<AggregatorUpdated>(), 1);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(voting.get_votes(0, accounts.django), 2);
+
+            // elections without the flag take contract ballots regardless
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.set_aggregator(accounts.eve, false).unwrap();
+            let election_id = voting.create_election(ElectionConfig::default()).unwrap();
+            voting.open_voting(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting.vote(election_id, accounts.django).unwrap();
+        }
+
+        /// We test that ballot weight grows with an unbroken registration
+        /// tenure, up to the cap.
+        #[ink::test]
+        fn tenure_boosts_ballot_weight() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    tenure_boost: Some(TenureBoost {
+                        blocks_per_step: 10,
+                        max_multiplier: 3,
+                    }),
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_voting(0).unwrap();
+            voting.set_identity(accounts.bob, Some(1)).unwrap();
+            voting.set_identity(accounts.charlie, Some(2)).unwrap();
+            advance_blocks(15);
+            // re-registering under another identity keeps the tenure
+            voting.set_identity(accounts.bob, Some(3)).unwrap();
+            // leaving the registry resets it
+            voting.set_identity(accounts.charlie, None).unwrap();
+            voting.set_identity(accounts.charlie, Some(2)).unwrap();
+            assert_eq!(voting.get_registered_since(accounts.bob), Some(0));
+            assert_eq!(voting.get_registered_since(accounts.charlie), Some(15));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(voting.get_votes(0, accounts.django), 2 + 1 + 1);
+
+            advance_blocks(20);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    tenure_boost: Some(TenureBoost {
+                        blocks_per_step: 10,
+                        max_multiplier: 3,
+                    }),
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(voting.get_votes(election_id, accounts.django), 3);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    tenure_boost: Some(TenureBoost {
+                        blocks_per_step: 0,
+                        max_multiplier: 3,
+                    }),
+                    ..Default::default()
+                }),
+                Err(ConfigError::InvalidTenureBoost)
+            );
+        }
+
+        /// Signs a ballot for `candidate` with the ECDSA key `secret`, and
+        /// returns it with the account of the key.
+        fn sign_ballot(
+            voting: &Voting,
+            secret: [u8; 32],
+            election_id: ElectionId,
+            candidate: AccountId,
+            nonce: u64,
+        ) -> (AccountId, SignedBallot) {
+            use secp256k1::{Message, SecretKey, SECP256K1};
+            let secret = SecretKey::from_slice(&secret).unwrap();
+            let hash = voting.signed_ballot_hash(election_id, candidate, nonce);
+            let message = Message::from_digest_slice(hash.as_ref()).unwrap();
+            let (recovery_id, compact) = SECP256K1
+                .sign_ecdsa_recoverable(&message, &secret)
+                .serialize_compact();
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            let mut account = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(
+                &secret.public_key(SECP256K1).serialize(),
+                &mut account,
+            );
+            let ballot = SignedBallot {
+                candidate,
+                nonce,
+                signature,
+            };
+            (AccountId::from(account), ballot)
+        }
+
+        /// We test that signed ballots are settled in a batch, skipping the
+        /// ones that cannot be cast and refunding their fees.
+        #[ink::test]
+        fn signed_ballots_are_settled_in_batches() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                signed_ballots: true,
+                vote_fee: 10,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config.clone(), generous_limits()).unwrap();
+            voting.open_voting(0).unwrap();
+            let (first, ballot) = sign_ballot(&voting, [1; 32], 0, accounts.django, 0);
+            let (second, other) = sign_ballot(&voting, [2; 32], 0, accounts.django, 0);
+            let (_, again) = sign_ballot(&voting, [1; 32], 0, accounts.eve, 1);
+            let batch = vec![ballot, other, again];
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            set_value(20);
+            assert_eq!(
+                voting.settle_ballots(0, batch.clone()),
+                Err(VoteError::IncorrectFee)
+            );
+            set_value(30);
+            let outcomes = voting.settle_ballots(0, batch).unwrap();
+            assert!(outcomes[0].is_ok() && outcomes[1].is_ok());
+            assert_eq!(outcomes[2], Err(VoteError::AlreadyVoted));
+            let skipped = events_of::<BallotSkipped>();
+            assert_eq!(skipped.len(), 1);
+            let skipped =
+                <BallotSkipped as ink::scale::Decode>::decode(&mut &skipped[0].data[..]).unwrap();
+            assert_eq!(
+                (skipped.index, skipped.code),
+                (2, VoteError::AlreadyVoted.code())
+            );
+            assert_eq!(voting.get_votes(0, accounts.django), 2);
+            assert_eq!(voting.get_votes(0, accounts.eve), 0);
+            assert_eq!(voting.get_voters(0, 0, 10), [first, second]);
+            assert_eq!(voting.get_ballot_nonce(first), 1);
+            assert_eq!(voting.get_claimable(accounts.bob), 10);
+            assert_eq!(voting.elections.get(0).unwrap().fee_pot, 20);
+
+            // a settled ballot cannot be replayed elsewhere
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            set_value(0);
+            let election_id = voting.create_election(config).unwrap();
+            voting.open_voting(election_id).unwrap();
+            set_value(10);
+            let (_, replayed) = sign_ballot(&voting, [2; 32], election_id, accounts.django, 0);
+            assert_eq!(
+                voting.settle_ballots(election_id, vec![replayed]).unwrap(),
+                [Err(VoteError::StaleNonce)]
+            );
+
+            set_value(0);
+            let unsigned = voting.create_election(ElectionConfig::default()).unwrap();
+            assert_eq!(
+                voting.settle_ballots(unsigned, Vec::new()),
+                Err(VoteError::SignedBallotsDisabled)
+            );
+        }
+
+        /// We test that error codes are pinned and never shared.
+        #[ink::test]
+        fn error_codes_are_stable() {
+            assert_eq!(VoteError::AlreadyVoted.code(), 1001);
+            assert_eq!(VoteError::NoAdminBond.code(), 1104);
+            assert_eq!(VoteError::ElectionPaused.code(), 1105);
+            assert_eq!(VoteError::NotStale.code(), 1106);
+            assert_eq!(RegistrationError::ElectionNotFound.code(), 2001);
+            assert_eq!(RegistrationError::StorageBudgetExceeded.code(), 2028);
+            assert_eq!(RegistrationError::ElectionPaused.code(), 2029);
+            assert_eq!(VoteError::TransferFailed.code(), 1019);
+            assert_eq!(RegistrationError::TransferFailed.code(), 2016);
+        }
+
+        /// We test that a posted tally is refuted by a fraud proof, and that an
+        /// unchallenged one finalizes the election.
+        #[ink::test]
+        fn optimistic_tallies_are_open_to_fraud_proofs() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = |closes_at| ElectionConfig {
+                voting_closes_at: Some(Moment::Block(closes_at)),
+                optimistic_tally: Some(OptimisticTally {
+                    proposer: accounts.charlie,
+                    bond: 50,
+                    challenge_period: 5,
+                }),
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config(2), generous_limits()).unwrap();
+            voting.open_voting(0).unwrap();
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            voting.vote(0, accounts.eve).unwrap();
+
+            let wrong = vec![(accounts.django, 2), (accounts.eve, 5)];
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            set_value(50);
+            assert_eq!(
+                voting.propose_tally(0, wrong.clone()),
+                Err(VoteError::VotingStillOpen)
+            );
+            advance_blocks(3);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.propose_tally(0, wrong.clone()),
+                Err(VoteError::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.propose_tally(0, wrong.clone()).unwrap();
+            assert_eq!(
+                voting.propose_tally(0, wrong.clone()),
+                Err(VoteError::TallyAlreadyProposed)
+            );
+            set_value(0);
+            assert_eq!(voting.finalize(0), Err(VoteError::TallyUnderChallenge));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(voting.challenge_tally(0, 0), Err(VoteError::NoFraud));
+            voting.challenge_tally(0, 1).unwrap();
+            assert_eq!(voting.get_claimable(accounts.bob), 50);
+            assert_eq!(voting.get_tally_claim(0), None);
+            assert_eq!(emitted::<TallyRefuted>(), 1);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            set_value(50);
+            assert_eq!(voting.propose_tally(0, wrong), Err(VoteError::TallyRefuted));
+            set_value(0);
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_results(0).unwrap().totals[1], (accounts.eve, 1));
+
+            // an honest tally stands once the challenge period has passed
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let election_id = voting.create_election(config(10)).unwrap();
+            voting.open_voting(election_id).unwrap();
+            voting.vote(election_id, accounts.eve).unwrap();
+            advance_blocks(8);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            set_value(50);
+            voting
+                .propose_tally(election_id, vec![(accounts.eve, 1)])
+                .unwrap();
+            set_value(0);
+            advance_blocks(5);
+            assert_eq!(
+                voting.challenge_tally(election_id, 0),
+                Err(VoteError::ChallengeClosed)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.finalize(election_id).unwrap();
+            assert_eq!(voting.get_current_winner(election_id), [accounts.eve]);
+            assert_eq!(voting.get_claimable(accounts.charlie), 50);
+            assert_eq!(voting.treasury_report().deposits, 0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    voting_closes_at: None,
+                    ..config(30)
+                }),
+                Err(ConfigError::UnsupportedOptimisticTally)
+            );
+        }
+
+        /// We test that a successful recall vacates the winner's seat.
+        #[ink::test]
+        fn recall_vacates_the_seat() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    recall_threshold: 2,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(
+                voting.start_recall(election_id),
+                Err(VoteError::RecallUnavailable)
+            );
+            voting.finalize(election_id).unwrap();
+
+            voting.start_recall(election_id).unwrap();
+            assert_eq!(
+                voting.sign_recall(election_id),
+                Err(VoteError::AlreadySigned)
+            );
+            assert_eq!(
+                voting.vote_recall(election_id, true),
+                Err(VoteError::RecallClosed)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.sign_recall(election_id).unwrap();
+            assert_eq!(
+                voting.get_recall(election_id).unwrap().status,
+                RecallStatus::Voting
+            );
+
+            voting.vote_recall(election_id, true).unwrap();
+            assert_eq!(
+                voting.vote_recall(election_id, true),
+                Err(VoteError::AlreadyVoted)
+            );
+            assert_eq!(
+                voting.close_recall(election_id),
+                Err(VoteError::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.close_recall(election_id),
+                Ok(RecallStatus::Succeeded)
+            );
+            assert!(voting.is_seat_vacated(election_id));
+            assert_eq!(
+                voting.get_recall(election_id).unwrap().target,
+                accounts.django
+            );
+        }
+
+        /// We test that the incumbent is barred after the term limit and may run
+        /// again after sitting out a term.
+        #[ink::test]
+        fn term_limit_bars_the_incumbent() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let config = ElectionConfig {
+                term_limit: 2,
+                ..Default::default()
+            };
+            let run_term = |voting: &mut Voting, winner: AccountId| {
+                let election_id = voting.create_election(config.clone()).unwrap();
+                voting.open_voting(election_id).unwrap();
+                voting.vote(election_id, winner).unwrap();
+                voting.finalize(election_id).unwrap();
+                election_id
+            };
+
+            let first = run_term(&mut voting, accounts.django);
+            let second = run_term(&mut voting, accounts.django);
+            assert_eq!(voting.get_consecutive_terms(accounts.django), 2);
+            assert_eq!(voting.get_win_history(accounts.django), vec![first, second]);
+
+            let election_id = voting.create_election(config.clone()).unwrap();
+            voting.open_registration(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(
+                voting.register_candidate(election_id),
+                Err(RegistrationError::TermLimitReached)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            run_term(&mut voting, accounts.eve);
+            assert_eq!(voting.get_consecutive_terms(accounts.django), 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.register_candidate(election_id).unwrap();
+        }
+
+        /// We test that finalized elections are archived and survive pruning.
+        #[ink::test]
+        fn finalized_elections_are_archived() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.vote(0, accounts.django).unwrap();
+            let election_id = voting.create_election(ElectionConfig::default()).unwrap();
+            voting.open_voting(election_id).unwrap();
+            voting.vote(election_id, accounts.eve).unwrap();
+
+            voting.finalize(election_id).unwrap();
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_archived_count(), 2);
+            let (first_round, result) = voting.get_round_result(0).unwrap();
+            assert_eq!(first_round, election_id);
+            assert_eq!(result.totals, vec![(accounts.eve, 1)]);
+            assert_eq!(voting.get_round_result(2), None);
+            let page = voting.get_archived_results(1, 10);
+            assert_eq!(page.len(), 1);
+            assert_eq!(page[0].0, 0);
+            assert_eq!(page[0].1.winners, vec![accounts.django]);
+
+            assert_eq!(
+                voting.prune_election(0, vec![accounts.alice, accounts.bob]),
+                Ok(1)
+            );
+            assert_eq!(voting.get_votes(0, accounts.django), 0);
+            let archived = voting.get_archived_result(0).unwrap();
+            assert_eq!(archived.totals, vec![(accounts.django, 1)]);
+            assert_eq!(archived.turnout, 1);
+            assert_eq!(voting.get_current_winner(0), vec![accounts.django]);
+        }
+
+        /// We test that a cloned election keeps the rules but not the tallies.
+        #[ink::test]
+        fn clone_election_copies_config_only() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let source_id = voting
+                .create_election(ElectionConfig {
+                    min_candidates: 3,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.vote(0, accounts.django).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let clone_id = voting.clone_election(source_id).unwrap();
+            assert_eq!(voting.get_status(clone_id), Some(ElectionStatus::Pending));
+            assert_eq!(
+                voting.open_voting(clone_id),
+                Err(VoteError::NotEnoughCandidates)
+            );
+            assert_eq!(voting.clone_election(42), Err(VoteError::ElectionNotFound));
+
+            let clone_of_default = voting.clone_election(0).unwrap();
+            assert_eq!(voting.get_votes(clone_of_default, accounts.django), 0);
+            voting.open_voting(clone_of_default).unwrap();
+        }
+
+        /// We test that candidates update their profile until voting opens,
+        /// and that every replaced profile stays in the history.
+        #[ink::test]
+        fn candidate_profiles_keep_their_history() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(ElectionConfig::default(), generous_limits()).unwrap();
+            voting.open_registration(0).unwrap();
+            let first = Hash::from([1; 32]);
+            let second = Hash::from([2; 32]);
+            assert_eq!(
+                voting.update_profile(0, b"Alice".to_vec(), first),
+                Err(RegistrationError::NotRegistered)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.register_candidate(0).unwrap();
+            assert_eq!(
+                voting.update_profile(0, vec![b'b'; 65], first),
+                Err(RegistrationError::NameTooLong)
+            );
+            voting.update_profile(0, b"Bob".to_vec(), first).unwrap();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            voting.update_profile(0, b"Bob".to_vec(), second).unwrap();
+            assert_eq!(emitted::<CandidateProfileUpdated>(), 2);
+
+            let profile = voting.get_profile(0, accounts.bob).unwrap();
+            assert_eq!((profile.manifesto, profile.revisions), (second, 1));
+            assert_eq!(
+                voting.get_profile_history(0, accounts.bob, 0, 10),
+                [ProfileRevision {
+                    name: b"Bob".to_vec(),
+                    manifesto: first,
+                    updated_at: 0,
+                    replaced_at: 1,
+                }]
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.update_profile(0, b"Bob".to_vec(), first),
+                Err(RegistrationError::RegistrationClosed)
+            );
+        }
+
+        /// We test that a candidate replaces its profile at most once per
+        /// cooldown, every replaced profile staying in the history.
+        #[ink::test]
+        fn profile_changes_wait_out_the_cooldown() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                profile_cooldown: 3,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            voting.open_registration(0).unwrap();
+            set_caller(accounts.bob);
+            voting.register_candidate(0).unwrap();
+            let manifesto = Hash::from([1; 32]);
+            voting
+                .update_profile(0, b"Bob".to_vec(), manifesto)
+                .unwrap();
+            advance_blocks(2);
+            assert_eq!(
+                voting.update_profile(0, b"Alice".to_vec(), manifesto),
+                Err(RegistrationError::CooldownActive)
+            );
+            advance_blocks(1);
+            voting
+                .update_profile(0, b"Bobby".to_vec(), manifesto)
+                .unwrap();
+            assert_eq!(
+                voting.update_profile(0, b"Alice".to_vec(), manifesto),
+                Err(RegistrationError::CooldownActive)
+            );
+
+            let history = voting.get_profile_history(0, accounts.bob, 0, 10);
+            assert_eq!(history.len(), 1);
+            assert_eq!(
+                (history[0].name.as_slice(), history[0].replaced_at),
+                (&b"Bob"[..], 3)
+            );
+            assert_eq!(voting.get_profile(0, accounts.bob).unwrap().name, b"Bobby");
+        }
+
+        /// We test that the moderator, or else the admin, grants verified
+        /// badges, and that a profile change withdraws them.
+        #[ink::test]
+        fn moderators_verify_candidates() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(ElectionConfig::default(), generous_limits()).unwrap();
+            voting.open_registration(0).unwrap();
+            for candidate in [accounts.bob, accounts.charlie] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(candidate);
+                voting.register_candidate(0).unwrap();
+            }
+            assert_eq!(
+                voting.set_verified(0, accounts.bob, true),
+                Err(VoteError::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.set_verified(0, accounts.django, true),
+                Err(VoteError::NotACandidate)
+            );
+            voting.set_verified(0, accounts.bob, true).unwrap();
+            assert_eq!(
+                voting.get_candidate_info(0, accounts.bob),
+                Some(CandidateInfo {
+                    candidate: accounts.bob,
+                    votes: 0,
+                    verified: true,
+                    profile: None,
+                    referred_by: None,
+                    display_name: None,
+                })
+            );
+            assert_eq!(voting.get_candidate_info(0, accounts.django), None);
+
+            voting.set_moderator(Some(accounts.eve)).unwrap();
+            assert_eq!(
+                voting.set_verified(0, accounts.charlie, true),
+                Err(VoteError::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting.set_verified(0, accounts.charlie, true).unwrap();
+            assert!(voting.is_verified(0, accounts.charlie));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting
+                .update_profile(0, b"B0b".to_vec(), Hash::from([1; 32]))
+                .unwrap();
+            assert!(!voting.is_verified(0, accounts.bob));
+            assert_eq!(emitted::<CandidateVerified>(), 3);
+        }
+
+        /// We test that candidates are listed and ranked per category.
+        #[ink::test]
+        fn categories_group_candidates() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(ElectionConfig::default(), generous_limits()).unwrap();
+            voting.open_registration(0).unwrap();
+            for candidate in [accounts.bob, accounts.charlie, accounts.django] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(candidate);
+                voting.register_candidate(0).unwrap();
+            }
+            assert_eq!(
+                voting.set_categories(0, accounts.bob, vec![1]),
+                Err(VoteError::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.set_categories(0, accounts.eve, vec![1]),
+                Err(VoteError::NotACandidate)
+            );
+            assert_eq!(
+                voting.set_categories(0, accounts.bob, (0..9).collect()),
+                Err(VoteError::TooManyCategories)
+            );
+            voting.set_categories(0, accounts.bob, vec![2, 1]).unwrap();
+            voting.set_categories(0, accounts.charlie, vec![1]).unwrap();
+            voting
+                .set_categories(0, accounts.django, vec![2, 2])
+                .unwrap();
+            assert_eq!(voting.get_categories(0, accounts.bob), [1, 2]);
+            assert_eq!(voting.get_categories(0, accounts.django), [2]);
+            assert_eq!(
+                voting.get_candidates_by_category(0, 1, 0, 10),
+                [accounts.bob, accounts.charlie]
+            );
+            assert_eq!(
+                voting.get_candidates_by_category(0, 1, 1, 10),
+                [accounts.charlie]
+            );
+
+            // retagging moves the candidate out of its old categories
+            voting.set_categories(0, accounts.bob, vec![2]).unwrap();
+            assert_eq!(
+                voting.get_candidates_by_category(0, 1, 0, 10),
+                [accounts.charlie]
+            );
+            assert_eq!(
+                voting.get_candidates_by_category(0, 2, 0, 10),
+                [accounts.django, accounts.bob]
+            );
+
+            voting.open_voting(0).unwrap();
+            assert_eq!(
+                voting.set_categories(0, accounts.bob, vec![1]),
+                Err(VoteError::InvalidTransition)
+            );
+            voting.vote(0, accounts.bob).unwrap();
+            for voter in [accounts.eve, accounts.frank] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(0, accounts.bob).unwrap();
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(voting.get_category_winner(0, 1), [accounts.charlie]);
+            assert_eq!(voting.get_category_winner(0, 2), [accounts.bob]);
+            assert!(voting.get_category_winner(0, 3).is_empty());
+        }
+
+        /// We test that one ballot covers every race of an election.
+        #[ink::test]
+        fn races_are_voted_together() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(ElectionConfig::default(), generous_limits()).unwrap();
+            let treasurer = voting.create_election(ElectionConfig::default()).unwrap();
+            let secretary = voting.create_election(ElectionConfig::default()).unwrap();
+            assert_eq!(voting.add_race(0, 0), Err(VoteError::AlreadyARace));
+            voting.add_race(0, treasurer).unwrap();
+            voting.add_race(0, secretary).unwrap();
+            assert_eq!(
+                voting.add_race(treasurer, secretary),
+                Err(VoteError::AlreadyARace)
+            );
+            assert_eq!(voting.get_races(0), [0, treasurer, secretary]);
+            assert_eq!(voting.get_race_of(secretary), Some(0));
+            assert_eq!(emitted::<RaceAdded>(), 2);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let own = voting.create_election(ElectionConfig::default()).unwrap();
+            assert_eq!(voting.add_race(0, own), Err(VoteError::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(0).unwrap();
+            voting.open_voting(treasurer).unwrap();
+            assert_eq!(
+                voting.vote_races(0, vec![accounts.bob, accounts.charlie]),
+                Err(VoteError::WrongRaceCount)
+            );
+            // a race still pending rejects the whole ballot
+            let choices = vec![accounts.bob, accounts.charlie, accounts.django];
+            assert_eq!(
+                voting.vote_races(0, choices.clone()),
+                Err(VoteError::VotingClosed)
+            );
+            assert_eq!(voting.get_votes(0, accounts.bob), 0);
+
+            voting.open_voting(secretary).unwrap();
+            assert_eq!(voting.vote_races(0, choices.clone()).unwrap().len(), 3);
+            assert_eq!(
+                voting.vote_races(0, choices.clone()),
+                Err(VoteError::AlreadyVoted)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting
+                .vote_races(0, vec![accounts.bob, accounts.eve, accounts.django])
+                .unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            voting
+                .vote_races(0, vec![accounts.charlie, accounts.eve, accounts.django])
+                .unwrap();
+            assert_eq!(voting.get_current_winner(0), [accounts.bob]);
+            assert_eq!(voting.get_current_winner(treasurer), [accounts.eve]);
+            assert_eq!(voting.get_votes(secretary, accounts.django), 3);
+        }
+
+        /// We test that the slate can be seeded at instantiation.
+        #[ink::test]
+        fn new_with_candidates_seeds_the_ballot() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_candidates(vec![accounts.django, accounts.eve, accounts.django]);
+            assert_eq!(
+                voting.get_results(0).unwrap().totals,
+                vec![(accounts.django, 0), (accounts.eve, 0)]
+            );
+
+            voting.vote(0, accounts.eve).unwrap();
+            assert_eq!(voting.get_current_winner(0), vec![accounts.eve]);
+        }
+
+        /// We test that the configuration is validated at instantiation.
+        #[ink::test]
+        fn new_with_config_validates() {
+            let config = ElectionConfig {
+                voting_opens_at: Some(Moment::Block(10)),
+                voting_closes_at: Some(Moment::Block(10)),
+                ..Default::default()
+            };
+            assert_eq!(
+                Voting::new_with_config(config, FeeLimits::default()).err(),
+                Some(ConfigError::VotingClosesBeforeOpening)
+            );
+
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                voting_closes_at: Some(Moment::Block(1)),
+                ..Default::default()
+            };
+            assert_eq!(
+                Voting::new_with_config(config, FeeLimits::default()).err(),
+                Some(ConfigError::VotingClosesInPast)
+            );
+
+            let voting =
+                Voting::new_with_config(ElectionConfig::default(), FeeLimits::default()).unwrap();
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Pending));
+        }
+
+        /// We test that deadlines, quorum and fees are enforced.
+        #[ink::test]
+        fn config_rules_are_enforced() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    voting_opens_at: Some(Moment::Block(1)),
+                    voting_closes_at: Some(Moment::Block(3)),
+                    quorum: 2,
+                    vote_fee: 10,
+                    candidacy_deposit: 100,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_registration(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(
+                voting.register_candidate(0),
+                Err(RegistrationError::IncorrectDeposit)
+            );
+            set_value(100);
+            voting.register_candidate(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(voting.open_voting(0), Err(VoteError::VotingNotOpenYet));
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            voting.open_voting(0).unwrap();
+
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::IncorrectFee)
+            );
+            set_value(10);
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(voting.finalize(0), Err(VoteError::VotingStillOpen));
+
+            advance_blocks(3);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::VotingClosed)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(voting.finalize(0), Err(VoteError::QuorumNotReached));
+        }
+
+        /// We test that a scheduled election opens on its own once its
+        /// opening has passed, and expires if it closes before opening.
+        #[ink::test]
+        fn phases_advance_lazily() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    voting_opens_at: Some(Moment::Block(2)),
+                    voting_closes_at: Some(Moment::Block(5)),
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            let expiring = voting
+                .create_election(ElectionConfig {
+                    voting_opens_at: Some(Moment::Block(2)),
+                    voting_closes_at: Some(Moment::Block(5)),
+                    min_candidates: 1,
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(voting.vote(0, accounts.eve), Err(VoteError::VotingClosed));
+
+            advance_blocks(2);
+            set_caller(accounts.bob);
+            voting.vote(0, accounts.eve).unwrap();
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Voting));
+            assert_eq!(voting.advance_phase(expiring), Ok(ElectionStatus::Pending));
+            set_caller(accounts.alice);
+            assert_eq!(voting.open_voting(0), Err(VoteError::InvalidTransition));
+
+            advance_blocks(4);
+            assert_eq!(
+                voting.advance_phase(expiring),
+                Ok(ElectionStatus::Cancelled)
+            );
+            voting.finalize(0).unwrap();
+        }
+
+        /// We test that a paused election takes no ballot and does not close
+        /// while other elections go on, and that resuming it pushes its
+        /// deadline back by the pause.
+        #[ink::test]
+        fn elections_pause_independently() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                voting_closes_at: Some(Moment::Block(10)),
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config.clone(), generous_limits()).unwrap();
+            voting.open_voting(0).unwrap();
+            let other = voting.create_election(config).unwrap();
+            assert_eq!(voting.resume_election(0), Err(VoteError::InvalidTransition));
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.pause_election(0), Err(VoteError::Unauthorized));
+            set_caller(accounts.alice);
+            voting.pause_election(0).unwrap();
+            assert!(voting.is_election_paused(0));
+            assert!(!voting.is_election_paused(other));
+            assert_eq!(voting.pause_election(0), Err(VoteError::ElectionPaused));
+            assert_eq!(emitted::<ElectionPauseChanged>(), 1);
+
+            set_caller(accounts.bob);
+            assert_eq!(voting.vote(0, accounts.eve), Err(VoteError::ElectionPaused));
+            set_caller(accounts.alice);
+            voting.open_voting(other).unwrap();
+
+            advance_blocks(5);
+            voting.resume_election(0).unwrap();
+            assert_eq!(
+                voting.get_config(0).unwrap().voting_closes_at,
+                Some(Moment::Block(15))
+            );
+            assert_eq!(
+                voting.get_config(other).unwrap().voting_closes_at,
+                Some(Moment::Block(10))
+            );
+            set_caller(accounts.bob);
+            voting.vote(0, accounts.eve).unwrap();
+
+            advance_blocks(6);
+            assert_eq!(voting.finalize(0), Err(VoteError::VotingStillOpen));
+        }
+
+        /// We test that a stale election expires rather than being paused,
+        /// so that no pause holds off its expiry.
+        #[ink::test]
+        fn stale_elections_cannot_be_paused() {
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    voting_closes_at: Some(Moment::Block(10)),
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_voting(0).unwrap();
+
+            advance_blocks(11 + EXPIRY_BLOCKS);
+            assert_eq!(voting.pause_election(0), Err(VoteError::InvalidTransition));
+            assert!(!voting.is_election_paused(0));
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Expired));
+            assert_eq!(voting.resume_election(0), Err(VoteError::InvalidTransition));
+            assert_eq!(emitted::<ElectionExpired>(), 1);
+        }
+
+        /// We test that an election left unfinalized past the expiry window
+        /// expires, paying the keeper and unlocking its refunds and bond.
+        #[ink::test]
+        fn stale_elections_expire() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.set_admin_bond(50).unwrap();
+            let config = ElectionConfig {
+                voting_closes_at: Some(Moment::Block(10)),
+                ..Default::default()
+            };
+            set_value(50);
+            let stale = voting.create_election(config.clone()).unwrap();
+            let lazy = voting.create_election(config).unwrap();
+            set_value(0);
+            for election_id in [stale, lazy] {
+                voting.open_voting(election_id).unwrap();
+            }
+            set_caller(accounts.bob);
+            voting.vote(stale, accounts.django).unwrap();
+            set_value(20);
+            voting.tip_bounty(stale).unwrap();
+            set_value(0);
+
+            advance_blocks(11);
+            set_caller(accounts.eve);
+            assert_eq!(voting.expire(stale), Err(VoteError::NotStale));
+            assert_eq!(voting.expire(0), Err(VoteError::NotStale));
+
+            advance_blocks(EXPIRY_BLOCKS);
+            assert_eq!(voting.expire(stale), Ok(25));
+            assert_eq!(voting.get_status(stale), Some(ElectionStatus::Expired));
+            assert_eq!(voting.get_claimable(accounts.eve), 25);
+            assert_eq!(voting.expire(stale), Err(VoteError::NotStale));
+            assert_eq!(voting.finalize(stale), Err(VoteError::InvalidTransition));
+            assert_eq!(voting.process_refunds(stale, 100), Ok(true));
+            assert_eq!(voting.advance_phase(lazy), Ok(ElectionStatus::Expired));
+            assert_eq!(voting.get_claimable(accounts.eve), 30);
+            assert_eq!(emitted::<ElectionExpired>(), 2);
+
+            set_caller(accounts.alice);
+            assert_eq!(voting.reclaim_admin_bond(stale), Ok(45));
+            assert_eq!(voting.reclaim_admin_bond(lazy), Ok(45));
+            assert_eq!(voting.treasury_report().deposits, 0);
+            assert_eq!(voting.treasury_report().prize_escrow, 0);
+        }
+
+        /// We test that any message taking a stale election expires it
+        /// first, while read-only ones report the phase last stored.
+        #[ink::test]
+        fn messages_expire_stale_elections() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                voting_closes_at: Some(Moment::Block(10)),
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config.clone(), generous_limits()).unwrap();
+            let other = voting.create_election(config).unwrap();
+            for election_id in [0, other] {
+                voting.open_voting(election_id).unwrap();
+            }
+
+            advance_blocks(11 + EXPIRY_BLOCKS);
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Voting));
+            assert_eq!(voting.cancel(0), Err(VoteError::InvalidTransition));
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Expired));
+            set_caller(accounts.bob);
+            assert!(voting.register_voter(other).is_err());
+            assert_eq!(voting.get_status(other), Some(ElectionStatus::Expired));
+            assert_eq!(emitted::<ElectionExpired>(), 2);
+            assert_eq!(voting.expire(0), Err(VoteError::NotStale));
+        }
+
+        /// We test that deadlines can be given as timestamps, and that the
+        /// voting window must be neither too short nor too long.
+        #[ink::test]
+        fn timestamp_deadlines_work() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_timestamp(1_000);
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    voting_opens_at: Some(Moment::Time(2_000)),
+                    voting_closes_at: Some(Moment::Time(8_000)),
+                    ..Default::default()
+                },
+                FeeLimits::default(),
+            )
+            .unwrap();
+            assert_eq!(voting.open_voting(0), Err(VoteError::VotingNotOpenYet));
+
+            set_timestamp(2_000);
+            voting.open_voting(0).unwrap();
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(voting.finalize(0), Err(VoteError::VotingStillOpen));
+
+            set_timestamp(8_001);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::VotingClosed)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+
+            assert_eq!(
+                voting
+                    .create_election(ElectionConfig {
+                        voting_closes_at: Some(Moment::Time(8_000)),
+                        ..Default::default()
+                    })
+                    .err(),
+                Some(ConfigError::VotingClosesInPast)
+            );
+            assert_eq!(
+                voting
+                    .create_election(ElectionConfig {
+                        voting_closes_at: Some(Moment::Time(9_000)),
+                        ..Default::default()
+                    })
+                    .err(),
+                Some(ConfigError::VotingWindowTooShort)
+            );
+            assert_eq!(
+                voting
+                    .create_election(ElectionConfig {
+                        voting_opens_at: Some(Moment::Block(MAX_VOTING_BLOCKS)),
+                        voting_closes_at: Some(Moment::Block(MAX_VOTING_BLOCKS + 1)),
+                        ..Default::default()
+                    })
+                    .err(),
+                None
+            );
+            assert_eq!(
+                voting
+                    .create_election(ElectionConfig {
+                        voting_closes_at: Some(Moment::Block(MAX_VOTING_BLOCKS + 1)),
+                        ..Default::default()
+                    })
+                    .err(),
+                Some(ConfigError::VotingWindowTooLong)
+            );
+        }
+
+        /// We test that a campaign fund goes to a candidate reaching the
+        /// threshold, and back to the supporters of one who does not.
+        #[ink::test]
+        fn campaign_funds_follow_the_threshold() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    campaign_threshold: 2,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_voting(0).unwrap();
+            assert_eq!(
+                voting.fund_campaign(0, accounts.bob),
+                Err(VoteError::NotACandidate)
+            );
+            voting.vote(0, accounts.bob).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(0, accounts.django).unwrap();
+
+            let pledge = |voting: &mut Voting, supporter, candidate, amount| {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(supporter);
+                set_value(amount);
+                voting.fund_campaign(0, candidate).unwrap();
+                set_value(0);
+            };
+            pledge(&mut voting, accounts.eve, accounts.bob, 100);
+            pledge(&mut voting, accounts.frank, accounts.bob, 50);
+            pledge(&mut voting, accounts.frank, accounts.django, 30);
+            assert_eq!(voting.get_campaign_fund(0, accounts.bob), 150);
+            assert_eq!(voting.treasury_report().deposits, 180);
+            assert_eq!(
+                voting.refund_campaign_pledge(0, accounts.django),
+                Err(VoteError::ResultNotEffective)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.vote(0, accounts.bob).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            assert_eq!(
+                voting.refund_campaign_pledge(0, accounts.bob),
+                Err(VoteError::CampaignThresholdReached)
+            );
+            assert_eq!(
+                voting.release_campaign_fund(0, accounts.django),
+                Err(VoteError::CampaignThresholdMissed)
+            );
+            assert_eq!(voting.refund_campaign_pledge(0, accounts.django), Ok(30));
+            assert_eq!(voting.get_claimable(accounts.frank), 30);
+            assert_eq!(voting.release_campaign_fund(0, accounts.bob), Ok(150));
+            assert_eq!(voting.get_claimable(accounts.bob), 150);
+            assert_eq!(
+                voting.release_campaign_fund(0, accounts.bob),
+                Err(VoteError::NothingToClaim)
+            );
+            assert_eq!(
+                voting.get_campaign_pledge(0, accounts.bob, accounts.eve),
+                100
+            );
+            assert_eq!(voting.treasury_report().deposits, 0);
+        }
+
+        /// We test that a tied prize pool is split evenly, remainder to the treasury.
+        #[ink::test]
+        fn tied_winners_split_the_prize() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            set_value(101);
+            voting.fund_prize(0).unwrap();
+            set_value(0);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.eve).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_prize(0, accounts.django), 50);
+            assert_eq!(voting.get_prize(0, accounts.eve), 50);
+            assert_eq!(voting.get_treasury(), 1);
+
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 1_000_101,
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(voting.claim_prize(0), Ok(50));
+            assert_eq!(voting.claim_prize(0), Err(VoteError::NothingToClaim));
+            assert_eq!(voting.get_prize(0, accounts.eve), 50);
+            assert_eq!(voting.get_claimable(accounts.django), 50);
+        }
+
+        /// We test that `claim` pays out everything owed to the caller, once.
+        #[ink::test]
+        fn claim_pays_out_the_ledger() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            set_value(100);
+            voting.fund_prize(0).unwrap();
+            set_value(0);
+            voting.vote(0, accounts.django).unwrap();
+            voting.finalize(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(voting.claim(), Err(VoteError::NothingToClaim));
+            voting.claim_prize(0).unwrap();
+            assert_eq!(emitted::<Credited>(), 1);
+            assert_eq!(voting.get_claimable(accounts.django), 100);
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 1_000_000,
+            );
+            let before = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                accounts.django,
+            )
+            .unwrap();
+            assert_eq!(voting.claim(), Ok(100));
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    accounts.django
+                ),
+                Ok(before + 100)
+            );
+            assert_eq!(voting.get_claimable(accounts.django), 0);
+            assert_eq!(voting.claim(), Err(VoteError::NothingToClaim));
+            assert_eq!(emitted::<Claimed>(), 1);
+        }
+
+        /// We test that the protocol's share of collected fees is routed on finalization.
+        #[ink::test]
+        fn protocol_fee_is_routed_on_finalize() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    vote_fee: 1_000,
+                    candidacy_deposit: 9_000,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            let protocol_fee = ProtocolFee {
+                beneficiary: accounts.charlie,
+                basis_points: 500,
+            };
+            assert_eq!(
+                voting.set_protocol_fee(ProtocolFee {
+                    basis_points: MAX_PROTOCOL_FEE_BPS + 1,
+                    ..protocol_fee.clone()
+                }),
+                Err(VoteError::FeeTooHigh)
+            );
+            voting.set_protocol_fee(protocol_fee.clone()).unwrap();
+            assert_eq!(voting.get_protocol_fee(), protocol_fee);
+
+            voting.open_registration(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            set_value(9_000);
+            voting.register_candidate(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            set_value(0);
+            voting.open_voting(0).unwrap();
+            set_value(1_000);
+            voting.vote(0, accounts.django).unwrap();
+
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_claimable(accounts.charlie), 500);
+            assert_eq!(voting.get_treasury(), 9_500);
+            assert_eq!(emitted::<FeeRouted>(), 1);
+        }
+
+        /// We test that the treasury report moves funds between its buckets as
+        /// they are collected, settled and paid out.
+        #[ink::test]
+        fn treasury_report_tracks_every_bucket() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    vote_fee: 1_000,
+                    candidacy_deposit: 9_000,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting
+                .set_protocol_fee(ProtocolFee {
+                    beneficiary: accounts.charlie,
+                    basis_points: 500,
+                })
+                .unwrap();
+            voting.open_registration(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            set_value(9_000);
+            voting.register_candidate(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            set_value(300);
+            voting.fund_prize(0).unwrap();
+            set_value(0);
+            voting.open_voting(0).unwrap();
+            set_value(1_000);
+            voting.vote(0, accounts.django).unwrap();
+            set_value(0);
+            assert_eq!(
+                voting.treasury_report(),
+                TreasuryReport {
+                    fee_income: 1_000,
+                    prize_escrow: 300,
+                    deposits: 9_000,
+                    payouts: 0,
+                }
+            );
+
+            voting.finalize(0).unwrap();
+            assert_eq!(
+                voting.treasury_report(),
+                TreasuryReport {
+                    fee_income: 9_500,
+                    prize_escrow: 300,
+                    deposits: 0,
+                    payouts: 500,
+                }
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.claim_prize(0).unwrap();
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 1_000_000,
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.claim().unwrap();
+            assert_eq!(
+                voting.treasury_report(),
+                TreasuryReport {
+                    fee_income: 9_500,
+                    prize_escrow: 0,
+                    deposits: 0,
+                    payouts: 300,
+                }
+            );
+            assert_eq!(voting.get_treasury(), 9_500);
+        }
+
+        /// We test that only the surplus above the contract's obligations can
+        /// be withdrawn, and only by the owner.
+        #[ink::test]
+        fn only_the_surplus_can_be_withdrawn() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    vote_fee: 1_000,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            set_value(300);
+            voting.fund_prize(0).unwrap();
+            set_value(0);
+            voting.open_voting(0).unwrap();
+            set_value(1_000);
+            voting.vote(0, accounts.django).unwrap();
+            set_value(0);
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 1_001_300,
+            );
+            // the unsettled vote fee and the prize pool are held back
+            assert_eq!(voting.get_surplus(), 1_000_000);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.withdraw_surplus(accounts.bob, 1),
+                Err(VoteError::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.withdraw_surplus(accounts.eve, 1_000_001),
+                Err(VoteError::ExceedsSurplus)
+            );
+            let before =
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve)
+                    .unwrap();
+            voting.withdraw_surplus(accounts.eve, 400_000).unwrap();
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve),
+                Ok(before + 400_000)
+            );
+            assert_eq!(voting.get_surplus(), 600_000);
+            assert_eq!(emitted::<SurplusWithdrawn>(), 1);
+
+            // once finalized, the fee is the treasury's and may be withdrawn
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_treasury(), 1_000);
+            assert_eq!(voting.get_surplus(), 601_000);
+            voting.withdraw_surplus(accounts.eve, 601_000).unwrap();
+            assert_eq!(voting.get_treasury(), 0);
+            assert_eq!(voting.treasury_report().prize_escrow, 300);
+        }
+
+        /// We test that elections taking a fee token refuse native payments and
+        /// route the collected tokens to the beneficiary and the admin.
+        #[ink::test]
+        fn token_fees_bypass_native_accounting() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let token = accounts.frank;
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    vote_fee: 1_000,
+                    candidacy_deposit: 9_000,
+                    fee_token: Some(token),
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting
+                .set_protocol_fee(ProtocolFee {
+                    beneficiary: accounts.charlie,
+                    basis_points: 500,
+                })
+                .unwrap();
+            voting.open_registration(0).unwrap();
+            assert_eq!(
+                voting.can_register(accounts.django, 0, 9_000),
+                Err(RegistrationError::IncorrectDeposit)
+            );
+            assert_eq!(voting.can_register(accounts.django, 0, 0), Ok(()));
+            voting.open_voting(0).unwrap();
+            set_value(1_000);
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::IncorrectFee)
+            );
+            set_value(0);
+
+            // the off-chain engine cannot call the token, so book its fees directly
+            voting.update_election(0, |election| election.fee_pot = 2_000);
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_token_claimable(token, accounts.charlie), 100);
+            assert_eq!(voting.get_token_claimable(token, accounts.alice), 1_900);
+            assert_eq!(voting.get_claimable(accounts.charlie), 0);
+            assert_eq!(voting.get_treasury(), 0);
+            assert_eq!(voting.treasury_report(), TreasuryReport::default());
+            assert_eq!(emitted::<TokensCredited>(), 2);
+        }
+
+        /// We test that an election's configuration is returned as it stands.
+        #[ink::test]
+        fn election_config_is_exposed() {
+            let mut voting =
+                Voting::new_with_config(ElectionConfig::default(), generous_limits()).unwrap();
+            let config = ElectionConfig {
+                vote_fee: 10,
+                public_ballot: true,
+                mode: VotingMode::Approval(2),
+                ..Default::default()
+            };
+            let election_id = voting.create_election(config.clone()).unwrap();
+            assert_eq!(voting.get_config(election_id), Some(config.clone()));
+            voting.set_vote_fee(election_id, 20).unwrap();
+            assert_eq!(
+                voting.get_config(election_id),
+                Some(ElectionConfig {
+                    vote_fee: 20,
+                    ..config
+                })
+            );
+            assert_eq!(voting.get_config(election_id + 1), None);
+        }
+
+        /// We test that anyone may finalize an election after its deadline and
+        /// collect the bounty once, while only the admin may finalize earlier.
+        #[ink::test]
+        fn keepers_finalize_for_a_bounty() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    voting_closes_at: Some(Moment::Block(2)),
+                    vote_fee: 100,
+                    finalization_bounty: 30,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_voting(0).unwrap();
+            set_value(100);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            set_value(0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(voting.finalize(0), Err(VoteError::VotingStillOpen));
+            advance_blocks(3);
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_claimable(accounts.eve), 30);
+            assert_eq!(voting.get_treasury(), 170);
+            assert_eq!(voting.treasury_report().payouts, 30);
+            assert_eq!(voting.finalize(0), Err(VoteError::InvalidTransition));
+            assert_eq!(voting.get_claimable(accounts.eve), 30);
+            assert_eq!(emitted::<BountyPaid>(), 1);
+
+            // without a deadline only the admin finalizes, and earns nothing
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    finalization_bounty: 30,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(voting.finalize(election_id), Err(VoteError::Unauthorized));
+        }
+
+        /// We test that quadratic ballots cost the square of their votes, up
+        /// to the per-voter cap.
+        #[ink::test]
+        fn quadratic_votes_are_priced_by_their_square() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    mode: VotingMode::Quadratic(3),
+                    vote_fee: 10,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_voting(0).unwrap();
+
+            set_value(30);
+            assert_eq!(
+                voting.vote_quadratic(0, accounts.django, 3),
+                Err(VoteError::IncorrectFee)
+            );
+            set_value(90);
+            voting.vote_quadratic(0, accounts.django, 3).unwrap();
+            assert_eq!(voting.get_votes(0, accounts.django), 3);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            set_value(160);
+            assert_eq!(
+                voting.vote_quadratic(0, accounts.eve, 4),
+                Err(VoteError::InvalidVoteCount)
+            );
+            set_value(0);
+            assert_eq!(
+                voting.vote_quadratic(0, accounts.eve, 0),
+                Err(VoteError::InvalidVoteCount)
+            );
+            set_value(10);
+            voting.vote(0, accounts.eve).unwrap();
+            assert_eq!(voting.get_votes(0, accounts.eve), 1);
+            assert_eq!(voting.get_config(0).unwrap().mode, VotingMode::Quadratic(3));
+            assert_eq!(voting.elections.get(0).unwrap().fee_pot, 100);
+
+            // other modes take no quadratic ballots
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            set_value(0);
+            let election_id = voting.create_election(ElectionConfig::default()).unwrap();
+            voting.open_voting(election_id).unwrap();
+            assert_eq!(
+                voting.vote_quadratic(election_id, accounts.django, 1),
+                Err(VoteError::UnsupportedBallotType)
+            );
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    mode: VotingMode::Quadratic(0),
+                    ..Default::default()
+                }),
+                Err(ConfigError::NoBallotsPerVoter)
+            );
+        }
+
+        /// We test that fee updates are clamped, evented and locked during voting.
+        #[ink::test]
+        fn fees_are_bounded_and_locked_while_voting() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(ElectionConfig::default(), generous_limits()).unwrap();
+            assert_eq!(voting.set_vote_fee(0, 5_000_000), Ok(1_000_000));
+            assert_eq!(voting.set_creation_fee(77), Ok(77));
+            assert_eq!(emitted::<FeeUpdated>(), 2);
+
+            assert_eq!(
+                voting.create_election(ElectionConfig::default()),
+                Err(ConfigError::IncorrectCreationFee)
+            );
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    candidacy_deposit: 2_000_000,
+                    ..Default::default()
+                }),
+                Err(ConfigError::FeeAboveMaximum)
+            );
+            set_value(77);
+            let election_id = voting.create_election(ElectionConfig::default()).unwrap();
+            assert_eq!(voting.get_treasury(), 77);
+            set_value(0);
+
+            voting.open_voting(election_id).unwrap();
+            assert_eq!(voting.set_creation_fee(0), Err(VoteError::FeesLocked));
+            voting.finalize(election_id).unwrap();
+            voting.set_candidacy_deposit(0, 10).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(voting.set_creation_fee(0), Err(VoteError::Unauthorized));
+        }
+
+        /// We test that can_vote reports what vote would do, without voting.
+        #[ink::test]
+        fn can_vote_dry_runs_the_checks() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(voting.can_vote(accounts.bob, 0, accounts.django), Ok(()));
+            assert_eq!(
+                voting.can_vote(accounts.bob, 7, accounts.django),
+                Err(VoteError::ElectionNotFound)
+            );
+            assert_eq!(voting.get_votes(0, accounts.django), 0);
+
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(
+                voting.can_vote(accounts.alice, 0, accounts.django),
+                Err(VoteError::AlreadyVoted)
+            );
+
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    endorsements_required: 1,
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(
+                voting.can_vote(accounts.bob, election_id, accounts.django),
+                Err(VoteError::VotingClosed)
+            );
+            voting.open_voting(election_id).unwrap();
+            assert_eq!(
+                voting.can_vote(accounts.bob, election_id, accounts.django),
+                Err(VoteError::NotACandidate)
+            );
+        }
+
+        /// We test that can_register reports bans, caps and deposit mismatches.
+        #[ink::test]
+        fn can_register_dry_runs_the_checks() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    max_candidates: 1,
+                    candidacy_deposit: 50,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            assert_eq!(
+                voting.can_register(accounts.django, 0, 50),
+                Err(RegistrationError::RegistrationClosed)
+            );
+            voting.open_registration(0).unwrap();
+            assert_eq!(
+                voting.can_register(accounts.django, 0, 10),
+                Err(RegistrationError::IncorrectDeposit)
+            );
+            assert_eq!(voting.can_register(accounts.django, 0, 50), Ok(()));
+
+            voting.ban_candidate(0, accounts.eve).unwrap();
+            assert_eq!(
+                voting.can_register(accounts.eve, 0, 50),
+                Err(RegistrationError::Banned)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            set_value(50);
+            voting.register_candidate(0).unwrap();
+            assert_eq!(
+                voting.can_register(accounts.frank, 0, 50),
+                Err(RegistrationError::CandidateCapReached)
+            );
+            assert_eq!(
+                voting.ban_candidate(0, accounts.frank),
+                Err(VoteError::Unauthorized)
+            );
+        }
+
+        /// We test that every step of an election's life emits an event.
+        #[ink::test]
+        fn state_changes_are_evented() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    endorsements_required: 1,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            assert_eq!(emitted::<ElectionCreated>(), 1);
+
+            voting.open_registration(0).unwrap();
+            voting.endorse(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.register_candidate(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(0).unwrap();
+            voting.vote(0, accounts.django).unwrap();
+            voting.finalize(0).unwrap();
+
+            assert_eq!(emitted::<Endorsed>(), 1);
+            assert_eq!(emitted::<CandidateRegistered>(), 1);
+            assert_eq!(emitted::<PhaseChanged>(), 3);
+            assert_eq!(emitted::<VoteCast>(), 1);
+
+            let vote_cast = events_of::<VoteCast>().remove(0);
+            assert_eq!(vote_cast.topics.len(), 4);
+            let decoded =
+                <VoteCast as ink::scale::Decode>::decode(&mut &vote_cast.data[..]).unwrap();
+            assert_eq!(decoded.voter, accounts.alice);
+            assert_eq!(decoded.candidate, accounts.django);
+        }
+
+        /// We test that every accepted ballot reports the candidate's new total.
+        #[ink::test]
+        fn ballots_report_running_totals() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(voting.vote(0, accounts.django), Ok(2));
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::AlreadyVoted)
+            );
+
+            let totals: Vec<u32> = events_of::<TallyUpdated>()
+                .iter()
+                .map(|event| {
+                    <TallyUpdated as ink::scale::Decode>::decode(&mut &event.data[..])
+                        .unwrap()
+                        .new_total
+                })
+                .collect();
+            assert_eq!(totals, [1, 2, 3]);
+        }
+
+        /// We test that ballots are numbered in one sequence across elections.
+        #[ink::test]
+        fn ballots_are_numbered_in_sequence() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting.clone_election(0).unwrap();
+            voting.open_voting(election_id).unwrap();
+
+            assert_eq!(voting.vote(0, accounts.django), Ok(0));
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::AlreadyVoted)
+            );
+            assert_eq!(voting.vote(election_id, accounts.eve), Ok(1));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(voting.vote(0, accounts.eve), Ok(2));
+
+            let ids: Vec<BallotId> = events_of::<VoteCast>()
+                .iter()
+                .map(|event| {
+                    <VoteCast as ink::scale::Decode>::decode(&mut &event.data[..])
+                        .unwrap()
+                        .ballot_id
+                })
+                .collect();
+            assert_eq!(ids, [0, 1, 2]);
+        }
+
+        /// We test that accounts can page through the ballots they cast.
+        #[ink::test]
+        fn vote_history_is_recorded() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let public = voting
+                .create_election(ElectionConfig {
+                    public_ballot: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(public).unwrap();
+            assert_eq!(voting.get_vote_history(accounts.alice, 0, 10), []);
+
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(public, accounts.eve).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.vote(public, accounts.eve).unwrap();
+
+            let history = voting.get_vote_history(accounts.alice, 0, 10);
+            assert_eq!(
+                history,
+                [
+                    VoteRecord {
+                        election_id: 0,
+                        ballot_id: 0,
+                        candidate: None,
+                    },
+                    VoteRecord {
+                        election_id: public,
+                        ballot_id: 2,
+                        candidate: Some(accounts.eve),
+                    },
+                ]
+            );
+            assert_eq!(voting.get_vote_history(accounts.alice, 1, 10), history[1..]);
+            assert_eq!(voting.get_vote_history(accounts.bob, 0, 10).len(), 1);
+        }
+
+        /// We test that approval elections take one ballot per approved
+        /// candidate, up to the configured number.
+        #[ink::test]
+        fn voters_approve_several_candidates() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    mode: VotingMode::Approval(0),
+                    ..Default::default()
+                }),
+                Err(ConfigError::NoBallotsPerVoter)
+            );
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    mode: VotingMode::Approval(2),
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+
+            voting.vote(election_id, accounts.django).unwrap();
+            assert_eq!(
+                voting.vote(election_id, accounts.django),
+                Err(VoteError::AlreadyVoted)
+            );
+            voting.vote(election_id, accounts.eve).unwrap();
+            assert_eq!(
+                voting.vote(election_id, accounts.frank),
+                Err(VoteError::AlreadyVoted)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(election_id, accounts.eve).unwrap();
+
+            assert_eq!(voting.get_votes(election_id, accounts.django), 1);
+            assert_eq!(voting.get_votes(election_id, accounts.eve), 2);
+            assert_eq!(voting.get_results(election_id).unwrap().turnout, 3);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(election_id).unwrap();
+            assert_eq!(
+                voting.prune_election(election_id, vec![accounts.alice, accounts.charlie]),
+                Ok(1)
+            );
+        }
+
+        /// We test that split ballots share one vote across candidates in
+        /// per-mille units.
+        #[ink::test]
+        fn ballots_can_be_split() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.vote_split(0, vec![(accounts.django, 600)]),
+                Err(VoteError::UnsupportedBallotType)
+            );
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    mode: VotingMode::Split,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+
+            for invalid in [
+                vec![],
+                vec![(accounts.django, 600), (accounts.eve, 401)],
+                vec![(accounts.django, 500), (accounts.django, 500)],
+                vec![(accounts.django, 0)],
+            ] {
+                assert_eq!(
+                    voting.vote_split(election_id, invalid),
+                    Err(VoteError::InvalidSplit)
+                );
+            }
+            voting
+                .vote_split(
+                    election_id,
+                    vec![(accounts.django, 600), (accounts.eve, 400)],
+                )
+                .unwrap();
+            assert_eq!(
+                voting.vote_split(election_id, vec![(accounts.eve, 1)]),
+                Err(VoteError::AlreadyVoted)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting
+                .vote_split(election_id, vec![(accounts.eve, 250)])
+                .unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(election_id, accounts.eve).unwrap();
+
+            assert_eq!(voting.get_votes(election_id, accounts.django), 600);
+            assert_eq!(voting.get_votes(election_id, accounts.eve), 1_650);
+            assert_eq!(voting.get_current_winner(election_id), [accounts.eve]);
+            assert_eq!(voting.get_results(election_id).unwrap().turnout, 3);
+            assert_eq!(emitted::<SplitVoteCast>(), 3);
+            assert_eq!(emitted::<VoteCast>(), 0);
+        }
+
+        /// We test that score and cumulative ballots are validated against
+        /// the election's mode.
+        #[ink::test]
+        fn ballots_follow_the_voting_mode() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let scored = voting
+                .create_election(ElectionConfig {
+                    mode: VotingMode::Score(5),
+                    ..Default::default()
+                })
+                .unwrap();
+            let cumulative = voting
+                .create_election(ElectionConfig {
+                    mode: VotingMode::Cumulative(3),
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    mode: VotingMode::Score(0),
+                    ..Default::default()
+                }),
+                Err(ConfigError::NoBallotsPerVoter)
+            );
+            voting.open_voting(scored).unwrap();
+            voting.open_voting(cumulative).unwrap();
+
+            assert_eq!(
+                voting.vote_ballot(0, BallotChoice::Scored(vec![(accounts.django, 1)])),
+                Err(VoteError::UnsupportedBallotType)
+            );
+            assert_eq!(
+                voting.vote_split(scored, vec![(accounts.django, 1)]),
+                Err(VoteError::UnsupportedBallotType)
+            );
+            for invalid in [
+                vec![],
+                vec![(accounts.django, 6)],
+                vec![(accounts.django, 0)],
+                vec![(accounts.django, 2), (accounts.django, 3)],
+            ] {
+                assert_eq!(
+                    voting.vote_ballot(scored, BallotChoice::Scored(invalid)),
+                    Err(VoteError::InvalidAllotment)
+                );
+            }
+            voting
+                .vote_ballot(
+                    scored,
+                    BallotChoice::Scored(vec![(accounts.django, 5), (accounts.eve, 4)]),
+                )
+                .unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(scored, accounts.eve).unwrap();
+            assert_eq!(voting.get_votes(scored, accounts.django), 5);
+            assert_eq!(voting.get_votes(scored, accounts.eve), 9);
+            assert_eq!(voting.get_current_winner(scored), [accounts.eve]);
+
+            assert_eq!(
+                voting.vote_ballot(
+                    cumulative,
+                    BallotChoice::Cumulative(vec![(accounts.django, 2), (accounts.eve, 2)])
+                ),
+                Err(VoteError::InvalidAllotment)
+            );
+            voting
+                .vote_ballot(
+                    cumulative,
+                    BallotChoice::Cumulative(vec![(accounts.django, 2), (accounts.eve, 1)]),
+                )
+                .unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting
+                .vote_ballot(cumulative, BallotChoice::Single(accounts.eve))
+                .unwrap();
+            assert_eq!(voting.get_votes(cumulative, accounts.django), 2);
+            assert_eq!(voting.get_votes(cumulative, accounts.eve), 4);
+            assert_eq!(emitted::<SplitVoteCast>(), 4);
+        }
+
+        /// We test that a tally module only counts ballots it can take.
+        #[ink::test]
+        fn tally_modules_exclude_built_in_counts() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let module = Some(accounts.frank);
+            for config in [
+                ElectionConfig {
+                    mode: VotingMode::SingleTransferable(1),
+                    tally_module: module,
+                    ..Default::default()
+                },
+                ElectionConfig {
+                    track_participation: true,
+                    tally_module: module,
+                    ..Default::default()
+                },
+            ] {
+                assert_eq!(
+                    voting.create_election(config),
+                    Err(ConfigError::UnsupportedTallyModule)
+                );
+            }
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    mode: VotingMode::Score(3),
+                    tally_module: module,
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(voting.get_config(election_id).unwrap().tally_module, module);
+
+            // ballots of the wrong kind never reach the module
+            voting.open_voting(election_id).unwrap();
+            assert_eq!(
+                voting.vote_split(election_id, vec![(accounts.django, 1)]),
+                Err(VoteError::UnsupportedBallotType)
+            );
+        }
+
+        /// We test that the electorate is checked before the eligibility
+        /// source is asked, and that anonymous ballots cannot be vetted.
+        #[ink::test]
+        fn eligibility_source_follows_the_electorate() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let source = Some(accounts.frank);
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    closed_electorate: true,
+                    eligibility_source: source,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    closed_electorate: true,
+                    anonymous: true,
+                    eligibility_source: source,
+                    ..Default::default()
+                }),
+                Err(ConfigError::UnsupportedAnonymity)
+            );
+            voting.open_voting(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::NotInElectorate)
+            );
+            assert_eq!(voting.get_config(0).unwrap().eligibility_source, source);
+        }
+
+        /// We test that ballots are weighted by an asset or a weight source,
+        /// never both.
+        #[ink::test]
+        fn weight_source_replaces_asset_weight() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let weight_source = Some(WeightSource {
+                calculator: accounts.frank,
+                snapshot_block: Some(5),
+            });
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    asset_weight: Some(AssetWeight {
+                        asset_id: 7,
+                        min_holding: 0,
+                        unit: 1,
+                        max_weight: None,
+                    }),
+                    weight_source,
+                    ..Default::default()
+                }),
+                Err(ConfigError::ConflictingWeights)
+            );
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    closed_electorate: true,
+                    anonymous: true,
+                    weight_source,
+                    ..Default::default()
+                }),
+                Err(ConfigError::UnsupportedAnonymity)
+            );
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    weight_source,
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(
+                voting.get_config(election_id).unwrap().weight_source,
+                weight_source
+            );
+        }
+
+        /// We test that ballots are weighted by a checkpointed token alone,
+        /// at a snapshot block that pins the electorate for re-runs.
+        #[ink::test]
+        fn token_weight_excludes_other_weights() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let token_weight = Some(TokenWeight {
+                token: accounts.frank,
+                snapshot_block: 5,
+                min_holding: 0,
+                unit: 1_000,
+            });
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    weight_source: Some(WeightSource {
+                        calculator: accounts.frank,
+                        snapshot_block: Some(5),
+                    }),
+                    token_weight,
+                    ..Default::default()
+                }),
+                Err(ConfigError::ConflictingWeights)
+            );
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    closed_electorate: true,
+                    anonymous: true,
+                    token_weight,
+                    ..Default::default()
+                }),
+                Err(ConfigError::UnsupportedAnonymity)
+            );
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    token_weight,
+                    ..Default::default()
+                })
+                .unwrap();
+            assert_eq!(
+                voting.get_config(election_id).unwrap().token_weight,
+                token_weight
+            );
+            voting.cancel(election_id).unwrap();
+            assert!(voting.rerun_election(election_id).is_ok());
+        }
+
+        /// We test that the admin subscribes a hook until voting opens.
+        #[ink::test]
+        fn hooks_are_set_by_the_admin() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(ElectionConfig::default(), generous_limits()).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.set_hook(0, Some(accounts.frank)),
+                Err(VoteError::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.set_hook(0, Some(accounts.frank)).unwrap();
+            assert_eq!(voting.get_config(0).unwrap().hook, Some(accounts.frank));
+
+            // off-chain the hook cannot be reached, so unsubscribe it to vote
+            voting.set_hook(0, None).unwrap();
+            voting.open_voting(0).unwrap();
+            assert_eq!(
+                voting.set_hook(0, Some(accounts.frank)),
+                Err(VoteError::ConfigFrozen)
+            );
+            voting.vote(0, accounts.django).unwrap();
+            voting.finalize(0).unwrap();
+            assert_eq!(
+                voting.set_hook(0, Some(accounts.frank)),
+                Err(VoteError::ConfigFrozen)
+            );
+            assert_eq!(emitted::<HookUpdated>(), 2);
+            assert_eq!(emitted::<ConfigChanged>(), 2);
+        }
+
+        /// We test that in identity mode one person votes once, whichever of
+        /// their accounts they use.
+        #[ink::test]
+        fn identities_vote_once_across_accounts() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(ElectionConfig::default(), generous_limits()).unwrap();
+            let election_id = voting.clone_election(0).unwrap();
+            voting.set_identity(accounts.bob, Some(7)).unwrap();
+            voting.set_identity(accounts.charlie, Some(7)).unwrap();
+            voting.set_identity_mode(true).unwrap();
+            voting.open_voting(0).unwrap();
+            voting.open_voting(election_id).unwrap();
+            assert_eq!(
+                voting.set_identity_mode(false),
+                Err(VoteError::ConfigFrozen)
+            );
+
+            assert_eq!(voting.vote(0, accounts.django), Err(VoteError::NoIdentity));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(
+                voting.set_identity(accounts.bob, None),
+                Err(VoteError::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(voting.vote(0, accounts.eve), Err(VoteError::AlreadyVoted));
+            voting.vote(election_id, accounts.eve).unwrap();
+            assert_eq!(voting.get_votes(0, accounts.django), 1);
+            assert_eq!(voting.get_votes(0, accounts.eve), 0);
+        }
+
+        /// We test that memos are published with the ballot and bounded in length.
+        #[ink::test]
+        fn vote_memos_are_evented() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.vote_with_memo(0, accounts.django, vec![0; MAX_MEMO_LEN + 1]),
+                Err(VoteError::MemoTooLong)
+            );
+            assert_eq!(
+                voting.vote_with_memo(0, accounts.django, b"best plan".to_vec()),
+                Ok(0)
+            );
+            assert_eq!(voting.get_votes(0, accounts.django), 1);
+
+            let event = events_of::<VoteCast>().remove(0);
+            let decoded = <VoteCast as ink::scale::Decode>::decode(&mut &event.data[..]).unwrap();
+            assert_eq!(decoded.memo, b"best plan");
+        }
+
+        /// We test that a rationale hash is kept with the ballot it came with.
+        #[ink::test]
+        fn vote_reasons_are_recorded() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let reason = Hash::from([7; 32]);
+            assert_eq!(voting.vote(0, accounts.django), Ok(0));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(voting.vote_with_reason(0, accounts.django, reason), Ok(1));
+
+            assert_eq!(voting.get_reason(0), None);
+            assert_eq!(voting.get_reason(1), Some(reason));
+            assert_eq!(voting.get_votes(0, accounts.django), 2);
+            assert_eq!(emitted::<ReasonRecorded>(), 1);
+        }
+
+        /// We test that tied winners are listed in ascending account order
+        /// rather than in the order they entered the race, before and after
+        /// finalization.
+        #[ink::test]
+        fn winners_are_in_canonical_order() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.vote(0, accounts.frank).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(
+                voting.get_current_winner(0),
+                [accounts.django, accounts.frank]
+            );
+            assert_eq!(
+                voting.get_results(0).unwrap().winners,
+                [accounts.django, accounts.frank]
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+            assert_eq!(
+                voting.get_archived_result(0).unwrap().winners,
+                [accounts.django, accounts.frank]
+            );
+            assert_eq!(
+                voting.get_results(0).unwrap().totals,
+                [(accounts.frank, 1), (accounts.django, 1)]
+            );
+
+            let mut winners = [1u8, 3, 2];
+            crate::counting::canonical_order(&mut winners, &[(1, 1), (2, 5), (3, 5)]);
+            assert_eq!(winners, [2, 3, 1]);
+        }
+
+        /// We test that vote arithmetic behaves alike whatever the width of
+        /// the count.
+        #[ink::test]
+        fn vote_arithmetic_is_width_agnostic() {
+            use crate::counting::VoteArithmetic;
+            assert_eq!(<u64 as VoteArithmetic>::ZERO, 0);
+            assert_eq!(VoteArithmetic::checked_add(u32::MAX, 1), None);
+            assert_eq!(VoteArithmetic::checked_add(2u64, 3), Some(5));
+            assert_eq!(VoteArithmetic::saturating_add(u128::MAX, 1), u128::MAX);
+        }
+
+        /// We test that results can be enumerated page by page, in the order
+        /// runners entered the race, before and after finalization.
+        #[ink::test]
+        fn results_are_paginated() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            for (voter, candidate) in [
+                (accounts.alice, accounts.django),
+                (accounts.bob, accounts.eve),
+                (accounts.charlie, accounts.django),
+                (accounts.django, accounts.frank),
+            ] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(0, candidate).unwrap();
+            }
+            assert_eq!(voting.results_len(0), 3);
+            assert_eq!(
+                voting.get_results_page(0, 0, 2),
+                [(accounts.django, 2), (accounts.eve, 1)]
+            );
+            assert_eq!(voting.get_results_page(0, 2, 10), [(accounts.frank, 1)]);
+            assert_eq!(voting.get_results_page(0, 3, 10), []);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.results_len(0), 3);
+            assert_eq!(
+                voting.get_results_page(0, 1, 10),
+                [(accounts.eve, 1), (accounts.frank, 1)]
+            );
+            assert_eq!(voting.results_len(1), 0);
+            assert_eq!(voting.get_results_page(1, 0, 10), []);
+        }
+
+        /// We test that voters can be enumerated page by page, and per candidate
+        /// only on a public ballot.
+        #[ink::test]
+        fn voters_are_paginated() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    public_ballot: true,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_voting(0).unwrap();
+            for (voter, candidate) in [
+                (accounts.alice, accounts.django),
+                (accounts.bob, accounts.eve),
+                (accounts.charlie, accounts.django),
+            ] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(0, candidate).unwrap();
+            }
+
+            assert_eq!(
+                voting.get_voters(0, 0, 10),
+                [accounts.alice, accounts.bob, accounts.charlie]
+            );
+            assert_eq!(voting.get_voters(0, 1, 1), [accounts.bob]);
+            assert_eq!(voting.get_voters(0, 3, 10), []);
+            assert_eq!(
+                voting.get_voters_for(0, accounts.django, 0, 10),
+                [accounts.alice, accounts.charlie]
+            );
+            assert_eq!(
+                voting.get_voters_for(0, accounts.django, 1, 10),
+                [accounts.charlie]
+            );
+            assert_eq!(voting.get_voter_count_for(0, accounts.django), 2);
+            assert_eq!(voting.get_voter_count_for(0, accounts.frank), 0);
+
+            let secret = voting.create_election(ElectionConfig::default()).unwrap();
+            voting.open_voting(secret).unwrap();
+            voting.vote(secret, accounts.django).unwrap();
+            assert_eq!(voting.get_voters(secret, 0, 10), [accounts.charlie]);
+            assert_eq!(voting.get_voters_for(secret, accounts.django, 0, 10), []);
+            assert_eq!(voting.get_voter_count_for(secret, accounts.django), 0);
+        }
+
+        /// We test that migrating up-to-date storage is an owner-only no-op.
+        #[ink::test]
+        fn storage_migration_is_idempotent() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(voting.get_storage_version(), STORAGE_VERSION);
+            assert_eq!(voting.migrate_storage(), Ok(STORAGE_VERSION));
+            assert_eq!(voting.migrate_storage(), Ok(STORAGE_VERSION));
+            assert_eq!(emitted::<StorageMigrated>(), 0);
+
+            voting.storage_version = STORAGE_VERSION + 1;
+            assert_eq!(
+                voting.migrate_storage(),
+                Err(VoteError::UnsupportedStorageVersion)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(voting.migrate_storage(), Err(VoteError::Unauthorized));
+        }
+
+        /// We test that a survey counts answers per option without electing anyone.
+        #[ink::test]
+        fn surveys_report_a_distribution() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.create_survey(Hash::default(), vec![b"yes".to_vec()]),
+                Err(VoteError::InvalidSurvey)
+            );
+            assert_eq!(
+                voting.create_survey(Hash::default(), vec![vec![0; MAX_NAME_LEN + 1], vec![]]),
+                Err(VoteError::InvalidSurvey)
+            );
+            let options = vec![b"now".to_vec(), b"later".to_vec(), b"never".to_vec()];
+            let survey_id = voting.create_survey(Hash::default(), options).unwrap();
+            assert_eq!(voting.get_survey_distribution(survey_id), [0, 0, 0]);
+
+            for (voter, option) in [
+                (accounts.alice, 0),
+                (accounts.bob, 1),
+                (accounts.charlie, 1),
+                (accounts.django, 2),
+            ] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.answer_survey(survey_id, option).unwrap();
+            }
+            assert_eq!(
+                voting.answer_survey(survey_id, 0),
+                Err(VoteError::AlreadyVoted)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                voting.answer_survey(survey_id, 3),
+                Err(VoteError::InvalidOption)
+            );
+            assert_eq!(voting.close_survey(survey_id), Err(VoteError::Unauthorized));
+            assert_eq!(voting.get_survey(survey_id).unwrap().counts, [1, 2, 1]);
+            assert_eq!(voting.get_survey_distribution(survey_id), [250, 500, 250]);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.close_survey(survey_id).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                voting.answer_survey(survey_id, 0),
+                Err(VoteError::SurveyClosed)
+            );
+            assert_eq!(voting.answer_survey(7, 0), Err(VoteError::SurveyNotFound));
+            assert_eq!(emitted::<SurveyUpdated>(), 6);
+        }
+
+        /// We test that ownership is renounced for good, once no election is
+        /// in flight.
+        #[ink::test]
+        fn ownership_can_be_renounced() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            assert_eq!(voting.get_owner(), Some(accounts.alice));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(voting.renounce_ownership(), Err(VoteError::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.renounce_ownership(),
+                Err(VoteError::ElectionsInFlight)
+            );
+            voting.finalize(0).unwrap();
+            voting.renounce_ownership().unwrap();
+            assert_eq!(voting.get_owner(), None);
+            assert_eq!(emitted::<OwnershipRenounced>(), 1);
+            assert_eq!(voting.set_creation_fee(1), Err(VoteError::Unauthorized));
+            assert_eq!(voting.set_moderator(None), Err(VoteError::Unauthorized));
+            assert_eq!(voting.renounce_ownership(), Err(VoteError::Unauthorized));
+        }
+
+        /// We test that under a timelock sensitive actions only run once
+        /// scheduled and the delay has passed.
+        #[ink::test]
+        fn timelocked_actions_wait_out_the_delay() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_config(ElectionConfig::default(), generous_limits()).unwrap();
+            voting.open_voting(0).unwrap();
+            voting.set_timelock_delay(2).unwrap();
+            assert_eq!(
+                voting.ban_candidate(0, accounts.charlie),
+                Err(VoteError::Timelocked)
+            );
+            assert_eq!(voting.set_timelock_delay(1), Err(VoteError::Timelocked));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.schedule_action(AdminAction::CreationFee(5)),
+                Err(VoteError::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let fee_change = voting.schedule_action(AdminAction::CreationFee(5)).unwrap();
+            assert_eq!(emitted::<ActionScheduled>(), 1);
+            assert_eq!(
+                voting.execute_action(fee_change),
+                Err(VoteError::TimelockPending)
+            );
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert_eq!(
+                voting.execute_action(fee_change),
+                Err(VoteError::FeesLocked)
+            );
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.set_creation_fee(5), Err(VoteError::Timelocked));
+            voting.execute_action(fee_change).unwrap();
+            assert_eq!(voting.get_creation_fee(), 5);
+            assert_eq!(voting.get_scheduled_action(fee_change), None);
+            assert_eq!(emitted::<ActionExecuted>(), 1);
+
+            let ban = voting
+                .schedule_action(AdminAction::BanCandidate(0, accounts.charlie))
+                .unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(voting.cancel_action(ban), Err(VoteError::Unauthorized));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.cancel_action(ban).unwrap();
+            assert_eq!(emitted::<ActionCancelled>(), 1);
+            assert_eq!(voting.execute_action(ban), Err(VoteError::ActionNotFound));
+        }
+
+        /// We test that a role only reaches the election-admin messages it
+        /// was granted.
+        #[ink::test]
+        fn roles_reach_only_granted_messages() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let finalizer = 1;
+            let finalize = ink::selector_bytes!("finalize");
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.set_permission(finalizer, finalize, true),
+                Err(VoteError::Unauthorized)
+            );
+            assert_eq!(voting.finalize(0), Err(VoteError::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.set_permission(finalizer, finalize, true).unwrap();
+            voting.assign_role(accounts.bob, Some(finalizer)).unwrap();
+            assert_eq!(voting.get_role(accounts.bob), Some(finalizer));
+            assert!(voting.has_permission(finalizer, finalize));
+            assert_eq!(emitted::<PermissionUpdated>(), 1);
+            assert_eq!(emitted::<RoleAssigned>(), 1);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.ban_candidate(0, accounts.charlie),
+                Err(VoteError::Unauthorized)
+            );
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Finalized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.assign_role(accounts.bob, None).unwrap();
+            assert_eq!(voting.get_role(accounts.bob), None);
+        }
+
+        /// We test that ballots over the per-block cap are refused until the
+        /// next block.
+        #[ink::test]
+        fn ballots_per_block_are_capped() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                ballots_per_block: 2,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            voting.open_voting(0).unwrap();
+            for voter in [accounts.alice, accounts.bob] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(0, accounts.frank).unwrap();
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(voting.vote(0, accounts.frank), Err(VoteError::RateLimited));
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            voting.vote(0, accounts.frank).unwrap();
+            assert_eq!(voting.get_votes(0, accounts.frank), 3);
+        }
+
+        /// We test that registrations are capped per block and that an
+        /// account waits out its cooldown between candidacies.
+        #[ink::test]
+        fn registrations_are_paced() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                registrations_per_block: 2,
+                registration_cooldown: 3,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config.clone(), generous_limits()).unwrap();
+            voting.open_registration(0).unwrap();
+            for candidate in [accounts.alice, accounts.bob] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(candidate);
+                voting.register_candidate(0).unwrap();
+            }
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                voting.register_candidate(0),
+                Err(RegistrationError::RateLimited)
+            );
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            voting.register_candidate(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let second = voting.create_election(config).unwrap();
+            voting.open_registration(second).unwrap();
+            assert_eq!(
+                voting.register_candidate(second),
+                Err(RegistrationError::CooldownActive)
+            );
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            voting.register_candidate(second).unwrap();
+        }
+
+        /// We test that an election forbidding self-votes refuses them and
+        /// still takes other ballots.
+        #[ink::test]
+        fn self_votes_can_be_forbidden() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                forbid_self_votes: true,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            voting.open_voting(0).unwrap();
+            assert_eq!(
+                voting.vote(0, accounts.alice),
+                Err(VoteError::SelfVoteForbidden)
+            );
+            voting.vote(0, accounts.bob).unwrap();
+        }
+
+        /// We test that barred candidates cannot vote while other accounts can.
+        #[ink::test]
+        fn candidates_can_be_barred_from_voting() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                candidates_barred: true,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            voting.open_registration(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.register_candidate(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(0).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.vote(0, accounts.charlie),
+                Err(VoteError::CandidateBarred)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(0, accounts.bob).unwrap();
+        }
+
+        /// We test that ballots are tallied per constituency besides the
+        /// election as a whole.
+        #[ink::test]
+        fn constituencies_keep_their_own_tallies() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                constituencies: 2,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            assert_eq!(
+                voting.assign_constituency(0, 2, vec![accounts.alice]),
+                Err(VoteError::InvalidConstituency)
+            );
+            voting
+                .assign_constituency(0, 0, vec![accounts.alice, accounts.bob])
+                .unwrap();
+            voting
+                .assign_constituency(0, 1, vec![accounts.charlie])
+                .unwrap();
+            assert_eq!(voting.get_constituency(0, accounts.charlie), Some(1));
+            assert_eq!(emitted::<VotersAssigned>(), 2);
+            voting.open_voting(0).unwrap();
+
+            for (voter, candidate) in [
+                (accounts.alice, accounts.eve),
+                (accounts.bob, accounts.eve),
+                (accounts.charlie, accounts.frank),
+                (accounts.django, accounts.frank),
+            ] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(0, candidate).unwrap();
+            }
+            assert_eq!(voting.get_constituency_votes(0, 0, accounts.eve), 2);
+            assert_eq!(voting.get_constituency_votes(0, 1, accounts.frank), 1);
+            assert_eq!(voting.get_constituency_winner(0, 0), vec![accounts.eve]);
+            assert_eq!(voting.get_constituency_winner(0, 1), vec![accounts.frank]);
+            assert_eq!(
+                voting.get_current_winner(0),
+                vec![accounts.eve, accounts.frank]
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(
+                voting.assign_constituency(0, 1, vec![accounts.alice]),
+                Err(VoteError::AlreadyVoted)
+            );
+        }
+
+        /// We test that an electoral college is won on the electors of the
+        /// constituencies carried, not on votes.
+        #[ink::test]
+        fn electoral_college_counts_electors() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut config = ElectionConfig {
+                constituencies: 3,
+                electors: vec![5, 3],
+                ..Default::default()
+            };
+            assert_eq!(
+                Voting::new_with_config(config.clone(), generous_limits()).err(),
+                Some(ConfigError::InvalidElectors)
+            );
+            config.electors.push(3);
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            voting
+                .assign_constituency(0, 0, vec![accounts.alice, accounts.django])
+                .unwrap();
+            voting
+                .assign_constituency(0, 1, vec![accounts.bob])
+                .unwrap();
+            voting
+                .assign_constituency(0, 2, vec![accounts.charlie])
+                .unwrap();
+            voting.open_voting(0).unwrap();
+            for (voter, candidate) in [
+                (accounts.alice, accounts.eve),
+                (accounts.django, accounts.eve),
+                (accounts.bob, accounts.frank),
+                (accounts.charlie, accounts.frank),
+            ] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(0, candidate).unwrap();
+            }
+            assert_eq!(voting.get_votes(0, accounts.eve), 2);
+            assert_eq!(voting.get_votes(0, accounts.frank), 2);
+            assert_eq!(
+                voting.get_elector_totals(0),
+                vec![(accounts.eve, 5), (accounts.frank, 6)]
+            );
+            assert_eq!(voting.get_current_winner(0), vec![accounts.frank]);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+            let result = voting.get_results(0).unwrap();
+            assert_eq!(result.winners, vec![accounts.frank]);
+            assert_eq!(result.margin, 1);
+        }
+
+        /// We test that a topic delegation overrides the general one in
+        /// elections about the topic only.
+        #[ink::test]
+        fn topic_delegates_come_before_general_ones() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                topic: Some(7),
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            let general = voting.create_election(ElectionConfig::default()).unwrap();
+            voting.delegate(accounts.bob).unwrap();
+            voting.delegate_topic(7, accounts.charlie).unwrap();
+            assert_eq!(
+                voting.get_topic_delegate(7, accounts.alice),
+                Some(accounts.charlie)
+            );
+            assert_eq!(emitted::<TopicDelegationChanged>(), 1);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                voting.delegate_topic(7, accounts.alice),
+                Err(VoteError::DelegationCycle)
+            );
+
+            assert_eq!(voting.effective_weight(0, accounts.charlie), 2);
+            assert_eq!(voting.effective_weight(0, accounts.bob), 1);
+            assert_eq!(voting.effective_weight(general, accounts.bob), 2);
+            assert_eq!(voting.effective_weight(general, accounts.charlie), 1);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.undelegate_topic(7).unwrap();
+            assert_eq!(voting.effective_weight(0, accounts.bob), 2);
+            assert_eq!(voting.undelegate_topic(7), Err(VoteError::NotDelegating));
+        }
+
+        /// We test that simulated ballots move the outcome without being
+        /// recorded.
+        #[ink::test]
+        fn simulated_ballots_are_not_recorded() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.vote(0, accounts.eve).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.eve).unwrap();
+
+            let flipped = voting
+                .simulate_tally(0, vec![(accounts.frank, 2), (accounts.frank, 1)])
+                .unwrap();
+            assert_eq!(flipped.winners, vec![accounts.frank]);
+            assert_eq!(flipped.runner_up, vec![accounts.eve]);
+            assert_eq!(flipped.margin, 1);
+            assert_eq!(flipped.turnout, 4);
+            assert_eq!(voting.get_current_winner(0), vec![accounts.eve]);
+            assert_eq!(voting.get_votes(0, accounts.frank), 0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.simulate_tally(0, Vec::new()), None);
+        }
+
+        /// We test that ranks follow the votes, tied runners sharing one,
+        /// whether standings are tracked or counted.
+        #[ink::test]
+        fn ranks_follow_the_standings() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                track_standings: true,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            let counted = voting.create_election(ElectionConfig::default()).unwrap();
+            for election_id in [0, counted] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+                voting.open_voting(election_id).unwrap();
+                for (voter, candidate) in [
+                    (accounts.alice, accounts.eve),
+                    (accounts.bob, accounts.frank),
+                    (accounts.charlie, accounts.frank),
+                    (accounts.django, accounts.eve),
+                    (accounts.eve, accounts.bob),
+                ] {
+                    ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                    voting.vote(election_id, candidate).unwrap();
+                }
+                assert_eq!(voting.get_rank(election_id, accounts.eve), Some(1));
+                assert_eq!(voting.get_rank(election_id, accounts.frank), Some(1));
+                assert_eq!(voting.get_rank(election_id, accounts.bob), Some(3));
+                assert_eq!(voting.get_rank(election_id, accounts.charlie), None);
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+                voting.vote(election_id, accounts.frank).unwrap();
+                assert_eq!(voting.get_rank(election_id, accounts.eve), Some(2));
+                assert_eq!(voting.get_rank(election_id, accounts.frank), Some(1));
+            }
+        }
+
+        /// We test that the statistics describe the vote distribution.
+        #[ink::test]
+        fn stats_describe_the_distribution() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting =
+                Voting::new_with_candidates(vec![accounts.eve, accounts.frank, accounts.django]);
+            for (voter, candidate) in [
+                (accounts.alice, accounts.eve),
+                (accounts.bob, accounts.eve),
+                (accounts.charlie, accounts.eve),
+                (accounts.django, accounts.frank),
+            ] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(0, candidate).unwrap();
+            }
+            assert_eq!(
+                voting.get_stats(0),
+                Some(ElectionStats {
+                    turnout: 4,
+                    candidates: 3,
+                    total_votes: 4,
+                    mean_votes: 1,
+                    median_votes: 1,
+                    leader_share: 750,
+                    zero_vote_candidates: 1,
+                })
+            );
+            assert_eq!(voting.get_stats(1), None);
+        }
+
+        /// We test that a blind election hides its counts until voting
+        /// closes and emits no tally updates meanwhile.
+        #[ink::test]
+        fn blind_results_are_hidden_until_voting_closes() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                blind_results: true,
+                voting_closes_at: Some(Moment::Block(2)),
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            voting.open_voting(0).unwrap();
+            voting.vote(0, accounts.eve).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.eve).unwrap();
+
+            assert_eq!(emitted::<TallyUpdated>(), 0);
+            assert_eq!(voting.get_votes(0, accounts.eve), 0);
+            assert_eq!(voting.get_current_winner(0), Vec::<AccountId>::new());
+            assert_eq!(voting.get_rank(0, accounts.eve), None);
+            assert_eq!(voting.get_stats(0), None);
+            let hidden = voting.get_results(0).unwrap();
+            assert_eq!(hidden.totals, vec![(accounts.eve, 0)]);
+            assert_eq!(hidden.winners, Vec::<AccountId>::new());
+            assert_eq!(hidden.runner_up, Vec::<AccountId>::new());
+            assert_eq!(hidden.turnout, 2);
+
+            advance_blocks(3);
+            assert_eq!(voting.get_votes(0, accounts.eve), 2);
+            assert_eq!(voting.get_current_winner(0), vec![accounts.eve]);
+            voting.finalize(0).unwrap();
+            assert_eq!(
+                voting.get_results(0).unwrap().totals,
+                vec![(accounts.eve, 2)]
+            );
+        }
+
+        /// We test that a quiet election announces ballots by turnout alone.
+        #[ink::test]
+        fn quiet_events_name_no_voter() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                quiet_events: true,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config, generous_limits()).unwrap();
+            voting.open_voting(0).unwrap();
+            voting.vote(0, accounts.eve).unwrap();
+            set_caller(accounts.bob);
+            voting
+                .vote_with_reason(0, accounts.eve, Hash::from([7; 32]))
+                .unwrap();
+
+            assert_eq!(emitted::<VoteCast>(), 0);
+            assert_eq!(emitted::<ReasonRecorded>(), 0);
+            assert_eq!(emitted::<TallyUpdated>(), 2);
+            let counted: Vec<u32> = ink::env::test::recorded_events()
+                .filter(|event| event.topics == [[0u8; 32].to_vec()])
+                .map(|event| {
+                    <BallotCounted as ink::scale::Decode>::decode(&mut &event.data[..])
+                        .unwrap()
+                        .turnout
+                })
+                .collect();
+            assert_eq!(counted, [1, 2]);
+            assert_eq!(voting.get_votes(0, accounts.eve), 2);
+            assert_eq!(voting.get_reason(1), Some(Hash::from([7; 32])));
+        }
+
+        /// We test that a re-run admits exactly the original electorate,
+        /// which stays put until the re-run closes.
+        #[ink::test]
+        fn reruns_reuse_the_electorate() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let config = ElectionConfig {
+                closed_electorate: true,
+                ..Default::default()
+            };
+            let original = voting.create_election(config).unwrap();
+            voting
+                .add_voters(original, vec![accounts.bob, accounts.charlie])
+                .unwrap();
+            assert_eq!(
+                voting.rerun_election(original),
+                Err(VoteError::InvalidTransition)
+            );
+            voting.cancel(original).unwrap();
+
+            let rerun = voting.rerun_election(original).unwrap();
+            assert_eq!(emitted::<ElectionRerun>(), 1);
+            assert_eq!(voting.get_rerun_of(rerun), Some(original));
+            assert_eq!(voting.get_electorate_size(rerun), 2);
+            assert!(voting.is_voter(rerun, accounts.bob));
+            assert_eq!(
+                voting.add_voters(rerun, vec![accounts.django]),
+                Err(VoteError::InvalidTransition)
+            );
+            assert_eq!(
+                voting.remove_voter(original, accounts.bob),
+                Err(VoteError::InvalidTransition)
+            );
+            assert_eq!(
+                voting.rerun_election(original),
+                Err(VoteError::InvalidTransition)
+            );
+
+            voting.open_voting(rerun).unwrap();
+            assert_eq!(
+                voting.vote(rerun, accounts.eve),
+                Err(VoteError::NotInElectorate)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(rerun, accounts.eve).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(rerun).unwrap();
+            let second = voting.rerun_election(rerun).unwrap();
+            assert_eq!(voting.get_rerun_of(second), Some(original));
+            voting.cancel(second).unwrap();
+            assert!(voting.remove_voter(original, accounts.bob).is_ok());
+
+            let weighted = voting
+                .create_election(ElectionConfig {
+                    reputation_weighted: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.cancel(weighted).unwrap();
+            assert_eq!(
+                voting.rerun_election(weighted),
+                Err(VoteError::ElectorateNotPinned)
+            );
+        }
+
+        /// We test that the exported electorate and candidates load into
+        /// another election as they were.
+        #[ink::test]
+        fn electorate_exports_load_into_a_successor() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_candidates(vec![accounts.django, accounts.eve]);
+            let config = ElectionConfig {
+                closed_electorate: true,
+                max_candidates: 2,
+                ..Default::default()
+            };
+            let original = voting.create_election(config.clone()).unwrap();
+            voting
+                .add_voters(original, vec![accounts.bob, accounts.charlie, accounts.eve])
+                .unwrap();
+            assert_eq!(
+                voting.export_voters(original, 1, 5),
+                vec![accounts.charlie, accounts.eve]
+            );
+            voting.remove_voter(original, accounts.bob).unwrap();
+            let voters = voting.export_voters(original, 0, u32::MAX);
+            assert_eq!(voters, vec![accounts.eve, accounts.charlie]);
+            let candidates = voting.export_candidates(0, 0, u32::MAX);
+            assert_eq!(candidates, vec![accounts.django, accounts.eve]);
+            assert_eq!(voting.export_candidates(0, 1, 1), vec![accounts.eve]);
+
+            let successor = voting.create_election(config).unwrap();
+            voting.add_voters(successor, voters.clone()).unwrap();
+            assert_eq!(voting.export_voters(successor, 0, u32::MAX), voters);
+            let mut imported = candidates.clone();
+            imported.extend([accounts.django, accounts.frank]);
+            assert_eq!(
+                voting.import_candidates(successor, imported),
+                Ok(vec![true, true, false, false])
+            );
+            assert_eq!(voting.export_candidates(successor, 0, u32::MAX), candidates);
+            voting.open_voting(successor).unwrap();
+            assert_eq!(
+                voting.import_candidates(successor, vec![accounts.frank]),
+                Err(VoteError::InvalidTransition)
+            );
+        }
+
+        /// We test that a snapshot deployment takes the voters and tallies
+        /// of a moved election until voting opens, ballots included.
+        #[ink::test]
+        fn snapshot_deployments_import_ballots() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let config = ElectionConfig {
+                closed_electorate: true,
+                ..Default::default()
+            };
+            let mut voting = Voting::new_from_snapshot(config.clone(), generous_limits()).unwrap();
+            voting
+                .import_tallies(0, vec![(accounts.eve, 2), (accounts.frank, 1)])
+                .unwrap();
+            let voters = vec![
+                (accounts.alice, true),
+                (accounts.bob, true),
+                (accounts.charlie, true),
+                (accounts.django, false),
+                (accounts.bob, true),
+            ];
+            assert_eq!(voting.import_voters(0, voters), Ok(4));
+            assert_eq!(emitted::<VotersImported>(), 1);
+            assert_eq!(voting.get_electorate_size(0), 4);
+            let other = voting.create_election(config).unwrap();
+            assert_eq!(
+                voting.import_voters(other, vec![(accounts.bob, false)]),
+                Err(VoteError::InvalidTransition)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.import_tallies(0, vec![(accounts.bob, 1)]),
+                Err(VoteError::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(0).unwrap();
+            assert_eq!(
+                voting.import_tallies(0, vec![(accounts.eve, 1)]),
+                Err(VoteError::InvalidTransition)
+            );
+            assert_eq!(voting.vote(0, accounts.frank), Err(VoteError::AlreadyVoted));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.vote(0, accounts.frank).unwrap();
+            let results = voting.get_results(0).unwrap();
+            assert_eq!(results.totals, vec![(accounts.eve, 2), (accounts.frank, 2)]);
+            assert_eq!(results.turnout, 4);
+        }
+
+        /// We test that value sent without a purpose is refused or booked
+        /// as the instantiation policy says, never left untracked.
+        #[ink::test]
+        fn incoming_funds_follow_the_policy() {
+            let mut voting = Voting::new_with_funds_policy(
+                ElectionConfig::default(),
+                generous_limits(),
+                IncomingFunds::PrizePool(0),
+            )
+            .unwrap();
+            assert_eq!(voting.get_incoming_funds(), IncomingFunds::PrizePool(0));
+            set_value(300);
+            voting.receive().unwrap();
+            assert_eq!(emitted::<PrizeFunded>(), 1);
+            assert_eq!(voting.treasury_report().prize_escrow, 300);
+
+            voting.incoming_funds = IncomingFunds::Treasury;
+            voting.receive().unwrap();
+            assert_eq!(emitted::<FundsReceived>(), 1);
+            assert_eq!(voting.get_treasury(), 300);
+
+            voting.incoming_funds = IncomingFunds::Reject;
+            assert_eq!(voting.receive(), Err(VoteError::TransferRejected));
+        }
+
+        /// We test that tips on the finalization bounty go to the keeper
+        /// on top of it, or to the treasury when the election is cancelled.
+        #[ink::test]
+        fn tips_boost_the_finalization_bounty() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    voting_closes_at: Some(Moment::Block(2)),
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            let undated = voting.create_election(ElectionConfig::default()).unwrap();
+            voting.open_voting(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            set_value(40);
+            assert_eq!(
+                voting.tip_bounty(undated),
+                Err(VoteError::BountyUnavailable)
+            );
+            voting.tip_bounty(0).unwrap();
+            voting.tip_bounty(0).unwrap();
+            set_value(0);
+            assert_eq!(voting.get_bounty_tips(0), 80);
+            assert_eq!(emitted::<BountyTipped>(), 2);
+
+            advance_blocks(3);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_claimable(accounts.eve), 80);
+            assert_eq!(voting.get_bounty_tips(0), 0);
+            assert_eq!(voting.treasury_report().prize_escrow, 0);
+            assert_eq!(voting.tip_bounty(0), Err(VoteError::VotingClosed));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let cancelled = voting
+                .create_election(ElectionConfig {
+                    voting_closes_at: Some(Moment::Block(20)),
+                    ..Default::default()
+                })
+                .unwrap();
+            set_value(25);
+            voting.tip_bounty(cancelled).unwrap();
+            set_value(0);
+            voting.cancel(cancelled).unwrap();
+            assert_eq!(voting.get_treasury(), 25);
+            assert_eq!(voting.treasury_report().prize_escrow, 0);
+        }
+
+        /// We test that the participation score counts ballots, outright
+        /// wins and candidacies across every election.
+        #[ink::test]
+        fn participation_scores_span_elections() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.vote(0, accounts.django).unwrap();
+            voting.finalize(0).unwrap();
+            let second = voting.create_election(ElectionConfig::default()).unwrap();
+            voting.open_registration(second).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.register_candidate(second).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(second).unwrap();
+            voting.vote(second, accounts.eve).unwrap();
+
+            assert_eq!(
+                voting.participation_of(accounts.django),
+                ParticipationScore {
+                    voted: 0,
+                    won: 1,
+                    ran: 2,
+                }
+            );
+            assert_eq!(
+                voting.participation_of(accounts.alice),
+                ParticipationScore {
+                    voted: 2,
+                    won: 0,
+                    ran: 0,
+                }
+            );
+            assert_eq!(voting.participation_of(accounts.eve).ran, 1);
+        }
+
+        /// We test that constructors refuse configurations no election could
+        /// run under, naming what is wrong.
+        #[ink::test]
+        fn constructors_refuse_unrunnable_configs() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let refused = |config| Voting::new_with_config(config, generous_limits()).err();
+            assert_eq!(
+                refused(ElectionConfig {
+                    min_candidates: 3,
+                    max_candidates: 2,
+                    ..Default::default()
+                }),
+                Some(ConfigError::CandidateCapTooLow)
+            );
+            assert_eq!(
+                refused(ElectionConfig {
+                    mode: VotingMode::SingleTransferable(3),
+                    max_candidates: 2,
+                    ..Default::default()
+                }),
+                Some(ConfigError::CandidateCapTooLow)
+            );
+            assert_eq!(
+                refused(ElectionConfig {
+                    anonymous: true,
+                    closed_electorate: true,
+                    quorum: MAX_RING as u32 + 1,
+                    ..Default::default()
+                }),
+                Some(ConfigError::QuorumUnreachable)
+            );
+            assert_eq!(
+                refused(ElectionConfig {
+                    voting_closes_at: Some(Moment::Block(10)),
+                    optimistic_tally: Some(OptimisticTally {
+                        proposer: accounts.bob,
+                        bond: 0,
+                        challenge_period: 0,
+                    }),
+                    ..Default::default()
+                }),
+                Some(ConfigError::NoChallengePeriod)
+            );
+            assert_eq!(
+                refused(ElectionConfig {
+                    voting_opens_at: Some(Moment::Block(10)),
+                    voting_closes_at: Some(Moment::Block(10)),
+                    ..Default::default()
+                }),
+                Some(ConfigError::VotingClosesBeforeOpening)
+            );
+            assert_eq!(
+                refused(ElectionConfig {
+                    min_candidates: 2,
+                    max_candidates: 2,
+                    ..Default::default()
+                }),
+                None
+            );
+        }
+
+        /// We test that closing registration freezes the slate for the grace
+        /// period before voting may open.
+        #[ink::test]
+        fn slates_freeze_for_a_grace_period() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    grace_period: 3,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_registration(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.register_candidate(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(voting.open_voting(0), Err(VoteError::InvalidTransition));
+
+            voting.close_registration(0).unwrap();
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::SlateFrozen));
+            assert_eq!(voting.get_slate_frozen_at(0), Some(0));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                voting.register_candidate(0),
+                Err(RegistrationError::RegistrationClosed)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            assert_eq!(voting.open_voting(0), Err(VoteError::VotingNotOpenYet));
+            advance_blocks(2);
+            voting.open_voting(0).unwrap();
+            assert_eq!(voting.export_candidates(0, 0, 10), vec![accounts.django]);
+        }
+
+        /// We test that an election moves into its campaign and on to voting
+        /// as its block thresholds pass, taking statements and endorsements
+        /// but no ballots meanwhile.
+        #[ink::test]
+        fn campaigns_run_between_registration_and_voting() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    campaign: Some(CampaignPhase {
+                        starts_at: 2,
+                        ends_at: 4,
+                    }),
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_registration(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.register_candidate(0).unwrap();
+            voting.post_statement(0, Hash::from([1; 32])).unwrap();
+
+            advance_blocks(2);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                voting.register_candidate(0),
+                Err(RegistrationError::RegistrationClosed)
+            );
+            voting.endorse(0, accounts.django).unwrap();
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Campaign));
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::VotingClosed)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.post_statement(0, Hash::from([2; 32])).unwrap();
+            assert_eq!(
+                voting.get_statement(0, accounts.django),
+                Some(Hash::from([2; 32]))
+            );
+            assert_eq!(emitted::<StatementPosted>(), 2);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(voting.open_voting(0), Err(VoteError::VotingNotOpenYet));
+
+            advance_blocks(2);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Voting));
+            assert_eq!(voting.advance_phase(0), Ok(ElectionStatus::Voting));
+            assert_eq!(
+                voting.post_statement(0, Hash::from([3; 32])),
+                Err(RegistrationError::RegistrationClosed)
+            );
+        }
+
+        /// We test that ballots weigh more once their voter has attended
+        /// enough earlier elections, by the highest tier reached.
+        #[ink::test]
+        fn attendance_boosts_ballot_weight() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let tiers = |percents: [u32; 2]| ElectionConfig {
+                attendance_boost: vec![
+                    AttendanceTier {
+                        min_ballots: 1,
+                        percent: percents[0],
+                    },
+                    AttendanceTier {
+                        min_ballots: 2,
+                        percent: percents[1],
+                    },
+                ],
+                ..Default::default()
+            };
+            assert_eq!(
+                voting.create_election(tiers([150, MAX_ATTENDANCE_PERCENT + 1])),
+                Err(ConfigError::InvalidAttendanceBoost)
+            );
+            let second = voting.clone_election(0).unwrap();
+            let boosted = voting.create_election(tiers([150, 200])).unwrap();
+            voting.open_voting(second).unwrap();
+            voting.open_voting(boosted).unwrap();
+            voting.vote(0, accounts.django).unwrap();
+            voting.vote(second, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.eve).unwrap();
+            voting.vote(boosted, accounts.eve).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.vote(boosted, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(boosted, accounts.django).unwrap();
+            assert_eq!(voting.get_votes(boosted, accounts.eve), 1);
+            assert_eq!(voting.get_votes(boosted, accounts.django), 3);
+        }
+
+        /// We test that governed upgrades need a referendum approving that exact code hash.
+        #[ink::test]
+        fn governed_upgrades_need_approval() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let approved = Hash::from([1; 32]);
+            let rejected = Hash::from([2; 32]);
+            voting.govern_upgrades().unwrap();
+            assert_eq!(voting.upgrade(approved), Err(VoteError::UpgradeNotApproved));
+
+            let yes = voting.propose_upgrade(approved).unwrap();
+            let no = voting.propose_upgrade(rejected).unwrap();
+            voting.vote_referendum(yes, true).unwrap();
+            voting.vote_referendum(no, false).unwrap();
+            assert_eq!(
+                voting.vote_referendum(yes, false),
+                Err(VoteError::AlreadyVoted)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote_referendum(yes, true).unwrap();
+            assert_eq!(voting.upgrade(approved), Err(VoteError::Unauthorized));
+            assert_eq!(
+                voting.delegate_logic(approved),
+                Err(VoteError::Unauthorized)
+            );
+            assert_eq!(voting.close_referendum(yes), Err(VoteError::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(voting.close_referendum(yes), Ok(ReferendumStatus::Approved));
+            assert_eq!(voting.close_referendum(no), Ok(ReferendumStatus::Rejected));
+            assert_eq!(
+                voting.close_referendum(no),
+                Err(VoteError::ReferendumClosed)
+            );
+            assert_eq!(voting.get_referendum(yes).unwrap().yes, 2);
+            assert_eq!(voting.upgrade(rejected), Err(VoteError::UpgradeNotApproved));
+            assert!(voting.approved_upgrades.contains(approved));
+        }
+
+        /// We test that the query trait mirrors the regular getters.
+        #[ink::test]
+        fn query_trait_mirrors_getters() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(VotingQueries::winner(&voting, 0), [accounts.django]);
+            assert_eq!(
+                VotingQueries::status(&voting, 0),
+                Some(ElectionStatus::Voting)
+            );
+            assert_eq!(VotingQueries::status(&voting, 1), None);
+            assert_eq!(voting.votes_of(0, accounts.django), 1);
+        }
+
+        /// We test that trusted bridges relay ballots, tracked per chain.
+        #[ink::test]
+        fn bridges_relay_ballots() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            voting.set_bridge(accounts.frank, Some(2_000)).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                voting.relay_vote(0, accounts.bob, accounts.django),
+                Err(VoteError::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            assert_eq!(voting.relay_vote(0, accounts.bob, accounts.django), Ok(0));
+            assert_eq!(
+                voting.relay_vote(0, accounts.bob, accounts.django),
+                Err(VoteError::AlreadyVoted)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::AlreadyVoted)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(0, accounts.django).unwrap();
+
+            assert_eq!(voting.get_votes(0, accounts.django), 2);
+            assert_eq!(voting.get_relayed_turnout(0, 2_000), 1);
+            assert_eq!(voting.get_relay_origin(0, accounts.bob), Some(2_000));
+            assert_eq!(voting.get_relay_origin(0, accounts.charlie), None);
+            assert_eq!(emitted::<VoteRelayed>(), 1);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.set_bridge(accounts.frank, None).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            assert_eq!(
+                voting.relay_vote(0, accounts.eve, accounts.django),
+                Err(VoteError::Unauthorized)
+            );
+        }
+
+        /// Lets every account hold its first byte times 1_000 of every asset,
+        /// out of a supply of 10_000.
+        fn mock_assets() {
+            TestRuntime::set(|runtime| {
+                runtime.holding =
+                    |_, owner| Balance::from(AsRef::<[u8]>::as_ref(&owner)[0]) * 1_000;
+                runtime.supply = 10_000;
+            });
+        }
+
+        /// We test that asset-weighted ballots count the voter's holding.
+        #[ink::test]
+        fn asset_holdings_weight_ballots() {
+            mock_assets();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    asset_weight: Some(AssetWeight {
+                        asset_id: 7,
+                        min_holding: 2_000,
+                        unit: 1_000,
+                        max_weight: None,
+                    }),
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_voting(0).unwrap();
+
+            // alice holds 1_000, below the minimum
+            assert_eq!(
+                voting.vote(0, accounts.django),
+                Err(VoteError::InsufficientHolding)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(0, accounts.eve).unwrap();
+
+            assert_eq!(voting.get_votes(0, accounts.django), 2);
+            assert_eq!(voting.get_votes(0, accounts.eve), 3);
+            assert_eq!(voting.get_current_winner(0), [accounts.eve]);
+            assert_eq!(voting.get_results(0).unwrap().turnout, 2);
+        }
+
+        /// We test that the weight of a single ballot can be capped.
+        #[ink::test]
+        fn ballot_weight_is_capped() {
+            mock_assets();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let weighted = |max_weight| ElectionConfig {
+                asset_weight: Some(AssetWeight {
+                    asset_id: 7,
+                    min_holding: 0,
+                    unit: 1_000,
+                    max_weight,
+                }),
+                ..Default::default()
+            };
+            let mut voting = Voting::new();
+            assert_eq!(
+                voting.create_election(weighted(Some(WeightCap::SupplyPercent(101)))),
+                Err(ConfigError::InvalidWeightCap)
+            );
+            assert_eq!(
+                voting.create_election(weighted(Some(WeightCap::Votes(0)))),
+                Err(ConfigError::InvalidWeightCap)
+            );
+            let by_votes = voting
+                .create_election(weighted(Some(WeightCap::Votes(1))))
+                .unwrap();
+            let by_supply = voting
+                .create_election(weighted(Some(WeightCap::SupplyPercent(25))))
+                .unwrap();
+            voting.open_voting(by_votes).unwrap();
+            voting.open_voting(by_supply).unwrap();
+
+            // charlie holds 3_000 and bob 2_000; a quarter of the supply is worth 2 votes
+            for voter in [accounts.bob, accounts.charlie] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(by_votes, voter).unwrap();
+                voting.vote(by_supply, voter).unwrap();
+            }
+            assert_eq!(voting.get_votes(by_votes, accounts.bob), 1);
+            assert_eq!(voting.get_votes(by_votes, accounts.charlie), 1);
+            assert_eq!(voting.get_votes(by_supply, accounts.bob), 2);
+            assert_eq!(voting.get_votes(by_supply, accounts.charlie), 2);
+        }
+
+        /// We test that effective outcomes are noted with the runtime once,
+        /// and that a refused note can be retried.
+        #[ink::test]
+        fn outcomes_are_mirrored_to_the_runtime() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    mirror_outcome: true,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            voting.open_voting(0).unwrap();
+            voting.vote(0, accounts.django).unwrap();
+            assert_eq!(voting.mirror_outcome(0), Err(VoteError::ResultNotEffective));
+
+            TestRuntime::set(|runtime| runtime.refuse_notes = true);
+            voting.finalize(0).unwrap();
+            assert!(!voting.is_outcome_mirrored(0));
+            assert_eq!(voting.mirror_outcome(0), Err(VoteError::MirrorFailed));
+
+            TestRuntime::set(|runtime| runtime.refuse_notes = false);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            voting.mirror_outcome(0).unwrap();
+            assert!(voting.is_outcome_mirrored(0));
+            assert_eq!(
+                TestRuntime::notes(),
+                [(0, vec![accounts.django], voting.get_tally_hash(0).unwrap())]
+            );
+            assert_eq!(emitted::<OutcomeMirrored>(), 1);
+            assert_eq!(voting.mirror_outcome(0), Err(VoteError::AlreadyMirrored));
+
+            // effective at finalization, the outcome is noted right away
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mirrored = voting
+                .create_election(ElectionConfig {
+                    mirror_outcome: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            let unmirrored = voting.create_election(ElectionConfig::default()).unwrap();
+            for election_id in [mirrored, unmirrored] {
+                voting.open_voting(election_id).unwrap();
+                voting.vote(election_id, accounts.eve).unwrap();
+                voting.finalize(election_id).unwrap();
+            }
+            assert!(voting.is_outcome_mirrored(mirrored));
+            assert_eq!(TestRuntime::notes().len(), 2);
+            assert_eq!(
+                voting.mirror_outcome(unmirrored),
+                Err(VoteError::MirrorDisabled)
+            );
+        }
+
+        /// Lets threshold decryption XOR ciphertexts with the election key once
+        /// at least two shares are supplied.
+        fn mock_decryption() {
+            TestRuntime::set(|runtime| {
+                runtime.decrypt =
+                    |key, shares, ciphertext| (shares.len() >= 2).then(|| seal(key, ciphertext))
+            });
+        }
+
+        /// XORs `bytes` with `key`, which both encrypts and decrypts them for
+        /// `mock_decryption`.
+        fn seal(key: [u8; 32], bytes: &[u8]) -> Vec<u8> {
+            bytes
+                .iter()
+                .zip(key.iter().cycle())
+                .map(|(byte, key)| byte ^ key)
+                .collect()
+        }
+
+        /// We test that encrypted ballots stay secret until the decryption
+        /// committee meets its threshold, and are then revealed and counted.
+        #[ink::test]
+        fn encrypted_ballots_are_counted_once_revealed() {
+            use ink::scale::Encode;
+            mock_decryption();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let key = [7; 32];
+            let config = |threshold| ElectionConfig {
+                voting_closes_at: Some(Moment::Block(3)),
+                encrypted_ballots: Some(EncryptedBallots {
+                    public_key: key,
+                    trustees: vec![accounts.charlie, accounts.django, accounts.eve],
+                    threshold,
+                }),
+                ..Default::default()
+            };
+            let mut voting = Voting::new_with_config(config(2), generous_limits()).unwrap();
+            assert_eq!(
+                voting.create_election(config(4)),
+                Err(ConfigError::UnsupportedEncryptedBallots)
+            );
+            voting.open_voting(0).unwrap();
+            assert_eq!(
+                voting.vote(0, accounts.bob),
+                Err(VoteError::BallotsEncrypted)
+            );
+            let for_bob = seal(key, &accounts.bob.encode());
+            voting.vote_encrypted(0, for_bob.clone()).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote_encrypted(0, for_bob.clone()).unwrap();
+            assert_eq!(
+                voting.vote_encrypted(0, for_bob),
+                Err(VoteError::AlreadyVoted)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            voting.vote_encrypted(0, vec![1, 2, 3]).unwrap();
+            assert_eq!(emitted::<EncryptedVoteCast>(), 3);
+            assert_eq!(voting.get_votes(0, accounts.bob), 0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                voting.post_key_share(0, vec![1]),
+                Err(VoteError::VotingStillOpen)
+            );
+            advance_blocks(4);
+            assert_eq!(voting.finalize_step(0, 10), Err(VoteError::BallotsSealed));
+            voting.post_key_share(0, vec![1]).unwrap();
+            assert_eq!(
+                voting.post_key_share(0, vec![1]),
+                Err(VoteError::ShareAlreadyPosted)
+            );
+            assert_eq!(
+                voting.reveal_ballots(0, 10),
+                Err(VoteError::InvalidTransition)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            assert_eq!(
+                voting.post_key_share(0, vec![2]),
+                Err(VoteError::Unauthorized)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.post_key_share(0, vec![2]).unwrap();
+            assert_eq!(voting.get_key_share_count(0), 2);
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::RevealPhase));
+
+            // the third ballot decrypts to no account and is spoiled
+            assert_eq!(voting.reveal_ballots(0, 2), Ok(false));
+            assert_eq!(voting.get_votes(0, accounts.bob), 2);
+            assert_eq!(voting.reveal_ballots(0, 10), Ok(true));
+            assert_eq!(voting.get_revealed_count(0), 3);
+            assert_eq!(emitted::<BallotsRevealed>(), 2);
+            voting.finalize_step(0, 10).unwrap();
+            assert_eq!(voting.get_current_winner(0), vec![accounts.bob]);
+        }
+
+        /// Lets ring signature checks accept any signature that repeats its
+        /// key image over a nonempty ring.
+        fn mock_ring() {
+            TestRuntime::set(|runtime| {
+                runtime.verify =
+                    |ring, key_image, signature| !ring.is_empty() && signature == key_image.as_ref()
+            });
+        }
+
+        /// We test that anonymous ballots count once per nullifier, and only
+        /// when signed over the whole electorate.
+        #[ink::test]
+        fn anonymous_ballots_are_linked_by_key_image() {
+            mock_ring();
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_config(
+                ElectionConfig {
+                    closed_electorate: true,
+                    anonymous: true,
+                    ..Default::default()
+                },
+                generous_limits(),
+            )
+            .unwrap();
+            assert_eq!(
+                voting.create_election(ElectionConfig {
+                    anonymous: true,
+                    ..Default::default()
+                }),
+                Err(ConfigError::UnsupportedAnonymity)
+            );
+            let ring = vec![accounts.bob, accounts.charlie, accounts.django];
+            voting.add_voters(0, ring.clone()).unwrap();
+            voting.open_voting(0).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                voting.vote(0, accounts.eve),
+                Err(VoteError::BallotsAnonymous)
+            );
+
+            // a relayer outside the electorate submits the ballots
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            let image = Hash::from([1; 32]);
+            let vote =
+                |voting: &mut Voting, ring: Vec<AccountId>, image: Hash, signature: &[u8]| {
+                    voting.vote_anonymous(0, accounts.eve, ring, image, signature.to_vec())
+                };
+            assert_eq!(
+                vote(&mut voting, ring[..2].to_vec(), image, image.as_ref()),
+                Err(VoteError::InvalidRing)
+            );
+            assert_eq!(
+                vote(
+                    &mut voting,
+                    vec![accounts.bob, accounts.charlie, accounts.frank],
+                    image,
+                    image.as_ref()
+                ),
+                Err(VoteError::InvalidRing)
+            );
+            assert_eq!(
+                vote(&mut voting, ring.clone(), image, &[0; 32]),
+                Err(VoteError::InvalidSignature)
+            );
+            vote(&mut voting, ring.clone(), image, image.as_ref()).unwrap();
+            assert!(voting.is_nullifier_spent(voting.anonymous_nullifier(0, image)));
+            assert!(!voting.is_nullifier_spent(voting.anonymous_nullifier(1, image)));
+            assert_eq!(
+                vote(&mut voting, ring.clone(), image, image.as_ref()),
+                Err(VoteError::NullifierSpent)
+            );
+            let other = Hash::from([2; 32]);
+            vote(&mut voting, ring, other, other.as_ref()).unwrap();
+            assert_eq!(emitted::<AnonymousVoteCast>(), 2);
+            assert_eq!(voting.get_votes(0, accounts.eve), 2);
+            assert_eq!(voting.elections.get(0).unwrap().turnout, 2);
+            assert!(voting.get_voters(0, 0, 10).is_empty());
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+            assert_eq!(voting.get_current_winner(0), vec![accounts.eve]);
+        }
+
+        /// Runs `cases` random elections on one contract, each with up to
+        /// `max_candidates` candidates and `max_voters` ballots, and hands every
+        /// finalized election to `check` with the ballots cast per candidate.
+        fn random_elections(
+            seed: u64,
+            cases: u32,
+            tie_break: TieBreakPolicy,
+            check: impl Fn(&Voting, ElectionId, &[(AccountId, u32)]),
+        ) {
+            use rand::{rngs::StdRng, Rng, SeedableRng};
+
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut voting = Voting::new();
+            for _ in 0..cases {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+                let election_id = voting
+                    .create_election(ElectionConfig {
+                        tie_break,
+                        ..Default::default()
+                    })
+                    .unwrap();
+                voting.open_voting(election_id).unwrap();
+
+                let candidates: Vec<AccountId> = (0..rng.gen_range(1..=8u8))
+                    .map(|n| AccountId::from([n; 32]))
+                    .collect();
+                let mut cast: Vec<(AccountId, u32)> = Vec::new();
+                for voter in 0..rng.gen_range(0..=40u8) {
+                    let candidate = candidates[rng.gen_range(0..candidates.len())];
+                    let mut voter_id = [0xff; 32];
+                    voter_id[0] = voter;
+                    ink::env::test::set_caller::<ink::env::DefaultEnvironment>(AccountId::from(
+                        voter_id,
+                    ));
+                    voting.vote(election_id, candidate).unwrap();
+                    // a second ballot is always refused and never counted
+                    let other = candidates[rng.gen_range(0..candidates.len())];
+                    assert_eq!(
+                        voting.vote(election_id, other),
+                        Err(VoteError::AlreadyVoted)
+                    );
+                    match cast.iter_mut().find(|(c, _)| *c == candidate) {
+                        Some((_, ballots)) => *ballots += 1,
+                        None => cast.push((candidate, 1)),
+                    }
+                }
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+                voting.finalize(election_id).unwrap();
+                check(&voting, election_id, &cast);
+            }
+        }
+
+        /// We test that counts always add up to the ballots cast, in random elections.
+        #[ink::test]
+        fn tally_counts_every_ballot_once() {
+            random_elections(
+                1,
+                64,
+                TieBreakPolicy::AllowTie,
+                |voting, election_id, cast| {
+                    let results = voting.get_results(election_id).unwrap();
+                    let counted: u32 = results.totals.iter().map(|(_, votes)| votes).sum();
+                    let ballots: u32 = cast.iter().map(|(_, ballots)| ballots).sum();
+                    assert_eq!(counted, ballots);
+                    assert_eq!(results.turnout, ballots);
+                    for (candidate, ballots) in cast {
+                        assert_eq!(voting.get_votes(election_id, *candidate), *ballots);
+                    }
+                    assert_eq!(results.totals.len(), cast.len());
+                },
+            );
+        }
+
+        /// We test that winners always hold the top count, in random elections.
+        #[ink::test]
+        fn winners_hold_the_top_count() {
+            random_elections(
+                2,
+                64,
+                TieBreakPolicy::AllowTie,
+                |voting, election_id, cast| {
+                    let results = voting.get_results(election_id).unwrap();
+                    let top = cast.iter().map(|(_, ballots)| *ballots).max();
+                    let mut expected: Vec<AccountId> = cast
+                        .iter()
+                        .filter(|(_, ballots)| Some(*ballots) == top)
+                        .map(|(candidate, _)| *candidate)
+                        .collect();
+                    // tied winners come in canonical, account order
+                    expected.sort();
+                    assert_eq!(results.winners, expected);
+                    if expected.len() != 1 {
+                        assert_eq!(results.margin, 0);
+                    }
+                    for runner_up in &results.runner_up {
+                        assert!(voting.get_votes(election_id, *runner_up) < top.unwrap_or(0));
+                    }
+                },
+            );
+        }
+
+        /// We test that the earliest-runner policy always yields at most one winner.
+        #[ink::test]
+        fn earliest_runner_yields_one_winner() {
+            random_elections(
+                3,
+                64,
+                TieBreakPolicy::EarliestRunner,
+                |voting, election_id, cast| {
+                    let winners = voting.get_current_winner(election_id);
+                    assert_eq!(winners.len(), usize::from(!cast.is_empty()));
+                    if let Some(winner) = winners.first() {
+                        let top = cast.iter().map(|(_, ballots)| *ballots).max().unwrap();
+                        assert_eq!(voting.get_votes(election_id, *winner), top);
+                        let first_to_top = cast.iter().find(|(_, b)| *b == top).unwrap().0;
+                        assert_eq!(*winner, first_to_top);
+                    }
+                },
+            );
+        }
+
+        /// We test that finalization can be spread over several bounded steps.
+        #[ink::test]
+        fn finalization_resumes_in_steps() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new_with_candidates(vec![
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+                accounts.eve,
+                accounts.frank,
+            ]);
+            voting.vote(0, accounts.django).unwrap();
+
+            assert_eq!(voting.finalize_step(0, 2), Ok(false));
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Tallying));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(voting.vote(0, accounts.eve), Err(VoteError::VotingClosed));
+            assert_eq!(voting.finalize_step(0, 2), Err(VoteError::Unauthorized));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(voting.finalize_step(0, 2), Ok(false));
+            assert_eq!(voting.finalize_step(0, 2), Ok(true));
+            assert_eq!(voting.get_status(0), Some(ElectionStatus::Finalized));
+            let archived = voting.get_archived_result(0).unwrap();
+            assert_eq!(archived.winners, [accounts.django]);
+            assert_eq!(archived.totals.len(), 5);
+            assert_eq!(
+                voting.finalize_step(0, 2),
+                Err(VoteError::InvalidTransition)
+            );
+        }
+
+        /// We test that finalization is summed up in one event committing to
+        /// the sorted final tally.
+        #[ink::test]
+        fn finalization_seals_the_tally() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::default();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(0, accounts.eve).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            voting.vote(0, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            voting.vote(0, accounts.eve).unwrap();
+            assert_eq!(voting.get_tally_hash(0), None);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+            let mut expected = [0u8; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &vec![(accounts.django, 1u32), (accounts.eve, 2u32)],
+                &mut expected,
+            );
+            assert_eq!(voting.get_tally_hash(0), Some(Hash::from(expected)));
+
+            let sealed = events_of::<RoundFinalized>();
+            assert_eq!(sealed.len(), 1);
+            let sealed =
+                <RoundFinalized as ink::scale::Decode>::decode(&mut &sealed[0].data[..]).unwrap();
+            assert_eq!(sealed.round, 0);
+            assert_eq!(sealed.winners, [accounts.eve]);
+            assert_eq!((sealed.turnout, sealed.total_votes), (3, 3));
+            assert_eq!(sealed.tally_hash, Hash::from(expected));
+            assert_eq!(sealed.ballot_root, voting.get_ballot_root(0).unwrap());
+        }
+
+        /// Builds the Merkle proof of leaf `index` the way an off-chain
+        /// observer replaying `VoteCast` events would.
+        fn ballot_proof(leaves: &[Hash], mut index: usize) -> (Vec<Hash>, Hash) {
+            let mut level = leaves.to_vec();
+            let mut zero = Hash::from([0; 32]);
+            let mut proof = Vec::new();
+            for _ in 0..BALLOT_TREE_DEPTH {
+                if level.len() % 2 == 1 {
+                    level.push(zero);
                 }
+                proof.push(level[index ^ 1]);
+                level = level
+                    .chunks(2)
+                    .map(|pair| Voting::hash_pair(&pair[0], &pair[1]))
+                    .collect();
+                zero = Voting::hash_pair(&zero, &zero);
+                index /= 2;
             }
-            current_winners
+            (proof, level[0])
         }
-    }
 
-    // TODO:
-    // Write unitary tests
-    // Write integration tests
-    // e2e tests?
-    // README file
-    // upload github
+        /// We test that finalization commits to a Merkle root of the ballots
+        /// that proves the inclusion of each of them.
+        #[ink::test]
+        fn ballot_inclusion_is_provable() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::default();
+            let ballots = [
+                (accounts.bob, accounts.eve),
+                (accounts.charlie, accounts.django),
+                (accounts.frank, accounts.eve),
+            ];
+            for (voter, candidate) in ballots {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(voter);
+                voting.vote(0, candidate).unwrap();
+            }
+            assert_eq!(voting.get_ballot_root(0), None);
 
-    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
-    /// module and test functions are marked with a `#[test]` attribute.
-    /// The below code is technically just normal Rust code.
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.finalize(0).unwrap();
+            let leaves: Vec<Hash> = ballots
+                .iter()
+                .map(|(voter, candidate)| Voting::ballot_leaf(0, *voter, *candidate))
+                .collect();
+            for (index, (voter, candidate)) in ballots.into_iter().enumerate() {
+                let (proof, root) = ballot_proof(&leaves, index);
+                assert_eq!(voting.get_ballot_root(0), Some(root));
+                assert!(voting.verify_ballot(0, index as u32, voter, candidate, proof.clone()));
+                assert!(!voting.verify_ballot(0, index as u32, voter, accounts.alice, proof));
+            }
+            let (proof, _) = ballot_proof(&leaves, 0);
+            assert!(!voting.verify_ballot(0, 1, accounts.bob, accounts.eve, proof));
+        }
 
-        /// We test if the default constructor does its job.
+        /// We test that the tie-break policy can settle a tie for first place.
         #[ink::test]
-        fn default_works() {
-            let voting = Voting::default();
+        fn earliest_runner_breaks_ties() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    tie_break: TieBreakPolicy::EarliestRunner,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_voting(election_id).unwrap();
+            voting.vote(election_id, accounts.django).unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.vote(election_id, accounts.eve).unwrap();
 
-            // assert current winners == []
-            // assert runners.length == 0
+            assert_eq!(
+                voting.get_current_winner(election_id),
+                vec![accounts.django]
+            );
+            assert_eq!(voting.get_runner_up(election_id), vec![accounts.eve]);
+            assert_eq!(voting.get_margin(election_id), 0);
         }
 
-        /// We test a simple use case of our contract.
+        /// We test that candidates need enough distinct endorsements.
         #[ink::test]
-        fn accept_new_vote() {
-            // How such unit test could "mock data"?
-            // let mut voting = Voting::new();
+        fn candidacy_needs_endorsements() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut voting = Voting::new();
+            let election_id = voting
+                .create_election(ElectionConfig {
+                    endorsements_required: 2,
+                    ..Default::default()
+                })
+                .unwrap();
+            voting.open_registration(election_id).unwrap();
+
+            voting.endorse(election_id, accounts.django).unwrap();
+            assert_eq!(
+                voting.endorse(election_id, accounts.django),
+                Err(RegistrationError::AlreadyEndorsed)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(
+                voting.endorse(election_id, accounts.django),
+                Err(RegistrationError::SelfEndorsement)
+            );
+            assert_eq!(
+                voting.register_candidate(election_id),
+                Err(RegistrationError::NotEnoughEndorsements)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            voting.endorse(election_id, accounts.django).unwrap();
+            assert_eq!(voting.get_endorsements(election_id, accounts.django), 2);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            voting.register_candidate(election_id).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            voting.open_voting(election_id).unwrap();
+            assert_eq!(
+                voting.vote(election_id, accounts.eve),
+                Err(VoteError::NotACandidate)
+            );
+            voting.vote(election_id, accounts.django).unwrap();
         }
     }
 
@@ -148,6 +19257,7 @@ mod voting {
         use super::*;
 
         use ink::primitives::AccountId;
+        use ink_e2e::events::{ContractEmitted, EventWithTopics};
         /// A helper function used for calling contract messages.
         use ink_e2e::ContractsBackend;
 
@@ -155,7 +19265,7 @@ mod voting {
         type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
         /// We test that we can upload and instantiate the contract using its default constructor.
-        #[ink_e2e::test]
+        #[ink_e2e::test(environment = crate::runtime::VotingEnvironment)]
         async fn default_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // Given
             let mut constructor = VotingRef::default();
@@ -171,7 +19281,7 @@ mod voting {
             // Then
             let alice_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
 
-            let get_alice_votes = call_builder.get_votes(alice_account);
+            let get_alice_votes = call_builder.get_votes(0, alice_account);
             // let alice_votes = call_builder.get_votes(&ink_e2e::alice().public_key());
             let alice_votes = client
                 .call(&ink_e2e::alice(), &get_alice_votes)
@@ -182,35 +19292,860 @@ mod voting {
             Ok(())
         }
 
-        // #[ink_e2e::test]
-        // async fn it_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
-        //     // Given
-        //     let mut constructor = VotingRef::new(false);
-        //     let contract = client
-        //         .instantiate("voting", &ink_e2e::bob(), &mut constructor)
-        //         .submit()
-        //         .await
-        //         .expect("instantiate failed");
-        //     let mut call_builder = contract.call_builder::<Voting>();
+        /// We test a whole election: registration, several voters, a rejected
+        /// second ballot, finalization and the winner.
+        #[ink_e2e::test(environment = crate::runtime::VotingEnvironment)]
+        async fn full_election_flow(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let mut constructor =
+                VotingRef::new_with_config(ElectionConfig::default(), FeeLimits::default());
+            let contract = client
+                .instantiate("voting", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Voting>();
+            let bob: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+
+            client
+                .call(&ink_e2e::alice(), &call_builder.open_registration(0))
+                .submit()
+                .await
+                .expect("open_registration failed");
+            for candidate in [ink_e2e::bob(), ink_e2e::charlie()] {
+                client
+                    .call(&candidate, &call_builder.register_candidate(0))
+                    .submit()
+                    .await
+                    .expect("register_candidate failed");
+            }
+            client
+                .call(&ink_e2e::alice(), &call_builder.open_voting(0))
+                .submit()
+                .await
+                .expect("open_voting failed");
+
+            // When
+            for (voter, candidate) in [
+                (ink_e2e::dave(), charlie),
+                (ink_e2e::eve(), bob),
+                (ink_e2e::ferdie(), charlie),
+            ] {
+                client
+                    .call(&voter, &call_builder.vote(0, candidate))
+                    .submit()
+                    .await
+                    .expect("vote failed");
+            }
+            let second_ballot = client
+                .call(&ink_e2e::dave(), &call_builder.vote(0, bob))
+                .dry_run()
+                .await?;
+            assert_eq!(second_ballot.return_value(), Err(VoteError::AlreadyVoted));
+
+            client
+                .call(&ink_e2e::alice(), &call_builder.finalize(0))
+                .submit()
+                .await
+                .expect("finalize failed");
+
+            // Then
+            let results = client
+                .call(&ink_e2e::alice(), &call_builder.get_results(0))
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("election exists");
+            assert_eq!(results.status, ElectionStatus::Finalized);
+            assert_eq!(results.winners, [charlie]);
+            assert_eq!(results.totals, [(bob, 1), (charlie, 2)]);
+            assert_eq!(results.turnout, 3);
+
+            let winner = client
+                .call(&ink_e2e::bob(), &call_builder.get_current_winner(0))
+                .dry_run()
+                .await?;
+            assert_eq!(winner.return_value(), [charlie]);
+
+            Ok(())
+        }
+
+        /// Decodes the events of type `T` among those a call emitted.
+        fn decode_events<T: ink::env::Event + ink::scale::Decode>(
+            events: Vec<EventWithTopics<ContractEmitted<crate::runtime::VotingEnvironment>>>,
+        ) -> Vec<T> {
+            events
+                .into_iter()
+                .filter(|event| {
+                    event.topics.first().map(|topic| topic.as_ref())
+                        == T::SIGNATURE_TOPIC.as_ref().map(|topic| &topic[..])
+                })
+                .map(|event| T::decode(&mut &event.event.data[..]).expect("event decodes"))
+                .collect()
+        }
+
+        /// We test that an exact tie is shared by default, and won by the
+        /// earlier runner under `TieBreakPolicy::EarliestRunner`.
+        #[ink_e2e::test(environment = crate::runtime::VotingEnvironment)]
+        async fn exact_ties_follow_the_tie_break(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Given
+            let mut constructor = VotingRef::new();
+            let contract = client
+                .instantiate("voting", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Voting>();
+            let bob: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            let earliest = client
+                .call(
+                    &ink_e2e::alice(),
+                    &call_builder.create_election(ElectionConfig {
+                        tie_break: TieBreakPolicy::EarliestRunner,
+                        ..Default::default()
+                    }),
+                )
+                .submit()
+                .await
+                .expect("create_election failed")
+                .return_value()
+                .expect("valid config");
+            client
+                .call(&ink_e2e::alice(), &call_builder.open_voting(earliest))
+                .submit()
+                .await
+                .expect("open_voting failed");
+
+            // When
+            for election_id in [0, earliest] {
+                for (voter, candidate) in [(ink_e2e::dave(), bob), (ink_e2e::eve(), charlie)] {
+                    let vote = client
+                        .call(&voter, &call_builder.vote(election_id, candidate))
+                        .submit()
+                        .await
+                        .expect("vote failed");
+                    let cast = decode_events::<VoteCast>(vote.contract_emitted_events()?);
+                    assert_eq!(cast.len(), 1);
+                    assert_eq!(
+                        (cast[0].election_id, cast[0].candidate),
+                        (election_id, candidate)
+                    );
+                }
+                client
+                    .call(&ink_e2e::alice(), &call_builder.finalize(election_id))
+                    .submit()
+                    .await
+                    .expect("finalize failed");
+            }
+
+            // Then
+            let shared = client
+                .call(&ink_e2e::alice(), &call_builder.get_results(0))
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("election exists");
+            assert_eq!(shared.winners, [bob, charlie]);
+            assert_eq!(shared.margin, 0);
+            assert_eq!(shared.totals, [(bob, 1), (charlie, 1)]);
+            let broken = client
+                .call(&ink_e2e::alice(), &call_builder.get_results(earliest))
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("election exists");
+            assert_eq!(broken.winners, [bob]);
+            assert_eq!(broken.runner_up, [charlie]);
+
+            Ok(())
+        }
+
+        /// We test that a single-transferable-vote election fills every seat
+        /// and announces them together.
+        #[ink_e2e::test(environment = crate::runtime::VotingEnvironment)]
+        async fn multi_winner_election_fills_every_seat(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Given
+            let mut constructor = VotingRef::new_with_config(
+                ElectionConfig {
+                    mode: VotingMode::SingleTransferable(2),
+                    ..Default::default()
+                },
+                FeeLimits::default(),
+            );
+            let contract = client
+                .instantiate("voting", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Voting>();
+            let bob: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            client
+                .call(&ink_e2e::alice(), &call_builder.open_voting(0))
+                .submit()
+                .await
+                .expect("open_voting failed");
+
+            // When
+            for (voter, ranking) in [
+                (ink_e2e::dave(), vec![bob, charlie]),
+                (ink_e2e::eve(), vec![bob]),
+                (ink_e2e::ferdie(), vec![charlie]),
+            ] {
+                client
+                    .call(&voter, &call_builder.vote_ranked(0, ranking))
+                    .submit()
+                    .await
+                    .expect("vote_ranked failed");
+            }
+            let finalize = client
+                .call(&ink_e2e::alice(), &call_builder.finalize(0))
+                .submit()
+                .await
+                .expect("finalize failed");
+
+            // Then
+            let filled = decode_events::<SeatsFilled>(finalize.contract_emitted_events()?);
+            assert_eq!(filled.len(), 1);
+            assert_eq!(filled[0].elected, [bob, charlie]);
+            let elected = client
+                .call(&ink_e2e::alice(), &call_builder.get_stv_elected(0))
+                .dry_run()
+                .await?;
+            assert_eq!(elected.return_value(), [bob, charlie]);
+            let results = client
+                .call(&ink_e2e::alice(), &call_builder.get_results(0))
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("election exists");
+            assert_eq!(results.winners, [bob, charlie]);
+
+            Ok(())
+        }
+
+        /// We test that a second ballot signed by the same key is rejected on
+        /// chain, leaving the tally and the ballot events as they were.
+        #[ink_e2e::test(environment = crate::runtime::VotingEnvironment)]
+        async fn double_vote_from_one_key_is_rejected(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Given
+            let mut constructor = VotingRef::new();
+            let contract = client
+                .instantiate("voting", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Voting>();
+            let bob: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            client
+                .call(&ink_e2e::dave(), &call_builder.vote(0, bob))
+                .submit()
+                .await
+                .expect("vote failed");
+
+            // When
+            let second = client
+                .call(&ink_e2e::dave(), &call_builder.vote(0, charlie))
+                .submit()
+                .await;
+
+            // Then
+            assert!(second.is_err());
+            let second = client
+                .call(&ink_e2e::dave(), &call_builder.vote(0, charlie))
+                .dry_run()
+                .await?;
+            assert_eq!(second.return_value(), Err(VoteError::AlreadyVoted));
+            let results = client
+                .call(&ink_e2e::alice(), &call_builder.get_results(0))
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("election exists");
+            assert_eq!(results.totals, [(bob, 1)]);
+            assert_eq!(results.turnout, 1);
+
+            Ok(())
+        }
+
+        /// We test that a ballot which would overflow a runner's tally is
+        /// rejected rather than wrapped or saturated, and that the voter may
+        /// still vote for another runner.
+        #[ink_e2e::test(environment = crate::runtime::VotingEnvironment)]
+        async fn overflowing_ballot_is_rejected(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            // Given
+            let mut constructor =
+                VotingRef::new_from_snapshot(ElectionConfig::default(), FeeLimits::default());
+            let contract = client
+                .instantiate("voting", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Voting>();
+            let bob: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie: AccountId = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &call_builder.import_tallies(0, vec![(bob, u32::MAX)]),
+                )
+                .submit()
+                .await
+                .expect("import_tallies failed");
+            let overflow = client
+                .call(
+                    &ink_e2e::alice(),
+                    &call_builder.import_tallies(0, vec![(bob, 1)]),
+                )
+                .dry_run()
+                .await?;
+            assert_eq!(overflow.return_value(), Err(VoteError::VoteOverflow));
+            client
+                .call(&ink_e2e::alice(), &call_builder.open_voting(0))
+                .submit()
+                .await
+                .expect("open_voting failed");
+
+            // When
+            let overflow = client
+                .call(&ink_e2e::dave(), &call_builder.vote(0, bob))
+                .submit()
+                .await;
+
+            // Then
+            assert!(overflow.is_err());
+            let overflow = client
+                .call(&ink_e2e::dave(), &call_builder.vote(0, bob))
+                .dry_run()
+                .await?;
+            assert_eq!(overflow.return_value(), Err(VoteError::VoteOverflow));
+            let vote = client
+                .call(&ink_e2e::dave(), &call_builder.vote(0, charlie))
+                .submit()
+                .await
+                .expect("vote failed");
+            assert_eq!(
+                decode_events::<VoteCast>(vote.contract_emitted_events()?).len(),
+                1
+            );
+            let results = client
+                .call(&ink_e2e::alice(), &call_builder.get_results(0))
+                .dry_run()
+                .await?
+                .return_value()
+                .expect("election exists");
+            assert_eq!(results.totals, [(bob, u32::MAX), (charlie, 1)]);
+            assert_eq!(results.winners, [bob]);
+
+            Ok(())
+        }
+    }
+
+    /// In-process tests against a `pallet-contracts` runtime, covering what the
+    /// off-chain environment cannot: real balance transfers and calls between
+    /// contracts. No node is needed.
+    ///
+    /// Run them with `cargo test --features sandbox-tests`.
+    #[cfg(all(test, feature = "sandbox-tests"))]
+    mod sandbox_tests {
+        use super::*;
+        use ink_e2e::{ChainBackend, ContractsBackend};
+        use voting_proxy::voting_proxy::{AdminCommand, VotingProxy, VotingProxyRef};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// We test that a funded prize is really paid out to the winner.
+        #[ink_e2e::test(
+            backend(runtime_only(sandbox = ink_e2e::DefaultSandbox)),
+            environment = crate::runtime::VotingEnvironment
+        )]
+        async fn prize_is_paid_to_the_winner<Client: ink_e2e::E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let mut constructor = VotingRef::new();
+            let contract = client
+                .instantiate("voting", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut call_builder = contract.call_builder::<Voting>();
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            client
+                .call(&ink_e2e::alice(), &call_builder.fund_prize(0))
+                .value(1_000_000_000)
+                .submit()
+                .await
+                .expect("fund_prize failed");
+            client
+                .call(&ink_e2e::charlie(), &call_builder.vote(0, bob))
+                .submit()
+                .await
+                .expect("vote failed");
+            client
+                .call(&ink_e2e::alice(), &call_builder.finalize(0))
+                .submit()
+                .await
+                .expect("finalize failed");
+
+            let before = client.free_balance(bob).await?;
+            let claim = client
+                .call(&ink_e2e::bob(), &call_builder.claim_prize(0))
+                .submit()
+                .await
+                .expect("claim_prize failed");
+            assert_eq!(claim.return_value(), Ok(1_000_000_000));
+            let claim = client
+                .call(&ink_e2e::bob(), &call_builder.claim())
+                .submit()
+                .await
+                .expect("claim failed");
+            assert_eq!(claim.return_value(), Ok(1_000_000_000));
+            assert!(client.free_balance(bob).await? > before);
+            Ok(())
+        }
+
+        /// We test that the proxy forwards calls to its current target.
+        #[ink_e2e::test(
+            backend(runtime_only(sandbox = ink_e2e::DefaultSandbox)),
+            environment = crate::runtime::VotingEnvironment
+        )]
+        async fn proxy_forwards_to_its_target<Client: ink_e2e::E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let mut first = VotingRef::new();
+            let first = client
+                .instantiate("voting", &ink_e2e::alice(), &mut first)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut second =
+                VotingRef::new_with_config(ElectionConfig::default(), FeeLimits::default());
+            let second = client
+                .instantiate("voting", &ink_e2e::alice(), &mut second)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let mut proxy = VotingProxyRef::new(first.account_id);
+            let proxy = client
+                .instantiate("voting_proxy", &ink_e2e::alice(), &mut proxy)
+                .submit()
+                .await
+                .expect("instantiate failed");
+            let via_proxy = proxy.call_builder::<Voting>();
+
+            let status = client
+                .call(&ink_e2e::bob(), &via_proxy.get_status(0))
+                .dry_run()
+                .await?;
+            assert_eq!(status.return_value(), Some(ElectionStatus::Voting));
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &proxy
+                        .call_builder::<VotingProxy>()
+                        .admin(AdminCommand::SetTarget(second.account_id)),
+                )
+                .submit()
+                .await
+                .expect("admin failed");
+            let status = client
+                .call(&ink_e2e::bob(), &via_proxy.get_status(0))
+                .dry_run()
+                .await?;
+            assert_eq!(status.return_value(), Some(ElectionStatus::Pending));
+            Ok(())
+        }
+    }
+}
+
+/// A typed off-chain client for services integrating a deployed contract,
+/// over `subxt`. It encodes messages under their fixed selectors, dry-runs
+/// them for the gas they need, and decodes results and events into the
+/// contract's own types.
+#[cfg(feature = "client")]
+pub mod client {
+    use crate::queries::{ElectionId, ElectionStatus};
+    use crate::voting::{BallotId, ElectionResult, PhaseChanged, VoteCast, VoteError};
+    use ink::env::Event as _;
+    use ink::prelude::vec::Vec;
+    use ink::primitives::AccountId;
+    use ink::scale::{Decode, Encode};
+    use subxt::{
+        events::{EventDetails, Events, StaticEvent},
+        ext::futures::{Stream, StreamExt},
+        tx::Signer,
+        utils::AccountId32,
+        OnlineClient, PolkadotConfig,
+    };
+
+    /// Selector of `vote`.
+    const VOTE: [u8; 4] = [0x08, 0x3b, 0xe2, 0x60];
+    /// Selector of `get_results`.
+    const GET_RESULTS: [u8; 4] = [0x72, 0xa2, 0xe8, 0x2e];
+
+    /// Errors of the client.
+    #[derive(Debug)]
+    pub enum Error {
+        /// The node could not be reached or rejected the request.
+        Subxt(subxt::Error),
+        /// A reply could not be decoded.
+        Codec(ink::scale::Error),
+        /// The call failed before reaching the contract, e.g. for lack of
+        /// funds or gas.
+        DispatchFailed,
+        /// The contract does not know the message, e.g. after an upgrade.
+        Lang(ink::LangError),
+        /// The contract refused the ballot.
+        Vote(VoteError),
+        /// The ballot was accepted but its `VoteCast` event is missing.
+        MissingEvent,
+    }
+
+    impl From<subxt::Error> for Error {
+        fn from(error: subxt::Error) -> Self {
+            Error::Subxt(error)
+        }
+    }
+
+    impl From<ink::scale::Error> for Error {
+        fn from(error: ink::scale::Error) -> Self {
+            Error::Codec(error)
+        }
+    }
+
+    /// An event of the contract.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Event {
+        VoteCast(Ballot),
+        PhaseChanged(PhaseChange),
+        /// Any other event, by its signature topic, with its raw data.
+        Other {
+            topic: [u8; 32],
+            data: Vec<u8>,
+        },
+    }
+
+    /// The fields of a `VoteCast` event.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Decode)]
+    pub struct Ballot {
+        pub election_id: ElectionId,
+        pub ballot_id: BallotId,
+        pub voter: AccountId,
+        pub candidate: AccountId,
+        pub fee: u128,
+        pub memo: Vec<u8>,
+    }
+
+    /// The fields of a `PhaseChanged` event.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Decode)]
+    pub struct PhaseChange {
+        pub election_id: ElectionId,
+        pub from: ElectionStatus,
+        pub to: ElectionStatus,
+    }
+
+    impl Event {
+        /// Decodes an event from its signature topic and data.
+        pub fn decode(topic: [u8; 32], data: &[u8]) -> Result<Self, ink::scale::Error> {
+            let mut input = data;
+            Ok(match Some(topic) {
+                t if t == VoteCast::SIGNATURE_TOPIC => Event::VoteCast(Decode::decode(&mut input)?),
+                t if t == PhaseChanged::SIGNATURE_TOPIC => {
+                    Event::PhaseChanged(Decode::decode(&mut input)?)
+                }
+                _ => Event::Other {
+                    topic,
+                    data: data.to_vec(),
+                },
+            })
+        }
+
+        /// Returns the election the event is about, if it is a known one.
+        pub fn election_id(&self) -> Option<ElectionId> {
+            match self {
+                Event::VoteCast(ballot) => Some(ballot.election_id),
+                Event::PhaseChanged(change) => Some(change.election_id),
+                Event::Other { .. } => None,
+            }
+        }
+    }
+
+    /// `pallet-contracts`' event carrying what a contract emitted.
+    #[derive(Debug, Decode, subxt::ext::scale_decode::DecodeAsType)]
+    #[codec(crate = subxt::ext::codec)]
+    #[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+    struct ContractEmitted {
+        contract: AccountId32,
+        data: Vec<u8>,
+    }
+
+    impl StaticEvent for ContractEmitted {
+        const PALLET: &'static str = "Contracts";
+        const EVENT: &'static str = "ContractEmitted";
+    }
+
+    /// Weight of a call, as `pallet-contracts` encodes it.
+    #[derive(Debug, Clone, Copy)]
+    #[ink::scale_derive(Encode, Decode)]
+    struct Weight {
+        #[codec(compact)]
+        ref_time: u64,
+        #[codec(compact)]
+        proof_size: u64,
+    }
+
+    /// Storage deposit charged or refunded by a call.
+    #[derive(Debug)]
+    #[ink::scale_derive(Decode)]
+    #[allow(dead_code)]
+    enum StorageDeposit {
+        Refund(u128),
+        Charge(u128),
+    }
+
+    /// Output of a contract that returned.
+    #[derive(Debug)]
+    #[ink::scale_derive(Decode)]
+    struct ExecReturnValue {
+        _flags: u32,
+        data: Vec<u8>,
+    }
+
+    /// The leading fields of the `ContractsApi_call` reply. The events that
+    /// follow are not needed, and the dispatch error is only told apart.
+    #[derive(Debug)]
+    #[ink::scale_derive(Decode)]
+    struct DryRun {
+        _gas_consumed: Weight,
+        gas_required: Weight,
+        _storage_deposit: StorageDeposit,
+        _debug_message: Vec<u8>,
+        result: Result<ExecReturnValue, ()>,
+    }
+
+    /// A client of one deployed contract.
+    pub struct Client {
+        api: OnlineClient<PolkadotConfig>,
+        contract: AccountId32,
+    }
+
+    impl Client {
+        /// Connects to the node at `url`, for the contract at `contract`.
+        pub async fn new(url: &str, contract: AccountId32) -> Result<Self, Error> {
+            let api = OnlineClient::<PolkadotConfig>::from_url(url).await?;
+            Ok(Self { api, contract })
+        }
+
+        /// Uses an existing connection, for the contract at `contract`.
+        pub fn with_api(api: OnlineClient<PolkadotConfig>, contract: AccountId32) -> Self {
+            Self { api, contract }
+        }
+
+        /// Casts the ballot of `signer` for `candidate`, paying `fee`, and
+        /// waits for it to be finalized. Returns the ballot as the contract
+        /// recorded it.
+        pub async fn cast_vote(
+            &self,
+            signer: &impl Signer<PolkadotConfig>,
+            election_id: ElectionId,
+            candidate: AccountId,
+            fee: u128,
+        ) -> Result<Ballot, Error> {
+            let mut input = VOTE.to_vec();
+            (election_id, candidate).encode_to(&mut input);
+            let (gas, reply) = self.dry_run(signer.account_id(), fee, &input).await?;
+            let reply: Result<Result<BallotId, VoteError>, ink::LangError> =
+                Decode::decode(&mut &reply[..])?;
+            reply.map_err(Error::Lang)?.map_err(Error::Vote)?;
+
+            let call = subxt::dynamic::tx(
+                "Contracts",
+                "call",
+                ink::prelude::vec![
+                    subxt::dynamic::Value::unnamed_variant(
+                        "Id",
+                        [subxt::dynamic::Value::from_bytes(self.contract.0)],
+                    ),
+                    subxt::dynamic::Value::u128(fee),
+                    subxt::dynamic::Value::named_composite([
+                        ("ref_time", subxt::dynamic::Value::u128(gas.ref_time.into())),
+                        (
+                            "proof_size",
+                            subxt::dynamic::Value::u128(gas.proof_size.into())
+                        ),
+                    ]),
+                    subxt::dynamic::Value::unnamed_variant("None", []),
+                    subxt::dynamic::Value::from_bytes(input),
+                ],
+            );
+            let events = self
+                .api
+                .tx()
+                .sign_and_submit_then_watch_default(&call, signer)
+                .await?
+                .wait_for_finalized_success()
+                .await?;
+            self.decode_all(events.iter())?
+                .into_iter()
+                .find_map(|event| match event {
+                    Event::VoteCast(ballot) => Some(ballot),
+                    _ => None,
+                })
+                .ok_or(Error::MissingEvent)
+        }
+
+        /// Returns the outcome of an election as of the latest block, read
+        /// with `get_results`.
+        pub async fn results(
+            &self,
+            election_id: ElectionId,
+        ) -> Result<Option<ElectionResult>, Error> {
+            let mut input = GET_RESULTS.to_vec();
+            election_id.encode_to(&mut input);
+            let (_, reply) = self.dry_run(&self.contract, 0, &input).await?;
+            let reply: Result<Option<ElectionResult>, ink::LangError> =
+                Decode::decode(&mut &reply[..])?;
+            reply.map_err(Error::Lang)
+        }
+
+        /// Yields the outcome of an election after every finalized block in
+        /// which its ballots or phase changed, ending once it is finalized or
+        /// cancelled.
+        pub async fn watch_results(
+            &self,
+            election_id: ElectionId,
+        ) -> Result<impl Stream<Item = Result<ElectionResult, Error>> + '_, Error> {
+            let blocks = self.api.blocks().subscribe_finalized().await?;
+            Ok(subxt::ext::futures::stream::unfold(
+                (blocks, false),
+                move |(mut blocks, done)| async move {
+                    if done {
+                        return None;
+                    }
+                    loop {
+                        let block = match blocks.next().await? {
+                            Ok(block) => block,
+                            Err(error) => return Some((Err(error.into()), (blocks, true))),
+                        };
+                        let touched = match block.events().await {
+                            Ok(events) => self.events(&events).map(|events| {
+                                events
+                                    .into_iter()
+                                    .filter(|event| event.election_id() == Some(election_id))
+                                    .collect::<Vec<_>>()
+                            }),
+                            Err(error) => Err(error.into()),
+                        };
+                        let touched = match touched {
+                            Ok(touched) if touched.is_empty() => continue,
+                            Ok(touched) => touched,
+                            Err(error) => return Some((Err(error), (blocks, true))),
+                        };
+                        let ended = touched.iter().any(|event| {
+                            matches!(
+                                event,
+                                Event::PhaseChanged(PhaseChange {
+                                    to: ElectionStatus::Finalized
+                                        | ElectionStatus::Cancelled
+                                        | ElectionStatus::Expired,
+                                    ..
+                                })
+                            )
+                        });
+                        match self.results(election_id).await {
+                            Ok(Some(result)) => return Some((Ok(result), (blocks, ended))),
+                            Ok(None) => return None,
+                            Err(error) => return Some((Err(error), (blocks, true))),
+                        }
+                    }
+                },
+            ))
+        }
 
-        //     let get = call_builder.get();
-        //     let get_result = client.call(&ink_e2e::bob(), &get).dry_run().await?;
-        //     assert!(matches!(get_result.return_value(), false));
+        /// Decodes the events the contract emitted in a block.
+        pub fn events(&self, events: &Events<PolkadotConfig>) -> Result<Vec<Event>, Error> {
+            self.decode_all(events.iter())
+        }
 
-        //     // When
-        //     let flip = call_builder.flip();
-        //     let _flip_result = client
-        //         .call(&ink_e2e::bob(), &flip)
-        //         .submit()
-        //         .await
-        //         .expect("flip failed");
+        /// Decodes the events of this contract among `events`.
+        fn decode_all(
+            &self,
+            events: impl Iterator<Item = Result<EventDetails<PolkadotConfig>, subxt::Error>>,
+        ) -> Result<Vec<Event>, Error> {
+            let mut decoded = Vec::new();
+            for event in events {
+                let event = event?;
+                if let Some(event) =
+                    self.contract_event(event.as_event::<ContractEmitted>()?, event.topics())?
+                {
+                    decoded.push(event);
+                }
+            }
+            Ok(decoded)
+        }
 
-        //     // Then
-        //     let get = call_builder.get();
-        //     let get_result = client.call(&ink_e2e::bob(), &get).dry_run().await?;
-        //     assert!(matches!(get_result.return_value(), true));
+        /// Decodes an emitted event if it is one of this contract's.
+        fn contract_event(
+            &self,
+            emitted: Option<ContractEmitted>,
+            topics: &[subxt::utils::H256],
+        ) -> Result<Option<Event>, Error> {
+            let Some(emitted) = emitted.filter(|emitted| emitted.contract == self.contract) else {
+                return Ok(None);
+            };
+            let Some(topic) = topics.first() else {
+                return Ok(None);
+            };
+            Ok(Some(Event::decode(topic.0, &emitted.data)?))
+        }
 
-        //     Ok(())
-        // }
+        /// Dry-runs a call of the contract from `origin` through the
+        /// `ContractsApi_call` runtime API. Returns the gas it needs and its
+        /// encoded reply.
+        async fn dry_run(
+            &self,
+            origin: &AccountId32,
+            value: u128,
+            input: &[u8],
+        ) -> Result<(Weight, Vec<u8>), Error> {
+            let args = (
+                origin.0,
+                self.contract.0,
+                value,
+                Option::<Weight>::None,
+                Option::<u128>::None,
+                input,
+            )
+                .encode();
+            let reply: Vec<u8> = self
+                .api
+                .runtime_api()
+                .at_latest()
+                .await?
+                .call_raw("ContractsApi_call", Some(&args))
+                .await?;
+            let dry_run = DryRun::decode(&mut &reply[..])?;
+            // A reverted call still carries the message's encoded result,
+            // which tells why it was refused.
+            match dry_run.result {
+                Ok(ExecReturnValue { data, .. }) => Ok((dry_run.gas_required, data)),
+                Err(()) => Err(Error::DispatchFailed),
+            }
+        }
     }
 }
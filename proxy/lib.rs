@@ -0,0 +1,195 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Stable front for the voting contract.
+///
+/// Every call is forwarded, input and value included, to the voting contract
+/// currently set as target, so integrators keep one address across
+/// redeployments. Only the `@` selector is handled by the proxy itself.
+///
+/// ink! lets a contract with a wildcard message define only one other
+/// message, so the voting contract itself cannot fall back on unknown
+/// selectors; the proxy does once the admin lists the known ones.
+#[ink::contract]
+pub mod voting_proxy {
+    use ink::prelude::vec::Vec;
+
+    /// Defines the storage of your contract.
+    #[ink(storage)]
+    pub struct VotingProxy {
+        /// Voting contract calls are forwarded to.
+        target: AccountId,
+        /// Account allowed to rotate the target and the admin.
+        admin: AccountId,
+        /// Selectors the target is known to handle.
+        known: ink::storage::Mapping<[u8; 4], ()>,
+        /// Number of `known` selectors; while zero every call is forwarded.
+        known_selectors: u32,
+    }
+
+    /// Emitted when calls start being forwarded to another contract.
+    #[ink(event)]
+    pub struct TargetChanged {
+        #[ink(topic)]
+        old: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    /// Emitted when the admin hands the proxy over.
+    #[ink(event)]
+    pub struct AdminChanged {
+        #[ink(topic)]
+        old: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    /// Emitted when a call is refused because its selector is not known.
+    #[ink(event)]
+    pub struct UnknownSelector {
+        #[ink(topic)]
+        caller: AccountId,
+        selector: [u8; 4],
+    }
+
+    /// What the admin asks the proxy to do.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum AdminCommand {
+        /// Forwards calls to another voting contract from now on.
+        SetTarget(AccountId),
+        /// Hands the admin rights over to another account.
+        SetAdmin(AccountId),
+        /// Marks selectors as handled by the target, or no longer handled.
+        SetKnown(Vec<[u8; 4]>, bool),
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum ProxyError {
+        Unauthorized,
+        UnknownSelector,
+    }
+
+    impl VotingProxy {
+        /// Constructor forwarding to `target`, administered by the caller.
+        #[ink(constructor)]
+        pub fn new(target: AccountId) -> Self {
+            Self {
+                target,
+                admin: Self::env().caller(),
+                known: Default::default(),
+                known_selectors: 0,
+            }
+        }
+
+        /// Runs an admin command. Only the admin may call it.
+        #[ink(message, selector = @)]
+        pub fn admin(&mut self, command: AdminCommand) -> Result<(), ProxyError> {
+            let caller = self.env().caller();
+            if caller != self.admin {
+                return Err(ProxyError::Unauthorized);
+            }
+            match command {
+                AdminCommand::SetTarget(new) => {
+                    let old = core::mem::replace(&mut self.target, new);
+                    self.env().emit_event(TargetChanged { old, new });
+                }
+                AdminCommand::SetAdmin(new) => {
+                    self.admin = new;
+                    self.env().emit_event(AdminChanged { old: caller, new });
+                }
+                AdminCommand::SetKnown(selectors, known) => {
+                    for selector in selectors {
+                        if self.known.contains(selector) == known {
+                            continue;
+                        }
+                        if known {
+                            self.known.insert(selector, &());
+                            self.known_selectors += 1;
+                        } else {
+                            self.known.remove(selector);
+                            self.known_selectors -= 1;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Forwards any other call to the target as a tail call, so its
+        /// output becomes the proxy's output. Once selectors are listed as
+        /// known, a call with another one is refused with `UnknownSelector`
+        /// and an event instead of trapping in the target.
+        #[ink(message, payable, selector = _)]
+        pub fn forward(&self) -> Result<(), ProxyError> {
+            if self.known_selectors > 0 {
+                let selector = ink::env::decode_input::<[u8; 4]>().unwrap_or_default();
+                if !self.known.contains(selector) {
+                    self.env().emit_event(UnknownSelector {
+                        caller: self.env().caller(),
+                        selector,
+                    });
+                    return Err(ProxyError::UnknownSelector);
+                }
+            }
+            ink::env::call::build_call::<ink::env::DefaultEnvironment>()
+                .call(self.target)
+                .transferred_value(self.env().transferred_value())
+                .call_flags(ink::env::CallFlags::FORWARD_INPUT | ink::env::CallFlags::TAIL_CALL)
+                .invoke();
+            unreachable!("a tail call never returns");
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// We test that only the admin rotates the target and the admin, with events.
+        #[ink::test]
+        fn admin_rotates_target_and_admin() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut proxy = VotingProxy::new(accounts.django);
+
+            assert_eq!(proxy.admin(AdminCommand::SetTarget(accounts.eve)), Ok(()));
+            assert_eq!(proxy.target, accounts.eve);
+            assert_eq!(proxy.admin(AdminCommand::SetAdmin(accounts.bob)), Ok(()));
+            assert_eq!(
+                proxy.admin(AdminCommand::SetTarget(accounts.frank)),
+                Err(ProxyError::Unauthorized)
+            );
+            assert_eq!(ink::env::test::recorded_events().count(), 2);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(proxy.admin(AdminCommand::SetTarget(accounts.frank)), Ok(()));
+            assert_eq!(proxy.target, accounts.frank);
+        }
+
+        /// We test that listing a selector twice counts it once.
+        #[ink::test]
+        fn admin_lists_known_selectors() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut proxy = VotingProxy::new(accounts.django);
+            let vote = ink::selector_bytes!("vote");
+            let finalize = ink::selector_bytes!("finalize");
+
+            let listed = AdminCommand::SetKnown(vec![vote, finalize, vote], true);
+            assert_eq!(proxy.admin(listed), Ok(()));
+            assert_eq!(proxy.known_selectors, 2);
+            assert_eq!(
+                proxy.admin(AdminCommand::SetKnown(vec![vote], false)),
+                Ok(())
+            );
+            assert_eq!(proxy.known_selectors, 1);
+            assert!(proxy.known.contains(finalize));
+            assert!(!proxy.known.contains(vote));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                proxy.admin(AdminCommand::SetKnown(vec![vote], true)),
+                Err(ProxyError::Unauthorized)
+            );
+        }
+    }
+}
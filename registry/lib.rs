@@ -0,0 +1,171 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Voter roll for the voting contract.
+///
+/// Keeps the accounts allowed to vote, in every election or in a single
+/// one, and answers the voting contract through `EligibilitySource`, so the
+/// roll is administered and audited apart from the ballots. An election
+/// uses it by naming it as its `eligibility_source`.
+#[ink::contract]
+pub mod voting_registry {
+    use ink::prelude::vec::Vec;
+    use voting::{eligibility::EligibilitySource, queries::ElectionId};
+
+    /// `(account, election)` key of the roll, `None` for every election.
+    type RollKey = (AccountId, Option<ElectionId>);
+
+    #[ink(storage)]
+    pub struct VotingRegistry {
+        /// Account allowed to change the roll and hand it over.
+        registrar: AccountId,
+        /// Accounts on the roll, for one election or, under `None`, for all.
+        roll: ink::storage::Mapping<RollKey, ()>,
+        /// Number of entries on the roll.
+        entries: u32,
+    }
+
+    /// Emitted when an account is put on the roll or taken off it.
+    #[ink(event)]
+    pub struct RollChanged {
+        #[ink(topic)]
+        account: AccountId,
+        /// `None` for every election.
+        election_id: Option<ElectionId>,
+        enrolled: bool,
+    }
+
+    /// Emitted when the registrar hands the roll over.
+    #[ink(event)]
+    pub struct RegistrarChanged {
+        #[ink(topic)]
+        old: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum RegistryError {
+        Unauthorized,
+    }
+
+    impl Default for VotingRegistry {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl VotingRegistry {
+        /// Constructor of an empty roll kept by the caller.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                registrar: Self::env().caller(),
+                roll: Default::default(),
+                entries: 0,
+            }
+        }
+
+        /// Puts `accounts` on the roll of an election, or of every election
+        /// under `None`, or takes them off it. Only the registrar may call it.
+        #[ink(message)]
+        pub fn enroll(
+            &mut self,
+            accounts: Vec<AccountId>,
+            election_id: Option<ElectionId>,
+            enrolled: bool,
+        ) -> Result<(), RegistryError> {
+            if self.env().caller() != self.registrar {
+                return Err(RegistryError::Unauthorized);
+            }
+            for account in accounts {
+                if self.roll.contains((account, election_id)) == enrolled {
+                    continue;
+                }
+                if enrolled {
+                    self.roll.insert((account, election_id), &());
+                    self.entries += 1;
+                } else {
+                    self.roll.remove((account, election_id));
+                    self.entries -= 1;
+                }
+                self.env().emit_event(RollChanged {
+                    account,
+                    election_id,
+                    enrolled,
+                });
+            }
+            Ok(())
+        }
+
+        /// Hands the roll over to another registrar. Only the registrar may
+        /// call it.
+        #[ink(message)]
+        pub fn set_registrar(&mut self, new: AccountId) -> Result<(), RegistryError> {
+            let caller = self.env().caller();
+            if caller != self.registrar {
+                return Err(RegistryError::Unauthorized);
+            }
+            self.registrar = new;
+            self.env().emit_event(RegistrarChanged { old: caller, new });
+            Ok(())
+        }
+
+        /// Returns the number of entries on the roll.
+        #[ink(message)]
+        pub fn entries(&self) -> u32 {
+            self.entries
+        }
+    }
+
+    impl EligibilitySource for VotingRegistry {
+        #[ink(message)]
+        fn is_eligible(&self, account: AccountId, election_id: ElectionId) -> bool {
+            self.roll.contains((account, None::<ElectionId>))
+                || self.roll.contains((account, Some(election_id)))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// We test that an account enrolled for every election is eligible in
+        /// each, and one enrolled for an election only in that one.
+        #[ink::test]
+        fn roll_scopes_eligibility() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VotingRegistry::new();
+            let everywhere = vec![accounts.bob, accounts.bob];
+            assert_eq!(registry.enroll(everywhere, None, true), Ok(()));
+            assert_eq!(registry.enroll(vec![accounts.eve], Some(1), true), Ok(()));
+            assert_eq!(registry.entries(), 2);
+            assert_eq!(ink::env::test::recorded_events().count(), 2);
+
+            assert!(registry.is_eligible(accounts.bob, 0));
+            assert!(registry.is_eligible(accounts.bob, 7));
+            assert!(registry.is_eligible(accounts.eve, 1));
+            assert!(!registry.is_eligible(accounts.eve, 0));
+
+            assert_eq!(registry.enroll(vec![accounts.bob], None, false), Ok(()));
+            assert!(!registry.is_eligible(accounts.bob, 0));
+            assert_eq!(registry.entries(), 1);
+        }
+
+        /// We test that only the registrar changes the roll and hands it over.
+        #[ink::test]
+        fn registrar_keeps_the_roll() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut registry = VotingRegistry::new();
+            assert_eq!(registry.set_registrar(accounts.bob), Ok(()));
+            assert_eq!(
+                registry.enroll(vec![accounts.eve], None, true),
+                Err(RegistryError::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(registry.enroll(vec![accounts.eve], None, true), Ok(()));
+            assert!(registry.is_eligible(accounts.eve, 0));
+        }
+    }
+}
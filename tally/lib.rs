@@ -0,0 +1,142 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Tally engine for the voting contract.
+///
+/// Counts the ballots one voting contract hands it through `TallyModule`
+/// and reports the totals at finalization, so the counting rules can be
+/// swapped and audited apart from the ballots. An election uses it by
+/// naming it as its `tally_module`.
+///
+/// Every kind of ballot is counted the way the voting contract counts it:
+/// a split ballot in thousandths of a vote, a ranked one on its first
+/// preference, and the others by the votes or points they give.
+#[ink::contract]
+pub mod voting_tally {
+    use ink::prelude::vec::Vec;
+    use voting::{queries::ElectionId, tally::TallyModule, voting::BallotChoice};
+
+    #[ink(storage)]
+    pub struct VotingTally {
+        /// Voting contract whose ballots are counted.
+        voting: AccountId,
+        /// Votes of each runner of an election.
+        votes: ink::storage::Mapping<(ElectionId, AccountId), u32>,
+        /// Ballots counted in each election.
+        ballots: ink::storage::Mapping<ElectionId, u32>,
+    }
+
+    impl VotingTally {
+        /// Constructor counting the ballots of `voting`.
+        #[ink(constructor)]
+        pub fn new(voting: AccountId) -> Self {
+            Self {
+                voting,
+                votes: Default::default(),
+                ballots: Default::default(),
+            }
+        }
+
+        /// Returns the ballots counted in an election.
+        #[ink(message)]
+        pub fn ballots(&self, election_id: ElectionId) -> u32 {
+            self.ballots.get(election_id).unwrap_or_default()
+        }
+
+        /// Adds `votes` to a runner of an election.
+        fn add(&mut self, election_id: ElectionId, runner: AccountId, votes: u32) {
+            let total = self.votes.get((election_id, runner)).unwrap_or_default();
+            self.votes
+                .insert((election_id, runner), &total.saturating_add(votes));
+        }
+    }
+
+    impl TallyModule for VotingTally {
+        /// Refuses any ballot not handed over by the voting contract, and
+        /// ranked ballots that rank no one.
+        #[ink(message)]
+        fn ingest_ballot(
+            &mut self,
+            election_id: ElectionId,
+            _voter: AccountId,
+            ballot: BallotChoice,
+            weight: u32,
+        ) -> bool {
+            if self.env().caller() != self.voting {
+                return false;
+            }
+            match ballot {
+                BallotChoice::Single(runner) => self.add(election_id, runner, weight),
+                BallotChoice::Quadratic(runner, votes) => {
+                    self.add(election_id, runner, weight.saturating_mul(u32::from(votes)))
+                }
+                BallotChoice::Ranked(ranking) => {
+                    let Some(first) = ranking.first() else {
+                        return false;
+                    };
+                    self.add(election_id, *first, weight)
+                }
+                BallotChoice::Split(allocations)
+                | BallotChoice::Scored(allocations)
+                | BallotChoice::Cumulative(allocations) => {
+                    for (runner, share) in allocations {
+                        self.add(election_id, runner, weight.saturating_mul(u32::from(share)));
+                    }
+                }
+            }
+            let ballots = self.ballots(election_id);
+            self.ballots.insert(election_id, &ballots.saturating_add(1));
+            true
+        }
+
+        #[ink(message)]
+        fn compute_result(&self, election_id: ElectionId, runners: Vec<AccountId>) -> Vec<u32> {
+            runners
+                .into_iter()
+                .map(|runner| self.votes.get((election_id, runner)).unwrap_or_default())
+                .collect()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// We test that ballots of every kind add up per runner.
+        #[ink::test]
+        fn ballots_add_up() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut tally = VotingTally::new(accounts.alice);
+            let ballots = [
+                BallotChoice::Single(accounts.eve),
+                BallotChoice::Quadratic(accounts.frank, 3),
+                BallotChoice::Ranked(vec![accounts.frank, accounts.eve]),
+                BallotChoice::Split(vec![(accounts.eve, 250), (accounts.frank, 750)]),
+            ];
+            for ballot in ballots {
+                assert!(tally.ingest_ballot(0, accounts.bob, ballot, 2));
+            }
+            assert!(!tally.ingest_ballot(0, accounts.bob, BallotChoice::Ranked(Vec::new()), 2));
+            assert!(tally.ingest_ballot(1, accounts.bob, BallotChoice::Single(accounts.eve), 5));
+
+            assert_eq!(tally.ballots(0), 4);
+            assert_eq!(
+                tally.compute_result(0, vec![accounts.eve, accounts.frank, accounts.django]),
+                [502, 1508, 0]
+            );
+            assert_eq!(tally.compute_result(1, vec![accounts.eve]), [5]);
+        }
+
+        /// We test that only the voting contract hands ballots over.
+        #[ink::test]
+        fn only_the_voting_contract_is_counted() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut tally = VotingTally::new(accounts.django);
+            let ballot = BallotChoice::Single(accounts.eve);
+            assert!(!tally.ingest_ballot(0, accounts.bob, ballot.clone(), 1));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert!(tally.ingest_ballot(0, accounts.bob, ballot, 1));
+            assert_eq!(tally.compute_result(0, vec![accounts.eve]), [1]);
+        }
+    }
+}
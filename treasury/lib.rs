@@ -0,0 +1,167 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Treasury for the voting contract.
+///
+/// Holds the funds the voting contract sweeps into it through `Treasury`,
+/// along with any other deposit, and pays them out at the treasurer's
+/// request, so the funds are held and audited apart from the ballots. The
+/// treasurer may itself be a contract, e.g. the voting contract's
+/// governance.
+#[ink::contract]
+pub mod voting_treasury {
+    use voting::treasury::Treasury;
+
+    #[ink(storage)]
+    pub struct VotingTreasury {
+        /// Account allowed to spend the funds and hand the treasury over.
+        treasurer: AccountId,
+        /// Funds held, as deposited and not spent yet.
+        funds: Balance,
+        /// Funds deposited by each account over time.
+        deposited: ink::storage::Mapping<AccountId, Balance>,
+    }
+
+    /// Emitted when funds are deposited.
+    #[ink(event)]
+    pub struct Deposited {
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when the treasurer spends funds.
+    #[ink(event)]
+    pub struct Spent {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when the treasurer hands the treasury over.
+    #[ink(event)]
+    pub struct TreasurerChanged {
+        #[ink(topic)]
+        old: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum TreasuryError {
+        Unauthorized,
+        InsufficientFunds,
+        TransferFailed,
+    }
+
+    impl Default for VotingTreasury {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl VotingTreasury {
+        /// Constructor of an empty treasury kept by the caller.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                treasurer: Self::env().caller(),
+                funds: 0,
+                deposited: Default::default(),
+            }
+        }
+
+        /// Pays `amount` of the funds to `to`. Only the treasurer may call it.
+        #[ink(message)]
+        pub fn spend(&mut self, to: AccountId, amount: Balance) -> Result<(), TreasuryError> {
+            if self.env().caller() != self.treasurer {
+                return Err(TreasuryError::Unauthorized);
+            }
+            if amount > self.funds {
+                return Err(TreasuryError::InsufficientFunds);
+            }
+            self.funds -= amount;
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| TreasuryError::TransferFailed)?;
+            self.env().emit_event(Spent { to, amount });
+            Ok(())
+        }
+
+        /// Hands the treasury over to another treasurer. Only the treasurer
+        /// may call it.
+        #[ink(message)]
+        pub fn set_treasurer(&mut self, new: AccountId) -> Result<(), TreasuryError> {
+            let caller = self.env().caller();
+            if caller != self.treasurer {
+                return Err(TreasuryError::Unauthorized);
+            }
+            self.treasurer = new;
+            self.env().emit_event(TreasurerChanged { old: caller, new });
+            Ok(())
+        }
+
+        /// Returns the funds `account` deposited over time.
+        #[ink(message)]
+        pub fn deposited_by(&self, account: AccountId) -> Balance {
+            self.deposited.get(account).unwrap_or_default()
+        }
+    }
+
+    impl Treasury for VotingTreasury {
+        #[ink(message, payable)]
+        fn deposit(&mut self) {
+            let from = self.env().caller();
+            let amount = self.env().transferred_value();
+            self.funds += amount;
+            self.deposited
+                .insert(from, &self.deposited_by(from).saturating_add(amount));
+            self.env().emit_event(Deposited { from, amount });
+        }
+
+        #[ink(message)]
+        fn funds(&self) -> Balance {
+            self.funds
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// We test that deposits are booked per depositor and that only the
+        /// treasurer spends them, within the funds held.
+        #[ink::test]
+        fn treasurer_spends_deposits() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut treasury = VotingTreasury::new();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(40);
+            treasury.deposit();
+            treasury.deposit();
+            assert_eq!(treasury.funds(), 80);
+            assert_eq!(treasury.deposited_by(accounts.django), 80);
+            assert_eq!(
+                treasury.spend(accounts.eve, 10),
+                Err(TreasuryError::Unauthorized)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                contract, 2_000_000,
+            );
+            assert_eq!(
+                treasury.spend(accounts.eve, 81),
+                Err(TreasuryError::InsufficientFunds)
+            );
+            assert_eq!(treasury.spend(accounts.eve, 30), Ok(()));
+            assert_eq!(treasury.funds(), 50);
+            assert_eq!(treasury.set_treasurer(accounts.bob), Ok(()));
+            assert_eq!(
+                treasury.spend(accounts.eve, 30),
+                Err(TreasuryError::Unauthorized)
+            );
+        }
+    }
+}